@@ -0,0 +1,178 @@
+//! Derive macros for [chuchi-crypto](https://docs.rs/chuchi-crypto).
+//!
+//! Use them through the `derive` feature of `chuchi-crypto` which
+//! re-exports them.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::{
+	parenthesized, parse_macro_input, Data, DeriveInput, Error, Fields, Ident,
+	LitStr, Path, Token,
+};
+
+/// Generates an `Encrypted{Name}` struct where every field marked with
+/// `#[encrypted]` is stored as an `Encrypted<T>`, together with
+/// `encrypt(&self, &Keyset)` and `decrypt(&self, &Keyset)` methods.
+///
+/// Fields which are not encrypted are cloned. The struct level
+/// `#[encrypt(..)]` attribute accepts:
+/// - `name = Ident` to rename the generated struct
+/// - `derive(..)` to add derives to the generated struct
+/// - `crate = "path"` if chuchi-crypto is not available as `chuchi_crypto`
+#[proc_macro_derive(Encrypt, attributes(encrypt, encrypted))]
+pub fn derive_encrypt(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+
+	expand(input)
+		.unwrap_or_else(Error::into_compile_error)
+		.into()
+}
+
+struct Options {
+	name: Option<Ident>,
+	derives: Vec<Path>,
+	krate: Path,
+}
+
+fn parse_options(input: &DeriveInput) -> syn::Result<Options> {
+	let mut opts = Options {
+		name: None,
+		derives: vec![],
+		krate: syn::parse_quote!(::chuchi_crypto),
+	};
+
+	for attr in input.attrs.iter().filter(|a| a.path().is_ident("encrypt")) {
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("name") {
+				opts.name = Some(meta.value()?.parse()?);
+			} else if meta.path.is_ident("derive") {
+				let content;
+				parenthesized!(content in meta.input);
+				let paths =
+					Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+				opts.derives.extend(paths);
+			} else if meta.path.is_ident("crate") {
+				let s: LitStr = meta.value()?.parse()?;
+				opts.krate = s.parse()?;
+			} else {
+				return Err(meta.error("unknown encrypt attribute"));
+			}
+
+			Ok(())
+		})?;
+	}
+
+	Ok(opts)
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+	let opts = parse_options(&input)?;
+
+	let Data::Struct(data) = &input.data else {
+		return Err(Error::new(
+			Span::call_site(),
+			"Encrypt can only be derived for structs",
+		));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(Error::new(
+			Span::call_site(),
+			"Encrypt needs a struct with named fields",
+		));
+	};
+
+	let krate = &opts.krate;
+	let vis = &input.vis;
+	let ident = &input.ident;
+	let name = opts
+		.name
+		.clone()
+		.unwrap_or_else(|| format_ident!("Encrypted{}", ident));
+	let derives = &opts.derives;
+	let derives =
+		(!derives.is_empty()).then(|| quote!(#[derive(#(#derives),*)]));
+	let (impl_gen, ty_gen, where_clause) = input.generics.split_for_impl();
+	let generics = &input.generics;
+
+	let mut struct_fields = vec![];
+	let mut encrypt_fields = vec![];
+	let mut decrypt_fields = vec![];
+
+	for field in &fields.named {
+		let f_vis = &field.vis;
+		let f_ident = field.ident.as_ref().unwrap();
+		let f_ty = &field.ty;
+
+		let mut encrypted = false;
+		for attr in field
+			.attrs
+			.iter()
+			.filter(|a| a.path().is_ident("encrypted"))
+		{
+			attr.meta.require_path_only()?;
+			encrypted = true;
+		}
+
+		if encrypted {
+			struct_fields.push(quote! {
+				#f_vis #f_ident: #krate::cipher::encrypted_field::Encrypted<#f_ty>
+			});
+			encrypt_fields.push(quote! {
+				#f_ident: #krate::cipher::encrypted_field::Encrypted::encrypt(
+					&self.#f_ident,
+					keyset,
+				)
+			});
+			decrypt_fields.push(quote! {
+				#f_ident: self.#f_ident.decrypt(keyset)?
+			});
+		} else {
+			struct_fields.push(quote!(#f_vis #f_ident: #f_ty));
+			encrypt_fields.push(quote! {
+				#f_ident: ::std::clone::Clone::clone(&self.#f_ident)
+			});
+			decrypt_fields.push(quote! {
+				#f_ident: ::std::clone::Clone::clone(&self.#f_ident)
+			});
+		}
+	}
+
+	let doc = format!("The encrypted form of [`{ident}`].");
+
+	Ok(quote! {
+		#[doc = #doc]
+		#derives
+		#vis struct #name #generics #where_clause {
+			#(#struct_fields,)*
+		}
+
+		impl #impl_gen #ident #ty_gen #where_clause {
+			/// Encrypts every field marked with `#[encrypted]`.
+			pub fn encrypt(
+				&self,
+				keyset: &#krate::cipher::Keyset,
+			) -> #name #ty_gen {
+				#name {
+					#(#encrypt_fields,)*
+				}
+			}
+		}
+
+		impl #impl_gen #name #ty_gen #where_clause {
+			/// Decrypts every encrypted field.
+			pub fn decrypt(
+				&self,
+				keyset: &#krate::cipher::Keyset,
+			) -> ::std::result::Result<
+				#ident #ty_gen,
+				#krate::error::DecryptError,
+			> {
+				::std::result::Result::Ok(#ident {
+					#(#decrypt_fields,)*
+				})
+			}
+		}
+	})
+}
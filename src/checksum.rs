@@ -0,0 +1,284 @@
+//! CRC checksums to detect accidental corruption and typos.
+//!
+//! The checksum is typed by its algorithm, so a CRC-32C can't be compared
+//! with a CRC-32 by accident.
+//!
+//! ## Note
+//! A checksum does not protect against intentional modifications, use a
+//! [`HmacKey`](crate::hash::HmacKey) or a signature for that.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::checksum::{self, Crc32c, Hasher};
+//!
+//! let mut hasher = Hasher::<Crc32c>::new();
+//! hasher.update(b"1234");
+//! hasher.update(b"56789");
+//! let checksum = hasher.finalize();
+//! assert_eq!(checksum, checksum::crc32c(b"123456789"));
+//! assert_eq!(checksum.to_string(), "e3069283");
+//! ```
+
+use crate::error::DecodeError;
+
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+mod sealed {
+	pub trait Sealed {}
+}
+
+/// A CRC algorithm with a 32 bit output.
+///
+/// This trait is sealed and implemented by `Crc32` and `Crc32c`.
+pub trait Algorithm: sealed::Sealed {
+	#[doc(hidden)]
+	const TABLE: [u32; 256];
+}
+
+/// CRC-32 (ISO-HDLC), used by zip, png and gzip.
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32;
+
+impl sealed::Sealed for Crc32 {}
+
+impl Algorithm for Crc32 {
+	const TABLE: [u32; 256] = table(0xedb8_8320);
+}
+
+/// CRC-32C (Castagnoli), used by iSCSI, ext4 and many storage formats.
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32c;
+
+impl sealed::Sealed for Crc32c {}
+
+impl Algorithm for Crc32c {
+	const TABLE: [u32; 256] = table(0x82f6_3b78);
+}
+
+// creates the lookup table for a reflected polynomial
+const fn table(poly: u32) -> [u32; 256] {
+	let mut table = [0u32; 256];
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = i as u32;
+		let mut j = 0;
+		while j < 8 {
+			crc = (crc >> 1) ^ (poly & (crc & 1).wrapping_neg());
+			j += 1;
+		}
+		table[i] = crc;
+		i += 1;
+	}
+
+	table
+}
+
+/// Calculates the CRC-32 of `data`.
+pub fn crc32(data: impl AsRef<[u8]>) -> Checksum<Crc32> {
+	Hasher::<Crc32>::checksum(data)
+}
+
+/// Calculates the CRC-32C of `data`.
+pub fn crc32c(data: impl AsRef<[u8]>) -> Checksum<Crc32c> {
+	Hasher::<Crc32c>::checksum(data)
+}
+
+/// An incremental CRC hasher.
+pub struct Hasher<A: Algorithm> {
+	crc: u32,
+	algorithm: PhantomData<A>,
+}
+
+impl<A: Algorithm> Hasher<A> {
+	pub fn new() -> Self {
+		Self {
+			crc: !0,
+			algorithm: PhantomData,
+		}
+	}
+
+	pub fn update(&mut self, data: impl AsRef<[u8]>) {
+		for &b in data.as_ref() {
+			self.crc = A::TABLE[((self.crc ^ b as u32) & 0xff) as usize]
+				^ (self.crc >> 8);
+		}
+	}
+
+	pub fn finalize(self) -> Checksum<A> {
+		Checksum::new(!self.crc)
+	}
+
+	pub fn checksum(data: impl AsRef<[u8]>) -> Checksum<A> {
+		let mut hasher = Self::new();
+		hasher.update(data);
+		hasher.finalize()
+	}
+}
+
+impl<A: Algorithm> Clone for Hasher<A> {
+	fn clone(&self) -> Self {
+		Self {
+			crc: self.crc,
+			algorithm: PhantomData,
+		}
+	}
+}
+
+impl<A: Algorithm> fmt::Debug for Hasher<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Hasher")
+	}
+}
+
+impl<A: Algorithm> io::Write for Hasher<A> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+pub type Crc32Checksum = Checksum<Crc32>;
+
+pub type Crc32cChecksum = Checksum<Crc32c>;
+
+/// A checksum created by the algorithm `A`.
+///
+/// Displayed and parsed as 8 lowercase hex characters, the bytes are
+/// big endian.
+pub struct Checksum<A: Algorithm> {
+	value: u32,
+	algorithm: PhantomData<A>,
+}
+
+impl<A: Algorithm> Checksum<A> {
+	pub const LEN: usize = 4;
+
+	pub fn new(value: u32) -> Self {
+		Self {
+			value,
+			algorithm: PhantomData,
+		}
+	}
+
+	pub fn value(&self) -> u32 {
+		self.value
+	}
+
+	pub fn from_bytes(bytes: [u8; 4]) -> Self {
+		Self::new(u32::from_be_bytes(bytes))
+	}
+
+	pub fn to_bytes(&self) -> [u8; 4] {
+		self.value.to_be_bytes()
+	}
+
+	/// Returns true if the data has this checksum.
+	pub fn verify(&self, data: impl AsRef<[u8]>) -> bool {
+		Hasher::<A>::checksum(data) == *self
+	}
+}
+
+impl<A: Algorithm> Clone for Checksum<A> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<A: Algorithm> Copy for Checksum<A> {}
+
+impl<A: Algorithm> PartialEq for Checksum<A> {
+	fn eq(&self, other: &Self) -> bool {
+		self.value == other.value
+	}
+}
+
+impl<A: Algorithm> Eq for Checksum<A> {}
+
+impl<A: Algorithm> Hash for Checksum<A> {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		state.write_u32(self.value);
+	}
+}
+
+impl<A: Algorithm> fmt::Debug for Checksum<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Checksum").field(&self.to_string()).finish()
+	}
+}
+
+impl<A: Algorithm> fmt::Display for Checksum<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:08x}", self.value)
+	}
+}
+
+impl<A: Algorithm> FromStr for Checksum<A> {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != 8 {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		// from_str_radix would accept a sign
+		if !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		u32::from_str_radix(s, 16)
+			.map(Self::new)
+			.map_err(|_| DecodeError::InvalidBytes)
+	}
+}
+
+impl<A: Algorithm> From<Checksum<A>> for u32 {
+	fn from(checksum: Checksum<A>) -> Self {
+		checksum.value
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn check_values() {
+		assert_eq!(crc32(b"123456789").value(), 0xcbf43926);
+		assert_eq!(crc32c(b"123456789").value(), 0xe3069283);
+		assert_eq!(crc32(b"").value(), 0);
+		assert_eq!(crc32c([0u8; 32]).value(), 0x8a9136aa);
+	}
+
+	#[test]
+	fn incremental() {
+		let data: Vec<u8> = (0..=255).collect();
+
+		let mut hasher = Hasher::<Crc32c>::new();
+		for chunk in data.chunks(7) {
+			io::Write::write_all(&mut hasher, chunk).unwrap();
+		}
+		assert_eq!(hasher.finalize(), crc32c(&data));
+		assert!(crc32c(&data).verify(&data));
+		assert!(!crc32c(&data).verify(&data[1..]));
+	}
+
+	#[test]
+	fn encoding() {
+		let checksum = crc32(b"123456789");
+		assert_eq!(checksum.to_string(), "cbf43926");
+		assert_eq!(checksum.to_bytes(), [0xcb, 0xf4, 0x39, 0x26]);
+		assert_eq!(Crc32Checksum::from_bytes(checksum.to_bytes()), checksum);
+		assert_eq!("CBF43926".parse::<Crc32Checksum>().unwrap(), checksum);
+
+		assert!("cbf4392".parse::<Crc32Checksum>().is_err());
+		assert!("+bf43926".parse::<Crc32Checksum>().is_err());
+	}
+}
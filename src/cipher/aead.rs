@@ -0,0 +1,569 @@
+use super::{padding, Ciphertext, MacNotEqual, Nonce, Nonce12, WrappedKey};
+#[cfg(feature = "password")]
+use crate::error::PasswordError;
+use crate::error::{DecryptError, RandomError, TryFromError};
+use crate::fill_random;
+#[cfg(feature = "password")]
+use crate::hash::password::{KeyParams, Params};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use zeroize::Zeroize;
+
+use chacha20poly1305::aead::{Aead as _, AeadInPlace, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit, XChaCha20Poly1305};
+
+/// An authenticated cipher using ChaCha20-Poly1305 (RFC 8439).
+///
+/// ## Warning
+/// Never use the same nonce twice with the same key.
+#[derive(Clone)]
+pub struct Aead {
+	key: [u8; 32],
+}
+
+impl Aead {
+	pub const LEN: usize = 32;
+
+	pub const NONCE_LEN: usize = 12;
+
+	pub const TAG_LEN: usize = 16;
+
+	/// Creates a new random key.
+	pub fn new() -> Self {
+		let mut key = [0u8; 32];
+		fill_random(&mut key);
+
+		Self { key }
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new random key using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut key = [0u8; 32];
+		rng.fill_bytes(&mut key);
+
+		Self { key }
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.key
+	}
+
+	fn cipher(&self) -> ChaCha20Poly1305 {
+		ChaCha20Poly1305::new(self.key.as_slice().into())
+	}
+
+	/// Encrypts the plaintext returning the ciphertext with the tag appended.
+	pub fn encrypt(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> Vec<u8> {
+		self.cipher()
+			.encrypt(
+				&nonce.into().to_bytes().into(),
+				Payload {
+					msg: plaintext,
+					aad,
+				},
+			)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt`, returning
+	/// an Error if the tag does not match.
+	pub fn decrypt(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		ciphertext: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		self.cipher()
+			.decrypt(
+				&nonce.into().to_bytes().into(),
+				Payload {
+					msg: ciphertext,
+					aad,
+				},
+			)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the buffer in place and appends the tag.
+	///
+	/// Doesn't allocate if the buffer has capacity for `TAG_LEN` more
+	/// bytes.
+	pub fn encrypt_in_place(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		buffer: &mut Vec<u8>,
+	) {
+		self.cipher()
+			.encrypt_in_place(&nonce.into().to_bytes().into(), aad, buffer)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+	}
+
+	/// Decrypts a buffer which was encrypted with `encrypt_in_place` and
+	/// removes the tag.
+	///
+	/// If the tag does not match the buffer is left unchanged.
+	pub fn decrypt_in_place(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		buffer: &mut Vec<u8>,
+	) -> Result<(), MacNotEqual> {
+		self.cipher()
+			.decrypt_in_place(&nonce.into().to_bytes().into(), aad, buffer)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the buffer in place returning the tag.
+	pub fn encrypt_in_place_detached(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		buffer: &mut [u8],
+	) -> [u8; 16] {
+		self.cipher()
+			.encrypt_in_place_detached(
+				&nonce.into().to_bytes().into(),
+				aad,
+				buffer,
+			)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+			.into()
+	}
+
+	/// Decrypts a buffer which was encrypted with
+	/// `encrypt_in_place_detached`.
+	///
+	/// If the tag does not match the buffer is left unchanged.
+	pub fn decrypt_in_place_detached(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		buffer: &mut [u8],
+		tag: &[u8; 16],
+	) -> Result<(), MacNotEqual> {
+		self.cipher()
+			.decrypt_in_place_detached(
+				&nonce.into().to_bytes().into(),
+				aad,
+				buffer,
+				tag.into(),
+			)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the plaintext returning the ciphertext and the tag
+	/// separately.
+	pub fn encrypt_detached(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> (Vec<u8>, [u8; 16]) {
+		let mut ciphertext = plaintext.to_vec();
+		let tag = self.encrypt_in_place_detached(nonce, aad, &mut ciphertext);
+
+		(ciphertext, tag)
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt_detached`,
+	/// returning an Error if the tag does not match.
+	pub fn decrypt_detached(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		ciphertext: &[u8],
+		tag: &[u8; 16],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		let mut plaintext = ciphertext.to_vec();
+		self.decrypt_in_place_detached(nonce, aad, &mut plaintext, tag)?;
+
+		Ok(plaintext)
+	}
+}
+
+impl fmt::Debug for Aead {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Aead")
+	}
+}
+
+impl From<[u8; 32]> for Aead {
+	fn from(key: [u8; 32]) -> Self {
+		Self { key }
+	}
+}
+
+impl TryFrom<&[u8]> for Aead {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; 32]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from)
+	}
+}
+
+impl Drop for Aead {
+	fn drop(&mut self) {
+		self.key.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Aead {}
+
+/// An authenticated cipher using XChaCha20-Poly1305.
+///
+/// The nonce is 24 bytes long and can therefore safely be generated randomly,
+/// which is what `seal` and `open` do.
+#[derive(Clone)]
+pub struct XAead {
+	key: [u8; 32],
+}
+
+impl XAead {
+	pub const LEN: usize = 32;
+
+	pub const TAG_LEN: usize = 16;
+
+	/// Creates a new random key.
+	pub fn new() -> Self {
+		let mut key = [0u8; 32];
+		fill_random(&mut key);
+
+		Self { key }
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new random key using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut key = [0u8; 32];
+		rng.fill_bytes(&mut key);
+
+		Self { key }
+	}
+
+	/// Derives a key from a password using Argon2id.
+	///
+	/// Returns the key and the `KeyParams` which need to be stored to
+	/// derive the same key again with `from_password_params`.
+	#[cfg(feature = "password")]
+	pub fn from_password(
+		password: impl AsRef<[u8]>,
+		salt: &[u8],
+		params: &Params,
+	) -> Result<(Self, KeyParams), PasswordError> {
+		let key_params = KeyParams::new(salt, params)?;
+		let key = Self::from_password_params(password, &key_params)?;
+
+		Ok((key, key_params))
+	}
+
+	/// Derives a key from a password with the salt and parameters returned
+	/// by `from_password`.
+	#[cfg(feature = "password")]
+	pub fn from_password_params(
+		password: impl AsRef<[u8]>,
+		params: &KeyParams,
+	) -> Result<Self, PasswordError> {
+		let mut key = [0u8; 32];
+		params.derive_key(password.as_ref(), &mut key)?;
+
+		Ok(Self { key })
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.key
+	}
+
+	pub(super) fn cipher(&self) -> XChaCha20Poly1305 {
+		XChaCha20Poly1305::new(self.key.as_slice().into())
+	}
+
+	/// Encrypts the plaintext returning the ciphertext with the tag appended.
+	///
+	/// ## Warning
+	/// Never use the same nonce twice with the same key.
+	pub fn encrypt(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> Vec<u8> {
+		self.cipher()
+			.encrypt(
+				nonce.as_ref().into(),
+				Payload {
+					msg: plaintext,
+					aad,
+				},
+			)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt`, returning
+	/// an Error if the tag does not match.
+	pub fn decrypt(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		ciphertext: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		self.cipher()
+			.decrypt(
+				nonce.as_ref().into(),
+				Payload {
+					msg: ciphertext,
+					aad,
+				},
+			)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the buffer in place and appends the tag.
+	///
+	/// Doesn't allocate if the buffer has capacity for `TAG_LEN` more
+	/// bytes.
+	///
+	/// ## Warning
+	/// Never use the same nonce twice with the same key.
+	pub fn encrypt_in_place(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		buffer: &mut Vec<u8>,
+	) {
+		self.cipher()
+			.encrypt_in_place(nonce.as_ref().into(), aad, buffer)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+	}
+
+	/// Decrypts a buffer which was encrypted with `encrypt_in_place` and
+	/// removes the tag.
+	///
+	/// If the tag does not match the buffer is left unchanged.
+	pub fn decrypt_in_place(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		buffer: &mut Vec<u8>,
+	) -> Result<(), MacNotEqual> {
+		self.cipher()
+			.decrypt_in_place(nonce.as_ref().into(), aad, buffer)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the buffer in place returning the tag.
+	///
+	/// ## Warning
+	/// Never use the same nonce twice with the same key.
+	pub fn encrypt_in_place_detached(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		buffer: &mut [u8],
+	) -> [u8; 16] {
+		self.cipher()
+			.encrypt_in_place_detached(nonce.as_ref().into(), aad, buffer)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+			.into()
+	}
+
+	/// Decrypts a buffer which was encrypted with
+	/// `encrypt_in_place_detached`.
+	///
+	/// If the tag does not match the buffer is left unchanged.
+	pub fn decrypt_in_place_detached(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		buffer: &mut [u8],
+		tag: &[u8; 16],
+	) -> Result<(), MacNotEqual> {
+		self.cipher()
+			.decrypt_in_place_detached(
+				nonce.as_ref().into(),
+				aad,
+				buffer,
+				tag.into(),
+			)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the plaintext returning the ciphertext and the tag
+	/// separately.
+	pub fn encrypt_detached(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> (Vec<u8>, [u8; 16]) {
+		let mut ciphertext = plaintext.to_vec();
+		let tag = self.encrypt_in_place_detached(nonce, aad, &mut ciphertext);
+
+		(ciphertext, tag)
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt_detached`,
+	/// returning an Error if the tag does not match.
+	pub fn decrypt_detached(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		ciphertext: &[u8],
+		tag: &[u8; 16],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		let mut plaintext = ciphertext.to_vec();
+		self.decrypt_in_place_detached(nonce, aad, &mut plaintext, tag)?;
+
+		Ok(plaintext)
+	}
+
+	/// Encrypts the plaintext with a random nonce.
+	///
+	/// Returns the nonce followed by the ciphertext and the tag.
+	pub fn seal(&self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+		let nonce = Nonce::new();
+		let ciphertext = self.encrypt(&nonce, aad, plaintext);
+
+		let mut sealed = Vec::with_capacity(Nonce::LEN + ciphertext.len());
+		sealed.extend_from_slice(nonce.as_ref());
+		sealed.extend_from_slice(&ciphertext);
+
+		sealed
+	}
+
+	/// Opens a message which was created with `seal`.
+	pub fn open(
+		&self,
+		aad: &[u8],
+		sealed: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		if sealed.len() < Nonce::LEN + Self::TAG_LEN {
+			return Err(MacNotEqual);
+		}
+
+		let (nonce, ciphertext) = sealed.split_at(Nonce::LEN);
+		let nonce = Nonce::try_from(nonce).unwrap();
+
+		self.decrypt(&nonce, aad, ciphertext)
+	}
+
+	/// Like `seal` but pads the plaintext first to hide its length.
+	///
+	/// See [`padding`](super::padding).
+	pub fn seal_padded(&self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+		let mut padded = padding::pad(plaintext);
+		let sealed = self.seal(aad, &padded);
+		padded.zeroize();
+
+		sealed
+	}
+
+	/// Opens a message which was created with `seal_padded`.
+	pub fn open_padded(
+		&self,
+		aad: &[u8],
+		sealed: &[u8],
+	) -> Result<Vec<u8>, DecryptError> {
+		let mut plaintext = self
+			.open(aad, sealed)
+			.map_err(|_| DecryptError::MacNotEqual)?;
+		padding::unpad_in_place(&mut plaintext)?;
+
+		Ok(plaintext)
+	}
+
+	/// Encrypts a message with a random nonce returning a `Ciphertext`
+	/// which contains the nonce.
+	pub fn seal_ciphertext(&self, aad: &[u8], plaintext: &[u8]) -> Ciphertext {
+		let nonce = Nonce::new();
+		let ciphertext = self.encrypt(&nonce, aad, plaintext);
+
+		Ciphertext::new(&nonce, &ciphertext)
+	}
+
+	/// Opens a `Ciphertext` which was created with `seal_ciphertext`.
+	pub fn open_ciphertext(
+		&self,
+		aad: &[u8],
+		ciphertext: &Ciphertext,
+	) -> Result<Vec<u8>, MacNotEqual> {
+		self.decrypt(&ciphertext.nonce(), aad, ciphertext.ciphertext_and_tag())
+	}
+
+	/// Encrypts this key with the key encryption key `kek`.
+	///
+	/// See [`WrappedKey`].
+	pub fn wrap(&self, kek: &XAead) -> WrappedKey {
+		WrappedKey::new(self, kek)
+	}
+}
+
+impl fmt::Debug for XAead {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("XAead")
+	}
+}
+
+impl From<[u8; 32]> for XAead {
+	fn from(key: [u8; 32]) -> Self {
+		Self { key }
+	}
+}
+
+impl TryFrom<&[u8]> for XAead {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; 32]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from)
+	}
+}
+
+impl Drop for XAead {
+	fn drop(&mut self) {
+		self.key.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for XAead {}
@@ -0,0 +1,238 @@
+use super::{MacNotEqual, Nonce12};
+use crate::error::{RandomError, TryFromError};
+use crate::fill_random;
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use zeroize::Zeroize;
+
+use aes_gcm::aead::{Aead as _, AeadInPlace, KeyInit, Payload};
+
+/// An authenticated cipher using AES-256-GCM, for when AES is required
+/// instead of ChaCha20.
+///
+/// The AES-NI, ARMv8 and CLMUL instructions are detected at runtime and
+/// used where available, otherwise a constant time software
+/// implementation is used.
+///
+/// ## Warning
+/// Never use the same nonce twice with the same key. Random 12 byte
+/// nonces should not be used for more than 2^32 messages.
+#[derive(Clone)]
+pub struct Aes256Gcm {
+	key: [u8; 32],
+}
+
+impl Aes256Gcm {
+	pub const LEN: usize = 32;
+
+	pub const NONCE_LEN: usize = 12;
+
+	pub const TAG_LEN: usize = 16;
+
+	/// Creates a new random key.
+	pub fn new() -> Self {
+		let mut key = [0u8; 32];
+		fill_random(&mut key);
+
+		Self { key }
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new random key using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut key = [0u8; 32];
+		rng.fill_bytes(&mut key);
+
+		Self { key }
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.key
+	}
+
+	fn cipher(&self) -> aes_gcm::Aes256Gcm {
+		aes_gcm::Aes256Gcm::new(self.key.as_slice().into())
+	}
+
+	/// Encrypts the plaintext returning the ciphertext with the tag appended.
+	pub fn encrypt(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> Vec<u8> {
+		self.cipher()
+			.encrypt(
+				&nonce.into().to_bytes().into(),
+				Payload {
+					msg: plaintext,
+					aad,
+				},
+			)
+			// only fails if the plaintext is longer than 64GiB
+			.expect("plaintext too long")
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt`, returning
+	/// an Error if the tag does not match.
+	pub fn decrypt(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		ciphertext: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		self.cipher()
+			.decrypt(
+				&nonce.into().to_bytes().into(),
+				Payload {
+					msg: ciphertext,
+					aad,
+				},
+			)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the buffer in place and appends the tag.
+	///
+	/// Doesn't allocate if the buffer has capacity for `TAG_LEN` more
+	/// bytes.
+	pub fn encrypt_in_place(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		buffer: &mut Vec<u8>,
+	) {
+		self.cipher()
+			.encrypt_in_place(&nonce.into().to_bytes().into(), aad, buffer)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+	}
+
+	/// Decrypts a buffer which was encrypted with `encrypt_in_place` and
+	/// removes the tag.
+	///
+	/// If the tag does not match the buffer is left unchanged.
+	pub fn decrypt_in_place(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		buffer: &mut Vec<u8>,
+	) -> Result<(), MacNotEqual> {
+		self.cipher()
+			.decrypt_in_place(&nonce.into().to_bytes().into(), aad, buffer)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the buffer in place returning the tag.
+	pub fn encrypt_in_place_detached(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		buffer: &mut [u8],
+	) -> [u8; 16] {
+		self.cipher()
+			.encrypt_in_place_detached(
+				&nonce.into().to_bytes().into(),
+				aad,
+				buffer,
+			)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+			.into()
+	}
+
+	/// Decrypts a buffer which was encrypted with
+	/// `encrypt_in_place_detached`.
+	///
+	/// If the tag does not match the buffer is left unchanged.
+	pub fn decrypt_in_place_detached(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		buffer: &mut [u8],
+		tag: &[u8; 16],
+	) -> Result<(), MacNotEqual> {
+		self.cipher()
+			.decrypt_in_place_detached(
+				&nonce.into().to_bytes().into(),
+				aad,
+				buffer,
+				tag.into(),
+			)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the plaintext returning the ciphertext and the tag
+	/// separately.
+	pub fn encrypt_detached(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> (Vec<u8>, [u8; 16]) {
+		let mut ciphertext = plaintext.to_vec();
+		let tag = self.encrypt_in_place_detached(nonce, aad, &mut ciphertext);
+
+		(ciphertext, tag)
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt_detached`,
+	/// returning an Error if the tag does not match.
+	pub fn decrypt_detached(
+		&self,
+		nonce: impl Into<Nonce12>,
+		aad: &[u8],
+		ciphertext: &[u8],
+		tag: &[u8; 16],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		let mut plaintext = ciphertext.to_vec();
+		self.decrypt_in_place_detached(nonce, aad, &mut plaintext, tag)?;
+
+		Ok(plaintext)
+	}
+}
+
+impl fmt::Debug for Aes256Gcm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Aes256Gcm")
+	}
+}
+
+impl From<[u8; 32]> for Aes256Gcm {
+	fn from(key: [u8; 32]) -> Self {
+		Self { key }
+	}
+}
+
+impl TryFrom<&[u8]> for Aes256Gcm {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; 32]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from)
+	}
+}
+
+impl Drop for Aes256Gcm {
+	fn drop(&mut self) {
+		self.key.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Aes256Gcm {}
@@ -0,0 +1,571 @@
+//! Encryption in the [age](https://age-encryption.org/v1) v1 format.
+//!
+//! Files encrypted here can be decrypted with the `age` and `rage`
+//! command line tools and the other way around. X25519 recipients use the
+//! crate's [`Keypair`] and [`PublicKey`], which can be converted to and
+//! from the `AGE-SECRET-KEY-1...` and `age1...` strings age uses.
+//! Passphrases are supported with scrypt recipients.
+//!
+//! The armored (PEM like) format and plugin recipients are not
+//! supported.
+//!
+//! The whole file is encrypted and decrypted in memory, there are no
+//! `Read` or `Write` adapters. For large files use the
+//! [stream](super::stream) cipher instead, which is not compatible with
+//! age.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::{age, Keypair};
+//!
+//! let identity = Keypair::new();
+//! let recipient = age::recipient_to_string(identity.public());
+//! assert!(recipient.starts_with("age1"));
+//!
+//! let recipient = age::recipient_from_str(&recipient).unwrap();
+//! let encrypted = age::encrypt(&[recipient], b"backup").unwrap();
+//!
+//! let decrypted = age::decrypt(&identity, &encrypted).unwrap();
+//! assert_eq!(decrypted, b"backup");
+//! ```
+
+use super::{Aead, Keypair, Nonce12, PublicKey};
+use crate::error::{AgeError, DecodeError};
+use crate::fill_random;
+
+use std::convert::TryInto;
+
+use zeroize::Zeroize;
+
+use subtle::ConstantTimeEq;
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use base64::engine::{general_purpose::STANDARD_NO_PAD, Engine};
+
+use bech32::{FromBase32, ToBase32, Variant};
+
+/// The scrypt work factor (log2 of N) used by the age command line tool.
+pub const DEFAULT_WORK_FACTOR: u8 = 18;
+
+/// The highest scrypt work factor which is accepted when decrypting.
+pub const MAX_WORK_FACTOR: u8 = 22;
+
+const VERSION_LINE: &[u8] = b"age-encryption.org/v1";
+const CHUNK_SIZE: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+const FILE_KEY_LEN: usize = 16;
+const NONCE_LEN: usize = 16;
+const COLUMNS: usize = 64;
+
+const X25519_LABEL: &[u8] = b"age-encryption.org/v1/X25519";
+const SCRYPT_LABEL: &[u8] = b"age-encryption.org/v1/scrypt";
+
+/// Returns the `age1...` string of a public key.
+pub fn recipient_to_string(public_key: &PublicKey) -> String {
+	bech32::encode("age", public_key.to_bytes().to_base32(), Variant::Bech32)
+		// the hrp is valid and the string is short enough
+		.unwrap()
+}
+
+/// Parses an `age1...` recipient.
+pub fn recipient_from_str(s: &str) -> Result<PublicKey, DecodeError> {
+	decode_bech32("age", s).map(PublicKey::from)
+}
+
+/// Returns the `AGE-SECRET-KEY-1...` string of a keypair.
+pub fn identity_to_string(keypair: &Keypair) -> String {
+	let mut secret = keypair.to_bytes();
+	let s =
+		bech32::encode("age-secret-key-", secret.to_base32(), Variant::Bech32)
+			// the hrp is valid and the string is short enough
+			.unwrap()
+			.to_uppercase();
+	secret.zeroize();
+
+	s
+}
+
+/// Parses an `AGE-SECRET-KEY-1...` identity.
+pub fn identity_from_str(s: &str) -> Result<Keypair, DecodeError> {
+	let mut secret = decode_bech32("age-secret-key-", s)?;
+	let keypair = Keypair::from(secret);
+	secret.zeroize();
+
+	Ok(keypair)
+}
+
+fn decode_bech32(hrp: &str, s: &str) -> Result<[u8; 32], DecodeError> {
+	let (s_hrp, data, variant) =
+		bech32::decode(s).map_err(|_| DecodeError::InvalidBytes)?;
+	if !s_hrp.eq_ignore_ascii_case(hrp) || variant != Variant::Bech32 {
+		return Err(DecodeError::InvalidBytes);
+	}
+
+	let mut bytes =
+		Vec::<u8>::from_base32(&data).map_err(|_| DecodeError::InvalidBytes)?;
+	let r = bytes
+		.as_slice()
+		.try_into()
+		.map_err(|_| DecodeError::InvalidLength);
+	bytes.zeroize();
+
+	r
+}
+
+/// Encrypts the plaintext to one or more X25519 recipients.
+///
+/// Returns `AgeError::NoRecipients` if the list is empty or
+/// `AgeError::InvalidPublicKey` if a public key has a low order.
+pub fn encrypt(
+	recipients: &[PublicKey],
+	plaintext: &[u8],
+) -> Result<Vec<u8>, AgeError> {
+	if recipients.is_empty() {
+		return Err(AgeError::NoRecipients);
+	}
+
+	let mut file_key = [0u8; FILE_KEY_LEN];
+	fill_random(&mut file_key);
+
+	let mut header = VERSION_LINE.to_vec();
+	header.push(b'\n');
+
+	for recipient in recipients {
+		let ephemeral = Keypair::new();
+		let share = ephemeral.public().to_bytes();
+		let shared = ephemeral.diffie_hellman(recipient);
+		if !shared.was_contributory() {
+			file_key.zeroize();
+			return Err(AgeError::InvalidPublicKey);
+		}
+
+		let mut salt = [0u8; 64];
+		salt[..32].copy_from_slice(&share);
+		salt[32..].copy_from_slice(&recipient.to_bytes());
+
+		let body =
+			wrap(&hkdf(&salt, shared.as_slice(), X25519_LABEL), &file_key);
+		write_stanza(&mut header, "X25519", &[&b64(&share)], &body);
+	}
+
+	let r = finish(&mut header, &file_key, plaintext);
+	file_key.zeroize();
+
+	Ok(r)
+}
+
+/// Encrypts the plaintext with a passphrase.
+///
+/// `work_factor` is log2 of the scrypt parameter N, use
+/// [`DEFAULT_WORK_FACTOR`] unless the passphrase is needed very often.
+/// Returns `AgeError::WorkFactorTooHigh` if it is not between 1 and
+/// [`MAX_WORK_FACTOR`].
+pub fn encrypt_with_passphrase(
+	passphrase: &str,
+	work_factor: u8,
+	plaintext: &[u8],
+) -> Result<Vec<u8>, AgeError> {
+	let mut salt = [0u8; 16];
+	fill_random(&mut salt);
+	let mut key = scrypt_key(passphrase, &salt, work_factor)?;
+
+	let mut file_key = [0u8; FILE_KEY_LEN];
+	fill_random(&mut file_key);
+
+	let mut header = VERSION_LINE.to_vec();
+	header.push(b'\n');
+	let body = wrap(&key, &file_key);
+	key.zeroize();
+	write_stanza(
+		&mut header,
+		"scrypt",
+		&[&b64(&salt), &work_factor.to_string()],
+		&body,
+	);
+
+	let r = finish(&mut header, &file_key, plaintext);
+	file_key.zeroize();
+
+	Ok(r)
+}
+
+/// Decrypts a file which was encrypted to the public key of the keypair.
+pub fn decrypt(identity: &Keypair, data: &[u8]) -> Result<Vec<u8>, AgeError> {
+	let header = Header::parse(data)?;
+	// a scrypt stanza is only allowed alone, so it can't be combined with
+	// an attackers recipient
+	if header.stanzas.iter().any(|s| s.tag == "scrypt") {
+		return Err(AgeError::InvalidFormat);
+	}
+
+	let public = identity.public().to_bytes();
+	let mut file_key = None;
+	for stanza in header.stanzas.iter().filter(|s| s.tag == "X25519") {
+		let [share] = stanza.args.as_slice() else {
+			return Err(AgeError::InvalidFormat);
+		};
+		let share: [u8; 32] = decode_b64(share)?
+			.try_into()
+			.map_err(|_| AgeError::InvalidFormat)?;
+		if stanza.body.len() != FILE_KEY_LEN + TAG_LEN {
+			return Err(AgeError::InvalidFormat);
+		}
+
+		let shared = identity.diffie_hellman(&PublicKey::from(share));
+		if !shared.was_contributory() {
+			return Err(AgeError::InvalidFormat);
+		}
+
+		let mut salt = [0u8; 64];
+		salt[..32].copy_from_slice(&share);
+		salt[32..].copy_from_slice(&public);
+
+		let key = hkdf(&salt, shared.as_slice(), X25519_LABEL);
+		if let Some(key) = unwrap(&key, &stanza.body) {
+			file_key = Some(key);
+			break;
+		}
+	}
+
+	let mut file_key = file_key.ok_or(AgeError::NoMatchingKey)?;
+	let r = header.decrypt(&file_key);
+	file_key.zeroize();
+
+	r
+}
+
+/// Decrypts a file which was encrypted with a passphrase.
+///
+/// Returns `AgeError::WorkFactorTooHigh` if the file uses a work factor
+/// above [`MAX_WORK_FACTOR`].
+pub fn decrypt_with_passphrase(
+	passphrase: &str,
+	data: &[u8],
+) -> Result<Vec<u8>, AgeError> {
+	let header = Header::parse(data)?;
+	let Some(stanza) = header.stanzas.iter().find(|s| s.tag == "scrypt") else {
+		return Err(AgeError::NoMatchingKey);
+	};
+	// a scrypt stanza is only allowed alone
+	if header.stanzas.len() != 1 {
+		return Err(AgeError::InvalidFormat);
+	}
+	let [salt, work_factor] = stanza.args.as_slice() else {
+		return Err(AgeError::InvalidFormat);
+	};
+
+	let salt: [u8; 16] = decode_b64(salt)?
+		.try_into()
+		.map_err(|_| AgeError::InvalidFormat)?;
+	// only canonical decimal numbers are allowed
+	if work_factor.starts_with('0')
+		|| !work_factor.bytes().all(|b| b.is_ascii_digit())
+	{
+		return Err(AgeError::InvalidFormat);
+	}
+	let work_factor = work_factor
+		.parse()
+		.map_err(|_| AgeError::WorkFactorTooHigh)?;
+	if stanza.body.len() != FILE_KEY_LEN + TAG_LEN {
+		return Err(AgeError::InvalidFormat);
+	}
+
+	let mut key = scrypt_key(passphrase, &salt, work_factor)?;
+	let file_key = unwrap(&key, &stanza.body);
+	key.zeroize();
+
+	let mut file_key = file_key.ok_or(AgeError::NoMatchingKey)?;
+	let r = header.decrypt(&file_key);
+	file_key.zeroize();
+
+	r
+}
+
+fn scrypt_key(
+	passphrase: &str,
+	salt: &[u8; 16],
+	work_factor: u8,
+) -> Result<[u8; 32], AgeError> {
+	if !(1..=MAX_WORK_FACTOR).contains(&work_factor) {
+		return Err(AgeError::WorkFactorTooHigh);
+	}
+
+	let mut full_salt = SCRYPT_LABEL.to_vec();
+	full_salt.extend_from_slice(salt);
+
+	let params = scrypt::Params::new(work_factor, 8, 1, 32)
+		// the work factor was checked above
+		.unwrap();
+	let mut key = [0u8; 32];
+	scrypt::scrypt(passphrase.as_bytes(), &full_salt, &params, &mut key)
+		// the output length is valid
+		.unwrap();
+
+	Ok(key)
+}
+
+fn hkdf(salt: &[u8], ikm: &[u8], info: &[u8]) -> [u8; 32] {
+	let mut key = [0u8; 32];
+	Hkdf::<Sha256>::new(Some(salt), ikm)
+		.expand(info, &mut key)
+		// 32 bytes is way below the max size of 255 * 32
+		.unwrap();
+
+	key
+}
+
+fn hmac(key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+	let mut hmac = Hmac::<Sha256>::new_from_slice(key)
+		// hmac accepts keys of any length
+		.unwrap();
+	hmac.update(data);
+
+	hmac.finalize().into_bytes().into()
+}
+
+fn wrap(key: &[u8; 32], file_key: &[u8; FILE_KEY_LEN]) -> Vec<u8> {
+	Aead::from(*key).encrypt(Nonce12::from([0; 12]), b"", file_key)
+}
+
+fn unwrap(key: &[u8; 32], body: &[u8]) -> Option<[u8; FILE_KEY_LEN]> {
+	let mut file_key = Aead::from(*key)
+		.decrypt(Nonce12::from([0; 12]), b"", body)
+		.ok()?;
+	let r = file_key.as_slice().try_into().ok();
+	file_key.zeroize();
+
+	r
+}
+
+fn b64(bytes: &[u8]) -> String {
+	STANDARD_NO_PAD.encode(bytes)
+}
+
+fn decode_b64(s: &str) -> Result<Vec<u8>, AgeError> {
+	// the engine rejects padding and non zero trailing bits
+	STANDARD_NO_PAD
+		.decode(s)
+		.map_err(|_| AgeError::InvalidFormat)
+}
+
+fn write_stanza(out: &mut Vec<u8>, tag: &str, args: &[&str], body: &[u8]) {
+	out.extend_from_slice(b"-> ");
+	out.extend_from_slice(tag.as_bytes());
+	for arg in args {
+		out.push(b' ');
+		out.extend_from_slice(arg.as_bytes());
+	}
+	out.push(b'\n');
+
+	let body = b64(body);
+	let mut lines = body.as_bytes().chunks(COLUMNS);
+	for line in lines.by_ref() {
+		out.extend_from_slice(line);
+		out.push(b'\n');
+	}
+	// the body ends with a line shorter than 64 columns
+	if body.len() % COLUMNS == 0 {
+		out.push(b'\n');
+	}
+}
+
+fn payload_nonce(counter: u64, last: bool) -> Nonce12 {
+	let mut nonce = [0u8; 12];
+	nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+	nonce[11] = last as u8;
+
+	nonce.into()
+}
+
+/// Adds the mac to the header and appends the encrypted payload.
+fn finish(
+	header: &mut Vec<u8>,
+	file_key: &[u8; FILE_KEY_LEN],
+	plaintext: &[u8],
+) -> Vec<u8> {
+	header.extend_from_slice(b"---");
+	let mut mac_key = hkdf(b"", file_key, b"header");
+	let mac = hmac(&mac_key, header);
+	mac_key.zeroize();
+
+	let chunks = plaintext.len().max(1).div_ceil(CHUNK_SIZE);
+	let mut out = Vec::with_capacity(
+		header.len() + 45 + NONCE_LEN + plaintext.len() + chunks * TAG_LEN,
+	);
+	out.extend_from_slice(header);
+	out.push(b' ');
+	out.extend_from_slice(b64(&mac).as_bytes());
+	out.push(b'\n');
+
+	let mut nonce = [0u8; NONCE_LEN];
+	fill_random(&mut nonce);
+	out.extend_from_slice(&nonce);
+
+	let aead = Aead::from(hkdf(&nonce, file_key, b"payload"));
+	if plaintext.is_empty() {
+		out.extend_from_slice(&aead.encrypt(payload_nonce(0, true), b"", b""));
+	}
+	for (i, chunk) in plaintext.chunks(CHUNK_SIZE).enumerate() {
+		let nonce = payload_nonce(i as u64, i == chunks - 1);
+		let start = out.len();
+		out.extend_from_slice(chunk);
+		let tag = aead.encrypt_in_place_detached(nonce, b"", &mut out[start..]);
+		out.extend_from_slice(&tag);
+	}
+
+	out
+}
+
+struct Stanza {
+	tag: String,
+	args: Vec<String>,
+	body: Vec<u8>,
+}
+
+struct Header<'a> {
+	stanzas: Vec<Stanza>,
+	/// the header up to and including `---`
+	mac_input: &'a [u8],
+	mac: [u8; 32],
+	payload: &'a [u8],
+}
+
+impl<'a> Header<'a> {
+	fn parse(data: &'a [u8]) -> Result<Self, AgeError> {
+		let mut rest = data;
+
+		if next_line(&mut rest)? != VERSION_LINE {
+			return Err(AgeError::InvalidFormat);
+		}
+
+		let mut stanzas = vec![];
+		loop {
+			let line_start = data.len() - rest.len();
+			let line = next_line(&mut rest)?;
+
+			if let Some(mac) = line.strip_prefix(b"--- ") {
+				// the line was checked to be printable ascii
+				let mac = std::str::from_utf8(mac).unwrap();
+				let mac = decode_b64(mac)?
+					.try_into()
+					.map_err(|_| AgeError::InvalidFormat)?;
+
+				return Ok(Self {
+					stanzas,
+					mac_input: &data[..line_start + 3],
+					mac,
+					payload: rest,
+				});
+			}
+
+			let Some(args) = line.strip_prefix(b"-> ") else {
+				return Err(AgeError::InvalidFormat);
+			};
+			let mut args = std::str::from_utf8(args)
+				.unwrap()
+				.split(' ')
+				.map(|a| {
+					if a.is_empty() {
+						Err(AgeError::InvalidFormat)
+					} else {
+						Ok(a.to_string())
+					}
+				})
+				.collect::<Result<Vec<_>, _>>()?;
+			let tag = args.remove(0);
+
+			let mut body = String::new();
+			loop {
+				let line = next_line(&mut rest)?;
+				if line.len() > COLUMNS {
+					return Err(AgeError::InvalidFormat);
+				}
+				body.push_str(std::str::from_utf8(line).unwrap());
+				if line.len() < COLUMNS {
+					break;
+				}
+			}
+
+			stanzas.push(Stanza {
+				tag,
+				args,
+				body: decode_b64(&body)?,
+			});
+		}
+	}
+
+	fn decrypt(
+		&self,
+		file_key: &[u8; FILE_KEY_LEN],
+	) -> Result<Vec<u8>, AgeError> {
+		let mut mac_key = hkdf(b"", file_key, b"header");
+		let mac = hmac(&mac_key, self.mac_input);
+		mac_key.zeroize();
+		if !bool::from(mac.ct_eq(&self.mac)) {
+			return Err(AgeError::MacNotEqual);
+		}
+
+		if self.payload.len() < NONCE_LEN {
+			return Err(AgeError::InvalidFormat);
+		}
+		let (nonce, mut rest) = self.payload.split_at(NONCE_LEN);
+		let aead = Aead::from(hkdf(nonce, file_key, b"payload"));
+
+		let mut plaintext = Vec::with_capacity(rest.len());
+		let mut counter = 0u64;
+		loop {
+			let last = rest.len() <= CHUNK_SIZE + TAG_LEN;
+			let len = if last {
+				rest.len()
+			} else {
+				CHUNK_SIZE + TAG_LEN
+			};
+			if len < TAG_LEN {
+				return Err(AgeError::InvalidFormat);
+			}
+			let (chunk, next) = rest.split_at(len);
+			rest = next;
+
+			let (chunk, tag) = chunk.split_at(len - TAG_LEN);
+			// only an empty file has an empty chunk
+			if last && chunk.is_empty() && counter != 0 {
+				return Err(AgeError::InvalidFormat);
+			}
+
+			let start = plaintext.len();
+			plaintext.extend_from_slice(chunk);
+			aead.decrypt_in_place_detached(
+				payload_nonce(counter, last),
+				b"",
+				&mut plaintext[start..],
+				tag.try_into().unwrap(),
+			)
+			.map_err(|_| AgeError::MacNotEqual)?;
+
+			if last {
+				return Ok(plaintext);
+			}
+			counter += 1;
+		}
+	}
+}
+
+/// Returns the next line without the newline, only printable ascii is
+/// allowed.
+fn next_line<'a>(rest: &mut &'a [u8]) -> Result<&'a [u8], AgeError> {
+	let pos = rest
+		.iter()
+		.position(|b| *b == b'\n')
+		.ok_or(AgeError::InvalidFormat)?;
+	let line = &rest[..pos];
+	*rest = &rest[pos + 1..];
+
+	if line.iter().all(|b| (32..=126).contains(b)) {
+		Ok(line)
+	} else {
+		Err(AgeError::InvalidFormat)
+	}
+}
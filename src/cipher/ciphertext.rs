@@ -0,0 +1,298 @@
+use super::{Nonce, XAead};
+use crate::error::{DecodeError, TryFromError};
+
+use std::convert::TryFrom;
+use std::fmt;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// An encrypted message created with `XAead::seal_ciphertext`.
+///
+/// Keeps the nonce together with the ciphertext and the tag so they
+/// can't be stored or sent separately by accident.
+///
+/// ## Layout
+/// `version (1 byte) | nonce (24 bytes) | ciphertext | tag (16 bytes)`
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Ciphertext {
+	bytes: Vec<u8>,
+}
+
+impl Ciphertext {
+	/// The current version of the binary layout.
+	pub const VERSION: u8 = 1;
+
+	/// The amount of bytes a ciphertext is longer than the plaintext.
+	pub const OVERHEAD: usize = 1 + Nonce::LEN + XAead::TAG_LEN;
+
+	pub(crate) fn new(nonce: &Nonce, ciphertext_and_tag: &[u8]) -> Self {
+		let mut bytes =
+			Vec::with_capacity(1 + Nonce::LEN + ciphertext_and_tag.len());
+		bytes.push(Self::VERSION);
+		bytes.extend_from_slice(nonce.as_ref());
+		bytes.extend_from_slice(ciphertext_and_tag);
+
+		Self { bytes }
+	}
+
+	/// Parses a ciphertext returning an error if the version is unknown or
+	/// the slice is too short.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+		if bytes.len() < Self::OVERHEAD {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		if bytes[0] != Self::VERSION {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		Ok(Self {
+			bytes: bytes.to_vec(),
+		})
+	}
+
+	pub fn version(&self) -> u8 {
+		self.bytes[0]
+	}
+
+	pub fn nonce(&self) -> Nonce {
+		Nonce::try_from(&self.bytes[1..1 + Nonce::LEN]).unwrap()
+	}
+
+	/// Returns the encrypted message without the tag.
+	pub fn ciphertext(&self) -> &[u8] {
+		&self.bytes[1 + Nonce::LEN..self.bytes.len() - XAead::TAG_LEN]
+	}
+
+	pub fn tag(&self) -> &[u8] {
+		&self.bytes[self.bytes.len() - XAead::TAG_LEN..]
+	}
+
+	/// Returns the encrypted message with the tag appended.
+	pub(crate) fn ciphertext_and_tag(&self) -> &[u8] {
+		&self.bytes[1 + Nonce::LEN..]
+	}
+
+	/// Returns the length of the plaintext.
+	pub fn plaintext_len(&self) -> usize {
+		self.bytes.len() - Self::OVERHEAD
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		self.bytes.clone()
+	}
+
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Ciphertext").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Ciphertext")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for Ciphertext {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		Self::from_bytes(v).map_err(TryFromError::from_any)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Ciphertext {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let bytes =
+			URL_SAFE_NO_PAD.decode(s).map_err(DecodeError::inv_bytes)?;
+		Self::from_bytes(&bytes)
+	}
+}
+
+impl AsRef<[u8]> for Ciphertext {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Ciphertext {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Ciphertext {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::from_bytes(&bytes).map_err(D::Error::custom)
+		}
+	}
+}
+
+#[cfg(feature = "protobuf")]
+mod impl_protobuf {
+	use super::*;
+
+	use protopuffer::{
+		bytes::BytesWrite,
+		decode::{DecodeMessage, FieldKind},
+		encode::{
+			EncodeError, EncodeMessage, FieldOpt, MessageEncoder, SizeBuilder,
+		},
+		WireType,
+	};
+
+	impl EncodeMessage for Ciphertext {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn is_default(&self) -> bool {
+			false
+		}
+
+		fn encoded_size(
+			&mut self,
+			field: Option<FieldOpt>,
+			builder: &mut SizeBuilder,
+		) -> Result<(), EncodeError> {
+			self.bytes.encoded_size(field, builder)
+		}
+
+		fn encode<B>(
+			&mut self,
+			field: Option<FieldOpt>,
+			encoder: &mut MessageEncoder<B>,
+		) -> Result<(), EncodeError>
+		where
+			B: BytesWrite,
+		{
+			self.bytes.encode(field, encoder)
+		}
+	}
+
+	impl<'m> DecodeMessage<'m> for Ciphertext {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn decode_default() -> Self {
+			let mut bytes = vec![0u8; Self::OVERHEAD];
+			bytes[0] = Self::VERSION;
+
+			Self { bytes }
+		}
+
+		fn merge(
+			&mut self,
+			kind: FieldKind<'m>,
+			_is_field: bool,
+		) -> Result<(), protopuffer::decode::DecodeError> {
+			let bytes = kind.try_unwrap_len()?;
+			*self = Self::from_bytes(bytes).map_err(|e| {
+				protopuffer::decode::DecodeError::Other(e.to_string())
+			})?;
+
+			Ok(())
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "postgres"))]
+mod impl_postgres {
+	use super::*;
+
+	use bytes::BytesMut;
+	use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+	impl ToSql for Ciphertext {
+		fn to_sql(
+			&self,
+			ty: &Type,
+			out: &mut BytesMut,
+		) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+		where
+			Self: Sized,
+		{
+			if *ty == Type::BYTEA {
+				return <&[u8] as ToSql>::to_sql(&self.as_ref(), ty, out);
+			}
+
+			self.to_string().to_sql(ty, out)
+		}
+
+		fn accepts(ty: &Type) -> bool
+		where
+			Self: Sized,
+		{
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
+		}
+
+		to_sql_checked!();
+	}
+
+	impl<'r> FromSql<'r> for Ciphertext {
+		fn from_sql(
+			ty: &Type,
+			raw: &'r [u8],
+		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Self::from_bytes(raw).map_err(Into::into);
+			}
+
+			let s = <&str as FromSql>::from_sql(ty, raw)?;
+			s.parse().map_err(Into::into)
+		}
+
+		fn accepts(ty: &Type) -> bool {
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+}
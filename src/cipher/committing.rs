@@ -0,0 +1,214 @@
+use super::{MacNotEqual, Nonce};
+use crate::error::{RandomError, TryFromError};
+use crate::fill_random;
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use zeroize::Zeroize;
+
+use subtle::ConstantTimeEq;
+
+use chacha20poly1305::aead::{Aead as _, Payload};
+use chacha20poly1305::{KeyInit, XChaCha20Poly1305};
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+/// A key committing authenticated cipher using XChaCha20-Poly1305.
+///
+/// Poly1305 is not key committing: a ciphertext can be crafted which
+/// decrypts successfully under many different keys, which enables
+/// partitioning oracle attacks when a server tries multiple keys (for
+/// example one per tenant). This cipher prevents that by deriving an
+/// encryption key and a 32 byte commitment from the key and the nonce
+/// with HKDF-SHA256. The commitment is prepended to the ciphertext and
+/// checked before decrypting, so a ciphertext only decrypts under the key
+/// which created it.
+///
+/// The output is 32 bytes longer than the one of `XAead`.
+#[derive(Clone)]
+pub struct CommittingAead {
+	key: [u8; 32],
+}
+
+impl CommittingAead {
+	pub const LEN: usize = 32;
+
+	pub const COMMITMENT_LEN: usize = 32;
+
+	pub const TAG_LEN: usize = 16;
+
+	/// Creates a new random key.
+	pub fn new() -> Self {
+		let mut key = [0u8; 32];
+		fill_random(&mut key);
+
+		Self { key }
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new random key using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut key = [0u8; 32];
+		rng.fill_bytes(&mut key);
+
+		Self { key }
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.key
+	}
+
+	/// Returns the cipher for the nonce and the commitment.
+	fn derive(&self, nonce: &Nonce) -> (XChaCha20Poly1305, [u8; 32]) {
+		let hkdf = Hkdf::<Sha256>::new(Some(nonce.as_ref()), &self.key);
+
+		let mut okm = [0u8; 64];
+		hkdf.expand(b"chuchi-crypto committing", &mut okm)
+			// 64 bytes is way below the max size of 255 * 32
+			.unwrap();
+
+		let cipher = XChaCha20Poly1305::new(okm[..32].into());
+		let mut commitment = [0u8; 32];
+		commitment.copy_from_slice(&okm[32..]);
+		okm.zeroize();
+
+		(cipher, commitment)
+	}
+
+	/// Encrypts the plaintext returning the commitment followed by the
+	/// ciphertext and the tag.
+	///
+	/// ## Warning
+	/// Never use the same nonce twice with the same key.
+	pub fn encrypt(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> Vec<u8> {
+		let (cipher, commitment) = self.derive(nonce);
+
+		let ciphertext = cipher
+			.encrypt(
+				nonce.as_ref().into(),
+				Payload {
+					msg: plaintext,
+					aad,
+				},
+			)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long");
+
+		let mut out = Vec::with_capacity(commitment.len() + ciphertext.len());
+		out.extend_from_slice(&commitment);
+		out.extend_from_slice(&ciphertext);
+
+		out
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt`, returning
+	/// an Error if the commitment or the tag does not match.
+	pub fn decrypt(
+		&self,
+		nonce: &Nonce,
+		aad: &[u8],
+		ciphertext: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		if ciphertext.len() < Self::COMMITMENT_LEN + Self::TAG_LEN {
+			return Err(MacNotEqual);
+		}
+
+		let (commitment, ciphertext) =
+			ciphertext.split_at(Self::COMMITMENT_LEN);
+		let (cipher, expected) = self.derive(nonce);
+
+		if !bool::from(expected.ct_eq(commitment)) {
+			return Err(MacNotEqual);
+		}
+
+		cipher
+			.decrypt(
+				nonce.as_ref().into(),
+				Payload {
+					msg: ciphertext,
+					aad,
+				},
+			)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts the plaintext with a random nonce.
+	///
+	/// Returns the nonce followed by the output of `encrypt`.
+	pub fn seal(&self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+		let nonce = Nonce::new();
+		let ciphertext = self.encrypt(&nonce, aad, plaintext);
+
+		let mut sealed = Vec::with_capacity(Nonce::LEN + ciphertext.len());
+		sealed.extend_from_slice(nonce.as_ref());
+		sealed.extend_from_slice(&ciphertext);
+
+		sealed
+	}
+
+	/// Opens a message which was created with `seal`.
+	pub fn open(
+		&self,
+		aad: &[u8],
+		sealed: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		if sealed.len() < Nonce::LEN {
+			return Err(MacNotEqual);
+		}
+
+		let (nonce, ciphertext) = sealed.split_at(Nonce::LEN);
+		let nonce = Nonce::try_from(nonce).unwrap();
+
+		self.decrypt(&nonce, aad, ciphertext)
+	}
+}
+
+impl fmt::Debug for CommittingAead {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("CommittingAead")
+	}
+}
+
+impl From<[u8; 32]> for CommittingAead {
+	fn from(key: [u8; 32]) -> Self {
+		Self { key }
+	}
+}
+
+impl TryFrom<&[u8]> for CommittingAead {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; 32]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from)
+	}
+}
+
+impl Drop for CommittingAead {
+	fn drop(&mut self) {
+		self.key.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for CommittingAead {}
@@ -0,0 +1,138 @@
+//! Deterministic encryption for equality searchable database columns.
+//!
+//! The same plaintext and aad always result in the same ciphertext, so an
+//! encrypted column can be indexed and queried with `WHERE email = ?`
+//! without storing a separate hmac next to it.
+//!
+//! ## Trade-offs
+//! Deterministic encryption leaks which rows contain the same value. With
+//! low entropy values (a country, a boolean, a birth year) the frequency
+//! of each ciphertext can reveal the plaintext. Only use it for columns
+//! which need to be searched and whose values are mostly unique, like
+//! emails. Use [`XAead`](super::XAead) for everything else.
+//!
+//! The length of the plaintext is not hidden either. Use a different aad
+//! per column (for example the table and column name) so equal values in
+//! different columns don't produce the same ciphertext.
+//!
+//! ## Construction
+//! This is AES-256-GCM-SIV (RFC 8452) with a fixed all zero nonce. GCM-SIV
+//! derives its IV from the aad and plaintext, so reusing the nonce only
+//! reveals equal messages, which is exactly what a deterministic cipher
+//! does. The tag is appended to the ciphertext.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::deterministic::DeterministicAead;
+//!
+//! let key = DeterministicAead::new();
+//!
+//! let a = key.encrypt(b"users.email", b"alice@example.com");
+//! let b = key.encrypt(b"users.email", b"alice@example.com");
+//! // the ciphertext can be looked up in the database
+//! assert_eq!(a, b);
+//!
+//! let email = key.decrypt(b"users.email", &a).expect("mac invalid");
+//! assert_eq!(email, b"alice@example.com");
+//! ```
+
+use super::{Aes256GcmSiv, MacNotEqual};
+use crate::error::{RandomError, TryFromError};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+/// The length of the tag which is appended to the ciphertext.
+pub const TAG_LEN: usize = Aes256GcmSiv::TAG_LEN;
+
+// GCM-SIV stays secure when a nonce is reused
+const NONCE: [u8; 12] = [0; 12];
+
+/// A key for deterministic authenticated encryption.
+///
+/// See the [module documentation](self) before using it.
+#[derive(Clone)]
+pub struct DeterministicAead {
+	inner: Aes256GcmSiv,
+}
+
+impl DeterministicAead {
+	pub const LEN: usize = 32;
+
+	/// Creates a new random key.
+	pub fn new() -> Self {
+		Self {
+			inner: Aes256GcmSiv::new(),
+		}
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new random key using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		Self {
+			inner: Aes256GcmSiv::new_with_rng(rng),
+		}
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.inner.to_bytes()
+	}
+
+	/// Encrypts the plaintext returning the ciphertext with the tag
+	/// appended.
+	///
+	/// The same key, aad and plaintext always return the same output.
+	pub fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+		self.inner.encrypt(NONCE, aad, plaintext)
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt`, returning
+	/// an Error if it was modified or the aad does not match.
+	pub fn decrypt(
+		&self,
+		aad: &[u8],
+		ciphertext: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		self.inner.decrypt(NONCE, aad, ciphertext)
+	}
+}
+
+impl fmt::Debug for DeterministicAead {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("DeterministicAead")
+	}
+}
+
+impl From<[u8; 32]> for DeterministicAead {
+	fn from(key: [u8; 32]) -> Self {
+		Self {
+			inner: Aes256GcmSiv::from(key),
+		}
+	}
+}
+
+impl TryFrom<&[u8]> for DeterministicAead {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; 32]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from)
+	}
+}
+
+// the key zeroizes itself on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for DeterministicAead {}
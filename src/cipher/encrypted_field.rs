@@ -0,0 +1,421 @@
+//! Struct fields which are encrypted when serialized.
+//!
+//! An [`EncryptedField`] holds the plaintext value in memory. Serializing
+//! it encodes the value as json and encrypts it with the [`Keyset`] set by
+//! [`with_keyset`], deserializing decrypts it again. Human readable
+//! formats store the ciphertext as a base64 string, binary formats as
+//! bytes.
+//!
+//! Serializing or deserializing without a keyset returns an error.
+//!
+//! ## Associated data
+//! Serialized fields and the derive macro all use the same associated
+//! data, so a ciphertext copied from one field or row into another still
+//! decrypts, as long as the type matches. If that matters, for example for
+//! a `role` column, use [`EncryptedField::encrypt_with_aad`] or
+//! [`Encrypted::encrypt_with_aad`] and pass something which identifies
+//! the value, like the table, column and primary key.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::encrypted_field::{with_keyset, EncryptedField};
+//! use chuchi_crypto::cipher::{Keyset, XAead};
+//!
+//! let keyset = Keyset::new(1, XAead::new());
+//!
+//! // usually a field of a struct deriving `Serialize` and `Deserialize`
+//! let email = EncryptedField::new("alice@example.com".to_string());
+//!
+//! let json = with_keyset(&keyset, || serde_json::to_string(&email)).unwrap();
+//! assert!(!json.contains("alice@example.com"));
+//!
+//! let email: EncryptedField<String> =
+//!     with_keyset(&keyset, || serde_json::from_str(&json)).unwrap();
+//! assert_eq!(*email, "alice@example.com");
+//! ```
+//!
+//! ## Derive
+//! With the `derive` feature, `#[derive(Encrypt)]` generates a second
+//! struct where every field marked with `#[encrypted]` holds an
+//! [`Encrypted`] ciphertext. It does not need a thread local keyset and
+//! the plaintext struct can stay free of wrappers.
+//!
+//! Fields which are not encrypted need to implement `Clone`.
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use chuchi_crypto::cipher::encrypted_field::Encrypt;
+//! use chuchi_crypto::cipher::{Keyset, XAead};
+//!
+//! #[derive(Encrypt)]
+//! #[encrypt(derive(Debug, Clone))]
+//! struct User {
+//!     id: u64,
+//!     #[encrypted]
+//!     email: String,
+//! }
+//!
+//! let keyset = Keyset::new(1, XAead::new());
+//!
+//! let user = User {
+//!     id: 1,
+//!     email: "alice@example.com".into(),
+//! };
+//! let encrypted: EncryptedUser = user.encrypt(&keyset);
+//! assert_eq!(encrypted.id, 1);
+//!
+//! let user = encrypted.decrypt(&keyset).unwrap();
+//! assert_eq!(user.email, "alice@example.com");
+//! # }
+//! ```
+
+use super::Keyset;
+use crate::error::DecryptError;
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use _serde::de::{DeserializeOwned, Error as _};
+use _serde::ser::Error as _;
+use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use zeroize::Zeroize;
+
+#[cfg(feature = "derive")]
+pub use chuchi_crypto_derive::Encrypt;
+
+const AAD: &[u8] = b"chuchi-crypto encrypted field";
+
+thread_local! {
+	static KEYSETS: RefCell<Vec<Keyset>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs the closure with the keyset used to serialize and deserialize
+/// every `EncryptedField` on the current thread.
+///
+/// Calls can be nested, the innermost keyset is used.
+pub fn with_keyset<R>(keyset: &Keyset, f: impl FnOnce() -> R) -> R {
+	struct PopOnDrop;
+
+	impl Drop for PopOnDrop {
+		fn drop(&mut self) {
+			KEYSETS.with(|k| k.borrow_mut().pop());
+		}
+	}
+
+	KEYSETS.with(|k| k.borrow_mut().push(keyset.clone()));
+	let _guard = PopOnDrop;
+
+	f()
+}
+
+fn with_current<R>(f: impl FnOnce(&Keyset) -> R) -> Option<R> {
+	KEYSETS.with(|k| k.borrow().last().map(f))
+}
+
+/// A value which is encrypted when serialized.
+///
+/// See the [module documentation](self).
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct EncryptedField<T> {
+	value: T,
+}
+
+impl<T> EncryptedField<T> {
+	pub fn new(value: T) -> Self {
+		Self { value }
+	}
+
+	pub fn into_inner(self) -> T {
+		self.value
+	}
+}
+
+impl<T> EncryptedField<T>
+where
+	T: Serialize + DeserializeOwned,
+{
+	/// Encrypts the value with an explicit keyset.
+	///
+	/// ## Panics
+	/// If the value cannot be serialized as json, for example a map with
+	/// non string keys.
+	pub fn encrypt(&self, keyset: &Keyset) -> Vec<u8> {
+		self.encrypt_with_aad(keyset, &[])
+	}
+
+	/// Like `encrypt` but binds the ciphertext to `aad`, it can only be
+	/// decrypted with `decrypt_with_aad` and the same aad.
+	///
+	/// ## Panics
+	/// If the value cannot be serialized as json.
+	pub fn encrypt_with_aad(&self, keyset: &Keyset, aad: &[u8]) -> Vec<u8> {
+		encrypt_value(keyset, &self.value, aad)
+	}
+
+	/// Decrypts a value which was encrypted with `encrypt` or serialized.
+	///
+	/// Returns `DecryptError::InvalidFormat` if the plaintext is not a
+	/// valid `T`.
+	pub fn decrypt(
+		keyset: &Keyset,
+		bytes: &[u8],
+	) -> Result<Self, DecryptError> {
+		Self::decrypt_with_aad(keyset, bytes, &[])
+	}
+
+	/// Decrypts a value which was encrypted with `encrypt_with_aad`.
+	pub fn decrypt_with_aad(
+		keyset: &Keyset,
+		bytes: &[u8],
+		aad: &[u8],
+	) -> Result<Self, DecryptError> {
+		decrypt_value(keyset, bytes, aad).map(Self::new)
+	}
+}
+
+/// Appends the caller supplied aad to the one every field uses.
+fn field_aad(aad: &[u8]) -> Vec<u8> {
+	let mut field_aad = Vec::with_capacity(AAD.len() + aad.len());
+	field_aad.extend_from_slice(AAD);
+	field_aad.extend_from_slice(aad);
+
+	field_aad
+}
+
+fn encrypt_value<T: Serialize>(
+	keyset: &Keyset,
+	value: &T,
+	aad: &[u8],
+) -> Vec<u8> {
+	let mut plaintext =
+		serde_json::to_vec(value).expect("value not serializable");
+	let ciphertext = keyset.encrypt(&field_aad(aad), &plaintext);
+	plaintext.zeroize();
+
+	ciphertext
+}
+
+fn decrypt_value<T: DeserializeOwned>(
+	keyset: &Keyset,
+	bytes: &[u8],
+	aad: &[u8],
+) -> Result<T, DecryptError> {
+	let mut plaintext = keyset.decrypt(&field_aad(aad), bytes)?;
+	let value = serde_json::from_slice(&plaintext)
+		.map_err(|_| DecryptError::InvalidFormat);
+	plaintext.zeroize();
+
+	value
+}
+
+/// The ciphertext of a `T`, the decrypted value is never kept.
+///
+/// Serializing it does not need a keyset, human readable formats store
+/// it as a base64 string, binary formats as bytes.
+pub struct Encrypted<T> {
+	bytes: Vec<u8>,
+	marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Encrypted<T> {
+	/// Wraps a ciphertext created by `encrypt` or
+	/// `EncryptedField::encrypt`.
+	pub fn from_bytes(bytes: Vec<u8>) -> Self {
+		Self {
+			bytes,
+			marker: PhantomData,
+		}
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+}
+
+impl<T> Encrypted<T>
+where
+	T: Serialize + DeserializeOwned,
+{
+	/// ## Panics
+	/// If the value cannot be serialized as json.
+	pub fn encrypt(value: &T, keyset: &Keyset) -> Self {
+		Self::encrypt_with_aad(value, keyset, &[])
+	}
+
+	/// Like `encrypt` but binds the ciphertext to `aad`, it can only be
+	/// decrypted with `decrypt_with_aad` and the same aad.
+	///
+	/// ## Panics
+	/// If the value cannot be serialized as json.
+	pub fn encrypt_with_aad(value: &T, keyset: &Keyset, aad: &[u8]) -> Self {
+		Self::from_bytes(encrypt_value(keyset, value, aad))
+	}
+
+	/// Returns `DecryptError::InvalidFormat` if the plaintext is not a
+	/// valid `T`.
+	pub fn decrypt(&self, keyset: &Keyset) -> Result<T, DecryptError> {
+		self.decrypt_with_aad(keyset, &[])
+	}
+
+	/// Decrypts a value which was encrypted with `encrypt_with_aad`.
+	pub fn decrypt_with_aad(
+		&self,
+		keyset: &Keyset,
+		aad: &[u8],
+	) -> Result<T, DecryptError> {
+		decrypt_value(keyset, &self.bytes, aad)
+	}
+}
+
+impl<T> Clone for Encrypted<T> {
+	fn clone(&self) -> Self {
+		Self::from_bytes(self.bytes.clone())
+	}
+}
+
+impl<T> PartialEq for Encrypted<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.bytes == other.bytes
+	}
+}
+
+impl<T> Eq for Encrypted<T> {}
+
+impl<T> fmt::Debug for Encrypted<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Encrypted")
+			.field(&format_args!(
+				"{}",
+				base64::display::Base64Display::new(
+					&self.bytes,
+					&URL_SAFE_NO_PAD
+				)
+			))
+			.finish()
+	}
+}
+
+impl<T> AsRef<[u8]> for Encrypted<T> {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+impl<T> Serialize for Encrypted<T> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		if serializer.is_human_readable() {
+			serializer.collect_str(&base64::display::Base64Display::new(
+				&self.bytes,
+				&URL_SAFE_NO_PAD,
+			))
+		} else {
+			serializer.serialize_bytes(&self.bytes)
+		}
+	}
+}
+
+impl<'de, T> Deserialize<'de> for Encrypted<T> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let bytes = if deserializer.is_human_readable() {
+			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+			URL_SAFE_NO_PAD
+				.decode(s.as_bytes())
+				.map_err(D::Error::custom)?
+		} else {
+			crate::serde_bytes::deserialize_vec(deserializer)?
+		};
+
+		Ok(Self::from_bytes(bytes))
+	}
+}
+
+impl<T> From<T> for EncryptedField<T> {
+	fn from(value: T) -> Self {
+		Self::new(value)
+	}
+}
+
+impl<T> Deref for EncryptedField<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.value
+	}
+}
+
+impl<T> DerefMut for EncryptedField<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.value
+	}
+}
+
+impl<T> fmt::Debug for EncryptedField<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("EncryptedField")
+	}
+}
+
+impl<T> Serialize for EncryptedField<T>
+where
+	T: Serialize + DeserializeOwned,
+{
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut plaintext =
+			serde_json::to_vec(&self.value).map_err(S::Error::custom)?;
+		let ciphertext = with_current(|k| k.encrypt(AAD, &plaintext));
+		plaintext.zeroize();
+		let ciphertext = ciphertext
+			.ok_or_else(|| S::Error::custom("no keyset for encrypted field"))?;
+
+		if serializer.is_human_readable() {
+			serializer.collect_str(&base64::display::Base64Display::new(
+				&ciphertext,
+				&URL_SAFE_NO_PAD,
+			))
+		} else {
+			serializer.serialize_bytes(&ciphertext)
+		}
+	}
+}
+
+impl<'de, T> Deserialize<'de> for EncryptedField<T>
+where
+	T: Serialize + DeserializeOwned,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let bytes = if deserializer.is_human_readable() {
+			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+			URL_SAFE_NO_PAD
+				.decode(s.as_bytes())
+				.map_err(D::Error::custom)?
+		} else {
+			crate::serde_bytes::deserialize_vec(deserializer)?
+		};
+
+		with_current(|k| Self::decrypt(k, &bytes))
+			.ok_or_else(|| D::Error::custom("no keyset for encrypted field"))?
+			.map_err(D::Error::custom)
+	}
+}
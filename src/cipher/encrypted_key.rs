@@ -0,0 +1,62 @@
+//! Password protected secret keys used by `export_encrypted`.
+//!
+//! The format is the `KeyParams` used to derive the key followed by the
+//! base64 encoded `Ciphertext`, separated by a `$`:
+//! `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<ciphertext>`
+
+use super::{Ciphertext, XAead};
+use crate::error::{DecryptError, PasswordError};
+use crate::hash::password::{KeyParams, Params};
+
+use std::str::FromStr;
+
+use zeroize::Zeroize;
+
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// Encrypts a secret key, `aad` should describe the type of the key.
+pub(crate) fn encrypt(
+	aad: &[u8],
+	secret: &[u8; 32],
+	password: &[u8],
+	params: &Params,
+) -> Result<String, PasswordError> {
+	let key_params = KeyParams::generate(params)?;
+	let key = XAead::from_password_params(password, &key_params)?;
+
+	let ciphertext = key.seal_ciphertext(aad, secret);
+
+	Ok(format!("{key_params}${ciphertext}"))
+}
+
+/// Decrypts a secret key encrypted with `encrypt`.
+pub(crate) fn decrypt(
+	aad: &[u8],
+	s: &str,
+	password: &[u8],
+) -> Result<[u8; 32], DecryptError> {
+	let (key_params, ciphertext) =
+		s.rsplit_once('$').ok_or(DecryptError::InvalidFormat)?;
+
+	let key_params = KeyParams::from_str(key_params)
+		.map_err(|_| DecryptError::InvalidFormat)?;
+	let ciphertext = URL_SAFE_NO_PAD
+		.decode(ciphertext)
+		.map_err(|_| DecryptError::InvalidFormat)
+		.and_then(|b| {
+			Ciphertext::from_bytes(&b).map_err(|_| DecryptError::InvalidFormat)
+		})?;
+
+	let key = XAead::from_password_params(password, &key_params)
+		.map_err(|_| DecryptError::InvalidFormat)?;
+
+	let mut plaintext = key
+		.open_ciphertext(aad, &ciphertext)
+		.map_err(|_| DecryptError::MacNotEqual)?;
+
+	let secret = <[u8; 32]>::try_from(plaintext.as_slice())
+		.map_err(|_| DecryptError::InvalidFormat);
+	plaintext.zeroize();
+
+	secret
+}
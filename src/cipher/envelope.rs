@@ -0,0 +1,290 @@
+//! Envelope encryption.
+//!
+//! Every payload is encrypted with a fresh random data key, the data key is
+//! then wrapped by a key encryption key (kek) and stored next to the
+//! payload. The kek never touches the data itself, it can live in a KMS
+//! and rotating it only requires rewrapping the data keys.
+//!
+//! The kek is provided by a [`KekProvider`], which is implemented for
+//! [`XAead`]. Providers which need to call a KMS asynchronously can wrap
+//! and unwrap the data key themselves and use [`Envelope`] directly.
+//!
+//! ## Format
+//! `version (1 byte) | kek id len (1 byte) | kek id |
+//! wrapped key len (2 bytes BE) | wrapped key | nonce (24 bytes) |
+//! ciphertext | tag (16 bytes)`
+//!
+//! Everything before the nonce is authenticated together with the `aad`.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::{envelope, XAead};
+//!
+//! let master_key = XAead::new();
+//!
+//! let blob = envelope::seal(&master_key, b"user 1", b"secret data")
+//!     .unwrap();
+//! let data = envelope::open(&master_key, b"user 1", &blob).unwrap();
+//! assert_eq!(data, b"secret data");
+//! ```
+//!
+//! ## Async providers
+//! ```
+//! use chuchi_crypto::cipher::envelope::Envelope;
+//! use chuchi_crypto::cipher::XAead;
+//!
+//! # let kms_wrap = |key: &XAead| key.to_bytes().to_vec();
+//! # let kms_unwrap = |b: &[u8]| XAead::try_from_slice(b).unwrap();
+//! let data_key = XAead::new();
+//! // let wrapped = kms.encrypt("key-1", &data_key.to_bytes()).await?;
+//! let wrapped = kms_wrap(&data_key);
+//!
+//! let envelope =
+//!     Envelope::seal(&data_key, b"key-1", &wrapped, b"", b"secret data");
+//! let bytes = envelope.into_bytes();
+//!
+//! let envelope = Envelope::from_bytes(&bytes).unwrap();
+//! assert_eq!(envelope.kek_id(), b"key-1");
+//! let data_key = kms_unwrap(envelope.wrapped_key());
+//! assert_eq!(envelope.open(&data_key, b"").unwrap(), b"secret data");
+//! ```
+
+use super::{MacNotEqual, Nonce, WrappedKey, XAead};
+use crate::error::{DecodeError, EnvelopeError, TryFromError};
+
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Wraps and unwraps data keys with a key encryption key.
+pub trait KekProvider {
+	type Error;
+
+	/// Identifies the kek which is used by `wrap_key`, it gets stored in
+	/// the envelope and passed to `unwrap_key`. Allows to rotate keks while
+	/// old envelopes still exist.
+	///
+	/// Can be at most 255 bytes long.
+	fn kek_id(&self) -> &[u8];
+
+	/// Wraps a newly generated data key.
+	fn wrap_key(&self, data_key: &XAead) -> Result<Vec<u8>, Self::Error>;
+
+	/// Unwraps a data key which was wrapped by the kek `kek_id`.
+	fn unwrap_key(
+		&self,
+		kek_id: &[u8],
+		wrapped_key: &[u8],
+	) -> Result<XAead, Self::Error>;
+}
+
+/// A local kek, wraps data keys as a [`WrappedKey`] and uses an empty kek
+/// id.
+impl KekProvider for XAead {
+	type Error = MacNotEqual;
+
+	fn kek_id(&self) -> &[u8] {
+		&[]
+	}
+
+	fn wrap_key(&self, data_key: &XAead) -> Result<Vec<u8>, MacNotEqual> {
+		Ok(data_key.wrap(self).to_bytes().to_vec())
+	}
+
+	fn unwrap_key(
+		&self,
+		_kek_id: &[u8],
+		wrapped_key: &[u8],
+	) -> Result<XAead, MacNotEqual> {
+		WrappedKey::from_bytes(wrapped_key)
+			.map_err(|_| MacNotEqual)?
+			.unwrap(self)
+	}
+}
+
+/// Encrypts the plaintext with a new data key which gets wrapped by the
+/// provider.
+///
+/// ## Panics
+/// If the kek id is longer than 255 bytes or the wrapped key longer than
+/// 65535 bytes.
+pub fn seal<P: KekProvider + ?Sized>(
+	provider: &P,
+	aad: &[u8],
+	plaintext: &[u8],
+) -> Result<Vec<u8>, P::Error> {
+	let data_key = XAead::new();
+	let wrapped_key = provider.wrap_key(&data_key)?;
+
+	Ok(Envelope::seal(
+		&data_key,
+		provider.kek_id(),
+		&wrapped_key,
+		aad,
+		plaintext,
+	)
+	.into_bytes())
+}
+
+/// Opens an envelope which was created with `seal`.
+pub fn open<P: KekProvider + ?Sized>(
+	provider: &P,
+	aad: &[u8],
+	envelope: &[u8],
+) -> Result<Vec<u8>, EnvelopeError<P::Error>> {
+	let envelope = Envelope::from_bytes(envelope)
+		.map_err(|_| EnvelopeError::InvalidFormat)?;
+
+	let data_key = provider
+		.unwrap_key(envelope.kek_id(), envelope.wrapped_key())
+		.map_err(EnvelopeError::Kek)?;
+
+	envelope
+		.open(&data_key, aad)
+		.map_err(|_| EnvelopeError::MacNotEqual)
+}
+
+/// A parsed envelope, see the [module](self) documentation.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Envelope {
+	bytes: Vec<u8>,
+	// the end of the kek id
+	kek_id_end: usize,
+	// the start of the nonce
+	header_len: usize,
+}
+
+impl Envelope {
+	/// The current version of the format.
+	pub const VERSION: u8 = 1;
+
+	/// Encrypts the plaintext with the data key and stores the wrapped key
+	/// next to it.
+	///
+	/// ## Panics
+	/// If the kek id is longer than 255 bytes or the wrapped key longer
+	/// than 65535 bytes.
+	pub fn seal(
+		data_key: &XAead,
+		kek_id: &[u8],
+		wrapped_key: &[u8],
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> Self {
+		let kek_id_len = u8::try_from(kek_id.len()).expect("kek id too long");
+		let wrapped_key_len =
+			u16::try_from(wrapped_key.len()).expect("wrapped key too long");
+
+		let header_len = 1 + 1 + kek_id.len() + 2 + wrapped_key.len();
+		let mut bytes = Vec::with_capacity(
+			header_len + Nonce::LEN + plaintext.len() + XAead::TAG_LEN,
+		);
+		bytes.push(Self::VERSION);
+		bytes.push(kek_id_len);
+		bytes.extend_from_slice(kek_id);
+		bytes.extend_from_slice(&wrapped_key_len.to_be_bytes());
+		bytes.extend_from_slice(wrapped_key);
+
+		let nonce = Nonce::new();
+		let aad = [&bytes, aad].concat();
+		let ciphertext = data_key.encrypt(&nonce, &aad, plaintext);
+
+		bytes.extend_from_slice(nonce.as_ref());
+		bytes.extend_from_slice(&ciphertext);
+
+		Self {
+			bytes,
+			kek_id_end: 2 + kek_id.len(),
+			header_len,
+		}
+	}
+
+	/// Parses an envelope returning an error if the version is unknown or
+	/// it is too short.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let too_short = |len| {
+			if bytes.len() < len {
+				Err(DecodeError::InvalidLength)
+			} else {
+				Ok(())
+			}
+		};
+
+		too_short(2)?;
+		if bytes[0] != Self::VERSION {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		let kek_id_end = 2 + bytes[1] as usize;
+		too_short(kek_id_end + 2)?;
+
+		let wrapped_key_len =
+			u16::from_be_bytes([bytes[kek_id_end], bytes[kek_id_end + 1]])
+				as usize;
+		let header_len = kek_id_end + 2 + wrapped_key_len;
+		too_short(header_len + Nonce::LEN + XAead::TAG_LEN)?;
+
+		Ok(Self {
+			bytes: bytes.to_vec(),
+			kek_id_end,
+			header_len,
+		})
+	}
+
+	pub fn version(&self) -> u8 {
+		self.bytes[0]
+	}
+
+	/// Returns the id of the kek which wrapped the data key.
+	pub fn kek_id(&self) -> &[u8] {
+		&self.bytes[2..self.kek_id_end]
+	}
+
+	/// Returns the data key wrapped by the kek.
+	pub fn wrapped_key(&self) -> &[u8] {
+		&self.bytes[self.kek_id_end + 2..self.header_len]
+	}
+
+	/// Decrypts the payload with the unwrapped data key.
+	pub fn open(
+		&self,
+		data_key: &XAead,
+		aad: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		let (header, rest) = self.bytes.split_at(self.header_len);
+		let (nonce, ciphertext) = rest.split_at(Nonce::LEN);
+		let nonce = Nonce::try_from(nonce).unwrap();
+
+		data_key.decrypt(&nonce, &[header, aad].concat(), ciphertext)
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		self.bytes.clone()
+	}
+
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+}
+
+impl fmt::Debug for Envelope {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Envelope")
+			.field("kek_id", &self.kek_id())
+			.field("wrapped_key", &self.wrapped_key())
+			.finish_non_exhaustive()
+	}
+}
+
+impl TryFrom<&[u8]> for Envelope {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		Self::from_bytes(v).map_err(TryFromError::from_any)
+	}
+}
+
+impl AsRef<[u8]> for Envelope {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
@@ -0,0 +1,121 @@
+use super::stream::{Decryptor, Encryptor};
+use super::XAead;
+use crate::fill_random;
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Written at the start of every encrypted file, the last byte is the
+/// version of the format.
+const MAGIC: &[u8; 7] = b"CHUCHI\x01";
+
+/// Encrypts the file at `src` and writes it to `dst`.
+///
+/// The file is encrypted in chunks with [`stream`](super::stream) so it
+/// never needs to fit into memory. `dst` is first written to a temporary
+/// file in the same directory which is then renamed, so `dst` either
+/// contains the complete file or is left untouched.
+///
+/// ## Format
+/// `CHUCHI || version (1 byte) || stream`, the magic and version are
+/// authenticated as associated data of the stream.
+///
+/// ## Example
+/// ```no_run
+/// use chuchi_crypto::cipher::{decrypt_file, encrypt_file, XAead};
+///
+/// let key = XAead::new();
+/// encrypt_file("backup.tar", "backup.tar.enc", &key).unwrap();
+/// decrypt_file("backup.tar.enc", "backup.tar", &key).unwrap();
+/// ```
+pub fn encrypt_file(
+	src: impl AsRef<Path>,
+	dst: impl AsRef<Path>,
+	key: &XAead,
+) -> io::Result<()> {
+	let mut src = File::open(src)?;
+
+	write_atomic(dst.as_ref(), |mut file| {
+		io::Write::write_all(&mut file, MAGIC)?;
+
+		let mut encryptor = Encryptor::new_with_aad(key, file, MAGIC)?;
+		io::copy(&mut src, &mut encryptor)?;
+
+		encryptor.finish()
+	})
+}
+
+/// Decrypts a file created by `encrypt_file` and writes it to `dst`.
+///
+/// `dst` is only created once the whole file was decrypted successfully.
+/// Returns an error with the kind `InvalidData` if the file is not in the
+/// expected format, was modified or truncated.
+pub fn decrypt_file(
+	src: impl AsRef<Path>,
+	dst: impl AsRef<Path>,
+	key: &XAead,
+) -> io::Result<()> {
+	let mut src = File::open(src)?;
+
+	let mut magic = [0u8; MAGIC.len()];
+	src.read_exact(&mut magic).map_err(|e| match e.kind() {
+		io::ErrorKind::UnexpectedEof => invalid_format(),
+		_ => e,
+	})?;
+	if &magic != MAGIC {
+		return Err(invalid_format());
+	}
+
+	write_atomic(dst.as_ref(), |mut file| {
+		let mut decryptor = Decryptor::new_with_aad(key, src, MAGIC)?;
+		io::copy(&mut decryptor, &mut file)?;
+
+		Ok(file)
+	})
+}
+
+fn invalid_format() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "not an encrypted file")
+}
+
+/// Writes to a temporary file next to `dst` and renames it once `f`
+/// returns successfully, else the temporary file is removed.
+///
+/// On unix the file is only readable by the owner, since it might contain
+/// the decrypted data.
+fn write_atomic(
+	dst: &Path,
+	f: impl FnOnce(File) -> io::Result<File>,
+) -> io::Result<()> {
+	let tmp = tmp_path(dst)?;
+	let mut options = OpenOptions::new();
+	options.write(true).create_new(true);
+	#[cfg(unix)]
+	std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+	let file = options.open(&tmp)?;
+
+	let r = f(file)
+		.and_then(|file| file.sync_all())
+		.and_then(|_| fs::rename(&tmp, dst));
+	if r.is_err() {
+		let _ = fs::remove_file(&tmp);
+	}
+
+	r
+}
+
+fn tmp_path(dst: &Path) -> io::Result<PathBuf> {
+	let name = dst.file_name().ok_or_else(|| {
+		io::Error::new(io::ErrorKind::InvalidInput, "dst is not a file")
+	})?;
+
+	let mut rand = [0u8; 8];
+	fill_random(&mut rand);
+
+	let mut tmp_name = std::ffi::OsString::from(".");
+	tmp_name.push(name);
+	tmp_name.push(format!(".{:016x}.tmp", u64::from_ne_bytes(rand)));
+
+	Ok(dst.with_file_name(tmp_name))
+}
@@ -0,0 +1,448 @@
+//! Hybrid Public Key Encryption (RFC 9180).
+//!
+//! Implements the suite DHKEM(X25519, HKDF-SHA256), HKDF-SHA256 and
+//! ChaCha20-Poly1305 in the base and auth modes.
+//!
+//! The sender gets an encapsulated key `enc` which needs to be sent
+//! together with the ciphertexts to the recipient.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::{hpke, Keypair};
+//!
+//! let bob = Keypair::new();
+//!
+//! let (enc, mut sender) =
+//!     hpke::setup_base_sender(bob.public(), b"my app").unwrap();
+//! let ct_1 = sender.seal(b"", b"first message");
+//! let ct_2 = sender.seal(b"", b"second message");
+//!
+//! let mut receiver =
+//!     hpke::setup_base_receiver(&enc, &bob, b"my app").unwrap();
+//! assert_eq!(receiver.open(b"", &ct_1).unwrap(), b"first message");
+//! assert_eq!(receiver.open(b"", &ct_2).unwrap(), b"second message");
+//! ```
+
+use super::kem::{Kem, X25519};
+use super::{Aead, Keypair, PublicKey, SharedSecret};
+use crate::error::HpkeError;
+
+use std::fmt;
+
+use zeroize::Zeroize;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+const MODE_BASE: u8 = 0x00;
+const MODE_AUTH: u8 = 0x02;
+
+// DHKEM(X25519, HKDF-SHA256)
+const KEM_SUITE_ID: &[u8] = b"KEM\x00\x20";
+
+/// The suite id of the kem, HKDF-SHA256 and ChaCha20Poly1305.
+fn hpke_suite_id(kem_id: u16) -> [u8; 10] {
+	let mut id = *b"HPKE\x00\x00\x00\x01\x00\x03";
+	id[4..6].copy_from_slice(&kem_id.to_be_bytes());
+	id
+}
+
+/// The context of the sender, created by `setup_base_sender` or
+/// `setup_auth_sender`.
+pub struct SenderContext {
+	inner: Context,
+}
+
+impl SenderContext {
+	/// Encrypts a message, the messages need to be opened in the same
+	/// order as they were sealed.
+	///
+	/// ## Panics
+	/// if more than `u64::MAX` messages are sealed.
+	pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+		let nonce = self.inner.next_nonce();
+		self.inner.key.encrypt(nonce, aad, plaintext)
+	}
+
+	/// Derives a secret from the context, the recipient derives the same
+	/// secret.
+	///
+	/// ## Panics
+	/// if `out` is longer than 8160 bytes.
+	pub fn export(&self, exporter_context: &[u8], out: &mut [u8]) {
+		self.inner.export(exporter_context, out)
+	}
+}
+
+impl fmt::Debug for SenderContext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("SenderContext")
+	}
+}
+
+/// The context of the recipient, created by `setup_base_receiver` or
+/// `setup_auth_receiver`.
+pub struct ReceiverContext {
+	inner: Context,
+}
+
+impl ReceiverContext {
+	/// Decrypts the next message.
+	///
+	/// If the message could not be opened, the context is not advanced.
+	pub fn open(
+		&mut self,
+		aad: &[u8],
+		ciphertext: &[u8],
+	) -> Result<Vec<u8>, HpkeError> {
+		let nonce = self.inner.nonce();
+		let plaintext = self
+			.inner
+			.key
+			.decrypt(nonce, aad, ciphertext)
+			.map_err(|_| HpkeError::MacNotEqual)?;
+		self.inner.next_nonce();
+
+		Ok(plaintext)
+	}
+
+	/// Derives a secret from the context, the sender derives the same
+	/// secret.
+	///
+	/// ## Panics
+	/// if `out` is longer than 8160 bytes.
+	pub fn export(&self, exporter_context: &[u8], out: &mut [u8]) {
+		self.inner.export(exporter_context, out)
+	}
+}
+
+impl fmt::Debug for ReceiverContext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("ReceiverContext")
+	}
+}
+
+/// Creates a context to send messages to `recipient`, returning the
+/// encapsulated key.
+pub fn setup_base_sender(
+	recipient: &PublicKey,
+	info: &[u8],
+) -> Result<(PublicKey, SenderContext), HpkeError> {
+	setup_sender(recipient, None, info, Keypair::new())
+}
+
+/// Creates a context to receive messages using the encapsulated key.
+pub fn setup_base_receiver(
+	enc: &PublicKey,
+	recipient: &Keypair,
+	info: &[u8],
+) -> Result<ReceiverContext, HpkeError> {
+	setup_receiver(enc, recipient, None, info)
+}
+
+/// Like `setup_base_sender` but the recipient can verify that the
+/// messages were sent by the owner of `sender`.
+pub fn setup_auth_sender(
+	recipient: &PublicKey,
+	sender: &Keypair,
+	info: &[u8],
+) -> Result<(PublicKey, SenderContext), HpkeError> {
+	setup_sender(recipient, Some(sender), info, Keypair::new())
+}
+
+/// Creates a context to receive messages which were sent by the owner of
+/// `sender`.
+pub fn setup_auth_receiver(
+	enc: &PublicKey,
+	recipient: &Keypair,
+	sender: &PublicKey,
+	info: &[u8],
+) -> Result<ReceiverContext, HpkeError> {
+	setup_receiver(enc, recipient, Some(sender), info)
+}
+
+/// Encrypts a single message, returning the encapsulated key and the
+/// ciphertext.
+pub fn seal_base(
+	recipient: &PublicKey,
+	info: &[u8],
+	aad: &[u8],
+	plaintext: &[u8],
+) -> Result<(PublicKey, Vec<u8>), HpkeError> {
+	let (enc, mut ctx) = setup_base_sender(recipient, info)?;
+	Ok((enc, ctx.seal(aad, plaintext)))
+}
+
+/// Decrypts a single message created with `seal_base`.
+pub fn open_base(
+	enc: &PublicKey,
+	recipient: &Keypair,
+	info: &[u8],
+	aad: &[u8],
+	ciphertext: &[u8],
+) -> Result<Vec<u8>, HpkeError> {
+	setup_base_receiver(enc, recipient, info)?.open(aad, ciphertext)
+}
+
+/// Encrypts a single message in the auth mode, returning the
+/// encapsulated key and the ciphertext.
+pub fn seal_auth(
+	recipient: &PublicKey,
+	sender: &Keypair,
+	info: &[u8],
+	aad: &[u8],
+	plaintext: &[u8],
+) -> Result<(PublicKey, Vec<u8>), HpkeError> {
+	let (enc, mut ctx) = setup_auth_sender(recipient, sender, info)?;
+	Ok((enc, ctx.seal(aad, plaintext)))
+}
+
+/// Decrypts a single message created with `seal_auth`.
+pub fn open_auth(
+	enc: &PublicKey,
+	recipient: &Keypair,
+	sender: &PublicKey,
+	info: &[u8],
+	aad: &[u8],
+	ciphertext: &[u8],
+) -> Result<Vec<u8>, HpkeError> {
+	setup_auth_receiver(enc, recipient, sender, info)?.open(aad, ciphertext)
+}
+
+/// Like `setup_base_sender` but with any [`Kem`], returning the
+/// encapsulation which needs to be sent to the recipient.
+///
+/// With [`X25519`] this is the same as `setup_base_sender`.
+pub fn setup_base_sender_with<K: Kem>(
+	recipient: &K::PublicKey,
+	info: &[u8],
+) -> Result<(K::Encapsulation, SenderContext), HpkeError> {
+	let (secret, enc) =
+		K::encapsulate(recipient).map_err(|_| HpkeError::InvalidPublicKey)?;
+	let inner = Context::new(K::ID, MODE_BASE, secret_bytes(&secret), info);
+
+	Ok((enc, SenderContext { inner }))
+}
+
+/// Like `setup_base_receiver` but with any [`Kem`].
+pub fn setup_base_receiver_with<K: Kem>(
+	enc: &K::Encapsulation,
+	recipient: &K::Keypair,
+	info: &[u8],
+) -> Result<ReceiverContext, HpkeError> {
+	let secret = K::decapsulate(recipient, enc)
+		.map_err(|_| HpkeError::InvalidPublicKey)?;
+	let inner = Context::new(K::ID, MODE_BASE, secret_bytes(&secret), info);
+
+	Ok(ReceiverContext { inner })
+}
+
+fn secret_bytes(secret: &SharedSecret) -> [u8; 32] {
+	let mut bytes = [0u8; 32];
+	bytes.copy_from_slice(secret.as_slice());
+	bytes
+}
+
+/// Like `setup_base_sender` or `setup_auth_sender` but with a given
+/// ephemeral keypair, only used to test against the test vectors.
+pub(super) fn setup_sender(
+	recipient: &PublicKey,
+	sender: Option<&Keypair>,
+	info: &[u8],
+	ephemeral: Keypair,
+) -> Result<(PublicKey, SenderContext), HpkeError> {
+	let enc = ephemeral.public().clone();
+
+	let mut dh = vec![ephemeral.diffie_hellman(recipient)];
+	let mut kem_context = [enc.as_ref(), recipient.as_ref()].concat();
+	if let Some(sender) = sender {
+		dh.push(sender.diffie_hellman(recipient));
+		kem_context.extend_from_slice(sender.public().as_ref());
+	}
+
+	let mode = if sender.is_some() {
+		MODE_AUTH
+	} else {
+		MODE_BASE
+	};
+	let shared_secret = extract_and_expand(&dh, &kem_context)?;
+	let inner = Context::new(X25519::ID, mode, shared_secret, info);
+
+	Ok((enc, SenderContext { inner }))
+}
+
+fn setup_receiver(
+	enc: &PublicKey,
+	recipient: &Keypair,
+	sender: Option<&PublicKey>,
+	info: &[u8],
+) -> Result<ReceiverContext, HpkeError> {
+	let mut dh = vec![recipient.diffie_hellman(enc)];
+	let mut kem_context = [enc.as_ref(), recipient.public().as_ref()].concat();
+	if let Some(sender) = sender {
+		dh.push(recipient.diffie_hellman(sender));
+		kem_context.extend_from_slice(sender.as_ref());
+	}
+
+	let mode = if sender.is_some() {
+		MODE_AUTH
+	} else {
+		MODE_BASE
+	};
+	let shared_secret = extract_and_expand(&dh, &kem_context)?;
+	let inner = Context::new(X25519::ID, mode, shared_secret, info);
+
+	Ok(ReceiverContext { inner })
+}
+
+/// Derives the shared secret of the kem.
+pub(super) fn extract_and_expand(
+	dh: &[SharedSecret],
+	kem_context: &[u8],
+) -> Result<[u8; 32], HpkeError> {
+	if !dh.iter().all(SharedSecret::was_contributory) {
+		return Err(HpkeError::InvalidPublicKey);
+	}
+
+	let mut ikm: Vec<u8> =
+		dh.iter().flat_map(|s| s.as_slice()).copied().collect();
+	let (mut prk, eae_prk) =
+		labeled_extract(KEM_SUITE_ID, b"", b"eae_prk", &ikm);
+	ikm.zeroize();
+	prk.zeroize();
+
+	let mut shared_secret = [0u8; 32];
+	labeled_expand(
+		&eae_prk,
+		KEM_SUITE_ID,
+		b"shared_secret",
+		kem_context,
+		&mut shared_secret,
+	);
+
+	Ok(shared_secret)
+}
+
+struct Context {
+	suite_id: [u8; 10],
+	key: Aead,
+	base_nonce: [u8; 12],
+	seq: u64,
+	exporter_secret: Hkdf<Sha256>,
+}
+
+impl Context {
+	/// The key schedule without a psk.
+	fn new(
+		kem_id: u16,
+		mode: u8,
+		mut shared_secret: [u8; 32],
+		info: &[u8],
+	) -> Self {
+		let suite_id = hpke_suite_id(kem_id);
+		let (psk_id_hash, _) =
+			labeled_extract(&suite_id, b"", b"psk_id_hash", b"");
+		let (info_hash, _) =
+			labeled_extract(&suite_id, b"", b"info_hash", info);
+		let ks_context = [&[mode], &psk_id_hash[..], &info_hash[..]].concat();
+
+		let (mut prk, secret) =
+			labeled_extract(&suite_id, &shared_secret, b"secret", b"");
+		shared_secret.zeroize();
+		prk.zeroize();
+
+		let mut key = [0u8; 32];
+		labeled_expand(&secret, &suite_id, b"key", &ks_context, &mut key);
+		let mut base_nonce = [0u8; 12];
+		labeled_expand(
+			&secret,
+			&suite_id,
+			b"base_nonce",
+			&ks_context,
+			&mut base_nonce,
+		);
+		let mut exporter_secret = [0u8; 32];
+		labeled_expand(
+			&secret,
+			&suite_id,
+			b"exp",
+			&ks_context,
+			&mut exporter_secret,
+		);
+
+		let this = Self {
+			key: Aead::from(key),
+			suite_id,
+			base_nonce,
+			seq: 0,
+			exporter_secret: Hkdf::from_prk(&exporter_secret)
+				.expect("32 bytes is a valid prk"),
+		};
+		key.zeroize();
+		exporter_secret.zeroize();
+
+		this
+	}
+
+	fn nonce(&self) -> [u8; 12] {
+		let mut nonce = self.base_nonce;
+		for (n, s) in nonce[4..].iter_mut().zip(self.seq.to_be_bytes()) {
+			*n ^= s;
+		}
+
+		nonce
+	}
+
+	/// Returns the current nonce and increments the sequence number.
+	fn next_nonce(&mut self) -> [u8; 12] {
+		let nonce = self.nonce();
+		self.seq = self.seq.checked_add(1).expect("message limit reached");
+
+		nonce
+	}
+
+	fn export(&self, exporter_context: &[u8], out: &mut [u8]) {
+		labeled_expand(
+			&self.exporter_secret,
+			&self.suite_id,
+			b"sec",
+			exporter_context,
+			out,
+		)
+	}
+}
+
+impl Drop for Context {
+	fn drop(&mut self) {
+		self.base_nonce.zeroize();
+	}
+}
+
+/// Returns the prk and the hkdf to expand it.
+fn labeled_extract(
+	suite_id: &[u8],
+	salt: &[u8],
+	label: &[u8],
+	ikm: &[u8],
+) -> ([u8; 32], Hkdf<Sha256>) {
+	let mut labeled_ikm = [b"HPKE-v1", suite_id, label, ikm].concat();
+	let (prk, hkdf) = Hkdf::<Sha256>::extract(Some(salt), &labeled_ikm);
+	labeled_ikm.zeroize();
+
+	(prk.into(), hkdf)
+}
+
+fn labeled_expand(
+	prk: &Hkdf<Sha256>,
+	suite_id: &[u8],
+	label: &[u8],
+	info: &[u8],
+	out: &mut [u8],
+) {
+	let len = u16::try_from(out.len()).expect("output too long");
+	prk.expand_multi_info(
+		&[&len.to_be_bytes(), b"HPKE-v1", suite_id, label, info],
+		out,
+	)
+	.expect("output too long")
+}
@@ -0,0 +1,463 @@
+//! Hybrid key encapsulation which combines X25519 and ML-KEM-768.
+//!
+//! The shared secret stays confidential as long as one of the two schemes
+//! is not broken, which protects data encrypted today against a future
+//! quantum computer.
+//!
+//! The secret is derived as
+//! `SHA3-256(ss_mlkem || ss_x25519 || ct_x25519 || pk_x25519 || label)`
+//! with the label `\.//^\`.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::hybrid::Keypair;
+//!
+//! let bob = Keypair::new();
+//!
+//! // alice encapsulates a secret to bob's public key
+//! let (ciphertext, alice_secret) = bob.public().encapsulate();
+//!
+//! // bob receives the ciphertext
+//! let bob_secret = bob.decapsulate(&ciphertext);
+//! assert_eq!(alice_secret, bob_secret);
+//!
+//! let key = alice_secret.derive_key(b"", b"file encryption");
+//! ```
+
+use super::{ml_kem, EphemeralKeypair, SharedSecret};
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::{RandomError, TryFromError};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_256, Shake256};
+
+use zeroize::Zeroize;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+const LABEL: &[u8] = b"\\.//^\\";
+
+fn combine(
+	ml_kem: &SharedSecret,
+	x25519: &SharedSecret,
+	ciphertext: &super::PublicKey,
+	public_key: &super::PublicKey,
+) -> SharedSecret {
+	let mut bytes: [u8; 32] = Sha3_256::new()
+		.chain_update(ml_kem.as_slice())
+		.chain_update(x25519.as_slice())
+		.chain_update(ciphertext.as_ref())
+		.chain_update(public_key.as_ref())
+		.chain_update(LABEL)
+		.finalize()
+		.into();
+	let secret = SharedSecret::from_bytes(bytes);
+	bytes.zeroize();
+
+	secret
+}
+
+/// A hybrid keypair, stored as a 32 byte seed from which the ML-KEM and
+/// the X25519 keys are derived.
+pub struct Keypair {
+	seed: [u8; 32],
+	ml_kem: ml_kem::Keypair,
+	x25519: super::Keypair,
+	public: PublicKey,
+}
+
+impl Keypair {
+	pub const LEN: usize = 32;
+
+	pub fn new() -> Self {
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut seed = [0u8; 32];
+		rng.fill_bytes(&mut seed);
+		let this = Self::from(seed);
+		seed.zeroize();
+
+		this
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	/// Returns the seed.
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.seed
+	}
+
+	pub fn public(&self) -> &PublicKey {
+		&self.public
+	}
+
+	/// Decapsulates the shared secret.
+	///
+	/// A ciphertext which was not created for this key returns a different
+	/// secret instead of an error, the error only shows once the derived
+	/// key is used.
+	pub fn decapsulate(&self, ciphertext: &Ciphertext) -> SharedSecret {
+		let x25519_ct = ciphertext.x25519();
+		let ml_kem = self.ml_kem.decapsulate(&ciphertext.ml_kem());
+		let x25519 = self.x25519.diffie_hellman(&x25519_ct);
+
+		combine(&ml_kem, &x25519, &x25519_ct, self.x25519.public())
+	}
+}
+
+impl fmt::Debug for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("public", self.public())
+			.finish_non_exhaustive()
+	}
+}
+
+impl TryFrom<&[u8]> for Keypair {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<&[u8; 32]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(|seed| Self::from(*seed))
+	}
+}
+
+impl From<[u8; 32]> for Keypair {
+	fn from(seed: [u8; 32]) -> Self {
+		let mut expanded = [0u8; 96];
+		Shake256::default()
+			.chain(seed)
+			.finalize_xof()
+			.read(&mut expanded);
+
+		let mut ml_kem_seed = [0u8; 64];
+		ml_kem_seed.copy_from_slice(&expanded[..64]);
+		let mut x25519_secret = [0u8; 32];
+		x25519_secret.copy_from_slice(&expanded[64..]);
+		expanded.zeroize();
+
+		let ml_kem = ml_kem::Keypair::from(ml_kem_seed);
+		let x25519 = super::Keypair::from(x25519_secret);
+		ml_kem_seed.zeroize();
+		x25519_secret.zeroize();
+
+		let public = PublicKey {
+			ml_kem: ml_kem.public().clone(),
+			x25519: x25519.public().clone(),
+		};
+
+		Self {
+			seed,
+			ml_kem,
+			x25519,
+			public,
+		}
+	}
+}
+
+impl Clone for Keypair {
+	fn clone(&self) -> Self {
+		Self::from(self.seed)
+	}
+}
+
+impl Drop for Keypair {
+	fn drop(&mut self) {
+		self.seed.zeroize();
+	}
+}
+
+// both keypairs zeroize themselves on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Keypair {}
+
+/// A hybrid public key, the ML-KEM key followed by the X25519 key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PublicKey {
+	ml_kem: ml_kem::PublicKey,
+	x25519: super::PublicKey,
+}
+
+impl PublicKey {
+	pub const LEN: usize = ml_kem::PublicKey::LEN + super::PublicKey::LEN;
+
+	/// Returns an error if the slice is not 1216 bytes long or the ML-KEM
+	/// key is not valid.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(Self::LEN);
+		bytes.extend_from_slice(&self.ml_kem.to_bytes());
+		bytes.extend_from_slice(self.x25519.as_ref());
+
+		bytes
+	}
+
+	/// Encapsulates a new shared secret to this key.
+	pub fn encapsulate(&self) -> (Ciphertext, SharedSecret) {
+		self.encapsulate_with_rng(&mut OsRng)
+	}
+
+	/// Like `encapsulate` but with the given rng.
+	pub fn encapsulate_with_rng(
+		&self,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> (Ciphertext, SharedSecret) {
+		let (ml_kem_ct, ml_kem) = self.ml_kem.encapsulate_with_rng(rng);
+		let ephemeral = EphemeralKeypair::new_with_rng(rng);
+		let x25519_ct = ephemeral.public().clone();
+		let x25519 = ephemeral.diffie_hellman(&self.x25519);
+
+		let secret = combine(&ml_kem, &x25519, &x25519_ct, &self.x25519);
+		let mut bytes = Box::new([0u8; Ciphertext::LEN]);
+		bytes[..ml_kem::Ciphertext::LEN].copy_from_slice(ml_kem_ct.as_ref());
+		bytes[ml_kem::Ciphertext::LEN..].copy_from_slice(x25519_ct.as_ref());
+		let ciphertext = Ciphertext { bytes };
+
+		(ciphertext, secret)
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey").field(&self.to_bytes()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey").field(&self.to_string()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.to_bytes(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for PublicKey {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		if v.len() != Self::LEN {
+			return Err(TryFromError::from_any(()));
+		}
+
+		let (ml_kem, x25519) = v.split_at(ml_kem::PublicKey::LEN);
+		Ok(Self {
+			ml_kem: ml_kem.try_into()?,
+			x25519: x25519.try_into()?,
+		})
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for PublicKey {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = vec![0u8; Self::LEN];
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(|_| {
+				Self::try_from(bytes.as_slice()).map_err(DecodeError::inv_bytes)
+			})
+	}
+}
+
+/// The hybrid ciphertext, the ML-KEM ciphertext followed by the ephemeral
+/// X25519 public key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Ciphertext {
+	bytes: Box<[u8; Self::LEN]>,
+}
+
+impl Ciphertext {
+	pub const LEN: usize = ml_kem::Ciphertext::LEN + super::PublicKey::LEN;
+
+	/// Returns an error if the slice is not 1120 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		self.bytes.to_vec()
+	}
+
+	fn ml_kem(&self) -> ml_kem::Ciphertext {
+		// the length is correct
+		self.bytes[..ml_kem::Ciphertext::LEN].try_into().unwrap()
+	}
+
+	fn x25519(&self) -> super::PublicKey {
+		// the length is correct
+		self.bytes[ml_kem::Ciphertext::LEN..].try_into().unwrap()
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Ciphertext").field(&self.to_bytes()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Ciphertext")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.to_bytes(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for Ciphertext {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; Self::LEN]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(|bytes| Self {
+				bytes: Box::new(bytes),
+			})
+	}
+}
+
+impl AsRef<[u8]> for Ciphertext {
+	fn as_ref(&self) -> &[u8] {
+		self.bytes.as_slice()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Ciphertext {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = vec![0u8; Self::LEN];
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(|_| {
+				Self::try_from(bytes.as_slice()).map_err(DecodeError::inv_bytes)
+			})
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for PublicKey {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.to_bytes())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for PublicKey {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::try_from(bytes.as_slice()).map_err(D::Error::custom)
+		}
+	}
+
+	impl Serialize for Ciphertext {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.to_bytes())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Ciphertext {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::try_from(bytes.as_slice()).map_err(D::Error::custom)
+		}
+	}
+}
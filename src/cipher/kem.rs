@@ -0,0 +1,188 @@
+//! A common interface for key encapsulation mechanisms.
+//!
+//! The sender encapsulates a fresh shared secret to the public key of the
+//! receiver and sends the encapsulation, which only the receiver can
+//! decapsulate. Code which is generic over [`Kem`] can switch between
+//! X25519, ML-KEM and the hybrid of both without changes, see
+//! [`hpke::setup_base_sender_with`]
+//! and [`sealed_box::seal_with`](super::sealed_box::seal_with).
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::kem::{Kem, X25519};
+//! use chuchi_crypto::cipher::Keypair;
+//!
+//! fn exchange<K: Kem>(keypair: &K::Keypair, public_key: &K::PublicKey) {
+//!     let (alice_secret, encapsulation) =
+//!         K::encapsulate(public_key).unwrap();
+//!     let bob_secret = K::decapsulate(keypair, &encapsulation).unwrap();
+//!     assert_eq!(alice_secret, bob_secret);
+//! }
+//!
+//! let bob = Keypair::new();
+//! exchange::<X25519>(&bob, bob.public());
+//! ```
+
+use super::{hpke, Keypair, PublicKey, SharedSecret};
+use crate::error::{KemError, TryFromError};
+
+use std::convert::TryFrom;
+
+use zeroize::Zeroize;
+
+#[cfg(feature = "ml-kem")]
+use super::{hybrid, ml_kem};
+
+/// A key encapsulation mechanism.
+pub trait Kem {
+	/// The identifier of the kem in the HPKE registry, used by
+	/// [`hpke`].
+	const ID: u16;
+
+	/// The length of an encoded encapsulation.
+	const ENCAPSULATION_LEN: usize;
+
+	/// The key to which a secret is encapsulated.
+	type PublicKey;
+
+	/// The key which decapsulates a secret.
+	type Keypair;
+
+	/// The encapsulated secret which is sent to the receiver.
+	type Encapsulation: AsRef<[u8]>
+		+ for<'a> TryFrom<&'a [u8], Error = TryFromError>;
+
+	/// Creates a new shared secret and its encapsulation.
+	fn encapsulate(
+		public_key: &Self::PublicKey,
+	) -> Result<(SharedSecret, Self::Encapsulation), KemError>;
+
+	/// Returns the shared secret the encapsulation contains.
+	fn decapsulate(
+		keypair: &Self::Keypair,
+		encapsulation: &Self::Encapsulation,
+	) -> Result<SharedSecret, KemError>;
+}
+
+/// DHKEM(X25519, HKDF-SHA256) from RFC 9180.
+///
+/// The encapsulation is an ephemeral public key, the secret is derived
+/// from the Diffie-Hellman output and both public keys.
+#[derive(Debug, Clone, Copy)]
+pub struct X25519;
+
+impl Kem for X25519 {
+	const ID: u16 = 0x0020;
+
+	const ENCAPSULATION_LEN: usize = PublicKey::LEN;
+
+	type PublicKey = PublicKey;
+
+	type Keypair = Keypair;
+
+	type Encapsulation = PublicKey;
+
+	fn encapsulate(
+		public_key: &PublicKey,
+	) -> Result<(SharedSecret, PublicKey), KemError> {
+		let ephemeral = Keypair::new();
+		let enc = ephemeral.public().clone();
+		let dh = ephemeral.diffie_hellman(public_key);
+
+		let kem_context = [enc.as_ref(), public_key.as_ref()].concat();
+		let mut secret = hpke::extract_and_expand(&[dh], &kem_context)
+			.map_err(|_| KemError::InvalidPublicKey)?;
+
+		let shared = SharedSecret::from_bytes(secret);
+		secret.zeroize();
+
+		Ok((shared, enc))
+	}
+
+	fn decapsulate(
+		keypair: &Keypair,
+		encapsulation: &PublicKey,
+	) -> Result<SharedSecret, KemError> {
+		let dh = keypair.diffie_hellman(encapsulation);
+
+		let kem_context =
+			[encapsulation.as_ref(), keypair.public().as_ref()].concat();
+		let mut secret = hpke::extract_and_expand(&[dh], &kem_context)
+			.map_err(|_| KemError::InvalidEncapsulation)?;
+
+		let shared = SharedSecret::from_bytes(secret);
+		secret.zeroize();
+
+		Ok(shared)
+	}
+}
+
+/// ML-KEM-768, see [`ml_kem`](super::ml_kem).
+///
+/// Decapsulation never fails, an invalid encapsulation results in a
+/// random looking secret.
+#[cfg(feature = "ml-kem")]
+#[derive(Debug, Clone, Copy)]
+pub struct MlKem768;
+
+#[cfg(feature = "ml-kem")]
+impl Kem for MlKem768 {
+	const ID: u16 = 0x0041;
+
+	const ENCAPSULATION_LEN: usize = ml_kem::Ciphertext::LEN;
+
+	type PublicKey = ml_kem::PublicKey;
+
+	type Keypair = ml_kem::Keypair;
+
+	type Encapsulation = ml_kem::Ciphertext;
+
+	fn encapsulate(
+		public_key: &ml_kem::PublicKey,
+	) -> Result<(SharedSecret, ml_kem::Ciphertext), KemError> {
+		let (ciphertext, secret) = public_key.encapsulate();
+		Ok((secret, ciphertext))
+	}
+
+	fn decapsulate(
+		keypair: &ml_kem::Keypair,
+		encapsulation: &ml_kem::Ciphertext,
+	) -> Result<SharedSecret, KemError> {
+		Ok(keypair.decapsulate(encapsulation))
+	}
+}
+
+/// The hybrid of X25519 and ML-KEM-768, see [`hybrid`](super::hybrid).
+///
+/// Decapsulation never fails, an invalid encapsulation results in a
+/// different secret.
+#[cfg(feature = "ml-kem")]
+#[derive(Debug, Clone, Copy)]
+pub struct Hybrid;
+
+#[cfg(feature = "ml-kem")]
+impl Kem for Hybrid {
+	const ID: u16 = 0x647a;
+
+	const ENCAPSULATION_LEN: usize = hybrid::Ciphertext::LEN;
+
+	type PublicKey = hybrid::PublicKey;
+
+	type Keypair = hybrid::Keypair;
+
+	type Encapsulation = hybrid::Ciphertext;
+
+	fn encapsulate(
+		public_key: &hybrid::PublicKey,
+	) -> Result<(SharedSecret, hybrid::Ciphertext), KemError> {
+		let (ciphertext, secret) = public_key.encapsulate();
+		Ok((secret, ciphertext))
+	}
+
+	fn decapsulate(
+		keypair: &hybrid::Keypair,
+		encapsulation: &hybrid::Ciphertext,
+	) -> Result<SharedSecret, KemError> {
+		Ok(keypair.decapsulate(encapsulation))
+	}
+}
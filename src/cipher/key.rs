@@ -1,4 +1,8 @@
 use super::{Mac, MacNotEqual};
+#[cfg(feature = "password")]
+use crate::error::PasswordError;
+#[cfg(feature = "password")]
+use crate::hash::password::{KeyParams, Params};
 use crate::xor;
 
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -36,7 +40,7 @@ impl Key {
 	) -> Self {
 		// is this really necessary See: https://github.com/RustCrypto/AEADs/pull/295
 		let shared_secret = hchacha::<U10>(
-			shared_secret.as_ref().into(),
+			shared_secret.as_slice().into(),
 			&GenericArray::default(),
 		)
 		.into();
@@ -48,6 +52,41 @@ impl Key {
 		}
 	}
 
+	/// Derives a key from a password using Argon2id.
+	///
+	/// Returns the key and the `KeyParams` which need to be stored to
+	/// derive the same key again with `from_password_params`.
+	#[cfg(feature = "password")]
+	pub fn from_password(
+		password: impl AsRef<[u8]>,
+		salt: &[u8],
+		params: &Params,
+	) -> Result<(Self, KeyParams), PasswordError> {
+		let key_params = KeyParams::new(salt, params)?;
+		let key = Self::from_password_params(password, &key_params)?;
+
+		Ok((key, key_params))
+	}
+
+	/// Derives a key from a password with the salt and parameters returned
+	/// by `from_password`.
+	#[cfg(feature = "password")]
+	pub fn from_password_params(
+		password: impl AsRef<[u8]>,
+		params: &KeyParams,
+	) -> Result<Self, PasswordError> {
+		let mut bytes = [0u8; 56];
+		params.derive_key(password.as_ref(), &mut bytes)?;
+
+		let mut shared_secret = [0u8; 32];
+		let mut initial_nonce = [0u8; 24];
+		shared_secret.copy_from_slice(&bytes[..32]);
+		initial_nonce.copy_from_slice(&bytes[32..]);
+		bytes.zeroize();
+
+		Ok(Self::new(shared_secret, initial_nonce))
+	}
+
 	/// Encrypts bytes generating returning the generated Mac-
 	pub fn encrypt(&mut self, msg: &mut [u8]) -> Mac {
 		self.new_cipher().encrypt(msg)
@@ -100,6 +139,9 @@ impl Drop for Key {
 	}
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Key {}
+
 /// A Key that allows to encrypt and decrypt messages.  
 /// Without having to borrow mutably.
 pub struct SyncKey {
@@ -163,6 +205,9 @@ impl Drop for SyncKey {
 	}
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SyncKey {}
+
 trait ToMac {
 	fn to_mac(self, msg_len: usize) -> Mac;
 }
@@ -200,13 +245,13 @@ impl Cipher {
 		xor_nonce_with_u64(&mut iv, count);
 
 		let mut cipher =
-			XChaCha20::new(shared_secret.into(), iv.as_ref().into());
+			XChaCha20::new(shared_secret.into(), iv.as_slice().into());
 
 		// Derive Poly1305 key from the first 32-bytes of the ChaCha20 keystream
 		let mut mac_key = [0u8; 32];
 		cipher.apply_keystream(&mut mac_key);
 
-		let poly = Poly1305::new(mac_key.as_ref().into());
+		let poly = Poly1305::new(mac_key.as_slice().into());
 
 		mac_key.zeroize();
 
@@ -1,15 +1,26 @@
+#[cfg(all(feature = "password", feature = "b64"))]
+use super::encrypted_key;
 use super::{PublicKey, SharedSecret};
-#[cfg(feature = "b64")]
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex"))]
 use crate::error::DecodeError;
-use crate::error::TryFromError;
+#[cfg(all(feature = "password", feature = "b64"))]
+use crate::error::{DecryptError, PasswordError};
+use crate::error::{RandomError, TryFromError};
+#[cfg(all(feature = "password", feature = "b64"))]
+use crate::hash::password::Params;
 
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
 
 use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 
 use x25519_dalek as x;
 
+use zeroize::Zeroize;
+
 #[cfg(feature = "b64")]
 use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
 
@@ -23,7 +34,18 @@ pub struct EphemeralKeypair {
 
 impl EphemeralKeypair {
 	pub fn new() -> Self {
-		let secret = x::EphemeralSecret::random_from_rng(OsRng);
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let secret = x::EphemeralSecret::random_from_rng(rng);
 		let public = PublicKey::from_ephemeral_secret(&secret);
 
 		Self { secret, public }
@@ -67,15 +89,48 @@ impl Keypair {
 	}
 
 	pub fn new() -> Self {
-		Self::from_static_secret(x::StaticSecret::random_from_rng(OsRng))
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		Self::from_static_secret(x::StaticSecret::random_from_rng(rng))
+	}
+
+	/// Derives a keypair from a seed, the same seed and context always
+	/// return the same keypair.
+	///
+	/// Use a different context for every key derived from the same seed.
+	///
+	/// ## Panics
+	/// If the seed is shorter than 32 bytes.
+	pub fn from_seed(seed: &[u8], context: &str) -> Self {
+		let mut secret =
+			crate::derive_from_seed(seed, "cipher keypair", context);
+		let keypair = Self::from(secret);
+		secret.zeroize();
+
+		keypair
 	}
 
 	/// ## Panics
 	/// if the slice is not 32 bytes long.
+	#[deprecated(note = "use `try_from_slice` instead")]
 	pub fn from_slice(slice: &[u8]) -> Self {
 		slice.try_into().unwrap()
 	}
 
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
 	pub fn to_bytes(&self) -> [u8; 32] {
 		self.secret.to_bytes()
 	}
@@ -118,11 +173,22 @@ impl fmt::Debug for Keypair {
 #[cfg(feature = "b64")]
 impl fmt::Display for Keypair {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		base64::display::Base64Display::new(&self.to_bytes(), &URL_SAFE_NO_PAD)
-			.fmt(f)
+		let mut bytes = self.to_bytes();
+		let r = base64::display::Base64Display::new(&bytes, &URL_SAFE_NO_PAD)
+			.fmt(f);
+		bytes.zeroize();
+
+		r
 	}
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for EphemeralKeypair {}
+
+// the static secret zeroizes itself on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Keypair {}
+
 impl From<[u8; 32]> for Keypair {
 	fn from(bytes: [u8; 32]) -> Self {
 		Self::from_static_secret(x::StaticSecret::from(bytes))
@@ -149,20 +215,107 @@ impl crate::FromStr for Keypair {
 		}
 
 		let mut bytes = [0u8; Self::LEN];
-		URL_SAFE_NO_PAD
+		let r = URL_SAFE_NO_PAD
 			.decode_slice_unchecked(s, &mut bytes)
 			.map(|_| Self::from(bytes))
-			.map_err(DecodeError::inv_bytes)
+			.map_err(DecodeError::inv_bytes);
+		bytes.zeroize();
+
+		r
+	}
+}
+
+#[cfg(feature = "hex")]
+impl Keypair {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).map(|mut bytes| {
+			let this = Self::from(bytes);
+			bytes.zeroize();
+
+			this
+		})
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, Keypair> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut bytes = self.get().to_bytes();
+		let r = crate::encoding::fmt_hex(&bytes, f);
+		bytes.zeroize();
+
+		r
+	}
+}
+
+#[cfg(all(feature = "password", feature = "b64"))]
+impl Keypair {
+	/// Encrypts the secret key with a password using Argon2id and
+	/// XChaCha20-Poly1305, which allows to store it on disk.
+	pub fn export_encrypted(&self, password: impl AsRef<[u8]>) -> String {
+		// the default params are valid
+		self.export_encrypted_with(password, &Params::default())
+			.unwrap()
+	}
+
+	/// Like `export_encrypted` but with custom Argon2id parameters.
+	pub fn export_encrypted_with(
+		&self,
+		password: impl AsRef<[u8]>,
+		params: &Params,
+	) -> Result<String, PasswordError> {
+		let mut bytes = self.to_bytes();
+		let r = encrypted_key::encrypt(
+			b"chuchi-crypto cipher keypair",
+			&bytes,
+			password.as_ref(),
+			params,
+		);
+		bytes.zeroize();
+
+		r
+	}
+
+	/// Decrypts a keypair exported with `export_encrypted`.
+	///
+	/// Returns `DecryptError::MacNotEqual` if the password is wrong.
+	pub fn import_encrypted(
+		s: &str,
+		password: impl AsRef<[u8]>,
+	) -> Result<Self, DecryptError> {
+		let mut bytes = encrypted_key::decrypt(
+			b"chuchi-crypto cipher keypair",
+			s,
+			password.as_ref(),
+		)?;
+		let this = Self::from(bytes);
+		bytes.zeroize();
+
+		Ok(this)
 	}
 }
 
-#[cfg(all(feature = "b64", feature = "serde"))]
+#[cfg(feature = "serde")]
 mod impl_serde {
 	use super::*;
 
+	#[cfg(feature = "b64")]
 	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
 	use std::str::FromStr;
 
+	#[cfg(feature = "b64")]
 	use _serde::de::Error;
 	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -171,7 +324,16 @@ mod impl_serde {
 		where
 			S: Serializer,
 		{
-			serializer.collect_str(&self)
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			let mut bytes = self.to_bytes();
+			let r = serializer.serialize_bytes(&bytes);
+			bytes.zeroize();
+
+			r
 		}
 	}
 
@@ -180,8 +342,19 @@ mod impl_serde {
 		where
 			D: Deserializer<'de>,
 		{
-			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
-			Self::from_str(s.as_ref()).map_err(D::Error::custom)
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let mut bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			let r = Ok(Self::from(bytes));
+			bytes.zeroize();
+
+			r
 		}
 	}
 }
@@ -202,6 +375,14 @@ mod impl_postgres {
 		where
 			Self: Sized,
 		{
+			if *ty == Type::BYTEA {
+				let mut bytes = self.to_bytes();
+				let r = <&[u8] as ToSql>::to_sql(&bytes.as_ref(), ty, out);
+				bytes.zeroize();
+
+				return r;
+			}
+
 			self.to_string().to_sql(ty, out)
 		}
 
@@ -209,7 +390,7 @@ mod impl_postgres {
 		where
 			Self: Sized,
 		{
-			<&str as ToSql>::accepts(ty)
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
 		}
 
 		to_sql_checked!();
@@ -220,12 +401,60 @@ mod impl_postgres {
 			ty: &Type,
 			raw: &'r [u8],
 		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Self::try_from(raw).map_err(Into::into);
+			}
+
 			let s = <&str as FromSql>::from_sql(ty, raw)?;
 			s.parse().map_err(Into::into)
 		}
 
 		fn accepts(ty: &Type) -> bool {
-			<&str as FromSql>::accepts(ty)
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "sqlx"))]
+mod impl_sqlx {
+	use super::*;
+
+	use sqlx::encode::IsNull;
+	use sqlx::error::BoxDynError;
+	use sqlx::{Database, Decode, Encode, Type};
+
+	impl<DB: Database> Type<DB> for Keypair
+	where
+		String: Type<DB>,
+	{
+		fn type_info() -> DB::TypeInfo {
+			<String as Type<DB>>::type_info()
+		}
+
+		fn compatible(ty: &DB::TypeInfo) -> bool {
+			<String as Type<DB>>::compatible(ty)
+		}
+	}
+
+	impl<'q, DB: Database> Encode<'q, DB> for Keypair
+	where
+		String: Encode<'q, DB>,
+	{
+		fn encode_by_ref(
+			&self,
+			buf: &mut DB::ArgumentBuffer<'q>,
+		) -> Result<IsNull, BoxDynError> {
+			self.to_string().encode(buf)
+		}
+	}
+
+	impl<'r, DB: Database> Decode<'r, DB> for Keypair
+	where
+		&'r str: Decode<'r, DB>,
+	{
+		fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+			let s = <&str as Decode<DB>>::decode(value)?;
+			s.parse().map_err(Into::into)
 		}
 	}
 }
@@ -0,0 +1,161 @@
+use super::{Ciphertext, XAead};
+use crate::error::DecryptError;
+
+use std::convert::TryInto;
+use std::fmt;
+
+/// A set of keys used for encryption where one of them is the primary key.
+///
+/// `encrypt` always uses the primary key and prepends its id to the
+/// ciphertext, `decrypt` reads that id and uses the matching key. This
+/// allows to rotate keys without having to reencrypt all existing data
+/// at once.
+///
+/// ## Layout
+/// `key id (4 bytes big endian) | Ciphertext`
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::cipher::{Keyset, XAead};
+///
+/// let mut keyset = Keyset::new(1, XAead::new());
+/// let old = keyset.encrypt(b"", b"secret");
+///
+/// keyset.rotate(2, XAead::new());
+/// let new = keyset.encrypt(b"", b"secret");
+///
+/// assert_eq!(Keyset::key_id(&old), Some(1));
+/// assert_eq!(Keyset::key_id(&new), Some(2));
+/// assert_eq!(keyset.decrypt(b"", &old).unwrap(), b"secret");
+/// assert_eq!(keyset.decrypt(b"", &new).unwrap(), b"secret");
+/// ```
+#[derive(Clone)]
+pub struct Keyset {
+	primary: u32,
+	keys: Vec<(u32, XAead)>,
+}
+
+impl Keyset {
+	/// The amount of bytes an encrypted message is longer than the
+	/// plaintext.
+	pub const OVERHEAD: usize = 4 + Ciphertext::OVERHEAD;
+
+	/// Creates a keyset with the given primary key.
+	pub fn new(id: u32, primary: XAead) -> Self {
+		Self {
+			primary: id,
+			keys: vec![(id, primary)],
+		}
+	}
+
+	pub fn primary_id(&self) -> u32 {
+		self.primary
+	}
+
+	pub fn primary(&self) -> &XAead {
+		self.get(self.primary).unwrap()
+	}
+
+	pub fn get(&self, id: u32) -> Option<&XAead> {
+		self.keys.iter().find(|(i, _)| *i == id).map(|(_, k)| k)
+	}
+
+	/// Returns the ids of all keys.
+	pub fn ids(&self) -> impl Iterator<Item = u32> + '_ {
+		self.keys.iter().map(|(i, _)| *i)
+	}
+
+	/// Adds a key which can only be used for decryption, replacing any
+	/// existing key with the same id.
+	///
+	/// ## Panics
+	/// if the id is the id of the primary key.
+	pub fn insert(&mut self, id: u32, key: XAead) {
+		assert_ne!(id, self.primary, "cannot replace the primary key");
+
+		match self.keys.iter_mut().find(|(i, _)| *i == id) {
+			Some((_, k)) => *k = key,
+			None => self.keys.push((id, key)),
+		}
+	}
+
+	/// Adds a new key and makes it the primary key, the previous primary
+	/// key is kept for decryption.
+	///
+	/// ## Panics
+	/// if a key with the same id already exists.
+	pub fn rotate(&mut self, id: u32, key: XAead) {
+		assert!(self.get(id).is_none(), "key id already exists");
+
+		self.keys.push((id, key));
+		self.primary = id;
+	}
+
+	/// Makes an existing key the primary key.
+	///
+	/// Returns false if no key with that id exists.
+	pub fn set_primary(&mut self, id: u32) -> bool {
+		let exists = self.get(id).is_some();
+		if exists {
+			self.primary = id;
+		}
+
+		exists
+	}
+
+	/// Removes an old key, the primary key can't be removed.
+	pub fn remove(&mut self, id: u32) -> Option<XAead> {
+		if id == self.primary {
+			return None;
+		}
+
+		let pos = self.keys.iter().position(|(i, _)| *i == id)?;
+		Some(self.keys.remove(pos).1)
+	}
+
+	/// Encrypts a message with the primary key.
+	pub fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+		let ciphertext = self.primary().seal_ciphertext(aad, plaintext);
+
+		let mut bytes = Vec::with_capacity(4 + ciphertext.as_ref().len());
+		bytes.extend_from_slice(&self.primary.to_be_bytes());
+		bytes.extend_from_slice(ciphertext.as_ref());
+
+		bytes
+	}
+
+	/// Decrypts a message with the key it was encrypted with.
+	pub fn decrypt(
+		&self,
+		aad: &[u8],
+		bytes: &[u8],
+	) -> Result<Vec<u8>, DecryptError> {
+		let id = Self::key_id(bytes).ok_or(DecryptError::InvalidFormat)?;
+		let key = self.get(id).ok_or(DecryptError::UnknownKey)?;
+
+		let ciphertext = Ciphertext::from_bytes(&bytes[4..])
+			.map_err(|_| DecryptError::InvalidFormat)?;
+
+		key.open_ciphertext(aad, &ciphertext)
+			.map_err(|_| DecryptError::MacNotEqual)
+	}
+
+	/// Returns the id of the key a message was encrypted with.
+	///
+	/// This can be used to find messages which should be reencrypted
+	/// with the current primary key.
+	pub fn key_id(bytes: &[u8]) -> Option<u32> {
+		bytes
+			.get(..4)
+			.map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+	}
+}
+
+impl fmt::Debug for Keyset {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keyset")
+			.field("primary", &self.primary)
+			.field("ids", &self.ids().collect::<Vec<_>>())
+			.finish()
+	}
+}
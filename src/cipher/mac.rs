@@ -25,10 +25,16 @@ impl Mac {
 
 	/// ## Panics
 	/// if the slice is not 16 bytes long.
+	#[deprecated(note = "use `try_from_slice` instead")]
 	pub fn from_slice(slice: &[u8]) -> Self {
 		slice.try_into().unwrap()
 	}
 
+	/// Returns an error if the slice is not 16 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
 	pub fn into_bytes(self) -> [u8; 16] {
 		self.tag.into()
 	}
@@ -0,0 +1,414 @@
+//! Post-quantum key encapsulation with ML-KEM-768 as specified in FIPS 203.
+//!
+//! The sender encapsulates a fresh shared secret to the public key of the
+//! receiver and sends the ciphertext, which only the receiver can
+//! decapsulate. Prefer [`hybrid`](super::hybrid) which combines ML-KEM with
+//! X25519.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::ml_kem::Keypair;
+//!
+//! let bob = Keypair::new();
+//!
+//! // alice encapsulates a secret to bob's public key
+//! let (ciphertext, alice_secret) = bob.public().encapsulate();
+//!
+//! // bob receives the ciphertext
+//! let bob_secret = bob.decapsulate(&ciphertext);
+//! assert_eq!(alice_secret, bob_secret);
+//! ```
+
+use super::SharedSecret;
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::{RandomError, TryFromError};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use ml_kem::kem::{
+	Decapsulate, DecapsulationKey, Encapsulate, EncapsulationKey,
+};
+use ml_kem::{EncodedSizeUser, KemCore, MlKem768, MlKem768Params, B32};
+
+use zeroize::Zeroize;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+type Params = MlKem768Params;
+
+/// An ML-KEM-768 keypair, stored as the 64 byte seed `d || z`.
+pub struct Keypair {
+	seed: [u8; 64],
+	inner: DecapsulationKey<Params>,
+	public: PublicKey,
+}
+
+impl Keypair {
+	pub const LEN: usize = 64;
+
+	pub fn new() -> Self {
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut seed = [0u8; 64];
+		rng.fill_bytes(&mut seed);
+		let this = Self::from(seed);
+		seed.zeroize();
+
+		this
+	}
+
+	/// Returns an error if the slice is not 64 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	/// Returns the seed.
+	pub fn to_bytes(&self) -> [u8; 64] {
+		self.seed
+	}
+
+	pub fn public(&self) -> &PublicKey {
+		&self.public
+	}
+
+	/// Decapsulates the shared secret.
+	///
+	/// A ciphertext which was not created for this key returns a random
+	/// looking secret instead of an error (implicit rejection), the error
+	/// only shows once the derived key is used.
+	pub fn decapsulate(&self, ciphertext: &Ciphertext) -> SharedSecret {
+		let ct = ciphertext.bytes.as_slice().try_into().unwrap();
+		// decapsulation cannot fail
+		let mut secret = self.inner.decapsulate(&ct).unwrap();
+		let shared = SharedSecret::from_bytes(secret.into());
+		secret.zeroize();
+
+		shared
+	}
+}
+
+impl fmt::Debug for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("public", self.public())
+			.finish_non_exhaustive()
+	}
+}
+
+impl TryFrom<&[u8]> for Keypair {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<&[u8; 64]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(|seed| Self::from(*seed))
+	}
+}
+
+impl From<[u8; 64]> for Keypair {
+	fn from(seed: [u8; 64]) -> Self {
+		let mut d = B32::try_from(&seed[..32]).unwrap();
+		let mut z = B32::try_from(&seed[32..]).unwrap();
+		let (inner, public) = MlKem768::generate_deterministic(&d, &z);
+		d.zeroize();
+		z.zeroize();
+
+		Self {
+			seed,
+			inner,
+			public: PublicKey { inner: public },
+		}
+	}
+}
+
+impl Clone for Keypair {
+	fn clone(&self) -> Self {
+		Self::from(self.seed)
+	}
+}
+
+impl Drop for Keypair {
+	fn drop(&mut self) {
+		self.seed.zeroize();
+	}
+}
+
+// the decapsulation key zeroizes itself on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Keypair {}
+
+/// An ML-KEM-768 public key (encapsulation key).
+#[derive(Clone, PartialEq)]
+pub struct PublicKey {
+	inner: EncapsulationKey<Params>,
+}
+
+impl PublicKey {
+	pub const LEN: usize = 1184;
+
+	/// Returns an error if the slice is not 1184 bytes long or fails the
+	/// modulus check of FIPS 203.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 1184] {
+		self.inner.as_bytes().into()
+	}
+
+	/// Encapsulates a new shared secret to this key.
+	pub fn encapsulate(&self) -> (Ciphertext, SharedSecret) {
+		self.encapsulate_with_rng(&mut OsRng)
+	}
+
+	/// Like `encapsulate` but with the given rng.
+	pub fn encapsulate_with_rng(
+		&self,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> (Ciphertext, SharedSecret) {
+		// encapsulation cannot fail
+		let (ct, mut secret) = self.inner.encapsulate(rng).unwrap();
+		let shared = SharedSecret::from_bytes(secret.into());
+		secret.zeroize();
+
+		(Ciphertext::from_raw(ct.into()), shared)
+	}
+}
+
+impl Eq for PublicKey {}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey").field(&self.to_bytes()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey").field(&self.to_string()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.to_bytes(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for PublicKey {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		if v.len() != Self::LEN {
+			return Err(TryFromError::from_any(()));
+		}
+
+		// every 12 bit coefficient of t needs to be reduced
+		let reduced = v[..Self::LEN - 32].chunks(3).all(|b| {
+			let c0 = b[0] as u16 | (b[1] as u16 & 0x0f) << 8;
+			let c1 = (b[1] >> 4) as u16 | (b[2] as u16) << 4;
+			c0 < 3329 && c1 < 3329
+		});
+		if !reduced {
+			return Err(TryFromError::from_any(()));
+		}
+
+		let encoded = v.try_into().map_err(TryFromError::from_any)?;
+		Ok(Self {
+			inner: EncapsulationKey::from_bytes(&encoded),
+		})
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for PublicKey {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = vec![0u8; Self::LEN];
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(|_| {
+				Self::try_from(bytes.as_slice()).map_err(DecodeError::inv_bytes)
+			})
+	}
+}
+
+/// The encapsulated shared secret which is sent to the receiver.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Ciphertext {
+	bytes: Box<[u8; 1088]>,
+}
+
+impl Ciphertext {
+	pub const LEN: usize = 1088;
+
+	fn from_raw(bytes: [u8; 1088]) -> Self {
+		Self {
+			bytes: Box::new(bytes),
+		}
+	}
+
+	/// Returns an error if the slice is not 1088 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 1088] {
+		*self.bytes
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Ciphertext").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Ciphertext")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Ciphertext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for Ciphertext {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; 1088]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from_raw)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Ciphertext {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = Box::new([0u8; Self::LEN]);
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, bytes.as_mut_slice())
+			.map_err(DecodeError::inv_bytes)
+			.map(|_| Self { bytes })
+	}
+}
+
+impl AsRef<[u8]> for Ciphertext {
+	fn as_ref(&self) -> &[u8] {
+		self.bytes.as_slice()
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for PublicKey {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.to_bytes())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for PublicKey {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::try_from(bytes.as_slice()).map_err(D::Error::custom)
+		}
+	}
+
+	impl Serialize for Ciphertext {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Ciphertext {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::try_from(bytes.as_slice()).map_err(D::Error::custom)
+		}
+	}
+}
@@ -42,9 +42,37 @@
 mod key;
 pub use key::{Key, SyncKey};
 
+mod aead;
+pub use aead::{Aead, XAead};
+
+mod committing;
+pub use committing::CommittingAead;
+
+#[cfg(feature = "aes-gcm")]
+mod aes;
+#[cfg(feature = "aes-gcm")]
+pub use aes::Aes256Gcm;
+
+#[cfg(feature = "aes-gcm-siv")]
+mod gcm_siv;
+#[cfg(feature = "aes-gcm-siv")]
+pub use gcm_siv::Aes256GcmSiv;
+
+mod ciphertext;
+pub use ciphertext::Ciphertext;
+
+mod wrapped_key;
+pub use wrapped_key::WrappedKey;
+
 mod keypair;
 pub use keypair::{EphemeralKeypair, Keypair};
 
+mod keyset;
+pub use keyset::Keyset;
+
+#[cfg(all(feature = "password", feature = "b64"))]
+pub(crate) mod encrypted_key;
+
 mod mac;
 pub use mac::Mac;
 
@@ -55,7 +83,55 @@ mod shared_secret;
 pub use shared_secret::SharedSecret;
 
 mod nonce;
-pub use nonce::Nonce;
+pub use nonce::{Nonce, Nonce12};
+
+mod session;
+pub use session::{Role, Session};
+
+mod precomputed;
+pub use precomputed::PrecomputedKey;
+
+mod nonce_sequence;
+pub use nonce_sequence::{
+	CounterNonces, NonceSequence, RandomNonces, SequenceNonce,
+};
+
+#[cfg(feature = "x448")]
+mod x448;
+#[cfg(feature = "x448")]
+pub use x448::{Keypair448, PublicKey448};
+
+pub mod sealed_box;
+
+pub mod hpke;
+
+pub mod kem;
+
+pub mod envelope;
+
+pub mod stream;
+
+mod file;
+pub use file::{decrypt_file, encrypt_file};
+
+pub mod ratchet;
+
+pub mod padding;
+
+#[cfg(feature = "age")]
+pub mod age;
+
+#[cfg(feature = "aes-gcm-siv")]
+pub mod deterministic;
+
+#[cfg(feature = "encrypted-field")]
+pub mod encrypted_field;
+
+#[cfg(feature = "ml-kem")]
+pub mod ml_kem;
+
+#[cfg(feature = "ml-kem")]
+pub mod hybrid;
 
 /// Get's returned as an error if the generated mac and the received
 /// MAC are not equal.
@@ -73,6 +149,35 @@ mod tests {
 	#[cfg(feature = "b64")]
 	use std::str::FromStr;
 
+	#[cfg(feature = "protobuf")]
+	#[test]
+	fn test_from_seed() {
+		let seed = [7u8; 32];
+
+		let a = Keypair::from_seed(&seed, "test");
+		let b = Keypair::from_seed(&seed, "test");
+		assert_eq!(a.to_bytes(), b.to_bytes());
+		assert_eq!(a.public(), b.public());
+
+		let other = Keypair::from_seed(&seed, "other");
+		assert_ne!(a.public(), other.public());
+		let other = Keypair::from_seed(&[8u8; 32], "test");
+		assert_ne!(a.public(), other.public());
+	}
+
+	#[cfg(feature = "protobuf")]
+	#[test]
+	fn test_protobuf() {
+		let public = Keypair::new().public().clone();
+
+		let bytes = protopuffer::to_vec(&mut public.clone()).unwrap();
+		assert_eq!(
+			protopuffer::from_slice::<PublicKey>(&bytes).unwrap(),
+			public
+		);
+		assert!(protopuffer::from_slice::<PublicKey>(&bytes[1..]).is_err());
+	}
+
 	#[test]
 	pub fn diffie_keypair() {
 		let alice = Keypair::new();
@@ -97,6 +202,168 @@ mod tests {
 		assert_eq!(alice_ssk, bob_ssk);
 	}
 
+	#[cfg(feature = "password")]
+	#[test]
+	pub fn from_password() {
+		use crate::hash::password::Params;
+
+		let params = Params {
+			memory_cost: 256,
+			time_cost: 1,
+			parallelism: 1,
+		};
+
+		let (key, key_params) =
+			XAead::from_password("my password", b"some salt", &params).unwrap();
+		let key_params = key_params.to_string().parse().unwrap();
+		let key_2 =
+			XAead::from_password_params("my password", &key_params).unwrap();
+		assert_eq!(key.to_bytes(), key_2.to_bytes());
+
+		let key_3 = XAead::from_password_params("other", &key_params).unwrap();
+		assert_ne!(key.to_bytes(), key_3.to_bytes());
+
+		let (mut key, key_params) =
+			Key::from_password("my password", b"some salt", &params).unwrap();
+		let mut key_2 =
+			Key::from_password_params("my password", &key_params).unwrap();
+
+		let mut msg = *b"hey";
+		let mac = key.encrypt(&mut msg);
+		key_2.decrypt(&mut msg, &mac).unwrap();
+		assert_eq!(&msg, b"hey");
+	}
+
+	#[test]
+	pub fn hpke() {
+		use crate::error::HpkeError;
+
+		let bob = Keypair::new();
+		let alice = Keypair::new();
+
+		let (enc, mut sender) =
+			hpke::setup_base_sender(bob.public(), b"info").unwrap();
+		let mut receiver =
+			hpke::setup_base_receiver(&enc, &bob, b"info").unwrap();
+		for i in 0..3u8 {
+			let ct = sender.seal(&[i], b"msg");
+			assert_eq!(
+				receiver.open(&[i + 1], &ct).unwrap_err(),
+				HpkeError::MacNotEqual
+			);
+			assert_eq!(receiver.open(&[i], &ct).unwrap(), b"msg");
+		}
+
+		let mut a = [0u8; 42];
+		let mut b = [0u8; 42];
+		sender.export(b"ctx", &mut a);
+		receiver.export(b"ctx", &mut b);
+		assert_eq!(a, b);
+
+		let (enc, ct) =
+			hpke::seal_auth(bob.public(), &alice, b"", b"", b"msg").unwrap();
+		assert_eq!(
+			hpke::open_auth(&enc, &bob, alice.public(), b"", b"", &ct).unwrap(),
+			b"msg"
+		);
+		let eve = Keypair::new();
+		assert_eq!(
+			hpke::open_auth(&enc, &bob, eve.public(), b"", b"", &ct)
+				.unwrap_err(),
+			HpkeError::MacNotEqual
+		);
+		assert_eq!(
+			hpke::open_base(&enc, &bob, b"", b"", &ct).unwrap_err(),
+			HpkeError::MacNotEqual
+		);
+
+		// a low order point
+		let zero = PublicKey::from([0u8; 32]);
+		assert_eq!(
+			hpke::seal_base(&zero, b"", b"", b"msg").unwrap_err(),
+			HpkeError::InvalidPublicKey
+		);
+	}
+
+	// test vector A.2.1 from RFC 9180
+	#[cfg(feature = "hex")]
+	#[test]
+	pub fn hpke_vector() {
+		let sk_e = Keypair::from_hex(
+			"f4ec9b33b792c372c1d2c2063507b684ef925b8c75a42dbcbf57d63ccd381600",
+		)
+		.unwrap();
+		let sk_r = Keypair::from_hex(
+			"8057991eef8f1f1af18f4a9491d16a1ce333f695d4db8e38da75975c4478e0fb",
+		)
+		.unwrap();
+		assert_eq!(
+			sk_r.public().to_hex(),
+			"4310ee97d88cc1f088a5576c77ab0cf5c3ac797f3d95139c6c84b5429c59662a"
+		);
+		let info = b"Ode on a Grecian Urn";
+
+		let (enc, mut sender) =
+			hpke::setup_sender(sk_r.public(), None, info, sk_e).unwrap();
+		assert_eq!(
+			enc.to_hex(),
+			"1afa08d3dec047a643885163f1180476fa7ddb54c6a8029ea33f95796bf2ac4a"
+		);
+
+		let ct = sender.seal(b"Count-0", b"Beauty is truth, truth beauty");
+		assert_eq!(
+			hex::encode(&ct),
+			"1c5250d8034ec2b784ba2cfd69dbdb8af406cfe3ff938e131f0def8c8b60b4db21993c62ce81883d2dd1b51a28"
+		);
+
+		let mut receiver =
+			hpke::setup_base_receiver(&enc, &sk_r, info).unwrap();
+		assert_eq!(
+			receiver.open(b"Count-0", &ct).unwrap(),
+			b"Beauty is truth, truth beauty"
+		);
+	}
+
+	#[test]
+	pub fn keyset() {
+		use crate::error::DecryptError;
+
+		let mut keyset = Keyset::new(1, XAead::new());
+		let old = keyset.encrypt(b"aad", b"msg");
+		assert_eq!(old.len(), 3 + Keyset::OVERHEAD);
+
+		keyset.rotate(2, XAead::new());
+		assert_eq!(keyset.primary_id(), 2);
+		let new = keyset.encrypt(b"aad", b"msg");
+		assert_eq!(Keyset::key_id(&new), Some(2));
+
+		assert_eq!(keyset.decrypt(b"aad", &old).unwrap(), b"msg");
+		assert_eq!(keyset.decrypt(b"aad", &new).unwrap(), b"msg");
+		assert_eq!(
+			keyset.decrypt(b"other", &new).unwrap_err(),
+			DecryptError::MacNotEqual
+		);
+
+		// changing the key id should not allow decryption
+		let mut changed = new.clone();
+		changed[3] = 1;
+		assert_eq!(
+			keyset.decrypt(b"aad", &changed).unwrap_err(),
+			DecryptError::MacNotEqual
+		);
+
+		assert!(keyset.remove(2).is_none());
+		assert!(keyset.remove(1).is_some());
+		assert_eq!(
+			keyset.decrypt(b"aad", &old).unwrap_err(),
+			DecryptError::UnknownKey
+		);
+		assert_eq!(
+			keyset.decrypt(b"aad", &new[..10]).unwrap_err(),
+			DecryptError::InvalidFormat
+		);
+	}
+
 	#[cfg(feature = "b64")]
 	#[test]
 	pub fn b64() {
@@ -200,4 +467,1712 @@ mod tests {
 		assert_eq!(msg, &msg1);
 		assert_eq!(msg, &msg2);
 	}
+
+	#[test]
+	pub fn aead_rfc8439() {
+		let key: [u8; 32] = std::array::from_fn(|i| 0x80 + i as u8);
+		let nonce = [
+			0x07, 0, 0, 0, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47,
+		];
+		let aad = [
+			0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6,
+			0xc7,
+		];
+		let msg =
+			b"Ladies and Gentlemen of the class of '99: If I could offer \
+			you only one tip for the future, sunscreen would be it.";
+
+		let aead = Aead::from(key);
+		let ciphertext = aead.encrypt(nonce, &aad, msg);
+		assert_eq!(ciphertext.len(), msg.len() + Aead::TAG_LEN);
+		assert_eq!(
+			&ciphertext[..8],
+			&[0xd3, 0x1a, 0x8d, 0x34, 0x64, 0x8e, 0x60, 0xdb]
+		);
+		assert_eq!(
+			&ciphertext[msg.len()..],
+			&[
+				0x1a, 0xe1, 0x0b, 0x59, 0x4f, 0x09, 0xe2, 0x6a, 0x7e, 0x90,
+				0x2e, 0xcb, 0xd0, 0x60, 0x06, 0x91
+			]
+		);
+
+		let plaintext = aead.decrypt(nonce, &aad, &ciphertext).unwrap();
+		assert_eq!(plaintext, msg);
+
+		// tampering with the aad or the ciphertext is detected
+		assert!(aead.decrypt(nonce, b"other aad", &ciphertext).is_err());
+		let mut tampered = ciphertext.clone();
+		tampered[0] ^= 1;
+		assert!(aead.decrypt(nonce, &aad, &tampered).is_err());
+		assert!(Aead::new().decrypt(nonce, &aad, &ciphertext).is_err());
+	}
+
+	#[cfg(feature = "aes-gcm")]
+	#[test]
+	pub fn aes_256_gcm() {
+		// the same inputs as the rfc 8439 test, the output was generated
+		// with python cryptography
+		let key: [u8; 32] = std::array::from_fn(|i| 0x80 + i as u8);
+		let nonce = [
+			0x07, 0, 0, 0, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47,
+		];
+		let aad = [
+			0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6,
+			0xc7,
+		];
+		let msg =
+			b"Ladies and Gentlemen of the class of '99: If I could offer \
+			you only one tip for the future, sunscreen would be it.";
+
+		let aead = Aes256Gcm::from(key);
+		let ciphertext = aead.encrypt(nonce, &aad, msg);
+		assert_eq!(ciphertext.len(), msg.len() + Aes256Gcm::TAG_LEN);
+		assert_eq!(
+			&ciphertext[..8],
+			&[0x7c, 0x0d, 0xf6, 0x1c, 0x33, 0xf0, 0xc9, 0x98]
+		);
+		assert_eq!(
+			&ciphertext[msg.len()..],
+			&[
+				0x02, 0x9f, 0x36, 0xe3, 0x4e, 0x07, 0x30, 0x2f, 0xbf, 0x98,
+				0x55, 0x97, 0xbc, 0xa5, 0x8e, 0x5f
+			]
+		);
+
+		let plaintext = aead.decrypt(nonce, &aad, &ciphertext).unwrap();
+		assert_eq!(plaintext, msg);
+
+		assert!(aead.decrypt(nonce, b"other aad", &ciphertext).is_err());
+		assert!(Aes256Gcm::new().decrypt(nonce, &aad, &ciphertext).is_err());
+
+		let mut buffer = msg.to_vec();
+		aead.encrypt_in_place(nonce, &aad, &mut buffer);
+		assert_eq!(buffer, ciphertext);
+		aead.decrypt_in_place(nonce, &aad, &mut buffer).unwrap();
+		assert_eq!(buffer, msg);
+
+		let tag = aead.encrypt_in_place_detached(nonce, &aad, &mut buffer);
+		assert_eq!(&tag, &ciphertext[msg.len()..]);
+		aead.decrypt_in_place_detached(nonce, &aad, &mut buffer, &tag)
+			.unwrap();
+		assert_eq!(buffer, msg);
+
+		let (detached, tag) = aead.encrypt_detached(nonce, &aad, msg);
+		assert_eq!(detached, &ciphertext[..msg.len()]);
+		let plaintext =
+			aead.decrypt_detached(nonce, &aad, &detached, &tag).unwrap();
+		assert_eq!(plaintext, msg);
+	}
+
+	#[cfg(feature = "aes-gcm-siv")]
+	#[test]
+	pub fn aes_256_gcm_siv() {
+		// the output was generated with python cryptography
+		let key: [u8; 32] = std::array::from_fn(|i| 0x80 + i as u8);
+		let nonce = [
+			0x07, 0, 0, 0, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47,
+		];
+		let aad = b"aad";
+		let msg =
+			b"Ladies and Gentlemen of the class of '99: If I could offer \
+			you only one tip for the future, sunscreen would be it.";
+
+		let aead = Aes256GcmSiv::from(key);
+		let ciphertext = aead.encrypt(nonce, aad, msg);
+		assert_eq!(ciphertext.len(), msg.len() + Aes256GcmSiv::TAG_LEN);
+		assert_eq!(
+			&ciphertext[msg.len()..],
+			&[
+				0xd3, 0x97, 0x77, 0x00, 0xc5, 0xae, 0x3c, 0x92, 0xa2, 0x45,
+				0x3d, 0x2e, 0x15, 0x15, 0x59, 0x63
+			]
+		);
+		let plaintext = aead.decrypt(nonce, aad, &ciphertext).unwrap();
+		assert_eq!(plaintext, msg);
+
+		// reusing the nonce only reveals equal messages
+		assert_eq!(aead.encrypt(nonce, aad, msg), ciphertext);
+		let other = aead.encrypt(nonce, aad, b"Ladies and Gentlemen");
+		assert_ne!(&other[..20], &ciphertext[..20]);
+
+		assert!(aead.decrypt(nonce, b"other aad", &ciphertext).is_err());
+		assert!(Aes256GcmSiv::new()
+			.decrypt(nonce, aad, &ciphertext)
+			.is_err());
+
+		let mut buffer = msg.to_vec();
+		let tag = aead.encrypt_in_place_detached(nonce, aad, &mut buffer);
+		assert_eq!(&tag, &ciphertext[msg.len()..]);
+		aead.decrypt_in_place_detached(nonce, aad, &mut buffer, &tag)
+			.unwrap();
+		assert_eq!(buffer, msg);
+	}
+
+	#[cfg(feature = "aes-gcm-siv")]
+	#[test]
+	pub fn deterministic() {
+		use deterministic::{DeterministicAead, TAG_LEN};
+
+		let key = DeterministicAead::new();
+		let msg = b"alice@example.com";
+
+		let ciphertext = key.encrypt(b"users.email", msg);
+		assert_eq!(ciphertext.len(), msg.len() + TAG_LEN);
+		assert_eq!(key.encrypt(b"users.email", msg), ciphertext);
+		assert_ne!(key.encrypt(b"users.name", msg), ciphertext);
+		assert_ne!(key.encrypt(b"users.email", b"bob@example.com"), ciphertext);
+		assert_ne!(
+			DeterministicAead::new().encrypt(b"users.email", msg),
+			ciphertext
+		);
+
+		let plaintext = key.decrypt(b"users.email", &ciphertext).unwrap();
+		assert_eq!(plaintext, msg);
+
+		assert!(key.decrypt(b"users.name", &ciphertext).is_err());
+		let mut tampered = ciphertext.clone();
+		tampered[0] ^= 1;
+		assert!(key.decrypt(b"users.email", &tampered).is_err());
+		assert!(key
+			.decrypt(b"users.email", &ciphertext[..TAG_LEN - 1])
+			.is_err());
+
+		// empty plaintexts work as well
+		let empty = key.encrypt(b"", b"");
+		assert_eq!(key.decrypt(b"", &empty).unwrap(), b"");
+
+		let key_2 = DeterministicAead::from(key.to_bytes());
+		assert_eq!(key_2.encrypt(b"users.email", msg), ciphertext);
+	}
+
+	#[cfg(feature = "aes-gcm-siv")]
+	#[test]
+	pub fn deterministic_rfc8452() {
+		use deterministic::DeterministicAead;
+
+		fn from_hex(s: &str) -> Vec<u8> {
+			(0..s.len())
+				.step_by(2)
+				.map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+				.collect()
+		}
+
+		// appendix C.3, the counter wrap tests use a zero key and nonce
+		let key = DeterministicAead::from([0; 32]);
+		let vectors = [
+			(
+				"000000000000000000000000000000004db923dc793ee6497c76dcc03a98e108",
+				"f3f80f2cf0cb2dd9c5984fcda908456cc537703b5ba70324a6793a7bf218d3ea\
+				ffffffff000000000000000000000000",
+			),
+			(
+				"eb3640277c7ffd1303c7a542d02d3e4c0000000000000000",
+				"18ce4f0b8cb4d0cac65fea8f79257b20888e53e72299e56d\
+				ffffffff000000000000000000000000",
+			),
+		];
+
+		for (plaintext, result) in vectors {
+			let ciphertext = key.encrypt(b"", &from_hex(plaintext));
+			assert_eq!(ciphertext, from_hex(result));
+			assert_eq!(
+				key.decrypt(b"", &ciphertext).unwrap(),
+				from_hex(plaintext)
+			);
+		}
+	}
+
+	#[cfg(feature = "encrypted-field")]
+	#[test]
+	pub fn encrypted_field() {
+		use encrypted_field::{with_keyset, EncryptedField};
+
+		let mut keyset = Keyset::new(1, XAead::new());
+		let field = EncryptedField::new(vec![1u32, 2, 3]);
+
+		// without a keyset serializing fails
+		assert!(serde_json::to_string(&field).is_err());
+
+		let json =
+			with_keyset(&keyset, || serde_json::to_string(&field)).unwrap();
+		let bin = with_keyset(&keyset, || bincode::serialize(&field)).unwrap();
+
+		keyset.rotate(2, XAead::new());
+		let field_2: EncryptedField<Vec<u32>> =
+			with_keyset(&keyset, || serde_json::from_str(&json)).unwrap();
+		assert_eq!(field_2, field);
+		let field_3: EncryptedField<Vec<u32>> =
+			with_keyset(&keyset, || bincode::deserialize(&bin)).unwrap();
+		assert_eq!(field_3, field);
+		assert!(
+			serde_json::from_str::<EncryptedField<Vec<u32>>>(&json).is_err()
+		);
+
+		// the wrong type or keyset can't decrypt the field
+		let other = Keyset::new(1, XAead::new());
+		assert!(with_keyset(&other, || {
+			serde_json::from_str::<EncryptedField<Vec<u32>>>(&json)
+		})
+		.is_err());
+		assert!(with_keyset(&keyset, || {
+			serde_json::from_str::<EncryptedField<String>>(&json)
+		})
+		.is_err());
+
+		// the explicit api uses the same format
+		let bytes = field.encrypt(&keyset);
+		assert_eq!(Keyset::key_id(&bytes), Some(2));
+		let field_4 = EncryptedField::<Vec<u32>>::decrypt(&keyset, &bytes);
+		assert_eq!(field_4.unwrap(), field);
+
+		// a ciphertext bound to a row can't be moved to another one
+		let bytes = field.encrypt_with_aad(&keyset, b"users.tags.1");
+		let field_5 = EncryptedField::<Vec<u32>>::decrypt_with_aad(
+			&keyset,
+			&bytes,
+			b"users.tags.1",
+		);
+		assert_eq!(field_5.unwrap(), field);
+		assert!(EncryptedField::<Vec<u32>>::decrypt_with_aad(
+			&keyset,
+			&bytes,
+			b"users.tags.2"
+		)
+		.is_err());
+		assert!(EncryptedField::<Vec<u32>>::decrypt(&keyset, &bytes).is_err());
+	}
+
+	#[cfg(feature = "derive")]
+	#[test]
+	pub fn derive_encrypt() {
+		use crate::error::DecryptError;
+		use encrypted_field::{Encrypt, Encrypted};
+
+		#[derive(Debug, Clone, PartialEq, Encrypt)]
+		#[encrypt(crate = "crate", name = Sealed, derive(Clone))]
+		struct User<T: Clone> {
+			id: T,
+			#[encrypted]
+			email: String,
+			#[encrypted]
+			tags: Vec<String>,
+		}
+
+		let keyset = Keyset::new(1, XAead::new());
+		let user = User {
+			id: 7u64,
+			email: "alice@example.com".into(),
+			tags: vec!["admin".into()],
+		};
+
+		let sealed: Sealed<u64> = user.encrypt(&keyset);
+		assert_eq!(sealed.id, 7);
+		assert_eq!(Keyset::key_id(sealed.email.as_bytes()), Some(1));
+		assert_eq!(sealed.decrypt(&keyset).unwrap(), user);
+
+		// the ciphertext can be stored and loaded again
+		let json = serde_json::to_string(&sealed.email).unwrap();
+		let email: Encrypted<String> = serde_json::from_str(&json).unwrap();
+		assert_eq!(email, sealed.email);
+
+		// a ciphertext of another type fails to decrypt
+		let mut wrong = sealed.clone();
+		wrong.email = Encrypted::from_bytes(sealed.tags.clone().into_bytes());
+		assert_eq!(
+			wrong.decrypt(&keyset).unwrap_err(),
+			DecryptError::InvalidFormat
+		);
+
+		let other = Keyset::new(1, XAead::new());
+		assert!(sealed.decrypt(&other).is_err());
+	}
+
+	#[test]
+	pub fn xaead_seal_open() {
+		let key = XAead::new();
+		let msg = b"hey thats a nice message";
+
+		let sealed = key.seal(b"aad", msg);
+		assert_eq!(sealed.len(), Nonce::LEN + msg.len() + XAead::TAG_LEN);
+		// a random nonce is used every time
+		assert_ne!(sealed, key.seal(b"aad", msg));
+
+		assert_eq!(key.open(b"aad", &sealed).unwrap(), msg);
+		assert!(key.open(b"other", &sealed).is_err());
+		assert!(key.open(b"aad", &sealed[..Nonce::LEN]).is_err());
+
+		let key_2 = XAead::from(key.to_bytes());
+		assert_eq!(key_2.open(b"aad", &sealed).unwrap(), msg);
+	}
+
+	#[test]
+	pub fn aead_in_place() {
+		let key = XAead::new();
+		let nonce = Nonce::new();
+		let msg = b"hey thats a nice message";
+
+		let mut buffer = Vec::with_capacity(msg.len() + XAead::TAG_LEN);
+		buffer.extend_from_slice(msg);
+		let ptr = buffer.as_ptr();
+		key.encrypt_in_place(&nonce, b"aad", &mut buffer);
+		// the buffer was not reallocated
+		assert_eq!(buffer.as_ptr(), ptr);
+		assert_eq!(buffer, key.encrypt(&nonce, b"aad", msg));
+
+		let encrypted = buffer.clone();
+		assert!(key.decrypt_in_place(&nonce, b"other", &mut buffer).is_err());
+		assert_eq!(buffer, encrypted);
+		key.decrypt_in_place(&nonce, b"aad", &mut buffer).unwrap();
+		assert_eq!(buffer, msg);
+
+		let mut buffer = *msg;
+		let tag = key.encrypt_in_place_detached(&nonce, b"aad", &mut buffer);
+		assert_eq!(&buffer[..], &encrypted[..msg.len()]);
+		assert_eq!(&tag[..], &encrypted[msg.len()..]);
+		let mut other_tag = tag;
+		other_tag[0] ^= 1;
+		assert!(key
+			.decrypt_in_place_detached(&nonce, b"aad", &mut buffer, &other_tag)
+			.is_err());
+		key.decrypt_in_place_detached(&nonce, b"aad", &mut buffer, &tag)
+			.unwrap();
+		assert_eq!(&buffer, msg);
+
+		let key = Aead::new();
+		let nonce = [1; 12];
+		let mut buffer = msg.to_vec();
+		key.encrypt_in_place(nonce, b"aad", &mut buffer);
+		assert_eq!(buffer, key.encrypt(nonce, b"aad", msg));
+		key.decrypt_in_place(nonce, b"aad", &mut buffer).unwrap();
+		assert_eq!(buffer, msg);
+	}
+
+	#[test]
+	pub fn aead_detached() {
+		let key = XAead::new();
+		let nonce = Nonce::new();
+		let msg = b"hey thats a nice message";
+
+		let combined = key.encrypt(&nonce, b"aad", msg);
+		let (ciphertext, tag) = key.encrypt_detached(&nonce, b"aad", msg);
+		assert_eq!(ciphertext, &combined[..msg.len()]);
+		assert_eq!(&tag, &combined[msg.len()..]);
+
+		let plaintext = key
+			.decrypt_detached(&nonce, b"aad", &ciphertext, &tag)
+			.unwrap();
+		assert_eq!(plaintext, msg);
+		assert!(key
+			.decrypt_detached(&nonce, b"other", &ciphertext, &tag)
+			.is_err());
+		assert!(key
+			.decrypt_detached(&nonce, b"aad", &ciphertext[1..], &tag)
+			.is_err());
+
+		let key = Aead::new();
+		let nonce = [1; 12];
+		let (ciphertext, tag) = key.encrypt_detached(nonce, b"", msg);
+		let plaintext =
+			key.decrypt_detached(nonce, b"", &ciphertext, &tag).unwrap();
+		assert_eq!(plaintext, msg);
+	}
+
+	#[test]
+	pub fn nonce_sequence() {
+		let mut nonces = CounterNonces::new(Nonce12::from([0xff; 12]));
+		let nonce = nonces.next_nonce().unwrap();
+		assert_eq!(
+			nonce.to_bytes(),
+			[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0]
+		);
+		let nonce = nonces.next_nonce().unwrap();
+		assert_eq!(&nonce.to_bytes()[4..], &1u64.to_be_bytes());
+		assert_eq!(nonces.counter(), 2);
+
+		// the nonces can be used directly with the aead
+		let key = Aead::new();
+		let ciphertext = key.encrypt(nonce, b"", b"hey");
+		assert_eq!(key.decrypt(nonce, b"", &ciphertext).unwrap(), b"hey");
+
+		let mut nonces = CounterNonces::<Nonce>::random();
+		let a = nonces.next_nonce().unwrap();
+		let b = nonces.next_nonce().unwrap();
+		assert_eq!(&a.as_ref()[..16], &b.as_ref()[..16]);
+		assert_ne!(a, b);
+
+		let mut nonces = RandomNonces::<Nonce12>::new();
+		assert_ne!(nonces.next_nonce().unwrap(), nonces.next_nonce().unwrap());
+		assert_eq!(nonces.count(), 2);
+	}
+
+	#[test]
+	pub fn session() {
+		use crate::error::SessionError;
+
+		let secret = [7u8; 32];
+		let mut alice =
+			Session::from_key(&secret, Role::Initiator).rekey_interval(4);
+		let mut bob =
+			Session::from_key(&secret, Role::Responder).rekey_interval(4);
+
+		let msgs: Vec<_> =
+			(0..10u8).map(|i| alice.seal(b"aad", &[i])).collect();
+		assert_eq!(alice.sent(), 10);
+		// each direction uses its own key
+		assert_ne!(&bob.seal(b"aad", &[0])[8..], &msgs[0][8..]);
+
+		// out of order and across a rekey
+		assert_eq!(bob.open(b"aad", &msgs[1]).unwrap(), [1]);
+		assert_eq!(bob.open(b"aad", &msgs[5]).unwrap(), [5]);
+		assert_eq!(bob.open(b"aad", &msgs[3]).unwrap(), [3]);
+		assert_eq!(bob.open(b"aad", &msgs[0]).unwrap(), [0]);
+		assert_eq!(
+			bob.open(b"aad", &msgs[3]).unwrap_err(),
+			SessionError::Replayed
+		);
+		// skipping an epoch
+		assert_eq!(bob.open(b"aad", &msgs[9]).unwrap(), [9]);
+		// the key of epoch 0 was erased
+		assert_eq!(
+			bob.open(b"aad", &msgs[2]).unwrap_err(),
+			SessionError::TooOld
+		);
+		assert_eq!(bob.open(b"aad", &msgs[6]).unwrap(), [6]);
+
+		// a modified message doesn't change the state
+		let mut modified = msgs[7].clone();
+		modified[10] ^= 1;
+		assert_eq!(
+			bob.open(b"aad", &modified).unwrap_err(),
+			SessionError::MacNotEqual
+		);
+		assert!(bob.open(b"other", &msgs[7]).is_err());
+		assert_eq!(bob.open(b"aad", &msgs[7]).unwrap(), [7]);
+		assert_eq!(
+			bob.open(b"aad", &msgs[7][..20]).unwrap_err(),
+			SessionError::InvalidFormat
+		);
+
+		// the replay window
+		let mut alice = Session::from_key(&secret, Role::Initiator);
+		let mut bob = Session::from_key(&secret, Role::Responder);
+		let first = alice.seal(b"", b"first");
+		for _ in 0..Session::REPLAY_WINDOW {
+			let msg = alice.seal(b"", b"");
+			bob.open(b"", &msg).unwrap();
+		}
+		assert_eq!(bob.open(b"", &first).unwrap_err(), SessionError::TooOld);
+	}
+
+	#[test]
+	pub fn double_ratchet() {
+		use crate::error::RatchetError;
+		use ratchet::DoubleRatchet;
+
+		let secret = [3u8; 32];
+		let bob_keypair = Keypair::new();
+		let mut alice =
+			DoubleRatchet::new_initiator(&secret, bob_keypair.public())
+				.unwrap();
+		let mut bob = DoubleRatchet::new_responder(&secret, bob_keypair);
+
+		assert_eq!(
+			bob.encrypt(b"", b"too early").unwrap_err(),
+			RatchetError::NotReady
+		);
+
+		let a0 = alice.encrypt(b"aad", b"a0").unwrap();
+		let a1 = alice.encrypt(b"aad", b"a1").unwrap();
+		let a2 = alice.encrypt(b"aad", b"a2").unwrap();
+
+		// out of order
+		assert_eq!(bob.decrypt(b"aad", &a1).unwrap(), b"a1");
+		assert_eq!(
+			bob.decrypt(b"aad", &a1).unwrap_err(),
+			RatchetError::Replayed
+		);
+		let b0 = bob.encrypt(b"aad", b"b0").unwrap();
+		assert_eq!(alice.decrypt(b"aad", &b0).unwrap(), b"b0");
+
+		// alice ratchets with a new key
+		let a3 = alice.encrypt(b"aad", b"a3").unwrap();
+		assert_ne!(a3[..32], a0[..32]);
+		assert_eq!(bob.decrypt(b"aad", &a3).unwrap(), b"a3");
+		// skipped keys of the previous chain are still available
+		assert_eq!(bob.decrypt(b"aad", &a2).unwrap(), b"a2");
+
+		// a failed decryption does not change the state
+		let mut modified = a0.clone();
+		modified[50] ^= 1;
+		assert_eq!(
+			bob.decrypt(b"aad", &modified).unwrap_err(),
+			RatchetError::MacNotEqual
+		);
+		assert!(bob.decrypt(b"other", &a0).is_err());
+		assert_eq!(bob.decrypt(b"aad", &a0).unwrap(), b"a0");
+
+		// store and restore the state
+		let bytes = bob.to_bytes();
+		let mut bob = DoubleRatchet::from_bytes(&bytes).unwrap();
+		assert!(DoubleRatchet::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+		let b1 = bob.encrypt(b"", b"b1").unwrap();
+		assert_eq!(alice.decrypt(b"", &b1).unwrap(), b"b1");
+
+		// skipping too many messages
+		for _ in 0..ratchet::MAX_SKIP + 1 {
+			alice.encrypt(b"", b"").unwrap();
+		}
+		let last = alice.encrypt(b"", b"").unwrap();
+		assert_eq!(
+			bob.decrypt(b"", &last).unwrap_err(),
+			RatchetError::TooManySkipped
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	pub fn double_ratchet_serde() {
+		use ratchet::DoubleRatchet;
+
+		let bob_keypair = Keypair::new();
+		let mut alice =
+			DoubleRatchet::new_initiator(&[3; 32], bob_keypair.public())
+				.unwrap();
+		let mut bob = DoubleRatchet::new_responder(&[3; 32], bob_keypair);
+
+		let msg = alice.encrypt(b"", b"hey").unwrap();
+		let json = serde_json::to_string(&bob).unwrap();
+		let mut bob_2: DoubleRatchet = serde_json::from_str(&json).unwrap();
+		assert_eq!(bob_2.decrypt(b"", &msg).unwrap(), b"hey");
+
+		let bin = bincode::serialize(&bob).unwrap();
+		let mut bob_3: DoubleRatchet = bincode::deserialize(&bin).unwrap();
+		assert_eq!(bob_3.decrypt(b"", &msg).unwrap(), b"hey");
+		assert_eq!(bob.decrypt(b"", &msg).unwrap(), b"hey");
+	}
+
+	#[test]
+	pub fn padding() {
+		use crate::error::DecryptError;
+
+		// padmé of the length plus the 0x80 byte
+		assert_eq!(padding::padded_len(0), 1);
+		assert_eq!(padding::padded_len(8), 10);
+		assert_eq!(padding::padded_len(99), 104);
+		assert_eq!(padding::padded_len(1000), 1024);
+		assert_eq!(padding::padded_len(9999), 10240);
+
+		for len in 0..300 {
+			let msg = vec![0xff; len];
+			let padded = padding::pad(&msg);
+			assert_eq!(padded.len(), padding::padded_len(len));
+			assert!(padded.len() <= (len + 1) * 112 / 100 + 1);
+			assert_eq!(padding::unpad(&padded).unwrap(), msg);
+		}
+
+		// messages with similar lengths can't be told apart
+		assert_eq!(
+			padding::pad(&[1; 100]).len(),
+			padding::pad(&[1; 103]).len()
+		);
+
+		assert_eq!(
+			padding::unpad(&[0, 0]).unwrap_err(),
+			DecryptError::InvalidFormat
+		);
+		assert!(padding::unpad(&[1, 0x80, 0, 0]).is_err());
+		assert!(padding::unpad(&[1, 0x81]).is_err());
+
+		let key = XAead::new();
+		let sealed = key.seal_padded(b"aad", b"hey");
+		assert_eq!(key.open_padded(b"aad", &sealed).unwrap(), b"hey");
+		assert_eq!(
+			key.open_padded(b"aad", &key.seal(b"aad", b"hey"))
+				.unwrap_err(),
+			DecryptError::InvalidFormat
+		);
+		assert_eq!(
+			key.open_padded(b"other", &sealed).unwrap_err(),
+			DecryptError::MacNotEqual
+		);
+	}
+
+	#[cfg(feature = "age")]
+	#[test]
+	pub fn age() {
+		use crate::error::AgeError;
+
+		let identity = Keypair::from([7u8; 32]);
+		let s = age::identity_to_string(&identity);
+		assert_eq!(
+			s,
+			"AGE-SECRET-KEY-1QURSWPC8QURSWPC8QURSWPC8QURSWPC8QURSWPC8QURSWPC8QURSKMP32K"
+		);
+		assert_eq!(
+			age::identity_from_str(&s).unwrap().to_bytes(),
+			identity.to_bytes()
+		);
+		let s = age::recipient_to_string(identity.public());
+		assert_eq!(
+			s,
+			"age1zwlyl6h27gzv0lfntr7fcqrjrzqazap8sy5zylkxwneh7llf0dksu37mwt"
+		);
+		assert_eq!(age::recipient_from_str(&s).unwrap(), *identity.public());
+		assert!(age::recipient_from_str(&s.replace('z', "q")).is_err());
+
+		// encrypted by an independent implementation of the spec
+		let mut file = concat!(
+			"age-encryption.org/v1\n",
+			"-> X25519 AewXAWj6+mFXl8NRArkutwfSMD396ivq4P2Qjb2H0SM\n",
+			"vRRNT6KYJhgWA1rgH/TZcTpVwPGCwjFtSPa/pxOEX9U\n",
+			"--- Zsvd3Dvu/DLGCGuMTSVhTRNMJucHhVy05DB0LwRJXEw\n",
+		)
+		.as_bytes()
+		.to_vec();
+		file.extend_from_slice(&[
+			0x30, 0xb7, 0x18, 0x58, 0xa8, 0xc6, 0xea, 0x95, 0x43, 0xef, 0x96,
+			0xce, 0x40, 0xf5, 0x91, 0xeb, 0x9d, 0x4c, 0x71, 0x23, 0x8c, 0x76,
+			0x25, 0x10, 0x1b, 0x54, 0x6f, 0xad, 0x86, 0xca, 0x0a, 0xc8, 0x75,
+			0x93, 0xa6, 0xaf, 0xd9, 0x0b, 0x36, 0x10, 0x20,
+		]);
+		assert_eq!(age::decrypt(&identity, &file).unwrap(), b"hello age");
+
+		let other = Keypair::new();
+		let recipients = [other.public().clone(), identity.public().clone()];
+		// empty, exactly one chunk and more than one chunk
+		for len in [0, 64 * 1024, 64 * 1024 + 1] {
+			let msg = vec![0x42; len];
+			let file = age::encrypt(&recipients, &msg).unwrap();
+			assert_eq!(age::decrypt(&identity, &file).unwrap(), msg);
+			assert_eq!(age::decrypt(&other, &file).unwrap(), msg);
+
+			let mut modified = file.clone();
+			*modified.last_mut().unwrap() ^= 1;
+			assert_eq!(
+				age::decrypt(&identity, &modified).unwrap_err(),
+				AgeError::MacNotEqual
+			);
+			// cutting of the last chunk
+			assert!(age::decrypt(&identity, &file[..file.len() - 17]).is_err());
+		}
+
+		assert_eq!(
+			age::decrypt(
+				&Keypair::new(),
+				&age::encrypt(&recipients, b"").unwrap()
+			)
+			.unwrap_err(),
+			AgeError::NoMatchingKey
+		);
+		assert_eq!(
+			age::encrypt(&[], b"msg").unwrap_err(),
+			AgeError::NoRecipients
+		);
+		assert_eq!(
+			age::encrypt(&[PublicKey::from([0u8; 32])], b"msg").unwrap_err(),
+			AgeError::InvalidPublicKey
+		);
+
+		let file = age::encrypt_with_passphrase("pass", 10, b"backup").unwrap();
+		assert_eq!(
+			age::decrypt_with_passphrase("pass", &file).unwrap(),
+			b"backup"
+		);
+		assert_eq!(
+			age::decrypt_with_passphrase("wrong", &file).unwrap_err(),
+			AgeError::NoMatchingKey
+		);
+		assert_eq!(
+			age::decrypt(&identity, &file).unwrap_err(),
+			AgeError::InvalidFormat
+		);
+		assert_eq!(
+			age::encrypt_with_passphrase("pass", 23, b"").unwrap_err(),
+			AgeError::WorkFactorTooHigh
+		);
+	}
+
+	fn kem_roundtrip<K: kem::Kem>(keypair: &K::Keypair, public: &K::PublicKey) {
+		let (secret, enc) = K::encapsulate(public).unwrap();
+		assert_eq!(enc.as_ref().len(), K::ENCAPSULATION_LEN);
+		assert_eq!(K::decapsulate(keypair, &enc).unwrap(), secret);
+
+		let (enc, mut sender) =
+			hpke::setup_base_sender_with::<K>(public, b"info").unwrap();
+		let mut receiver =
+			hpke::setup_base_receiver_with::<K>(&enc, keypair, b"info")
+				.unwrap();
+		let ct = sender.seal(b"aad", b"msg");
+		assert_eq!(receiver.open(b"aad", &ct).unwrap(), b"msg");
+
+		let sealed = sealed_box::seal_with::<K>(b"msg", public).unwrap();
+		assert_eq!(
+			sealed_box::open_with::<K>(&sealed, keypair).unwrap(),
+			b"msg"
+		);
+		let mut modified = sealed.clone();
+		modified[0] ^= 1;
+		assert!(sealed_box::open_with::<K>(&modified, keypair).is_err());
+		assert!(sealed_box::open_with::<K>(&sealed[..10], keypair).is_err());
+	}
+
+	#[test]
+	pub fn kem() {
+		use crate::error::KemError;
+		use kem::Kem;
+
+		let bob = Keypair::new();
+		kem_roundtrip::<kem::X25519>(&bob, bob.public());
+
+		// the x25519 kem is the one used by hpke
+		let (enc, mut sender) =
+			hpke::setup_base_sender_with::<kem::X25519>(bob.public(), b"")
+				.unwrap();
+		let mut receiver = hpke::setup_base_receiver(&enc, &bob, b"").unwrap();
+		let ct = sender.seal(b"", b"msg");
+		assert_eq!(receiver.open(b"", &ct).unwrap(), b"msg");
+
+		let zero = PublicKey::from([0u8; 32]);
+		assert_eq!(
+			kem::X25519::encapsulate(&zero).unwrap_err(),
+			KemError::InvalidPublicKey
+		);
+		assert_eq!(
+			kem::X25519::decapsulate(&bob, &zero).unwrap_err(),
+			KemError::InvalidEncapsulation
+		);
+
+		#[cfg(feature = "ml-kem")]
+		{
+			let bob = ml_kem::Keypair::new();
+			kem_roundtrip::<kem::MlKem768>(&bob, bob.public());
+			let bob = hybrid::Keypair::new();
+			kem_roundtrip::<kem::Hybrid>(&bob, bob.public());
+		}
+	}
+
+	#[test]
+	pub fn precomputed_key() {
+		let alice = Keypair::new();
+		let bob = Keypair::new();
+
+		let alice_key = PrecomputedKey::new(&alice, bob.public()).unwrap();
+		let bob_key = PrecomputedKey::new(&bob, alice.public()).unwrap();
+		assert_eq!(alice_key.peer(), bob.public());
+
+		for msg in [&b""[..], b"Hey Bob", &[1; 1000]] {
+			let sealed = alice_key.encrypt_to(msg);
+			assert_eq!(
+				sealed.len(),
+				Nonce::LEN + PrecomputedKey::TAG_LEN + msg.len()
+			);
+			assert_eq!(bob_key.decrypt_from(&sealed).unwrap(), msg);
+			// both directions use the same key
+			assert_eq!(alice_key.decrypt_from(&sealed).unwrap(), msg);
+		}
+
+		let nonce = Nonce::new();
+		let ct = bob_key.encrypt(&nonce, b"Hey Alice");
+		assert_eq!(alice_key.decrypt(&nonce, &ct).unwrap(), b"Hey Alice");
+		assert!(alice_key.decrypt(&Nonce::new(), &ct).is_err());
+
+		let mut sealed = alice_key.encrypt_to(b"msg");
+		*sealed.last_mut().unwrap() ^= 1;
+		assert!(bob_key.decrypt_from(&sealed).is_err());
+		assert!(bob_key.decrypt_from(&sealed[..20]).is_err());
+
+		let eve = PrecomputedKey::new(&Keypair::new(), alice.public()).unwrap();
+		assert!(eve.decrypt_from(&alice_key.encrypt_to(b"msg")).is_err());
+
+		assert!(PrecomputedKey::new(&alice, &PublicKey::from([0; 32])).is_err());
+	}
+
+	// checked against libsodium's crypto_box_easy_afternm
+	#[cfg(feature = "hex")]
+	#[test]
+	pub fn precomputed_key_vector() {
+		// the keys from RFC 7748
+		let alice = Keypair::from_hex(
+			"77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+		)
+		.unwrap();
+		let bob = Keypair::from_hex(
+			"5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+		)
+		.unwrap();
+
+		let key = PrecomputedKey::new(&alice, bob.public()).unwrap();
+		let nonce = Nonce::from(std::array::from_fn(|i| i as u8));
+		let ct = key.encrypt(&nonce, b"precomputed");
+		assert_eq!(
+			hex::encode(ct),
+			"0716834a75ddd679f4a112ae1a270982753c35c6741b250834e2fc"
+		);
+	}
+
+	#[test]
+	pub fn wrapped_key() {
+		let kek = XAead::new();
+		let data_key = XAead::new();
+
+		let wrapped = data_key.wrap(&kek);
+		assert_eq!(wrapped.as_ref().len(), WrappedKey::LEN);
+		// every wrap uses a new nonce
+		assert_ne!(wrapped, data_key.wrap(&kek));
+
+		let wrapped = WrappedKey::from_bytes(&wrapped.to_bytes()).unwrap();
+		let unwrapped = wrapped.unwrap(&kek).unwrap();
+		assert_eq!(unwrapped.to_bytes(), data_key.to_bytes());
+
+		// wrong kek
+		assert!(wrapped.unwrap(&XAead::new()).is_err());
+
+		// modified
+		let mut bytes = wrapped.to_bytes();
+		bytes[30] ^= 1;
+		let modified = WrappedKey::from_bytes(&bytes).unwrap();
+		assert!(modified.unwrap(&kek).is_err());
+
+		// a wrapped key can't be opened as a regular message
+		assert!(kek.open(b"", &wrapped.as_ref()[1..]).is_err());
+
+		assert!(WrappedKey::from_bytes(&bytes[1..]).is_err());
+		bytes[0] = 2;
+		assert!(WrappedKey::from_bytes(&bytes).is_err());
+
+		#[cfg(feature = "b64")]
+		{
+			let s = wrapped.to_string();
+			assert_eq!(s.parse::<WrappedKey>().unwrap(), wrapped);
+		}
+	}
+
+	#[test]
+	pub fn envelope() {
+		use crate::error::EnvelopeError;
+		use envelope::{Envelope, KekProvider};
+
+		struct Rotating {
+			current: (Vec<u8>, XAead),
+			old: (Vec<u8>, XAead),
+		}
+
+		impl KekProvider for Rotating {
+			type Error = &'static str;
+
+			fn kek_id(&self) -> &[u8] {
+				&self.current.0
+			}
+
+			fn wrap_key(&self, key: &XAead) -> Result<Vec<u8>, Self::Error> {
+				Ok(key.wrap(&self.current.1).to_bytes().to_vec())
+			}
+
+			fn unwrap_key(
+				&self,
+				kek_id: &[u8],
+				wrapped_key: &[u8],
+			) -> Result<XAead, Self::Error> {
+				let kek = [&self.current, &self.old]
+					.into_iter()
+					.find(|(id, _)| id == kek_id)
+					.map(|(_, kek)| kek)
+					.ok_or("unknown kek")?;
+
+				WrappedKey::from_bytes(wrapped_key)
+					.map_err(|_| "invalid wrapped key")?
+					.unwrap(kek)
+					.map_err(|_| "mac not equal")
+			}
+		}
+
+		let master_key = XAead::new();
+		let blob = envelope::seal(&master_key, b"aad", b"data").unwrap();
+		assert_eq!(
+			envelope::open(&master_key, b"aad", &blob).unwrap(),
+			b"data"
+		);
+		assert_eq!(
+			envelope::open(&master_key, b"other", &blob).unwrap_err(),
+			EnvelopeError::MacNotEqual
+		);
+		assert_eq!(
+			envelope::open(&XAead::new(), b"aad", &blob).unwrap_err(),
+			EnvelopeError::Kek(MacNotEqual)
+		);
+		assert_eq!(
+			envelope::open(&master_key, b"aad", &blob[..blob.len() - 1])
+				.unwrap_err(),
+			EnvelopeError::MacNotEqual
+		);
+		assert_eq!(
+			envelope::open(&master_key, b"aad", &blob[..20]).unwrap_err(),
+			EnvelopeError::InvalidFormat
+		);
+
+		let provider = Rotating {
+			current: (b"key-1".to_vec(), XAead::new()),
+			old: (b"key-0".to_vec(), XAead::new()),
+		};
+		let blob = envelope::seal(&provider, b"", b"").unwrap();
+		let envelope = Envelope::from_bytes(&blob).unwrap();
+		assert_eq!(envelope.kek_id(), b"key-1");
+		assert_eq!(envelope.wrapped_key().len(), WrappedKey::LEN);
+		assert_eq!(envelope::open(&provider, b"", &blob).unwrap(), b"");
+
+		// the header is authenticated, the local kek ignores the kek id
+		let blob = envelope::seal(&master_key, b"", b"").unwrap();
+		let mut modified = vec![Envelope::VERSION, 1, b'x'];
+		modified.extend_from_slice(&blob[2..]);
+		assert_eq!(
+			envelope::open(&master_key, b"", &modified).unwrap_err(),
+			EnvelopeError::MacNotEqual
+		);
+		let blob = envelope::seal(&provider, b"", b"").unwrap();
+
+		// rotate
+		let provider = Rotating {
+			current: (b"key-2".to_vec(), XAead::new()),
+			old: provider.current,
+		};
+		assert_eq!(envelope::open(&provider, b"", &blob).unwrap(), b"");
+		let provider = Rotating {
+			current: (b"key-3".to_vec(), XAead::new()),
+			old: provider.current,
+		};
+		assert_eq!(
+			envelope::open(&provider, b"", &blob).unwrap_err(),
+			EnvelopeError::Kek("unknown kek")
+		);
+	}
+
+	#[test]
+	pub fn committing_aead() {
+		let key = CommittingAead::new();
+		let msg = b"hey thats a nice message";
+
+		let sealed = key.seal(b"aad", msg);
+		assert_eq!(
+			sealed.len(),
+			Nonce::LEN
+				+ CommittingAead::COMMITMENT_LEN
+				+ msg.len() + CommittingAead::TAG_LEN
+		);
+		assert_eq!(key.open(b"aad", &sealed).unwrap(), msg);
+		assert!(key.open(b"other", &sealed).is_err());
+		assert!(key.open(b"aad", &sealed[..Nonce::LEN + 40]).is_err());
+
+		// another key fails on the commitment
+		let other = CommittingAead::new();
+		assert!(other.open(b"aad", &sealed).is_err());
+
+		// a modified commitment is rejected
+		let mut modified = sealed.clone();
+		modified[Nonce::LEN] ^= 1;
+		assert!(key.open(b"aad", &modified).is_err());
+
+		let key_2 = CommittingAead::from(key.to_bytes());
+		assert_eq!(key_2.open(b"aad", &sealed).unwrap(), msg);
+	}
+
+	#[test]
+	pub fn sealed_box() {
+		let bob = Keypair::new();
+		let msg = b"hey thats a nice message";
+
+		let sealed = sealed_box::seal(msg, bob.public());
+		assert_eq!(sealed.len(), msg.len() + sealed_box::OVERHEAD);
+
+		assert_eq!(sealed_box::open(&sealed, &bob).unwrap(), msg);
+		assert!(sealed_box::open(&sealed, &Keypair::new()).is_err());
+		assert!(sealed_box::open(&sealed[..10], &bob).is_err());
+	}
+
+	#[test]
+	pub fn derive_keys() {
+		let alice = Keypair::new();
+		let bob = Keypair::new();
+
+		let alice_ssk = alice.diffie_hellman(bob.public());
+		let bob_ssk = bob.diffie_hellman(alice.public());
+
+		let [mut alice_send, mut alice_recv] =
+			alice_ssk.derive_keys(b"salt", b"chat");
+		let [mut bob_recv, mut bob_send] =
+			bob_ssk.derive_keys(b"salt", b"chat");
+
+		let msg = b"hey thats a nice message";
+
+		let mut msg1 = *msg;
+		let mac1 = alice_send.encrypt(&mut msg1);
+		let mut msg2 = *msg;
+		let mac2 = bob_send.encrypt(&mut msg2);
+		// different keys lead to different ciphertexts
+		assert_ne!(msg1, msg2);
+
+		bob_recv.decrypt(&mut msg1, &mac1).unwrap();
+		alice_recv.decrypt(&mut msg2, &mac2).unwrap();
+		assert_eq!(&msg1, msg);
+		assert_eq!(&msg2, msg);
+
+		// another info leads to another key
+		let mut msg3 = *msg;
+		let mut other = alice_ssk.derive_key(b"salt", b"other");
+		let mac3 = other.encrypt(&mut msg3);
+		assert!(bob_ssk
+			.derive_key(b"salt", b"chat")
+			.decrypt(&mut msg3, &mac3)
+			.is_err());
+	}
+
+	#[test]
+	pub fn stream() {
+		use std::io::{Read, Write};
+
+		let key = XAead::new();
+
+		let sizes = [
+			0,
+			1,
+			stream::CHUNK_SIZE - 1,
+			stream::CHUNK_SIZE,
+			stream::CHUNK_SIZE + 1,
+			3 * stream::CHUNK_SIZE,
+		];
+		for size in sizes {
+			let data: Vec<u8> = (0..size).map(|i| i as u8).collect();
+
+			let mut encryptor =
+				stream::Encryptor::new(&key, Vec::new()).unwrap();
+			// write in odd sized pieces
+			for piece in data.chunks(1000) {
+				encryptor.write_all(piece).unwrap();
+			}
+			let encrypted = encryptor.finish().unwrap();
+
+			let mut decryptor =
+				stream::Decryptor::new(&key, encrypted.as_slice()).unwrap();
+			let mut decrypted = Vec::new();
+			decryptor.read_to_end(&mut decrypted).unwrap();
+			assert_eq!(decrypted, data);
+
+			// truncating the stream at a chunk boundary is detected
+			if size > stream::CHUNK_SIZE {
+				let len =
+					stream::HEADER_LEN + stream::CHUNK_SIZE + stream::TAG_LEN;
+				let mut decryptor =
+					stream::Decryptor::new(&key, &encrypted[..len]).unwrap();
+				assert!(decryptor.read_to_end(&mut Vec::new()).is_err());
+			}
+
+			// modifying the data is detected
+			let mut tampered = encrypted.clone();
+			*tampered.last_mut().unwrap() ^= 1;
+			let mut decryptor =
+				stream::Decryptor::new(&key, tampered.as_slice()).unwrap();
+			assert!(decryptor.read_to_end(&mut Vec::new()).is_err());
+		}
+	}
+
+	#[test]
+	pub fn stream_read_after_tampering() {
+		use std::io::{ErrorKind, Read, Write};
+
+		let key = XAead::new();
+
+		let mut encryptor = stream::Encryptor::new(&key, Vec::new()).unwrap();
+		encryptor.write_all(&[1u8; 2 * stream::CHUNK_SIZE]).unwrap();
+		let mut encrypted = encryptor.finish().unwrap();
+		encrypted[stream::HEADER_LEN] ^= 1;
+
+		let mut decryptor =
+			stream::Decryptor::new(&key, encrypted.as_slice()).unwrap();
+		let mut buf = [0u8; 1024];
+		let e = decryptor.read(&mut buf).unwrap_err();
+		assert_eq!(e.kind(), ErrorKind::InvalidData);
+
+		// the ciphertext of the tampered chunk is never returned
+		for _ in 0..3 {
+			let e = decryptor.read(&mut buf).unwrap_err();
+			assert_eq!(e.kind(), ErrorKind::InvalidData);
+		}
+		assert_eq!(buf, [0u8; 1024]);
+	}
+
+	#[cfg(feature = "rayon")]
+	#[test]
+	pub fn stream_par() {
+		use std::io::{Read, Write};
+
+		let key = XAead::new();
+
+		let sizes = [
+			0,
+			1,
+			stream::CHUNK_SIZE,
+			stream::CHUNK_SIZE + 1,
+			3 * stream::CHUNK_SIZE + 7,
+		];
+		for size in sizes {
+			let data: Vec<u8> = (0..size).map(|i| i as u8).collect();
+
+			// the parallel output can be read by the decryptor
+			let encrypted = stream::encrypt_chunks_par(&key, &data);
+			let mut decryptor =
+				stream::Decryptor::new(&key, encrypted.as_slice()).unwrap();
+			let mut decrypted = Vec::new();
+			decryptor.read_to_end(&mut decrypted).unwrap();
+			assert_eq!(decrypted, data);
+			assert_eq!(
+				stream::decrypt_chunks_par(&key, &encrypted).unwrap(),
+				data
+			);
+
+			// and the output of the encryptor can be decrypted in parallel
+			let mut encryptor =
+				stream::Encryptor::new(&key, Vec::new()).unwrap();
+			encryptor.write_all(&data).unwrap();
+			let encrypted = encryptor.finish().unwrap();
+			assert_eq!(
+				stream::decrypt_chunks_par(&key, &encrypted).unwrap(),
+				data
+			);
+
+			if size > stream::CHUNK_SIZE {
+				let len =
+					stream::HEADER_LEN + stream::CHUNK_SIZE + stream::TAG_LEN;
+				assert!(stream::decrypt_chunks_par(&key, &encrypted[..len])
+					.is_err());
+			}
+
+			let mut tampered = encrypted.clone();
+			tampered[stream::HEADER_LEN] ^= 1;
+			assert!(stream::decrypt_chunks_par(&key, &tampered).is_err());
+		}
+
+		assert!(stream::decrypt_chunks_par(&key, &[0; 20]).is_err());
+	}
+
+	/// Only accepts a few bytes at a time and is every second time not
+	/// ready, to test the backpressure of the async adapters.
+	#[cfg(feature = "tokio")]
+	struct Slow<T> {
+		inner: T,
+		ready: bool,
+	}
+
+	#[cfg(feature = "tokio")]
+	impl<T> Slow<T> {
+		fn poll<R>(
+			&mut self,
+			cx: &mut std::task::Context<'_>,
+			f: impl FnOnce(&mut T) -> std::task::Poll<R>,
+		) -> std::task::Poll<R> {
+			self.ready = !self.ready;
+			if !self.ready {
+				cx.waker().wake_by_ref();
+				return std::task::Poll::Pending;
+			}
+
+			f(&mut self.inner)
+		}
+	}
+
+	#[cfg(feature = "tokio")]
+	impl tokio::io::AsyncWrite for Slow<Vec<u8>> {
+		fn poll_write(
+			mut self: std::pin::Pin<&mut Self>,
+			cx: &mut std::task::Context<'_>,
+			buf: &[u8],
+		) -> std::task::Poll<std::io::Result<usize>> {
+			self.poll(cx, |inner| {
+				let len = buf.len().min(1000);
+				inner.extend_from_slice(&buf[..len]);
+				std::task::Poll::Ready(Ok(len))
+			})
+		}
+
+		fn poll_flush(
+			self: std::pin::Pin<&mut Self>,
+			_cx: &mut std::task::Context<'_>,
+		) -> std::task::Poll<std::io::Result<()>> {
+			std::task::Poll::Ready(Ok(()))
+		}
+
+		fn poll_shutdown(
+			self: std::pin::Pin<&mut Self>,
+			_cx: &mut std::task::Context<'_>,
+		) -> std::task::Poll<std::io::Result<()>> {
+			std::task::Poll::Ready(Ok(()))
+		}
+	}
+
+	#[cfg(feature = "tokio")]
+	impl tokio::io::AsyncRead for Slow<&[u8]> {
+		fn poll_read(
+			mut self: std::pin::Pin<&mut Self>,
+			cx: &mut std::task::Context<'_>,
+			buf: &mut tokio::io::ReadBuf<'_>,
+		) -> std::task::Poll<std::io::Result<()>> {
+			self.poll(cx, |inner| {
+				let len = buf.remaining().min(inner.len()).min(1000);
+				buf.put_slice(&inner[..len]);
+				*inner = &inner[len..];
+				std::task::Poll::Ready(Ok(()))
+			})
+		}
+	}
+
+	#[cfg(feature = "tokio")]
+	#[tokio::test]
+	pub async fn stream_tokio() {
+		use std::io::{Read, Write};
+		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+		let key = XAead::new();
+
+		let sizes = [
+			0,
+			1,
+			stream::CHUNK_SIZE,
+			stream::CHUNK_SIZE + 1,
+			3 * stream::CHUNK_SIZE + 7,
+		];
+		for size in sizes {
+			let data: Vec<u8> = (0..size).map(|i| i as u8).collect();
+
+			let slow = Slow {
+				inner: vec![],
+				ready: false,
+			};
+			let mut writer = stream::EncryptedWriter::new(&key, slow);
+			for piece in data.chunks(3000) {
+				writer.write_all(piece).await.unwrap();
+			}
+			writer.shutdown().await.unwrap();
+			let encrypted = writer.into_inner().inner;
+
+			// the format is the same as the sync one
+			let mut decryptor =
+				stream::Decryptor::new(&key, encrypted.as_slice()).unwrap();
+			let mut decrypted = Vec::new();
+			decryptor.read_to_end(&mut decrypted).unwrap();
+			assert_eq!(decrypted, data);
+
+			let mut encryptor =
+				stream::Encryptor::new(&key, Vec::new()).unwrap();
+			encryptor.write_all(&data).unwrap();
+			let encrypted = encryptor.finish().unwrap();
+
+			let slow = Slow {
+				inner: encrypted.as_slice(),
+				ready: false,
+			};
+			let mut reader = stream::DecryptedReader::new(&key, slow);
+			let mut decrypted = Vec::new();
+			reader.read_to_end(&mut decrypted).await.unwrap();
+			assert_eq!(decrypted, data);
+
+			if size > stream::CHUNK_SIZE {
+				let len =
+					stream::HEADER_LEN + stream::CHUNK_SIZE + stream::TAG_LEN;
+				let mut reader =
+					stream::DecryptedReader::new(&key, &encrypted[..len]);
+				assert!(reader.read_to_end(&mut Vec::new()).await.is_err());
+			}
+
+			let mut tampered = encrypted.clone();
+			*tampered.last_mut().unwrap() ^= 1;
+			let mut reader =
+				stream::DecryptedReader::new(&key, tampered.as_slice());
+			assert_eq!(
+				reader
+					.read_to_end(&mut Vec::new())
+					.await
+					.unwrap_err()
+					.kind(),
+				std::io::ErrorKind::InvalidData
+			);
+		}
+
+		let mut reader = stream::DecryptedReader::new(&key, &[0u8; 5][..]);
+		let err = reader.read_to_end(&mut Vec::new()).await;
+		assert_eq!(err.unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+	}
+
+	#[cfg(feature = "tokio")]
+	#[tokio::test]
+	pub async fn stream_tokio_read_after_tampering() {
+		use std::io::{ErrorKind, Write};
+		use tokio::io::AsyncReadExt;
+
+		let key = XAead::new();
+
+		let mut encryptor = stream::Encryptor::new(&key, Vec::new()).unwrap();
+		encryptor.write_all(&[1u8; 2 * stream::CHUNK_SIZE]).unwrap();
+		let mut encrypted = encryptor.finish().unwrap();
+		*encrypted.last_mut().unwrap() ^= 1;
+
+		let mut reader =
+			stream::DecryptedReader::new(&key, encrypted.as_slice());
+		let mut buf = vec![0u8; 2 * stream::CHUNK_SIZE];
+		reader
+			.read_exact(&mut buf[..stream::CHUNK_SIZE])
+			.await
+			.unwrap();
+		let e = reader.read(&mut buf).await.unwrap_err();
+		assert_eq!(e.kind(), ErrorKind::InvalidData);
+
+		// the last chunk failed, this is not reported as the end
+		buf.fill(0);
+		for _ in 0..3 {
+			let e = reader.read(&mut buf).await.unwrap_err();
+			assert_eq!(e.kind(), ErrorKind::InvalidData);
+		}
+		assert!(buf.iter().all(|b| *b == 0));
+	}
+
+	#[test]
+	pub fn encrypt_file() {
+		let dir = std::env::temp_dir()
+			.join(format!("chuchi-crypto-file-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let plain = dir.join("plain");
+		let enc = dir.join("plain.enc");
+		let dec = dir.join("dec");
+
+		let key = XAead::new();
+		let data: Vec<u8> =
+			(0..stream::CHUNK_SIZE * 2 + 5).map(|i| i as u8).collect();
+		std::fs::write(&plain, &data).unwrap();
+
+		super::encrypt_file(&plain, &enc, &key).unwrap();
+		let encrypted = std::fs::read(&enc).unwrap();
+		assert!(encrypted.starts_with(b"CHUCHI\x01"));
+
+		super::decrypt_file(&enc, &dec, &key).unwrap();
+		assert_eq!(std::fs::read(&dec).unwrap(), data);
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			let mode = std::fs::metadata(&dec).unwrap().permissions().mode();
+			assert_eq!(mode & 0o777, 0o600);
+		}
+		std::fs::remove_file(&dec).unwrap();
+
+		// the header is authenticated with the stream
+		let mut decryptor =
+			stream::Decryptor::new(&key, &encrypted[7..]).unwrap();
+		assert!(std::io::Read::read_to_end(&mut decryptor, &mut Vec::new())
+			.is_err());
+
+		// a modified file does not create the destination
+		let mut tampered = encrypted.clone();
+		*tampered.last_mut().unwrap() ^= 1;
+		std::fs::write(&enc, &tampered).unwrap();
+		let err = super::decrypt_file(&enc, &dec, &key).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+		assert!(!dec.exists());
+
+		let err = super::decrypt_file(&enc, &dec, &XAead::new()).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+		let err = super::decrypt_file(&plain, &dec, &key).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+		// no temporary files are left behind
+		let files = std::fs::read_dir(&dir).unwrap().count();
+		assert_eq!(files, 2);
+
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[cfg(feature = "zeroize")]
+	#[test]
+	pub fn zeroize_on_drop() {
+		fn assert_zeroize_on_drop<T: zeroize::ZeroizeOnDrop>() {}
+
+		assert_zeroize_on_drop::<Keypair>();
+		assert_zeroize_on_drop::<EphemeralKeypair>();
+		assert_zeroize_on_drop::<SharedSecret>();
+		assert_zeroize_on_drop::<Key>();
+		assert_zeroize_on_drop::<SyncKey>();
+		assert_zeroize_on_drop::<Aead>();
+		assert_zeroize_on_drop::<XAead>();
+		assert_zeroize_on_drop::<crate::token::Token<32>>();
+		#[cfg(feature = "hash")]
+		assert_zeroize_on_drop::<crate::hash::HmacKey>();
+	}
+
+	#[cfg(feature = "hash")]
+	#[test]
+	pub fn fingerprint() {
+		let a = Keypair::new();
+		let b = Keypair::new();
+		assert_eq!(a.public().fingerprint(), a.public().fingerprint());
+		assert_ne!(a.public().fingerprint(), b.public().fingerprint());
+		assert_eq!(
+			a.public().fingerprint(),
+			crate::hash::sha2::sha256(a.public().to_bytes())
+		);
+
+		#[cfg(feature = "b64")]
+		{
+			let short = a.public().short_fingerprint();
+			assert_eq!(short.len(), 11);
+			assert!(a
+				.public()
+				.fingerprint()
+				.to_string()
+				.starts_with(&short[..10]));
+		}
+	}
+
+	#[cfg(all(feature = "password", feature = "b64"))]
+	#[test]
+	pub fn export_encrypted() {
+		use crate::error::DecryptError;
+		use crate::hash::password::Params;
+
+		let params = Params {
+			memory_cost: 256,
+			time_cost: 1,
+			parallelism: 1,
+		};
+
+		let keypair = Keypair::new();
+		let exported = keypair
+			.export_encrypted_with("my password", &params)
+			.unwrap();
+		assert!(exported.starts_with("$argon2id$v=19$m=256,t=1,p=1$"));
+
+		let imported =
+			Keypair::import_encrypted(&exported, "my password").unwrap();
+		assert_eq!(imported.to_bytes(), keypair.to_bytes());
+
+		assert_eq!(
+			Keypair::import_encrypted(&exported, "other").unwrap_err(),
+			DecryptError::MacNotEqual
+		);
+		assert_eq!(
+			Keypair::import_encrypted(&keypair.to_string(), "my password")
+				.unwrap_err(),
+			DecryptError::InvalidFormat
+		);
+	}
+
+	#[test]
+	pub fn new_with_rng() {
+		use rand::{rngs::StdRng, SeedableRng};
+
+		let a = Keypair::new_with_rng(&mut StdRng::seed_from_u64(42));
+		let b = Keypair::new_with_rng(&mut StdRng::seed_from_u64(42));
+		assert_eq!(a.to_bytes(), b.to_bytes());
+
+		let a = XAead::new_with_rng(&mut StdRng::seed_from_u64(42));
+		let b = XAead::new_with_rng(&mut StdRng::seed_from_u64(42));
+		assert_eq!(a.to_bytes(), b.to_bytes());
+
+		let a = Nonce::new_with_rng(&mut StdRng::seed_from_u64(42));
+		let b = Nonce::new_with_rng(&mut StdRng::seed_from_u64(42));
+		assert_eq!(a, b);
+	}
+
+	#[test]
+	pub fn ciphertext() {
+		let key = XAead::new();
+
+		let ciphertext = key.seal_ciphertext(b"aad", b"Hey Bob");
+		assert_eq!(ciphertext.version(), Ciphertext::VERSION);
+		assert_eq!(ciphertext.plaintext_len(), 7);
+		assert_eq!(ciphertext.as_ref().len(), 7 + Ciphertext::OVERHEAD);
+
+		let bytes = ciphertext.to_bytes();
+		let ciphertext = Ciphertext::from_bytes(&bytes).unwrap();
+		let msg = key.open_ciphertext(b"aad", &ciphertext).unwrap();
+		assert_eq!(msg, b"Hey Bob");
+
+		assert!(key.open_ciphertext(b"other", &ciphertext).is_err());
+
+		let mut bytes = bytes;
+		bytes[0] = 2;
+		assert!(Ciphertext::from_bytes(&bytes).is_err());
+		assert!(
+			Ciphertext::from_bytes(&bytes[..Ciphertext::OVERHEAD - 1]).is_err()
+		);
+	}
+
+	#[cfg(feature = "b64")]
+	#[test]
+	pub fn ciphertext_b64() {
+		let key = XAead::new();
+		let ciphertext = key.seal_ciphertext(&[], b"Hey Bob");
+
+		let s = ciphertext.to_string();
+		let ciphertext_2 = Ciphertext::from_str(&s).unwrap();
+		assert_eq!(ciphertext, ciphertext_2);
+
+		#[cfg(feature = "serde")]
+		{
+			let json = serde_json::to_string(&ciphertext).unwrap();
+			assert_eq!(json, format!("\"{}\"", s));
+			let bin = bincode::serialize(&ciphertext).unwrap();
+			let ciphertext_2: Ciphertext = bincode::deserialize(&bin).unwrap();
+			assert_eq!(ciphertext, ciphertext_2);
+		}
+	}
+
+	#[cfg(feature = "ml-kem")]
+	#[test]
+	pub fn ml_kem() {
+		use sha2::{Digest, Sha256};
+
+		// the public key was generated with python cryptography from the
+		// same seed
+		let seed: [u8; 64] = std::array::from_fn(|i| i as u8);
+		let bob = ml_kem::Keypair::from(seed);
+		let public = bob.public().to_bytes();
+		assert_eq!(
+			format!("{:x}", Sha256::digest(public)),
+			"0b7934c83125c788995e2ba6bd761e33046b3e40571be53e023309a29f398cc9"
+		);
+
+		let (ciphertext, alice_secret) = bob.public().encapsulate();
+		assert_eq!(bob.decapsulate(&ciphertext), alice_secret);
+		assert_ne!(
+			ml_kem::Keypair::new().decapsulate(&ciphertext),
+			alice_secret
+		);
+
+		let ciphertext_2 =
+			ml_kem::Ciphertext::try_from_slice(&ciphertext.to_bytes()).unwrap();
+		assert_eq!(bob.decapsulate(&ciphertext_2), alice_secret);
+
+		assert_eq!(
+			&ml_kem::PublicKey::try_from_slice(&public).unwrap(),
+			bob.public()
+		);
+		// the coefficients need to be smaller than q
+		let mut unreduced = public;
+		unreduced[..2].copy_from_slice(&[0xff, 0xff]);
+		assert!(ml_kem::PublicKey::try_from_slice(&unreduced).is_err());
+	}
+
+	#[cfg(feature = "ml-kem")]
+	#[test]
+	pub fn hybrid_kem() {
+		let bob = hybrid::Keypair::new();
+		let (ciphertext, alice_secret) = bob.public().encapsulate();
+		assert_eq!(bob.decapsulate(&ciphertext), alice_secret);
+		assert_ne!(
+			hybrid::Keypair::new().decapsulate(&ciphertext),
+			alice_secret
+		);
+
+		// the same seed leads to the same keys
+		let bob_2 = hybrid::Keypair::from(bob.to_bytes());
+		assert_eq!(bob.public(), bob_2.public());
+		assert_eq!(bob_2.decapsulate(&ciphertext), alice_secret);
+
+		let public =
+			hybrid::PublicKey::try_from_slice(&bob.public().to_bytes())
+				.unwrap();
+		assert_eq!(&public, bob.public());
+
+		// replacing the x25519 part changes the secret
+		let mut bytes = ciphertext.to_bytes();
+		let (_, other) = bob.public().encapsulate();
+		let other_x25519 = EphemeralKeypair::new();
+		bytes[ml_kem::Ciphertext::LEN..]
+			.copy_from_slice(other_x25519.public().as_ref());
+		let tampered = hybrid::Ciphertext::try_from_slice(&bytes).unwrap();
+		assert_ne!(bob.decapsulate(&tampered), alice_secret);
+		assert_ne!(other, alice_secret);
+
+		let mut key = alice_secret.derive_key(b"", b"test");
+		let mut msg = *b"hey bob";
+		let mac = key.encrypt(&mut msg);
+		bob.decapsulate(&ciphertext)
+			.derive_key(b"", b"test")
+			.decrypt(&mut msg, &mac)
+			.unwrap();
+		assert_eq!(&msg, b"hey bob");
+	}
+
+	#[cfg(all(feature = "ml-kem", feature = "b64"))]
+	#[test]
+	pub fn hybrid_kem_b64() {
+		let bob = hybrid::Keypair::new();
+		let (ciphertext, _) = bob.public().encapsulate();
+
+		let public =
+			hybrid::PublicKey::from_str(&bob.public().to_string()).unwrap();
+		assert_eq!(&public, bob.public());
+		let ciphertext_2 =
+			hybrid::Ciphertext::from_str(&ciphertext.to_string()).unwrap();
+		assert_eq!(ciphertext, ciphertext_2);
+	}
+
+	#[cfg(feature = "x448")]
+	#[test]
+	pub fn x448() {
+		let alice = Keypair448::new();
+		let bob = Keypair448::new();
+		let alice_secret = alice.diffie_hellman(bob.public());
+		assert_eq!(alice_secret, bob.diffie_hellman(alice.public()));
+		assert!(alice_secret.was_contributory());
+		assert_ne!(alice_secret, alice.diffie_hellman(alice.public()));
+
+		let bob_2 = Keypair448::try_from_slice(&bob.to_bytes()).unwrap();
+		assert_eq!(bob.public(), bob_2.public());
+		assert!(PublicKey448::try_from_slice(&[0; 55]).is_err());
+
+		// zero has a low order
+		let zero = PublicKey448::from([0; 56]);
+		assert!(!alice.diffie_hellman(&zero).was_contributory());
+	}
+
+	#[cfg(all(feature = "x448", feature = "hex"))]
+	#[test]
+	pub fn x448_rfc7748() {
+		// section 6.2
+		let alice = Keypair448::from_hex(
+			"9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28d\
+			d9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b",
+		)
+		.unwrap();
+		let bob = Keypair448::from_hex(
+			"1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d\
+			6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d",
+		)
+		.unwrap();
+		assert_eq!(
+			alice.public().hex().to_string(),
+			"9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c\
+			22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0"
+		);
+		assert_eq!(alice.hex().to_string(), alice.to_hex());
+
+		// sha256 of the raw shared secret from the rfc
+		let shared = crate::decode_hex::<32>(
+			"80f23293e4cabe3226c8c81fcd1c5d359a67a542bba925f89aceb2c064c050cf",
+		)
+		.unwrap();
+		let shared = SharedSecret::from_bytes(shared);
+		assert_eq!(alice.diffie_hellman(bob.public()), shared);
+		assert_eq!(bob.diffie_hellman(alice.public()), shared);
+	}
+
+	#[cfg(all(feature = "x448", feature = "b64"))]
+	#[test]
+	pub fn x448_b64() {
+		let alice = Keypair448::new();
+
+		let alice_2 = Keypair448::from_str(&alice.to_string()).unwrap();
+		assert_eq!(alice.to_bytes(), alice_2.to_bytes());
+		let public =
+			PublicKey448::from_str(&alice.public().to_string()).unwrap();
+		assert_eq!(&public, alice.public());
+	}
 }
@@ -1,8 +1,10 @@
-use crate::error::TryFromError;
+use crate::error::{RandomError, TryFromError};
 use crate::fill_random;
 
 use std::convert::{TryFrom, TryInto};
 
+use rand::{CryptoRng, RngCore};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Nonce {
 	bytes: [u8; 24],
@@ -18,6 +20,20 @@ impl Nonce {
 		this
 	}
 
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new random Nonce using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut bytes = [0u8; 24];
+		rng.fill_bytes(&mut bytes);
+
+		Self { bytes }
+	}
+
 	/// Fills the nonce with new random bytes.
 	pub fn fill_random(&mut self) {
 		fill_random(&mut self.bytes);
@@ -30,10 +46,16 @@ impl Nonce {
 
 	/// ## Panics
 	/// if the slice is not 24 bytes long.
+	#[deprecated(note = "use `try_from_slice` instead")]
 	pub fn from_slice(slice: &[u8]) -> Self {
 		slice.try_into().unwrap()
 	}
 
+	/// Returns an error if the slice is not 24 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
 	pub fn to_bytes(&self) -> [u8; 24] {
 		self.bytes
 	}
@@ -73,3 +95,82 @@ impl AsRef<[u8]> for Nonce {
 		&self.bytes
 	}
 }
+
+/// A 12 byte nonce as used by `Aead`, `Aes256Gcm` and `Aes256GcmSiv`.
+///
+/// Random 12 byte nonces should not be used for more than 2^32 messages
+/// with the same key, use a [`NonceSequence`](super::NonceSequence) to
+/// keep track of that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Nonce12 {
+	bytes: [u8; 12],
+}
+
+impl Nonce12 {
+	pub const LEN: usize = 12;
+
+	/// Creates a new random Nonce.
+	pub fn new() -> Self {
+		let mut bytes = [0u8; 12];
+		fill_random(&mut bytes);
+
+		Self { bytes }
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new random Nonce using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut bytes = [0u8; 12];
+		rng.fill_bytes(&mut bytes);
+
+		Self { bytes }
+	}
+
+	/// Returns an error if the slice is not 12 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 12] {
+		self.bytes
+	}
+}
+
+impl From<[u8; 12]> for Nonce12 {
+	fn from(bytes: [u8; 12]) -> Self {
+		Self { bytes }
+	}
+}
+
+impl From<&[u8; 12]> for Nonce12 {
+	fn from(bytes: &[u8; 12]) -> Self {
+		Self { bytes: *bytes }
+	}
+}
+
+impl From<&Nonce12> for Nonce12 {
+	fn from(nonce: &Nonce12) -> Self {
+		*nonce
+	}
+}
+
+impl TryFrom<&[u8]> for Nonce12 {
+	type Error = TryFromError;
+
+	fn try_from(s: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; 12]>::try_from(s)
+			.map_err(TryFromError::from_any)
+			.map(Nonce12::from)
+	}
+}
+
+impl AsRef<[u8]> for Nonce12 {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
@@ -0,0 +1,178 @@
+use super::{Nonce, Nonce12};
+use crate::error::NonceExhausted;
+
+use std::marker::PhantomData;
+
+mod private {
+	pub trait Sealed {}
+}
+
+/// A nonce type which can be returned by a [`NonceSequence`].
+///
+/// Implemented for [`Nonce`] and [`Nonce12`].
+pub trait SequenceNonce: private::Sealed + Sized {
+	#[doc(hidden)]
+	fn random() -> Self;
+
+	#[doc(hidden)]
+	fn with_counter(&self, counter: u64) -> Self;
+
+	/// The maximum number of random nonces which should be used with the
+	/// same key.
+	#[doc(hidden)]
+	const RANDOM_LIMIT: u64;
+}
+
+impl private::Sealed for Nonce {}
+
+impl SequenceNonce for Nonce {
+	fn random() -> Self {
+		Nonce::new()
+	}
+
+	fn with_counter(&self, counter: u64) -> Self {
+		let mut bytes = self.to_bytes();
+		bytes[Nonce::LEN - 8..].copy_from_slice(&counter.to_be_bytes());
+
+		bytes.into()
+	}
+
+	const RANDOM_LIMIT: u64 = u64::MAX;
+}
+
+impl private::Sealed for Nonce12 {}
+
+impl SequenceNonce for Nonce12 {
+	fn random() -> Self {
+		Nonce12::new()
+	}
+
+	fn with_counter(&self, counter: u64) -> Self {
+		let mut bytes = self.to_bytes();
+		bytes[Nonce12::LEN - 8..].copy_from_slice(&counter.to_be_bytes());
+
+		bytes.into()
+	}
+
+	const RANDOM_LIMIT: u64 = 1 << 32;
+}
+
+/// A source of nonces which never returns the same nonce twice.
+///
+/// Use one sequence per key, once it is exhausted the key needs to be
+/// replaced.
+pub trait NonceSequence {
+	type Nonce;
+
+	/// Returns the next nonce or an error if no unused nonce is left.
+	fn next_nonce(&mut self) -> Result<Self::Nonce, NonceExhausted>;
+}
+
+/// Nonces made of a fixed prefix followed by a 64 bit big endian counter
+/// starting at zero.
+///
+/// The counter makes it possible for the receiver to check that the
+/// nonces are monotonic. If multiple senders use the same key each needs
+/// its own prefix.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::cipher::{CounterNonces, Nonce12, NonceSequence};
+///
+/// let mut nonces = CounterNonces::new(Nonce12::from([0; 12]));
+/// let first = nonces.next_nonce().unwrap();
+/// let second = nonces.next_nonce().unwrap();
+/// assert_ne!(first, second);
+/// assert_eq!(nonces.counter(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CounterNonces<N> {
+	prefix: N,
+	counter: u64,
+	exhausted: bool,
+}
+
+impl<N: SequenceNonce> CounterNonces<N> {
+	/// Creates a sequence using all but the last 8 bytes of the nonce as
+	/// the prefix.
+	pub fn new(prefix: N) -> Self {
+		Self {
+			prefix,
+			counter: 0,
+			exhausted: false,
+		}
+	}
+
+	/// Creates a sequence with a random prefix.
+	pub fn random() -> Self {
+		Self::new(N::random())
+	}
+
+	/// Returns the counter of the next nonce, which is the number of
+	/// nonces returned so far.
+	pub fn counter(&self) -> u64 {
+		self.counter
+	}
+}
+
+impl<N: SequenceNonce> NonceSequence for CounterNonces<N> {
+	type Nonce = N;
+
+	fn next_nonce(&mut self) -> Result<N, NonceExhausted> {
+		if self.exhausted {
+			return Err(NonceExhausted::new());
+		}
+
+		let nonce = self.prefix.with_counter(self.counter);
+		match self.counter.checked_add(1) {
+			Some(counter) => self.counter = counter,
+			None => self.exhausted = true,
+		}
+
+		Ok(nonce)
+	}
+}
+
+/// Random nonces which are limited to a safe number of messages.
+///
+/// A `Nonce12` sequence is exhausted after 2^32 nonces, after which the
+/// probability of a collision is no longer negligible. A 24 byte `Nonce`
+/// sequence is practically never exhausted.
+#[derive(Debug, Clone)]
+pub struct RandomNonces<N> {
+	count: u64,
+	marker: PhantomData<fn() -> N>,
+}
+
+impl<N: SequenceNonce> RandomNonces<N> {
+	pub fn new() -> Self {
+		Self {
+			count: 0,
+			marker: PhantomData,
+		}
+	}
+
+	/// Returns the number of nonces returned so far.
+	pub fn count(&self) -> u64 {
+		self.count
+	}
+}
+
+impl<N: SequenceNonce> Default for RandomNonces<N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<N: SequenceNonce> NonceSequence for RandomNonces<N> {
+	type Nonce = N;
+
+	fn next_nonce(&mut self) -> Result<N, NonceExhausted> {
+		if self.count >= N::RANDOM_LIMIT {
+			return Err(NonceExhausted::new());
+		}
+
+		self.count += 1;
+		Ok(N::random())
+	}
+}
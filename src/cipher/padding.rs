@@ -0,0 +1,80 @@
+//! Padding to hide the length of a message before encrypting it.
+//!
+//! Uses Padmé from "Reducing Metadata Leakage from Encrypted Files and
+//! Communication with PURBs", which rounds a length up so that only
+//! `O(log log L)` bits of it leak, with at most 12% overhead. Short
+//! messages are padded relatively more.
+//!
+//! The message is followed by a `0x80` byte and zeros up to the padded
+//! length (ISO/IEC 7816-4), so the original message can be recovered.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::padding;
+//!
+//! let padded = padding::pad(b"yes");
+//! assert_eq!(padded.len(), padding::padded_len(3));
+//! assert_eq!(padding::unpad(&padded).unwrap(), b"yes");
+//! ```
+
+use crate::error::DecryptError;
+
+/// Returns the length `pad` creates for a message of `len` bytes.
+pub fn padded_len(len: usize) -> usize {
+	padme(len + 1)
+}
+
+fn padme(len: usize) -> usize {
+	if len < 2 {
+		return len;
+	}
+
+	let e = usize::BITS - 1 - len.leading_zeros();
+	let s = u32::BITS - e.leading_zeros();
+	let mask = (1usize << (e - s)) - 1;
+
+	(len + mask) & !mask
+}
+
+/// Returns the padded message.
+pub fn pad(msg: &[u8]) -> Vec<u8> {
+	let mut padded = Vec::with_capacity(padded_len(msg.len()));
+	padded.extend_from_slice(msg);
+	pad_in_place(&mut padded);
+
+	padded
+}
+
+/// Pads the message in place.
+pub fn pad_in_place(msg: &mut Vec<u8>) {
+	let len = padded_len(msg.len());
+	msg.push(0x80);
+	msg.resize(len, 0);
+}
+
+/// Returns the message without the padding.
+///
+/// Returns `DecryptError::InvalidFormat` if the padding is not valid.
+pub fn unpad(padded: &[u8]) -> Result<&[u8], DecryptError> {
+	let pos = padded
+		.iter()
+		.rposition(|b| *b != 0)
+		.ok_or(DecryptError::InvalidFormat)?;
+
+	if padded[pos] != 0x80 || padded_len(pos) != padded.len() {
+		return Err(DecryptError::InvalidFormat);
+	}
+
+	Ok(&padded[..pos])
+}
+
+/// Removes the padding in place.
+///
+/// Returns `DecryptError::InvalidFormat` if the padding is not valid, in
+/// which case the message is left unchanged.
+pub fn unpad_in_place(padded: &mut Vec<u8>) -> Result<(), DecryptError> {
+	let len = unpad(padded)?.len();
+	padded.truncate(len);
+
+	Ok(())
+}
@@ -0,0 +1,132 @@
+use super::{Keypair, MacNotEqual, Nonce, PublicKey};
+use crate::error::LowOrderPublicKey;
+
+use std::fmt;
+
+use zeroize::Zeroize;
+
+use crypto_box::aead::Aead as _;
+use crypto_box::SalsaBox;
+
+/// The key between a keypair and a peer, derived once and reused for every
+/// message.
+///
+/// Computing a Diffie-Hellman exchange is a lot slower than encrypting a
+/// short message, so a server which talks to the same peer over and over
+/// should keep this around instead of calling `diffie_hellman` each time.
+///
+/// Compatible with libsodium's `crypto_box_beforenm`, `encrypt` and
+/// `decrypt` produce the same output as `crypto_box_easy_afternm` and
+/// `crypto_box_open_easy_afternm`.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::cipher::{Keypair, PrecomputedKey};
+///
+/// let alice = Keypair::new();
+/// let bob = Keypair::new();
+///
+/// let alice_key = PrecomputedKey::new(&alice, bob.public()).unwrap();
+/// let bob_key = PrecomputedKey::new(&bob, alice.public()).unwrap();
+///
+/// let msg = alice_key.encrypt_to(b"Hey Bob");
+/// assert_eq!(bob_key.decrypt_from(&msg).unwrap(), b"Hey Bob");
+/// ```
+pub struct PrecomputedKey {
+	inner: SalsaBox,
+	peer: PublicKey,
+}
+
+impl PrecomputedKey {
+	pub const TAG_LEN: usize = 16;
+
+	/// Derives the key, returns an error if the public key of the peer has
+	/// a low order.
+	pub fn new(
+		keypair: &Keypair,
+		peer: &PublicKey,
+	) -> Result<Self, LowOrderPublicKey> {
+		if !keypair.diffie_hellman(peer).was_contributory() {
+			return Err(LowOrderPublicKey::new());
+		}
+
+		let mut secret = keypair.to_bytes();
+		let inner = SalsaBox::new(
+			&crypto_box::PublicKey::from(peer.to_bytes()),
+			&crypto_box::SecretKey::from(secret),
+		);
+		secret.zeroize();
+
+		Ok(Self {
+			inner,
+			peer: peer.clone(),
+		})
+	}
+
+	/// Returns the public key of the peer.
+	pub fn peer(&self) -> &PublicKey {
+		&self.peer
+	}
+
+	/// Encrypts the plaintext returning the tag followed by the ciphertext.
+	///
+	/// ## Warning
+	/// Never use the same nonce twice with the same key, both sides share
+	/// the key.
+	pub fn encrypt(&self, nonce: &Nonce, plaintext: &[u8]) -> Vec<u8> {
+		self.inner
+			.encrypt(nonce.as_ref().into(), plaintext)
+			// only fails if the plaintext is longer than 256GiB
+			.expect("plaintext too long")
+	}
+
+	/// Decrypts a ciphertext which was created with `encrypt`.
+	pub fn decrypt(
+		&self,
+		nonce: &Nonce,
+		ciphertext: &[u8],
+	) -> Result<Vec<u8>, MacNotEqual> {
+		self.inner
+			.decrypt(nonce.as_ref().into(), ciphertext)
+			.map_err(|_| MacNotEqual)
+	}
+
+	/// Encrypts a message to the peer with a random nonce.
+	///
+	/// Returns the nonce followed by the output of `encrypt`.
+	pub fn encrypt_to(&self, plaintext: &[u8]) -> Vec<u8> {
+		let nonce = Nonce::new();
+		let ciphertext = self.encrypt(&nonce, plaintext);
+
+		let mut msg = Vec::with_capacity(Nonce::LEN + ciphertext.len());
+		msg.extend_from_slice(nonce.as_ref());
+		msg.extend_from_slice(&ciphertext);
+
+		msg
+	}
+
+	/// Decrypts a message from the peer which was created with
+	/// `encrypt_to`.
+	pub fn decrypt_from(&self, msg: &[u8]) -> Result<Vec<u8>, MacNotEqual> {
+		if msg.len() < Nonce::LEN + Self::TAG_LEN {
+			return Err(MacNotEqual);
+		}
+
+		let (nonce, ciphertext) = msg.split_at(Nonce::LEN);
+		let nonce = Nonce::try_from_slice(nonce).unwrap();
+
+		self.decrypt(&nonce, ciphertext)
+	}
+}
+
+impl fmt::Debug for PrecomputedKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PrecomputedKey")
+			.field("peer", &self.peer)
+			.finish_non_exhaustive()
+	}
+}
+
+// the key zeroizes itself on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for PrecomputedKey {}
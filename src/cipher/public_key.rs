@@ -1,4 +1,6 @@
-#[cfg(feature = "b64")]
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex"))]
 use crate::error::DecodeError;
 use crate::error::TryFromError;
 
@@ -33,10 +35,16 @@ impl PublicKey {
 
 	/// ## Panics
 	/// if the slice is not 32 bytes long.
+	#[deprecated(note = "use `try_from_slice` instead")]
 	pub fn from_slice(slice: &[u8]) -> Self {
 		slice.try_into().unwrap()
 	}
 
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
 	pub fn to_bytes(&self) -> [u8; 32] {
 		self.as_ref().try_into().unwrap()
 	}
@@ -44,6 +52,20 @@ impl PublicKey {
 	pub fn inner(&self) -> &x::PublicKey {
 		&self.inner
 	}
+
+	/// Returns the SHA-256 hash of the public key bytes, which can be used
+	/// as a stable identifier for the key.
+	#[cfg(feature = "hash")]
+	pub fn fingerprint(&self) -> crate::hash::sha2::Sha256Hash {
+		crate::hash::sha2::sha256(self.to_bytes())
+	}
+
+	/// Returns the first 8 bytes of the fingerprint as base64, useful in
+	/// logs.
+	#[cfg(all(feature = "hash", feature = "b64"))]
+	pub fn short_fingerprint(&self) -> String {
+		URL_SAFE_NO_PAD.encode(&self.fingerprint().as_ref()[..8])
+	}
 }
 
 #[cfg(not(feature = "b64"))]
@@ -125,14 +147,41 @@ impl AsRef<[u8]> for PublicKey {
 	}
 }
 
-#[cfg(all(feature = "b64", feature = "serde"))]
-mod impl_serde {
+#[cfg(feature = "hex")]
+impl PublicKey {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
 
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).map(Self::from)
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, PublicKey> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		crate::encoding::fmt_hex(self.get().as_ref(), f)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
 	use super::*;
 
+	#[cfg(feature = "b64")]
 	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
 	use std::str::FromStr;
 
+	#[cfg(feature = "b64")]
 	use _serde::de::Error;
 	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -141,7 +190,12 @@ mod impl_serde {
 		where
 			S: Serializer,
 		{
-			serializer.collect_str(&self)
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
 		}
 	}
 
@@ -150,8 +204,78 @@ mod impl_serde {
 		where
 			D: Deserializer<'de>,
 		{
-			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
-			Self::from_str(s.as_ref()).map_err(D::Error::custom)
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			Ok(Self::from(bytes))
+		}
+	}
+}
+
+#[cfg(feature = "protobuf")]
+mod impl_protobuf {
+	use super::*;
+
+	use protopuffer::{
+		bytes::BytesWrite,
+		decode::{DecodeError, DecodeMessage, FieldKind},
+		encode::{
+			EncodeError, EncodeMessage, FieldOpt, MessageEncoder, SizeBuilder,
+		},
+		WireType,
+	};
+
+	impl EncodeMessage for PublicKey {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn is_default(&self) -> bool {
+			false
+		}
+
+		fn encoded_size(
+			&mut self,
+			field: Option<FieldOpt>,
+			builder: &mut SizeBuilder,
+		) -> Result<(), EncodeError> {
+			self.to_bytes().encoded_size(field, builder)
+		}
+
+		fn encode<B>(
+			&mut self,
+			field: Option<FieldOpt>,
+			encoder: &mut MessageEncoder<B>,
+		) -> Result<(), EncodeError>
+		where
+			B: BytesWrite,
+		{
+			self.to_bytes().encode(field, encoder)
+		}
+	}
+
+	impl<'m> DecodeMessage<'m> for PublicKey {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn decode_default() -> Self {
+			Self::from([0u8; 32])
+		}
+
+		fn merge(
+			&mut self,
+			kind: FieldKind<'m>,
+			is_field: bool,
+		) -> Result<(), DecodeError> {
+			let mut t = self.to_bytes();
+			t.merge(kind, is_field)?;
+
+			*self = Self::from(t);
+
+			Ok(())
 		}
 	}
 }
@@ -172,6 +296,14 @@ mod impl_postgres {
 		where
 			Self: Sized,
 		{
+			if *ty == Type::BYTEA {
+				return <&[u8] as ToSql>::to_sql(
+					&self.to_bytes().as_ref(),
+					ty,
+					out,
+				);
+			}
+
 			self.to_string().to_sql(ty, out)
 		}
 
@@ -179,7 +311,7 @@ mod impl_postgres {
 		where
 			Self: Sized,
 		{
-			<&str as ToSql>::accepts(ty)
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
 		}
 
 		to_sql_checked!();
@@ -190,12 +322,60 @@ mod impl_postgres {
 			ty: &Type,
 			raw: &'r [u8],
 		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Self::try_from(raw).map_err(Into::into);
+			}
+
 			let s = <&str as FromSql>::from_sql(ty, raw)?;
 			s.parse().map_err(Into::into)
 		}
 
 		fn accepts(ty: &Type) -> bool {
-			<&str as FromSql>::accepts(ty)
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "sqlx"))]
+mod impl_sqlx {
+	use super::*;
+
+	use sqlx::encode::IsNull;
+	use sqlx::error::BoxDynError;
+	use sqlx::{Database, Decode, Encode, Type};
+
+	impl<DB: Database> Type<DB> for PublicKey
+	where
+		String: Type<DB>,
+	{
+		fn type_info() -> DB::TypeInfo {
+			<String as Type<DB>>::type_info()
+		}
+
+		fn compatible(ty: &DB::TypeInfo) -> bool {
+			<String as Type<DB>>::compatible(ty)
+		}
+	}
+
+	impl<'q, DB: Database> Encode<'q, DB> for PublicKey
+	where
+		String: Encode<'q, DB>,
+	{
+		fn encode_by_ref(
+			&self,
+			buf: &mut DB::ArgumentBuffer<'q>,
+		) -> Result<IsNull, BoxDynError> {
+			self.to_string().encode(buf)
+		}
+	}
+
+	impl<'r, DB: Database> Decode<'r, DB> for PublicKey
+	where
+		&'r str: Decode<'r, DB>,
+	{
+		fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+			let s = <&str as Decode<DB>>::decode(value)?;
+			s.parse().map_err(Into::into)
 		}
 	}
 }
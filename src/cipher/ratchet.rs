@@ -0,0 +1,565 @@
+//! The Double Ratchet algorithm as used by Signal for end-to-end encrypted
+//! messaging.
+//!
+//! Both parties need to agree on a 32 byte secret first, for example with
+//! X3DH or by deriving it from a [`SharedSecret`](super::SharedSecret).
+//! The initiator also needs to know the ratchet public key of the
+//! responder.
+//!
+//! Every message is encrypted with its own key. Keys are derived with a
+//! symmetric ratchet and every time the sending party changes a new
+//! X25519 exchange is mixed in, which provides forward secrecy and
+//! recovery after a compromise. Messages may arrive out of order, the keys
+//! of up to `MAX_SKIP` skipped messages are kept.
+//!
+//! ## Construction
+//! Follows the [specification](https://signal.org/docs/specifications/doubleratchet/)
+//! with HKDF-SHA256 as `KDF_RK`, HMAC-SHA256 as `KDF_CK` and
+//! ChaCha20-Poly1305 with a zero nonce as the message cipher, the header
+//! is authenticated as part of the associated data.
+//!
+//! ## Format
+//! `public_key (32 bytes) || previous_chain_len (4 bytes) ||
+//! message_number (4 bytes) || ciphertext || tag (16 bytes)`
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::ratchet::DoubleRatchet;
+//! use chuchi_crypto::cipher::Keypair;
+//!
+//! // agreed on beforehand
+//! let secret = [1u8; 32];
+//! let bob_keypair = Keypair::new();
+//!
+//! let mut alice =
+//!     DoubleRatchet::new_initiator(&secret, bob_keypair.public()).unwrap();
+//! let mut bob = DoubleRatchet::new_responder(&secret, bob_keypair);
+//!
+//! let msg = alice.encrypt(b"", b"Hey Bob").unwrap();
+//! assert_eq!(bob.decrypt(b"", &msg).unwrap(), b"Hey Bob");
+//!
+//! let reply = bob.encrypt(b"", b"Hey Alice").unwrap();
+//! assert_eq!(alice.decrypt(b"", &reply).unwrap(), b"Hey Alice");
+//!
+//! // the state can be stored and restored
+//! let bytes = alice.to_bytes();
+//! let alice = DoubleRatchet::from_bytes(&bytes).unwrap();
+//! ```
+
+use super::{Aead, Keypair, Nonce12, PublicKey};
+use crate::error::{DecodeError, RatchetError};
+
+use std::convert::TryInto;
+use std::fmt;
+
+use zeroize::Zeroize;
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// The maximum number of message keys which are skipped in one chain and
+/// the maximum number of skipped keys which are kept.
+pub const MAX_SKIP: u32 = 1000;
+
+/// The length of the header which is prepended to every message.
+pub const HEADER_LEN: usize = 40;
+
+const VERSION: u8 = 1;
+
+#[derive(Clone)]
+struct SkippedKey {
+	public_key: [u8; 32],
+	n: u32,
+	key: [u8; 32],
+}
+
+impl Drop for SkippedKey {
+	fn drop(&mut self) {
+		self.key.zeroize();
+	}
+}
+
+/// The state of one side of a Double Ratchet session.
+///
+/// See the [module documentation](self).
+#[derive(Clone)]
+pub struct DoubleRatchet {
+	dh_send: Keypair,
+	dh_recv: Option<PublicKey>,
+	root_key: [u8; 32],
+	chain_send: Option<[u8; 32]>,
+	chain_recv: Option<[u8; 32]>,
+	n_send: u32,
+	n_recv: u32,
+	prev_n_send: u32,
+	skipped: Vec<SkippedKey>,
+}
+
+impl DoubleRatchet {
+	/// Creates the state of the party which sends the first message.
+	///
+	/// Returns an error if the public key has a low order.
+	pub fn new_initiator(
+		secret: &[u8; 32],
+		remote: &PublicKey,
+	) -> Result<Self, RatchetError> {
+		let dh_send = Keypair::new();
+		let (root_key, chain_send) = kdf_root(secret, &dh(&dh_send, remote)?);
+
+		Ok(Self {
+			dh_send,
+			dh_recv: Some(remote.clone()),
+			root_key,
+			chain_send: Some(chain_send),
+			chain_recv: None,
+			n_send: 0,
+			n_recv: 0,
+			prev_n_send: 0,
+			skipped: vec![],
+		})
+	}
+
+	/// Creates the state of the party which receives the first message,
+	/// it can only send messages after that.
+	pub fn new_responder(secret: &[u8; 32], keypair: Keypair) -> Self {
+		Self {
+			dh_send: keypair,
+			dh_recv: None,
+			root_key: *secret,
+			chain_send: None,
+			chain_recv: None,
+			n_send: 0,
+			n_recv: 0,
+			prev_n_send: 0,
+			skipped: vec![],
+		}
+	}
+
+	/// Returns the current ratchet public key.
+	pub fn public(&self) -> &PublicKey {
+		self.dh_send.public()
+	}
+
+	/// Encrypts a message returning the header followed by the ciphertext
+	/// and the tag.
+	///
+	/// Returns `RatchetError::NotReady` if this side is the responder and
+	/// has not received a message yet.
+	pub fn encrypt(
+		&mut self,
+		aad: &[u8],
+		plaintext: &[u8],
+	) -> Result<Vec<u8>, RatchetError> {
+		let chain = self.chain_send.as_mut().ok_or(RatchetError::NotReady)?;
+		let mut message_key = kdf_chain(chain);
+
+		let mut header = [0u8; HEADER_LEN];
+		header[..32].copy_from_slice(self.dh_send.public().as_ref());
+		header[32..36].copy_from_slice(&self.prev_n_send.to_be_bytes());
+		header[36..].copy_from_slice(&self.n_send.to_be_bytes());
+		self.n_send = self
+			.n_send
+			.checked_add(1)
+			.expect("too many messages in one chain");
+
+		let ciphertext = Aead::from(message_key).encrypt(
+			Nonce12::from([0; 12]),
+			&associated_data(aad, &header),
+			plaintext,
+		);
+		message_key.zeroize();
+
+		let mut msg = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+		msg.extend_from_slice(&header);
+		msg.extend_from_slice(&ciphertext);
+
+		Ok(msg)
+	}
+
+	/// Decrypts a message created with `encrypt` by the other side.
+	///
+	/// If an error is returned the state is not changed.
+	pub fn decrypt(
+		&mut self,
+		aad: &[u8],
+		msg: &[u8],
+	) -> Result<Vec<u8>, RatchetError> {
+		if msg.len() < HEADER_LEN + Aead::TAG_LEN {
+			return Err(RatchetError::InvalidFormat);
+		}
+
+		let (header, ciphertext) = msg.split_at(HEADER_LEN);
+		let public_key: [u8; 32] = header[..32].try_into().unwrap();
+		let prev_n = u32::from_be_bytes(header[32..36].try_into().unwrap());
+		let n = u32::from_be_bytes(header[36..].try_into().unwrap());
+
+		// all changes are made on a copy which replaces the state only if
+		// the message could be decrypted
+		let mut state = self.clone();
+
+		let mut message_key = match state.take_skipped(&public_key, n) {
+			Some(key) => key,
+			None => {
+				let is_current = state
+					.dh_recv
+					.as_ref()
+					.is_some_and(|k| k.as_ref() == public_key);
+
+				if !is_current {
+					state.skip_until(prev_n)?;
+					state.dh_ratchet(PublicKey::from(public_key))?;
+				} else if n < state.n_recv {
+					return Err(RatchetError::Replayed);
+				}
+
+				state.skip_until(n)?;
+				let key = kdf_chain(state.chain_recv.as_mut().unwrap());
+				state.n_recv += 1;
+
+				key
+			}
+		};
+
+		let plaintext = Aead::from(message_key)
+			.decrypt(
+				Nonce12::from([0; 12]),
+				&associated_data(aad, header),
+				ciphertext,
+			)
+			.map_err(|_| RatchetError::MacNotEqual);
+		message_key.zeroize();
+
+		if plaintext.is_ok() {
+			*self = state;
+		}
+
+		plaintext
+	}
+
+	fn take_skipped(
+		&mut self,
+		public_key: &[u8; 32],
+		n: u32,
+	) -> Option<[u8; 32]> {
+		let pos = self
+			.skipped
+			.iter()
+			.position(|s| &s.public_key == public_key && s.n == n)?;
+
+		Some(self.skipped.remove(pos).key)
+	}
+
+	/// Stores the keys of all messages in the receiving chain up to `until`.
+	fn skip_until(&mut self, until: u32) -> Result<(), RatchetError> {
+		let Some(chain) = self.chain_recv.as_mut() else {
+			return Ok(());
+		};
+
+		if until.saturating_sub(self.n_recv) > MAX_SKIP {
+			return Err(RatchetError::TooManySkipped);
+		}
+
+		// a chain key only exists if a public key was received
+		let public_key = self.dh_recv.as_ref().unwrap().to_bytes();
+		while self.n_recv < until {
+			self.skipped.push(SkippedKey {
+				public_key,
+				n: self.n_recv,
+				key: kdf_chain(chain),
+			});
+			self.n_recv += 1;
+		}
+
+		if self.skipped.len() > MAX_SKIP as usize {
+			let remove = self.skipped.len() - MAX_SKIP as usize;
+			self.skipped.drain(..remove);
+		}
+
+		Ok(())
+	}
+
+	fn dh_ratchet(
+		&mut self,
+		public_key: PublicKey,
+	) -> Result<(), RatchetError> {
+		self.prev_n_send = self.n_send;
+		self.n_send = 0;
+		self.n_recv = 0;
+
+		let (root_key, chain_recv) =
+			kdf_root(&self.root_key, &dh(&self.dh_send, &public_key)?);
+		self.dh_send = Keypair::new();
+		let (root_key, chain_send) =
+			kdf_root(&root_key, &dh(&self.dh_send, &public_key)?);
+
+		self.root_key = root_key;
+		self.chain_recv = Some(chain_recv);
+		self.chain_send = Some(chain_send);
+		self.dh_recv = Some(public_key);
+
+		Ok(())
+	}
+
+	/// Serializes the state.
+	///
+	/// ## Warning
+	/// The bytes contain the secret keys of the session, store them
+	/// encrypted.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(
+			1 + 32 * 6 + 4 * 4 + self.skipped.len() * (32 + 4 + 32),
+		);
+
+		bytes.push(VERSION);
+		bytes.extend_from_slice(&self.dh_send.to_bytes());
+		write_option(&mut bytes, self.dh_recv.as_ref().map(|k| k.to_bytes()));
+		bytes.extend_from_slice(&self.root_key);
+		write_option(&mut bytes, self.chain_send);
+		write_option(&mut bytes, self.chain_recv);
+		bytes.extend_from_slice(&self.n_send.to_be_bytes());
+		bytes.extend_from_slice(&self.n_recv.to_be_bytes());
+		bytes.extend_from_slice(&self.prev_n_send.to_be_bytes());
+		bytes.extend_from_slice(&(self.skipped.len() as u32).to_be_bytes());
+		for skipped in &self.skipped {
+			bytes.extend_from_slice(&skipped.public_key);
+			bytes.extend_from_slice(&skipped.n.to_be_bytes());
+			bytes.extend_from_slice(&skipped.key);
+		}
+
+		bytes
+	}
+
+	/// Restores a state serialized with `to_bytes`.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let mut reader = Reader(bytes);
+
+		if reader.u8()? != VERSION {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		let mut secret = reader.array()?;
+		let dh_send = Keypair::from(secret);
+		secret.zeroize();
+
+		let mut this = Self {
+			dh_send,
+			dh_recv: reader.option()?.map(PublicKey::from),
+			root_key: reader.array()?,
+			chain_send: reader.option()?,
+			chain_recv: reader.option()?,
+			n_send: reader.u32()?,
+			n_recv: reader.u32()?,
+			prev_n_send: reader.u32()?,
+			skipped: vec![],
+		};
+
+		let len = reader.u32()?;
+		if len > MAX_SKIP {
+			return Err(DecodeError::InvalidBytes);
+		}
+		for _ in 0..len {
+			this.skipped.push(SkippedKey {
+				public_key: reader.array()?,
+				n: reader.u32()?,
+				key: reader.array()?,
+			});
+		}
+
+		if !reader.0.is_empty() {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		Ok(this)
+	}
+}
+
+impl fmt::Debug for DoubleRatchet {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DoubleRatchet")
+			.field("public", self.dh_send.public())
+			.field("remote", &self.dh_recv)
+			.field("n_send", &self.n_send)
+			.field("n_recv", &self.n_recv)
+			.field("skipped", &self.skipped.len())
+			.finish()
+	}
+}
+
+impl Drop for DoubleRatchet {
+	fn drop(&mut self) {
+		self.root_key.zeroize();
+		if let Some(k) = self.chain_send.as_mut() {
+			k.zeroize();
+		}
+		if let Some(k) = self.chain_recv.as_mut() {
+			k.zeroize();
+		}
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for DoubleRatchet {}
+
+fn dh(
+	keypair: &Keypair,
+	public_key: &PublicKey,
+) -> Result<[u8; 32], RatchetError> {
+	let secret = keypair.diffie_hellman(public_key);
+	if !secret.was_contributory() {
+		return Err(RatchetError::InvalidPublicKey);
+	}
+
+	Ok(secret.as_slice().try_into().unwrap())
+}
+
+/// Returns the next root key and a chain key.
+fn kdf_root(root_key: &[u8; 32], dh_out: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+	let hkdf = Hkdf::<Sha256>::new(Some(root_key), dh_out);
+
+	let mut okm = [0u8; 64];
+	hkdf.expand(b"chuchi-crypto double ratchet", &mut okm)
+		// 64 bytes is way below the max size of 255 * 32
+		.unwrap();
+
+	let mut root_key = [0u8; 32];
+	let mut chain_key = [0u8; 32];
+	root_key.copy_from_slice(&okm[..32]);
+	chain_key.copy_from_slice(&okm[32..]);
+	okm.zeroize();
+
+	(root_key, chain_key)
+}
+
+/// Advances the chain key and returns the message key.
+fn kdf_chain(chain_key: &mut [u8; 32]) -> [u8; 32] {
+	let message_key = hmac(chain_key, 0x01);
+	*chain_key = hmac(chain_key, 0x02);
+
+	message_key
+}
+
+fn hmac(key: &[u8; 32], byte: u8) -> [u8; 32] {
+	let mut hmac = Hmac::<Sha256>::new_from_slice(key)
+		// hmac accepts keys of any length
+		.unwrap();
+	hmac.update(&[byte]);
+
+	hmac.finalize().into_bytes().into()
+}
+
+fn associated_data(aad: &[u8], header: &[u8]) -> Vec<u8> {
+	let mut data = Vec::with_capacity(8 + aad.len() + header.len());
+	data.extend_from_slice(&(aad.len() as u64).to_be_bytes());
+	data.extend_from_slice(aad);
+	data.extend_from_slice(header);
+
+	data
+}
+
+fn write_option(bytes: &mut Vec<u8>, value: Option<[u8; 32]>) {
+	match value {
+		Some(v) => {
+			bytes.push(1);
+			bytes.extend_from_slice(&v);
+		}
+		None => bytes.push(0),
+	}
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+	fn take(&mut self, len: usize) -> Result<&[u8], DecodeError> {
+		if self.0.len() < len {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let (a, b) = self.0.split_at(len);
+		self.0 = b;
+		Ok(a)
+	}
+
+	fn u8(&mut self) -> Result<u8, DecodeError> {
+		self.take(1).map(|b| b[0])
+	}
+
+	fn u32(&mut self) -> Result<u32, DecodeError> {
+		self.take(4)
+			.map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+	}
+
+	fn array(&mut self) -> Result<[u8; 32], DecodeError> {
+		self.take(32).map(|b| b.try_into().unwrap())
+	}
+
+	fn option(&mut self) -> Result<Option<[u8; 32]>, DecodeError> {
+		match self.u8()? {
+			0 => Ok(None),
+			1 => self.array().map(Some),
+			_ => Err(DecodeError::InvalidBytes),
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	#[cfg(feature = "b64")]
+	use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+	impl Serialize for DoubleRatchet {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			let mut bytes = self.to_bytes();
+
+			#[cfg(feature = "b64")]
+			let r = if serializer.is_human_readable() {
+				serializer.collect_str(&base64::display::Base64Display::new(
+					&bytes,
+					&URL_SAFE_NO_PAD,
+				))
+			} else {
+				serializer.serialize_bytes(&bytes)
+			};
+			#[cfg(not(feature = "b64"))]
+			let r = serializer.serialize_bytes(&bytes);
+			bytes.zeroize();
+
+			r
+		}
+	}
+
+	impl<'de> Deserialize<'de> for DoubleRatchet {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			let mut bytes = if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				URL_SAFE_NO_PAD
+					.decode(s.as_bytes())
+					.map_err(D::Error::custom)?
+			} else {
+				crate::serde_bytes::deserialize_vec(deserializer)?
+			};
+			#[cfg(not(feature = "b64"))]
+			let mut bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+
+			let r = Self::from_bytes(&bytes).map_err(D::Error::custom);
+			bytes.zeroize();
+
+			r
+		}
+	}
+}
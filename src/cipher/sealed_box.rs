@@ -0,0 +1,131 @@
+//! Anonymous public-key encryption.
+//!
+//! Anyone knowing the public key of the recipient can seal a message, but
+//! only the recipient can open it. The sender stays anonymous since an
+//! ephemeral keypair is used for every message.
+//!
+//! The format is compatible with libsodium's `crypto_box_seal`:
+//! `ephemeral_pk || crypto_box(msg, nonce, recipient_pk, ephemeral_sk)` where
+//! the nonce is `blake2b(ephemeral_pk || recipient_pk)`.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::{sealed_box, Keypair};
+//!
+//! let bob = Keypair::new();
+//!
+//! // alice only needs to know bob's public key
+//! let sealed = sealed_box::seal(b"Hey Bob", bob.public());
+//!
+//! let msg = sealed_box::open(&sealed, &bob).expect("mac invalid");
+//! assert_eq!(msg, b"Hey Bob");
+//! ```
+
+use super::kem::Kem;
+use super::{Aead, Keypair, MacNotEqual, Nonce12, PublicKey, SharedSecret};
+use crate::error::KemError;
+
+use std::convert::TryFrom;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use zeroize::Zeroize;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+/// The amount of bytes a sealed message is longer than the plaintext.
+pub const OVERHEAD: usize = PublicKey::LEN + 16;
+
+/// Encrypts a message that only the owner of `recipient` can open.
+pub fn seal(plaintext: &[u8], recipient: &PublicKey) -> Vec<u8> {
+	seal_with_rng(plaintext, recipient, &mut OsRng)
+}
+
+/// Like `seal` but the ephemeral keypair is created with the given rng.
+pub fn seal_with_rng(
+	plaintext: &[u8],
+	recipient: &PublicKey,
+	rng: &mut (impl RngCore + CryptoRng),
+) -> Vec<u8> {
+	crypto_box::PublicKey::from(recipient.to_bytes())
+		.seal(rng, plaintext)
+		// only fails if the plaintext is longer than 256GiB
+		.expect("plaintext too long")
+}
+
+/// Decrypts a message which was created with `seal`, returning an Error
+/// if the message was not sealed for this keypair or was modified.
+pub fn open(
+	sealed: &[u8],
+	recipient: &Keypair,
+) -> Result<Vec<u8>, MacNotEqual> {
+	if sealed.len() < OVERHEAD {
+		return Err(MacNotEqual);
+	}
+
+	let mut bytes = recipient.to_bytes();
+	let secret = crypto_box::SecretKey::from(bytes);
+	bytes.zeroize();
+
+	secret.unseal(sealed).map_err(|_| MacNotEqual)
+}
+
+/// Like `seal` but with any [`Kem`], this format is not compatible with
+/// libsodium.
+///
+/// The output is the encapsulation followed by the ciphertext and a 16
+/// byte tag, the key is derived from the shared secret with HKDF-SHA256.
+pub fn seal_with<K: Kem>(
+	plaintext: &[u8],
+	recipient: &K::PublicKey,
+) -> Result<Vec<u8>, KemError> {
+	let (secret, encapsulation) = K::encapsulate(recipient)?;
+	let ciphertext = kem_aead(&secret, encapsulation.as_ref()).encrypt(
+		Nonce12::from([0; 12]),
+		b"",
+		plaintext,
+	);
+
+	let encapsulation = encapsulation.as_ref();
+	let mut sealed = Vec::with_capacity(encapsulation.len() + ciphertext.len());
+	sealed.extend_from_slice(encapsulation);
+	sealed.extend_from_slice(&ciphertext);
+
+	Ok(sealed)
+}
+
+/// Decrypts a message which was created with `seal_with` and the same
+/// [`Kem`].
+pub fn open_with<K: Kem>(
+	sealed: &[u8],
+	recipient: &K::Keypair,
+) -> Result<Vec<u8>, MacNotEqual> {
+	if sealed.len() < K::ENCAPSULATION_LEN + Aead::TAG_LEN {
+		return Err(MacNotEqual);
+	}
+
+	let (encapsulation, ciphertext) = sealed.split_at(K::ENCAPSULATION_LEN);
+	let encapsulation =
+		K::Encapsulation::try_from(encapsulation).map_err(|_| MacNotEqual)?;
+	let secret =
+		K::decapsulate(recipient, &encapsulation).map_err(|_| MacNotEqual)?;
+
+	kem_aead(&secret, encapsulation.as_ref())
+		.decrypt(Nonce12::from([0; 12]), b"", ciphertext)
+		.map_err(|_| MacNotEqual)
+}
+
+/// Every message uses a new key so the nonce can be fixed.
+fn kem_aead(secret: &SharedSecret, encapsulation: &[u8]) -> Aead {
+	let mut key = [0u8; 32];
+	Hkdf::<Sha256>::new(Some(encapsulation), secret.as_slice())
+		.expand(b"chuchi-crypto sealed box", &mut key)
+		// 32 bytes is way below the max size of 255 * 32
+		.unwrap();
+	let aead = Aead::from(key);
+	key.zeroize();
+
+	aead
+}
@@ -0,0 +1,317 @@
+use super::{Aead, Nonce12, SharedSecret};
+use crate::error::SessionError;
+
+use std::convert::TryInto;
+use std::fmt;
+
+use zeroize::Zeroize;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+/// How many rekey intervals a received message may skip.
+const MAX_SKIPPED_EPOCHS: u64 = 1024;
+
+const COUNTER_LEN: usize = 8;
+
+/// Which side of the session this is, both sides need a different role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+	Initiator,
+	Responder,
+}
+
+/// A transport cipher for a long running two way connection.
+///
+/// Each direction uses its own key and a message counter as the nonce.
+/// After every `rekey_interval` messages the key of a direction is
+/// replaced by deriving a new one from it with HKDF, the old key is
+/// erased. A message therefore can't be decrypted anymore once the keys
+/// have moved on, even if the current key is compromised.
+///
+/// Received messages may arrive out of order but not more than
+/// [`REPLAY_WINDOW`](Self::REPLAY_WINDOW) messages behind the newest one.
+/// Every message is only accepted once.
+///
+/// ## Format
+/// `counter (8 bytes big endian) || ciphertext || tag (16 bytes)`
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::cipher::{Keypair, Role, Session};
+///
+/// let alice = Keypair::new();
+/// let bob = Keypair::new();
+///
+/// let mut alice_session =
+///     Session::new(&alice.diffie_hellman(bob.public()), Role::Initiator);
+/// let mut bob_session =
+///     Session::new(&bob.diffie_hellman(alice.public()), Role::Responder);
+///
+/// let msg = alice_session.seal(b"", b"Hey Bob");
+/// assert_eq!(bob_session.open(b"", &msg).unwrap(), b"Hey Bob");
+/// // a replayed message is rejected
+/// assert!(bob_session.open(b"", &msg).is_err());
+/// ```
+pub struct Session {
+	send: SendState,
+	recv: RecvState,
+	rekey_interval: u64,
+}
+
+struct SendState {
+	key: [u8; 32],
+	epoch: u64,
+	counter: u64,
+}
+
+struct RecvState {
+	key: [u8; 32],
+	epoch: u64,
+	/// the key of the previous epoch, to accept messages which were sent
+	/// just before a rekey
+	prev_key: Option<[u8; 32]>,
+	/// the highest counter received
+	max: Option<u64>,
+	/// bit n is set if `max - n` was received
+	window: u64,
+}
+
+impl Session {
+	/// The number of messages after which the keys are ratcheted by
+	/// default.
+	pub const DEFAULT_REKEY_INTERVAL: u64 = 1 << 16;
+
+	/// How many messages before the newest received one are still
+	/// accepted.
+	pub const REPLAY_WINDOW: u64 = 64;
+
+	/// Creates a session from a shared secret, both sides need to use the
+	/// same secret.
+	pub fn new(secret: &SharedSecret, role: Role) -> Self {
+		Self::from_key(secret.as_slice(), role)
+	}
+
+	/// Creates a session from a uniformly random secret key of at least 32
+	/// bytes, both sides need to use the same key.
+	pub fn from_key(key: &[u8], role: Role) -> Self {
+		let hkdf = Hkdf::<Sha256>::new(None, key);
+
+		let mut initiator = [0u8; 32];
+		let mut responder = [0u8; 32];
+		// 32 bytes is way below the max size of 255 * 32
+		hkdf.expand(b"chuchi-crypto session initiator", &mut initiator)
+			.unwrap();
+		hkdf.expand(b"chuchi-crypto session responder", &mut responder)
+			.unwrap();
+
+		let (send, recv) = match role {
+			Role::Initiator => (initiator, responder),
+			Role::Responder => (responder, initiator),
+		};
+
+		let this = Self {
+			send: SendState {
+				key: send,
+				epoch: 0,
+				counter: 0,
+			},
+			recv: RecvState {
+				key: recv,
+				epoch: 0,
+				prev_key: None,
+				max: None,
+				window: 0,
+			},
+			rekey_interval: Self::DEFAULT_REKEY_INTERVAL,
+		};
+		initiator.zeroize();
+		responder.zeroize();
+
+		this
+	}
+
+	/// Sets after how many messages the keys are ratcheted, both sides
+	/// need to use the same value.
+	///
+	/// ## Panics
+	/// If the interval is zero.
+	pub fn rekey_interval(mut self, interval: u64) -> Self {
+		assert!(interval > 0, "rekey interval cannot be zero");
+		self.rekey_interval = interval;
+		self
+	}
+
+	/// Returns the number of messages sealed so far.
+	pub fn sent(&self) -> u64 {
+		self.send.counter
+	}
+
+	/// Encrypts the plaintext returning the counter followed by the
+	/// ciphertext and the tag.
+	///
+	/// ## Panics
+	/// After 2^64 messages.
+	pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+		let counter = self.send.counter;
+		self.send.counter = counter.checked_add(1).expect("session exhausted");
+
+		let epoch = counter / self.rekey_interval;
+		while self.send.epoch < epoch {
+			self.send.key = ratchet(&self.send.key);
+			self.send.epoch += 1;
+		}
+
+		let ciphertext =
+			Aead::from(self.send.key).encrypt(nonce(counter), aad, plaintext);
+
+		let mut msg = Vec::with_capacity(COUNTER_LEN + ciphertext.len());
+		msg.extend_from_slice(&counter.to_be_bytes());
+		msg.extend_from_slice(&ciphertext);
+
+		msg
+	}
+
+	/// Opens a message which was created with `seal` by the other side.
+	///
+	/// Messages which skip more than 1024 rekey intervals are rejected as
+	/// invalid.
+	pub fn open(
+		&mut self,
+		aad: &[u8],
+		msg: &[u8],
+	) -> Result<Vec<u8>, SessionError> {
+		if msg.len() < COUNTER_LEN + Aead::TAG_LEN {
+			return Err(SessionError::InvalidFormat);
+		}
+
+		let (counter, ciphertext) = msg.split_at(COUNTER_LEN);
+		let counter = u64::from_be_bytes(counter.try_into().unwrap());
+
+		let recv = &mut self.recv;
+		if let Some(max) = recv.max {
+			if counter <= max {
+				let diff = max - counter;
+				if diff >= Session::REPLAY_WINDOW {
+					return Err(SessionError::TooOld);
+				}
+				if recv.window & (1 << diff) != 0 {
+					return Err(SessionError::Replayed);
+				}
+			}
+		}
+
+		let epoch = counter / self.rekey_interval;
+		let mut keys = None;
+		let mut key = if epoch == recv.epoch {
+			recv.key
+		} else if epoch + 1 == recv.epoch {
+			recv.prev_key.ok_or(SessionError::TooOld)?
+		} else if epoch < recv.epoch {
+			return Err(SessionError::TooOld);
+		} else if epoch - recv.epoch > MAX_SKIPPED_EPOCHS {
+			return Err(SessionError::InvalidFormat);
+		} else {
+			let mut prev = recv.key;
+			let mut key = ratchet(&prev);
+			for _ in recv.epoch + 1..epoch {
+				prev.zeroize();
+				prev = key;
+				key = ratchet(&prev);
+			}
+			keys = Some((prev, key));
+			key
+		};
+
+		let plaintext = Aead::from(key)
+			.decrypt(nonce(counter), aad, ciphertext)
+			.map_err(|_| SessionError::MacNotEqual);
+		key.zeroize();
+
+		if let Some((mut prev, mut key)) = keys {
+			if plaintext.is_ok() {
+				recv.key.zeroize();
+				if let Some(k) = recv.prev_key.as_mut() {
+					k.zeroize();
+				}
+				recv.key = key;
+				recv.prev_key = Some(prev);
+				recv.epoch = epoch;
+			}
+			prev.zeroize();
+			key.zeroize();
+		}
+
+		if plaintext.is_ok() {
+			recv.mark_received(counter);
+		}
+
+		plaintext
+	}
+}
+
+impl RecvState {
+	fn mark_received(&mut self, counter: u64) {
+		match self.max {
+			Some(max) if counter <= max => {
+				self.window |= 1 << (max - counter);
+			}
+			Some(max) => {
+				let shift = counter - max;
+				self.window = if shift >= Session::REPLAY_WINDOW {
+					1
+				} else {
+					(self.window << shift) | 1
+				};
+				self.max = Some(counter);
+			}
+			None => {
+				self.window = 1;
+				self.max = Some(counter);
+			}
+		}
+	}
+}
+
+fn ratchet(key: &[u8; 32]) -> [u8; 32] {
+	let hkdf = Hkdf::<Sha256>::from_prk(key)
+		// the key is 32 bytes long which is the minimum
+		.unwrap();
+
+	let mut next = [0u8; 32];
+	hkdf.expand(b"chuchi-crypto session ratchet", &mut next)
+		// 32 bytes is way below the max size of 255 * 32
+		.unwrap();
+
+	next
+}
+
+fn nonce(counter: u64) -> Nonce12 {
+	let mut bytes = [0u8; Nonce12::LEN];
+	bytes[Nonce12::LEN - COUNTER_LEN..].copy_from_slice(&counter.to_be_bytes());
+
+	bytes.into()
+}
+
+impl fmt::Debug for Session {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Session")
+			.field("sent", &self.send.counter)
+			.field("received_max", &self.recv.max)
+			.field("rekey_interval", &self.rekey_interval)
+			.finish()
+	}
+}
+
+impl Drop for Session {
+	fn drop(&mut self) {
+		self.send.key.zeroize();
+		self.recv.key.zeroize();
+		if let Some(k) = self.recv.prev_key.as_mut() {
+			k.zeroize();
+		}
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Session {}
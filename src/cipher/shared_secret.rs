@@ -4,16 +4,52 @@ use std::{cmp, fmt};
 
 use x25519_dalek as x;
 
+use zeroize::Zeroize;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
 // should be hashed with
 pub struct SharedSecret {
-	inner: x::SharedSecret,
+	bytes: [u8; 32],
+	contributory: bool,
 }
 
 impl SharedSecret {
 	pub const LEN: usize = 32;
 
 	pub(crate) fn from_shared_secret(inner: x::SharedSecret) -> Self {
-		Self { inner }
+		Self {
+			bytes: inner.to_bytes(),
+			contributory: inner.was_contributory(),
+		}
+	}
+
+	/// Used for secrets which don't come from a diffie hellman exchange.
+	pub(crate) fn from_bytes(bytes: [u8; 32]) -> Self {
+		Self {
+			bytes,
+			contributory: true,
+		}
+	}
+
+	/// Hashes the 56 byte X448 output with SHA-256.
+	///
+	/// A `SharedSecret` is always 32 bytes, the raw output is longer and not
+	/// uniformly distributed so it gets compressed here instead of being
+	/// truncated. This means the secret does not match the raw output of
+	/// other X448 implementations, those need to hash it the same way.
+	#[cfg(feature = "x448")]
+	pub(crate) fn from_x448(mut raw: [u8; 56]) -> Self {
+		use sha2::Digest;
+		let contributory = !crate::ct::ct_is_zero(&raw);
+		let bytes = Sha256::digest(raw).into();
+		raw.zeroize();
+
+		Self {
+			bytes,
+			contributory,
+		}
 	}
 
 	// nonce size U24
@@ -21,15 +57,68 @@ impl SharedSecret {
 	/// Don't call this function with the same nonce again.
 	/// This probably leads to an insecure key.
 	pub fn to_key(&self, initial_nonce: Nonce) -> Key {
-		Key::new(self.to_bytes(), initial_nonce.into_bytes())
+		let mut bytes = self.to_bytes();
+		let key = Key::new(bytes, initial_nonce.into_bytes());
+		bytes.zeroize();
+
+		key
+	}
+
+	/// Derives a key using HKDF-SHA256.
+	///
+	/// The salt is optional (can be empty) but recommended, the info should
+	/// describe what the key is used for.
+	pub fn derive_key(&self, salt: &[u8], info: &[u8]) -> Key {
+		let [key] = self.derive_keys(salt, info);
+		key
+	}
+
+	/// Derives `N` independent keys using HKDF-SHA256.
+	///
+	/// This is useful if one exchange should result in multiple keys, for
+	/// example one for each direction.
+	pub fn derive_keys<const N: usize>(
+		&self,
+		salt: &[u8],
+		info: &[u8],
+	) -> [Key; N] {
+		let hkdf = Hkdf::<Sha256>::new(Some(salt), self.as_slice());
+
+		std::array::from_fn(|i| {
+			// the first 32 bytes are the secret and the rest the nonce
+			let mut okm = [0u8; 32 + Nonce::LEN];
+			hkdf.expand_multi_info(
+				&[info, &(i as u32).to_be_bytes()],
+				&mut okm,
+			)
+			// okm is way below the max size of 255 * 32
+			.unwrap();
+
+			let mut secret = [0u8; 32];
+			let mut nonce = [0u8; Nonce::LEN];
+			secret.copy_from_slice(&okm[..32]);
+			nonce.copy_from_slice(&okm[32..]);
+			okm.zeroize();
+
+			let key = Key::new(secret, nonce);
+			secret.zeroize();
+
+			key
+		})
 	}
 
 	fn to_bytes(&self) -> [u8; 32] {
-		self.inner.to_bytes()
+		self.bytes
+	}
+
+	/// Returns false if the public key had a low order, in which case the
+	/// secret is all zeros.
+	pub(crate) fn was_contributory(&self) -> bool {
+		self.contributory
 	}
 
 	pub(crate) fn as_slice(&self) -> &[u8] {
-		self.inner.as_bytes()
+		&self.bytes
 	}
 }
 
@@ -46,3 +135,12 @@ impl cmp::PartialEq for SharedSecret {
 }
 
 impl cmp::Eq for SharedSecret {}
+
+impl Drop for SharedSecret {
+	fn drop(&mut self) {
+		self.bytes.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SharedSecret {}
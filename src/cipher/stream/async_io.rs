@@ -0,0 +1,335 @@
+use super::{mac_not_equal, CHUNK_SIZE, HEADER_LEN, TAG_LEN};
+use crate::cipher::XAead;
+use crate::fill_random;
+
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::{cmp, fmt};
+
+use chacha20poly1305::aead::stream::{DecryptorBE32, EncryptorBE32};
+use chacha20poly1305::XChaCha20Poly1305;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Encrypts everything written to it and writes it to the inner writer,
+/// the async version of [`Encryptor`](super::Encryptor).
+///
+/// At most one encrypted chunk is buffered, if the inner writer is not
+/// ready no more data is accepted.
+///
+/// ## Warning
+/// `shutdown` needs to be called else the last chunk will be missing and
+/// the data cannot be decrypted.
+pub struct EncryptedWriter<W> {
+	// is only None after the last chunk was encrypted
+	inner: Option<EncryptorBE32<XChaCha20Poly1305>>,
+	writer: W,
+	// the plaintext of the current chunk
+	buf: Vec<u8>,
+	// encrypted data which was not yet written to the writer
+	out: Vec<u8>,
+	out_pos: usize,
+}
+
+impl<W: AsyncWrite + Unpin> EncryptedWriter<W> {
+	/// Creates a new EncryptedWriter, the header is written together with
+	/// the first chunk.
+	pub fn new(key: &XAead, writer: W) -> Self {
+		let mut nonce = [0u8; HEADER_LEN];
+		fill_random(&mut nonce);
+
+		Self {
+			inner: Some(EncryptorBE32::from_aead(
+				key.cipher(),
+				nonce.as_slice().into(),
+			)),
+			writer,
+			buf: Vec::with_capacity(CHUNK_SIZE + TAG_LEN),
+			out: nonce.to_vec(),
+			out_pos: 0,
+		}
+	}
+
+	pub fn get_ref(&self) -> &W {
+		&self.writer
+	}
+
+	/// Returns the inner writer.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+
+	/// Writes the pending encrypted data to the writer.
+	fn poll_write_out(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		while self.out_pos < self.out.len() {
+			let n = ready!(Pin::new(&mut self.writer)
+				.poll_write(cx, &self.out[self.out_pos..]))?;
+			if n == 0 {
+				return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+			}
+			self.out_pos += n;
+		}
+
+		self.out.clear();
+		self.out_pos = 0;
+
+		Poll::Ready(Ok(()))
+	}
+
+	/// Moves the encrypted chunk into `out`, which needs to be empty.
+	fn encrypt_chunk(&mut self, last: bool) -> io::Result<()> {
+		if last {
+			self.inner
+				.take()
+				.unwrap()
+				.encrypt_last_in_place(&[], &mut self.buf)
+				// encrypting the last chunk cannot overflow the counter
+				.unwrap();
+		} else {
+			self.inner
+				.as_mut()
+				.unwrap()
+				.encrypt_next_in_place(&[], &mut self.buf)
+				.map_err(|_| io::Error::other("too many chunks"))?;
+		}
+
+		std::mem::swap(&mut self.buf, &mut self.out);
+
+		Ok(())
+	}
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for EncryptedWriter<W> {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<io::Result<usize>> {
+		let this = self.get_mut();
+		if this.inner.is_none() {
+			return Poll::Ready(Err(io::Error::other("writer was shut down")));
+		}
+
+		ready!(this.poll_write_out(cx))?;
+		if buf.is_empty() {
+			return Poll::Ready(Ok(0));
+		}
+
+		// only encrypt a full chunk if more data follows
+		// since the last chunk needs to be marked
+		if this.buf.len() == CHUNK_SIZE {
+			this.encrypt_chunk(false)?;
+			ready!(this.poll_write_out(cx))?;
+		}
+
+		let len = cmp::min(CHUNK_SIZE - this.buf.len(), buf.len());
+		this.buf.extend_from_slice(&buf[..len]);
+
+		Poll::Ready(Ok(len))
+	}
+
+	/// Flushes the inner writer, the current chunk is not written since it
+	/// might not be full yet.
+	fn poll_flush(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		ready!(this.poll_write_out(cx))?;
+
+		Pin::new(&mut this.writer).poll_flush(cx)
+	}
+
+	/// Encrypts the last chunk, writes it and shuts the inner writer down.
+	fn poll_shutdown(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		ready!(this.poll_write_out(cx))?;
+
+		if this.inner.is_some() {
+			this.encrypt_chunk(true)?;
+			ready!(this.poll_write_out(cx))?;
+		}
+
+		Pin::new(&mut this.writer).poll_shutdown(cx)
+	}
+}
+
+impl<W> fmt::Debug for EncryptedWriter<W> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("EncryptedWriter")
+	}
+}
+
+/// Decrypts the data read from the inner reader, the async version of
+/// [`Decryptor`](super::Decryptor).
+///
+/// Returns an error with the kind `InvalidData` if the data was modified or
+/// truncated.
+pub struct DecryptedReader<R> {
+	// is only Some before the header was read
+	cipher: Option<XChaCha20Poly1305>,
+	// is only None before the header was read and after the last chunk was
+	// decrypted
+	inner: Option<DecryptorBE32<XChaCha20Poly1305>>,
+	reader: R,
+	// raw bytes read from the reader, one more than a chunk to know if it
+	// is the last one
+	raw: Vec<u8>,
+	filled: usize,
+	// the decrypted chunk
+	plaintext: Vec<u8>,
+	pos: usize,
+	// set once a chunk failed to decrypt, every read after that fails
+	failed: bool,
+}
+
+impl<R: AsyncRead + Unpin> DecryptedReader<R> {
+	/// Creates a new DecryptedReader, the header is read together with the
+	/// first chunk.
+	pub fn new(key: &XAead, reader: R) -> Self {
+		Self {
+			cipher: Some(key.cipher()),
+			inner: None,
+			reader,
+			raw: vec![0u8; CHUNK_SIZE + TAG_LEN + 1],
+			filled: 0,
+			plaintext: Vec::with_capacity(CHUNK_SIZE + TAG_LEN),
+			pos: 0,
+			failed: false,
+		}
+	}
+
+	pub fn get_ref(&self) -> &R {
+		&self.reader
+	}
+
+	/// Returns the inner reader.
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+
+	/// Reads until `raw` contains `len` bytes or the reader is at the end,
+	/// returning if the end was reached.
+	fn poll_fill(
+		&mut self,
+		cx: &mut Context<'_>,
+		len: usize,
+	) -> Poll<io::Result<bool>> {
+		while self.filled < len {
+			let mut buf = ReadBuf::new(&mut self.raw[self.filled..len]);
+			ready!(Pin::new(&mut self.reader).poll_read(cx, &mut buf))?;
+			let n = buf.filled().len();
+			if n == 0 {
+				return Poll::Ready(Ok(true));
+			}
+			self.filled += n;
+		}
+
+		Poll::Ready(Ok(false))
+	}
+
+	fn poll_decrypt_chunk(
+		&mut self,
+		cx: &mut Context<'_>,
+	) -> Poll<io::Result<()>> {
+		if self.cipher.is_some() {
+			let eof = ready!(self.poll_fill(cx, HEADER_LEN))?;
+			if eof {
+				return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+			}
+
+			self.inner = Some(DecryptorBE32::from_aead(
+				self.cipher.take().unwrap(),
+				self.raw[..HEADER_LEN].into(),
+			));
+			self.filled = 0;
+		}
+
+		let max = CHUNK_SIZE + TAG_LEN + 1;
+		let eof = ready!(self.poll_fill(cx, max))?;
+
+		self.plaintext.clear();
+		self.pos = 0;
+		if eof {
+			self.plaintext.extend_from_slice(&self.raw[..self.filled]);
+			self.filled = 0;
+			let res = self
+				.inner
+				.take()
+				.unwrap()
+				.decrypt_last_in_place(&[], &mut self.plaintext);
+			if res.is_err() {
+				return Poll::Ready(Err(self.fail()));
+			}
+		} else {
+			self.plaintext.extend_from_slice(&self.raw[..max - 1]);
+			// keep the first byte of the next chunk
+			self.raw[0] = self.raw[max - 1];
+			self.filled = 1;
+			let res = self
+				.inner
+				.as_mut()
+				.unwrap()
+				.decrypt_next_in_place(&[], &mut self.plaintext);
+			if res.is_err() {
+				return Poll::Ready(Err(self.fail()));
+			}
+		}
+
+		Poll::Ready(Ok(()))
+	}
+
+	/// Clears the buffers so no unauthenticated data can be returned and
+	/// makes every following read fail.
+	fn fail(&mut self) -> io::Error {
+		self.failed = true;
+		self.plaintext.fill(0);
+		self.plaintext.clear();
+		self.pos = 0;
+		self.raw.fill(0);
+		self.filled = 0;
+
+		mac_not_equal()
+	}
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for DecryptedReader<R> {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		if this.failed {
+			return Poll::Ready(Err(mac_not_equal()));
+		}
+		if buf.remaining() == 0 {
+			return Poll::Ready(Ok(()));
+		}
+
+		while this.pos == this.plaintext.len() {
+			// the last chunk was consumed
+			if this.cipher.is_none() && this.inner.is_none() {
+				return Poll::Ready(Ok(()));
+			}
+
+			ready!(this.poll_decrypt_chunk(cx))?;
+		}
+
+		let len = cmp::min(this.plaintext.len() - this.pos, buf.remaining());
+		buf.put_slice(&this.plaintext[this.pos..this.pos + len]);
+		this.pos += len;
+
+		Poll::Ready(Ok(()))
+	}
+}
+
+impl<R> fmt::Debug for DecryptedReader<R> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("DecryptedReader")
+	}
+}
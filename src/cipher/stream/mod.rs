@@ -0,0 +1,412 @@
+//! Encryption of data which does not fit into memory.
+//!
+//! Implements the STREAM construction using XChaCha20-Poly1305. The data is
+//! split into chunks of `CHUNK_SIZE` bytes, every chunk is encrypted with
+//! its own nonce consisting of a random prefix, a counter and a flag marking
+//! the last chunk. This prevents chunks from being reordered, removed or the
+//! stream being truncated.
+//!
+//! ## Format
+//! `nonce_prefix (19 bytes) || chunk || ... || last_chunk` where every chunk
+//! is the encrypted data followed by a 16 bytes tag.
+//!
+//! Associated data passed to `new_with_aad` is authenticated with every
+//! chunk but not written, the same needs to be passed when decrypting.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::stream::{Decryptor, Encryptor};
+//! use chuchi_crypto::cipher::XAead;
+//! use std::io::{Read, Write};
+//!
+//! let key = XAead::new();
+//!
+//! let mut encryptor = Encryptor::new(&key, Vec::new()).unwrap();
+//! encryptor.write_all(b"a lot of data").unwrap();
+//! let encrypted = encryptor.finish().unwrap();
+//!
+//! let mut decryptor = Decryptor::new(&key, encrypted.as_slice()).unwrap();
+//! let mut data = Vec::new();
+//! decryptor.read_to_end(&mut data).unwrap();
+//! assert_eq!(data, b"a lot of data");
+//! ```
+
+#[cfg(feature = "rayon")]
+use super::MacNotEqual;
+use super::XAead;
+use crate::fill_random;
+
+use std::io::{self, Read, Write};
+use std::{cmp, fmt};
+
+use chacha20poly1305::aead::stream::{DecryptorBE32, EncryptorBE32};
+use chacha20poly1305::XChaCha20Poly1305;
+
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "tokio")]
+pub use async_io::{DecryptedReader, EncryptedWriter};
+
+/// The amount of plaintext bytes in each chunk.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The length of the nonce prefix written at the start of the stream.
+pub const HEADER_LEN: usize = 19;
+
+pub const TAG_LEN: usize = 16;
+
+/// Encrypts the plaintext into the same format as [`Encryptor`], with the
+/// chunks spread across the rayon thread pool.
+///
+/// ## Panics
+/// If the plaintext has more than 2^32 chunks.
+#[cfg(feature = "rayon")]
+pub fn encrypt_chunks_par(key: &XAead, plaintext: &[u8]) -> Vec<u8> {
+	use chacha20poly1305::aead::AeadInPlace;
+	use rayon::prelude::*;
+
+	let chunks = cmp::max(plaintext.len(), 1).div_ceil(CHUNK_SIZE);
+	assert!(chunks as u64 <= u32::MAX as u64 + 1, "too many chunks");
+
+	let mut prefix = [0u8; HEADER_LEN];
+	fill_random(&mut prefix);
+
+	let mut out = vec![0u8; HEADER_LEN + plaintext.len() + chunks * TAG_LEN];
+	out[..HEADER_LEN].copy_from_slice(&prefix);
+
+	let cipher = key.cipher();
+	let encrypt = |(i, (out, chunk)): (usize, (&mut [u8], &[u8]))| {
+		let (buf, tag) = out.split_at_mut(chunk.len());
+		buf.copy_from_slice(chunk);
+		let nonce = chunk_nonce(&prefix, i, i == chunks - 1);
+		let t = cipher
+			.encrypt_in_place_detached(nonce.as_slice().into(), &[], buf)
+			// only fails if the chunk is longer than 256GiB
+			.unwrap();
+		tag.copy_from_slice(&t);
+	};
+
+	if plaintext.is_empty() {
+		encrypt((0, (&mut out[HEADER_LEN..], &[])));
+	} else {
+		out[HEADER_LEN..]
+			.par_chunks_mut(CHUNK_SIZE + TAG_LEN)
+			.zip(plaintext.par_chunks(CHUNK_SIZE))
+			.enumerate()
+			.for_each(encrypt);
+	}
+
+	out
+}
+
+/// Decrypts data created by [`Encryptor`] or [`encrypt_chunks_par`], with
+/// the chunks spread across the rayon thread pool.
+#[cfg(feature = "rayon")]
+pub fn decrypt_chunks_par(
+	key: &XAead,
+	data: &[u8],
+) -> Result<Vec<u8>, MacNotEqual> {
+	use chacha20poly1305::aead::AeadInPlace;
+	use rayon::prelude::*;
+
+	if data.len() < HEADER_LEN + TAG_LEN {
+		return Err(MacNotEqual);
+	}
+	let (prefix, body) = data.split_at(HEADER_LEN);
+	let prefix: &[u8; HEADER_LEN] = prefix.try_into().unwrap();
+
+	let chunks = body.len().div_ceil(CHUNK_SIZE + TAG_LEN);
+	// the last chunk needs to contain at least the tag
+	let last_len = body.len() - (chunks - 1) * (CHUNK_SIZE + TAG_LEN);
+	if last_len < TAG_LEN || chunks as u64 > u32::MAX as u64 + 1 {
+		return Err(MacNotEqual);
+	}
+
+	let mut out = vec![0u8; body.len() - chunks * TAG_LEN];
+	// split the output into the plaintext of each chunk
+	let mut outs = Vec::with_capacity(chunks);
+	let mut rest = out.as_mut_slice();
+	for chunk in body.chunks(CHUNK_SIZE + TAG_LEN) {
+		let (plaintext, next) =
+			std::mem::take(&mut rest).split_at_mut(chunk.len() - TAG_LEN);
+		outs.push(plaintext);
+		rest = next;
+	}
+
+	let cipher = key.cipher();
+	outs.into_par_iter()
+		.zip(body.par_chunks(CHUNK_SIZE + TAG_LEN))
+		.enumerate()
+		.try_for_each(|(i, (out, chunk))| {
+			let (ciphertext, tag) = chunk.split_at(chunk.len() - TAG_LEN);
+			out.copy_from_slice(ciphertext);
+			let nonce = chunk_nonce(prefix, i, i == chunks - 1);
+			cipher
+				.decrypt_in_place_detached(
+					nonce.as_slice().into(),
+					&[],
+					out,
+					tag.into(),
+				)
+				.map_err(|_| MacNotEqual)
+		})?;
+
+	Ok(out)
+}
+
+/// The nonce used by the STREAM construction for the chunk at `index`.
+#[cfg(feature = "rayon")]
+fn chunk_nonce(
+	prefix: &[u8; HEADER_LEN],
+	index: usize,
+	last: bool,
+) -> [u8; 24] {
+	let mut nonce = [0u8; 24];
+	nonce[..HEADER_LEN].copy_from_slice(prefix);
+	nonce[HEADER_LEN..HEADER_LEN + 4]
+		.copy_from_slice(&(index as u32).to_be_bytes());
+	nonce[23] = last as u8;
+
+	nonce
+}
+
+fn mac_not_equal() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "mac not equal")
+}
+
+/// Encrypts everything written to it and writes it to the inner writer.
+///
+/// ## Warning
+/// `finish` needs to be called else the last chunk will be missing and the
+/// data cannot be decrypted.
+pub struct Encryptor<W: Write> {
+	// is only None after the last chunk was encrypted
+	inner: Option<EncryptorBE32<XChaCha20Poly1305>>,
+	writer: W,
+	buf: Vec<u8>,
+	aad: Vec<u8>,
+}
+
+impl<W: Write> Encryptor<W> {
+	/// Creates a new Encryptor writing the header to the writer.
+	pub fn new(key: &XAead, writer: W) -> io::Result<Self> {
+		Self::new_with_aad(key, writer, &[])
+	}
+
+	/// Like `new` but authenticates `aad` with every chunk.
+	pub fn new_with_aad(
+		key: &XAead,
+		mut writer: W,
+		aad: &[u8],
+	) -> io::Result<Self> {
+		let mut nonce = [0u8; HEADER_LEN];
+		fill_random(&mut nonce);
+		writer.write_all(&nonce)?;
+
+		Ok(Self {
+			inner: Some(EncryptorBE32::from_aead(
+				key.cipher(),
+				nonce.as_slice().into(),
+			)),
+			writer,
+			buf: Vec::with_capacity(CHUNK_SIZE + TAG_LEN),
+			aad: aad.to_vec(),
+		})
+	}
+
+	fn encrypt_chunk(&mut self) -> io::Result<()> {
+		self.inner
+			.as_mut()
+			.unwrap()
+			.encrypt_next_in_place(&self.aad, &mut self.buf)
+			.map_err(|_| io::Error::other("too many chunks"))?;
+		self.writer.write_all(&self.buf)?;
+		self.buf.clear();
+
+		Ok(())
+	}
+
+	/// Encrypts the last chunk and returns the inner writer.
+	pub fn finish(mut self) -> io::Result<W> {
+		self.inner
+			.take()
+			.unwrap()
+			.encrypt_last_in_place(&self.aad, &mut self.buf)
+			// encrypting the last chunk cannot overflow the counter
+			.unwrap();
+		self.writer.write_all(&self.buf)?;
+		self.writer.flush()?;
+
+		Ok(self.writer)
+	}
+}
+
+impl<W: Write> Write for Encryptor<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		// only encrypt a full chunk if more data follows
+		// since the last chunk needs to be marked
+		if self.buf.len() == CHUNK_SIZE && !buf.is_empty() {
+			self.encrypt_chunk()?;
+		}
+
+		let len = cmp::min(CHUNK_SIZE - self.buf.len(), buf.len());
+		self.buf.extend_from_slice(&buf[..len]);
+
+		Ok(len)
+	}
+
+	/// Flushes the inner writer, the current chunk is not written since it
+	/// might not be full yet.
+	fn flush(&mut self) -> io::Result<()> {
+		self.writer.flush()
+	}
+}
+
+impl<W: Write> fmt::Debug for Encryptor<W> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Encryptor")
+	}
+}
+
+/// Decrypts the data read from the inner reader.
+///
+/// Returns an error with the kind `InvalidData` if the data was modified or
+/// truncated.
+pub struct Decryptor<R: Read> {
+	// is only None after the last chunk was decrypted
+	inner: Option<DecryptorBE32<XChaCha20Poly1305>>,
+	reader: R,
+	// contains the decrypted chunk or
+	// the raw bytes while reading the next one
+	buf: Vec<u8>,
+	pos: usize,
+	// set once a chunk failed to decrypt, every read after that fails
+	failed: bool,
+	aad: Vec<u8>,
+}
+
+impl<R: Read> Decryptor<R> {
+	/// Creates a new Decryptor reading the header from the reader.
+	pub fn new(key: &XAead, reader: R) -> io::Result<Self> {
+		Self::new_with_aad(key, reader, &[])
+	}
+
+	/// Like `new` but every chunk needs to be authenticated with `aad`.
+	pub fn new_with_aad(
+		key: &XAead,
+		mut reader: R,
+		aad: &[u8],
+	) -> io::Result<Self> {
+		let mut nonce = [0u8; HEADER_LEN];
+		reader.read_exact(&mut nonce)?;
+
+		Ok(Self {
+			inner: Some(DecryptorBE32::from_aead(
+				key.cipher(),
+				nonce.as_slice().into(),
+			)),
+			reader,
+			buf: Vec::with_capacity(CHUNK_SIZE + TAG_LEN + 1),
+			pos: 0,
+			failed: false,
+			aad: aad.to_vec(),
+		})
+	}
+
+	/// Returns the inner reader.
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+
+	fn decrypt_chunk(&mut self) -> io::Result<()> {
+		// the first byte of the next chunk might already be in the buffer
+		let mut peeked = None;
+		if self.pos < self.buf.len() {
+			peeked = Some(self.buf[self.pos]);
+		}
+		self.buf.clear();
+		self.pos = 0;
+		self.buf.extend(peeked);
+
+		// read one more byte than a chunk to know if this is the last chunk
+		let max = (CHUNK_SIZE + TAG_LEN + 1) as u64;
+		let to_read = max - self.buf.len() as u64;
+		(&mut self.reader)
+			.take(to_read)
+			.read_to_end(&mut self.buf)?;
+
+		if self.buf.len() as u64 == max {
+			let next = self.buf.pop().unwrap();
+			let res = self
+				.inner
+				.as_mut()
+				.unwrap()
+				.decrypt_next_in_place(&self.aad, &mut self.buf);
+			if res.is_err() {
+				return Err(self.fail());
+			}
+			// store the peeked byte after the plaintext
+			self.buf.push(next);
+		} else {
+			let res = self
+				.inner
+				.take()
+				.unwrap()
+				.decrypt_last_in_place(&self.aad, &mut self.buf);
+			if res.is_err() {
+				return Err(self.fail());
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Clears the buffer so no unauthenticated data can be returned and
+	/// makes every following read fail.
+	fn fail(&mut self) -> io::Error {
+		self.failed = true;
+		self.buf.fill(0);
+		self.buf.clear();
+		self.pos = 0;
+
+		mac_not_equal()
+	}
+
+	fn plaintext_len(&self) -> usize {
+		match self.inner {
+			// the peeked byte of the next chunk
+			Some(_) => self.buf.len() - 1,
+			None => self.buf.len(),
+		}
+	}
+}
+
+impl<R: Read> Read for Decryptor<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if self.failed {
+			return Err(mac_not_equal());
+		}
+
+		// the first chunk was not yet read or the current one is consumed
+		let is_start = self.inner.is_some() && self.buf.is_empty();
+		if is_start || self.pos == self.plaintext_len() {
+			if self.inner.is_none() {
+				return Ok(0);
+			}
+
+			self.decrypt_chunk()?;
+		}
+
+		let len = cmp::min(self.plaintext_len() - self.pos, buf.len());
+		buf[..len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+		self.pos += len;
+
+		Ok(len)
+	}
+}
+
+impl<R: Read> fmt::Debug for Decryptor<R> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Decryptor")
+	}
+}
@@ -0,0 +1,242 @@
+use super::{MacNotEqual, Nonce, XAead};
+use crate::error::{DecodeError, TryFromError};
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use zeroize::Zeroize;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// Binds the ciphertext to its purpose so a wrapped key can't be opened as
+/// a regular message.
+const AAD: &[u8] = b"chuchi-crypto wrapped key";
+
+/// A key encrypted with a key encryption key (kek), created with
+/// `XAead::wrap`.
+///
+/// Used for envelope encryption, every record is encrypted with its own
+/// data key which is stored wrapped by a master key. Rotating the master
+/// key then only requires rewrapping the data keys.
+///
+/// ## Layout
+/// `version (1 byte) | nonce (24 bytes) | key (32 bytes) | tag (16 bytes)`
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::cipher::{WrappedKey, XAead};
+///
+/// let kek = XAead::new();
+/// let data_key = XAead::new();
+///
+/// let wrapped = data_key.wrap(&kek);
+/// let bytes = wrapped.to_bytes();
+///
+/// let wrapped = WrappedKey::from_bytes(&bytes).unwrap();
+/// let unwrapped = wrapped.unwrap(&kek).unwrap();
+/// assert_eq!(unwrapped.to_bytes(), data_key.to_bytes());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct WrappedKey {
+	bytes: [u8; Self::LEN],
+}
+
+impl WrappedKey {
+	pub const LEN: usize = 1 + Nonce::LEN + XAead::LEN + XAead::TAG_LEN;
+
+	/// The current version of the binary layout.
+	pub const VERSION: u8 = 1;
+
+	pub(crate) fn new(key: &XAead, kek: &XAead) -> Self {
+		let nonce = Nonce::new();
+		let mut secret = key.to_bytes();
+		let ciphertext = kek.encrypt(&nonce, AAD, &secret);
+		secret.zeroize();
+
+		let mut bytes = [0u8; Self::LEN];
+		bytes[0] = Self::VERSION;
+		bytes[1..1 + Nonce::LEN].copy_from_slice(nonce.as_ref());
+		bytes[1 + Nonce::LEN..].copy_from_slice(&ciphertext);
+
+		Self { bytes }
+	}
+
+	/// Parses a wrapped key returning an error if the version is unknown or
+	/// the length is wrong.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let bytes: [u8; Self::LEN] =
+			bytes.try_into().map_err(|_| DecodeError::InvalidLength)?;
+
+		if bytes[0] != Self::VERSION {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		Ok(Self { bytes })
+	}
+
+	pub fn version(&self) -> u8 {
+		self.bytes[0]
+	}
+
+	/// Decrypts the key, returns an error if `kek` is not the key it was
+	/// wrapped with or the bytes were modified.
+	pub fn unwrap(&self, kek: &XAead) -> Result<XAead, MacNotEqual> {
+		let nonce = Nonce::try_from(&self.bytes[1..1 + Nonce::LEN]).unwrap();
+
+		let mut secret =
+			kek.decrypt(&nonce, AAD, &self.bytes[1 + Nonce::LEN..])?;
+		let key = XAead::try_from_slice(&secret).unwrap();
+		secret.zeroize();
+
+		Ok(key)
+	}
+
+	pub fn to_bytes(&self) -> [u8; Self::LEN] {
+		self.bytes
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for WrappedKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("WrappedKey").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for WrappedKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("WrappedKey")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for WrappedKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for WrappedKey {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		Self::from_bytes(v).map_err(TryFromError::from_any)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for WrappedKey {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let bytes =
+			URL_SAFE_NO_PAD.decode(s).map_err(DecodeError::inv_bytes)?;
+		Self::from_bytes(&bytes)
+	}
+}
+
+impl AsRef<[u8]> for WrappedKey {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for WrappedKey {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for WrappedKey {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::from_bytes(&bytes).map_err(D::Error::custom)
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "postgres"))]
+mod impl_postgres {
+	use super::*;
+
+	use bytes::BytesMut;
+	use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+	impl ToSql for WrappedKey {
+		fn to_sql(
+			&self,
+			ty: &Type,
+			out: &mut BytesMut,
+		) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+		where
+			Self: Sized,
+		{
+			if *ty == Type::BYTEA {
+				return <&[u8] as ToSql>::to_sql(&self.as_ref(), ty, out);
+			}
+
+			self.to_string().to_sql(ty, out)
+		}
+
+		fn accepts(ty: &Type) -> bool
+		where
+			Self: Sized,
+		{
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
+		}
+
+		to_sql_checked!();
+	}
+
+	impl<'r> FromSql<'r> for WrappedKey {
+		fn from_sql(
+			ty: &Type,
+			raw: &'r [u8],
+		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Self::from_bytes(raw).map_err(Into::into);
+			}
+
+			let s = <&str as FromSql>::from_sql(ty, raw)?;
+			s.parse().map_err(Into::into)
+		}
+
+		fn accepts(ty: &Type) -> bool {
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+}
@@ -0,0 +1,393 @@
+use super::SharedSecret;
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex"))]
+use crate::error::DecodeError;
+use crate::error::{RandomError, TryFromError};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use zeroize::Zeroize;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+const FIELD_LEN: usize = 56;
+
+// Keypair448
+
+/// An X448 keypair, for a 224 bit security level instead of the 128 bits
+/// of X25519.
+///
+/// The 56 byte output of the exchange is hashed with SHA-256 into a
+/// [`SharedSecret`].
+pub struct Keypair448 {
+	secret: [u8; FIELD_LEN],
+	public: PublicKey448,
+}
+
+impl Keypair448 {
+	pub const LEN: usize = FIELD_LEN;
+
+	pub fn new() -> Self {
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut secret = [0u8; FIELD_LEN];
+		rng.fill_bytes(&mut secret);
+		let this = Self::from(secret);
+		secret.zeroize();
+
+		this
+	}
+
+	/// Returns an error if the slice is not 56 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; FIELD_LEN] {
+		self.secret
+	}
+
+	pub fn public(&self) -> &PublicKey448 {
+		&self.public
+	}
+
+	/// Returns SHA-256 of the 56 byte X448 output.
+	///
+	/// To interoperate with other X448 implementations hash their raw output
+	/// with SHA-256 as well.
+	pub fn diffie_hellman(&self, public_key: &PublicKey448) -> SharedSecret {
+		SharedSecret::from_x448(::x448::x448_unchecked(
+			self.secret,
+			public_key.bytes,
+		))
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Keypair448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair448")
+			.field("secret", &self.to_bytes())
+			.field("public", &self.public)
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Keypair448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair448")
+			.field("secret", &self.to_string())
+			.field("public", &self.public)
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Keypair448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.secret, &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl From<[u8; FIELD_LEN]> for Keypair448 {
+	fn from(secret: [u8; FIELD_LEN]) -> Self {
+		let public = PublicKey448 {
+			bytes: ::x448::x448_unchecked(secret, ::x448::X448_BASEPOINT_BYTES),
+		};
+
+		Self { secret, public }
+	}
+}
+
+impl TryFrom<&[u8]> for Keypair448 {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<&[u8; FIELD_LEN]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(|secret| Self::from(*secret))
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Keypair448 {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = [0u8; Self::LEN];
+		let r = URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map(|_| Self::from(bytes))
+			.map_err(DecodeError::inv_bytes);
+		bytes.zeroize();
+
+		r
+	}
+}
+
+#[cfg(feature = "hex")]
+impl Keypair448 {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).map(|mut bytes| {
+			let this = Self::from(bytes);
+			bytes.zeroize();
+
+			this
+		})
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, Keypair448> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut bytes = self.get().to_bytes();
+		let r = crate::encoding::fmt_hex(&bytes, f);
+		bytes.zeroize();
+
+		r
+	}
+}
+
+impl Clone for Keypair448 {
+	fn clone(&self) -> Self {
+		Self {
+			secret: self.secret,
+			public: self.public.clone(),
+		}
+	}
+}
+
+impl Drop for Keypair448 {
+	fn drop(&mut self) {
+		self.secret.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Keypair448 {}
+
+// PublicKey448
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct PublicKey448 {
+	bytes: [u8; FIELD_LEN],
+}
+
+impl PublicKey448 {
+	pub const LEN: usize = FIELD_LEN;
+
+	/// Returns an error if the slice is not 56 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; FIELD_LEN] {
+		self.bytes
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for PublicKey448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey448").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for PublicKey448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey448")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for PublicKey448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl Hash for PublicKey448 {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.as_ref().hash(state)
+	}
+}
+
+impl From<[u8; FIELD_LEN]> for PublicKey448 {
+	fn from(bytes: [u8; FIELD_LEN]) -> Self {
+		Self { bytes }
+	}
+}
+
+impl TryFrom<&[u8]> for PublicKey448 {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; FIELD_LEN]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for PublicKey448 {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = [0u8; Self::LEN];
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.map(|_| Self::from(bytes))
+	}
+}
+
+impl AsRef<[u8]> for PublicKey448 {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+#[cfg(feature = "hex")]
+impl PublicKey448 {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).map(Self::from)
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, PublicKey448> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		crate::encoding::fmt_hex(self.get().as_ref(), f)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	#[cfg(feature = "b64")]
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Keypair448 {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.secret)
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Keypair448 {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let mut bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			let r = Ok(Self::from(bytes));
+			bytes.zeroize();
+
+			r
+		}
+	}
+
+	impl Serialize for PublicKey448 {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for PublicKey448 {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			crate::serde_bytes::deserialize::<_, { Self::LEN }>(deserializer)
+				.map(Self::from)
+		}
+	}
+}
@@ -0,0 +1,224 @@
+//! Signed and encrypted cookie values.
+//!
+//! A signed value can be read by the client but not modified, an encrypted
+//! value can neither be read nor modified. The name of the cookie is
+//! authenticated as well, so a value can't be moved to another cookie.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cookie::CookieKey;
+//!
+//! let key = CookieKey::new();
+//!
+//! let signed = key.sign_value("user", "42");
+//! assert_eq!(key.verify_value("user", &signed).unwrap(), "42");
+//! assert!(key.verify_value("admin", &signed).is_err());
+//!
+//! let encrypted = key.encrypt_value("session", "secret");
+//! assert_eq!(key.decrypt_value("session", &encrypted).unwrap(), "secret");
+//! ```
+
+use crate::cipher::{Ciphertext, XAead};
+use crate::error::{DecryptError, TokenError};
+use crate::hash::{HmacKey, Tag};
+
+use std::fmt;
+use std::str::FromStr;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use zeroize::Zeroize;
+
+/// The keys used to sign and encrypt cookie values.
+///
+/// Both keys are derived from a single master key.
+#[derive(Clone)]
+pub struct CookieKey {
+	signing: HmacKey,
+	encryption: XAead,
+}
+
+impl CookieKey {
+	/// The minimum length of a master key.
+	pub const MIN_MASTER_LEN: usize = 32;
+
+	/// Creates a new random key.
+	///
+	/// ## Panics
+	/// If the OsRng fails to return random bytes.
+	pub fn new() -> Self {
+		let mut master = [0u8; 32];
+		crate::fill_random(&mut master);
+		let key = Self::from_master(&master);
+		master.zeroize();
+
+		key
+	}
+
+	/// Derives the signing and the encryption key from a master key using
+	/// HKDF-SHA256.
+	///
+	/// The same master key always results in the same keys, so it can be
+	/// stored in the configuration.
+	///
+	/// ## Panics
+	/// If the master key is shorter than 32 bytes.
+	pub fn from_master(master: &[u8]) -> Self {
+		assert!(master.len() >= Self::MIN_MASTER_LEN, "master key too short");
+
+		let hkdf = Hkdf::<Sha256>::new(None, master);
+
+		let mut okm = [0u8; 64];
+		hkdf.expand(b"chuchi-crypto cookie", &mut okm)
+			// okm is way below the max size of 255 * 32
+			.unwrap();
+
+		let mut encryption = [0u8; 32];
+		encryption.copy_from_slice(&okm[32..]);
+
+		let key = Self {
+			signing: HmacKey::new(&okm[..32]),
+			encryption: XAead::from(encryption),
+		};
+		okm.zeroize();
+		encryption.zeroize();
+
+		key
+	}
+
+	/// Signs a value, the result is `<tag>.<value>`.
+	///
+	/// The value is not encoded, so it needs to only contain characters
+	/// allowed in a cookie.
+	pub fn sign_value(&self, name: &str, value: &str) -> String {
+		let tag = self.signing.sign(mac_input(name, value));
+
+		format!("{tag}.{value}")
+	}
+
+	/// Verifies a value signed with `sign_value` returning the value.
+	pub fn verify_value<'a>(
+		&self,
+		name: &str,
+		signed: &'a str,
+	) -> Result<&'a str, TokenError> {
+		let (tag, value) =
+			signed.split_once('.').ok_or(TokenError::InvalidFormat)?;
+		let tag = Tag::from_str(tag).map_err(|_| TokenError::InvalidFormat)?;
+
+		if self.signing.verify(mac_input(name, value), &tag) {
+			Ok(value)
+		} else {
+			Err(TokenError::InvalidSignature)
+		}
+	}
+
+	/// Encrypts a value, the result is a base64 encoded `Ciphertext`.
+	pub fn encrypt_value(&self, name: &str, value: &str) -> String {
+		self.encryption
+			.seal_ciphertext(name.as_bytes(), value.as_bytes())
+			.to_string()
+	}
+
+	/// Decrypts a value encrypted with `encrypt_value`.
+	pub fn decrypt_value(
+		&self,
+		name: &str,
+		encrypted: &str,
+	) -> Result<String, DecryptError> {
+		let ciphertext = Ciphertext::from_str(encrypted)
+			.map_err(|_| DecryptError::InvalidFormat)?;
+
+		let plaintext = self
+			.encryption
+			.open_ciphertext(name.as_bytes(), &ciphertext)
+			.map_err(|_| DecryptError::MacNotEqual)?;
+
+		String::from_utf8(plaintext).map_err(|e| {
+			e.into_bytes().zeroize();
+			DecryptError::InvalidFormat
+		})
+	}
+}
+
+impl fmt::Debug for CookieKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("CookieKey")
+	}
+}
+
+/// The name is prefixed with its length so `name` and `value` can't be
+/// shifted into each other.
+fn mac_input(name: &str, value: &str) -> Vec<u8> {
+	let mut input = Vec::with_capacity(8 + name.len() + value.len());
+	input.extend_from_slice(&(name.len() as u64).to_be_bytes());
+	input.extend_from_slice(name.as_bytes());
+	input.extend_from_slice(value.as_bytes());
+
+	input
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_signed() {
+		let key = CookieKey::from_master(&[1; 32]);
+
+		let signed = key.sign_value("user", "42.1");
+		assert_eq!(key.verify_value("user", &signed).unwrap(), "42.1");
+
+		// the same master key derives the same keys
+		let key_2 = CookieKey::from_master(&[1; 32]);
+		assert_eq!(key_2.verify_value("user", &signed).unwrap(), "42.1");
+
+		let other = CookieKey::from_master(&[2; 32]);
+		assert_eq!(
+			other.verify_value("user", &signed).unwrap_err(),
+			TokenError::InvalidSignature
+		);
+
+		let modified = signed.replace("42", "43");
+		assert_eq!(
+			key.verify_value("user", &modified).unwrap_err(),
+			TokenError::InvalidSignature
+		);
+		assert_eq!(
+			key.verify_value("use", &signed).unwrap_err(),
+			TokenError::InvalidSignature
+		);
+		assert_eq!(
+			key.verify_value("user", "42").unwrap_err(),
+			TokenError::InvalidFormat
+		);
+	}
+
+	#[test]
+	fn test_encrypted() {
+		let key = CookieKey::new();
+
+		let encrypted = key.encrypt_value("session", "my secret");
+		assert!(!encrypted.contains("my secret"));
+		assert_eq!(
+			key.decrypt_value("session", &encrypted).unwrap(),
+			"my secret"
+		);
+
+		assert_eq!(
+			key.decrypt_value("other", &encrypted).unwrap_err(),
+			DecryptError::MacNotEqual
+		);
+		assert_eq!(
+			CookieKey::new()
+				.decrypt_value("session", &encrypted)
+				.unwrap_err(),
+			DecryptError::MacNotEqual
+		);
+		assert_eq!(
+			key.decrypt_value("session", "invalid").unwrap_err(),
+			DecryptError::InvalidFormat
+		);
+	}
+}
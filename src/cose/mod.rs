@@ -0,0 +1,502 @@
+//! COSE (RFC 9052) keys and `COSE_Sign1` messages signed with ed25519.
+//!
+//! Public keys are encoded as `OKP` `COSE_Key` maps (P-256 keys as `EC2`
+//! maps) and messages are signed with the `EdDSA` algorithm. The algorithm is stored in the
+//! protected header and is checked when verifying.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cose::CoseSign1;
+//! use chuchi_crypto::signature::{Keypair, PublicKey};
+//!
+//! let keypair = Keypair::new();
+//! let cose_key = keypair.public().to_cose_key();
+//!
+//! let msg = CoseSign1::sign(&keypair, b"temperature: 21", b"").to_bytes();
+//!
+//! let public = PublicKey::from_cose_key(&cose_key).unwrap();
+//! let msg = CoseSign1::from_bytes(&msg).unwrap();
+//! assert!(msg.verify(&public, b""));
+//! assert_eq!(msg.payload(), Some(&b"temperature: 21"[..]));
+//! ```
+
+use crate::error::DecodeError;
+use crate::signature::{Keypair, PublicKey, Signature};
+
+use ciborium::value::{Integer, Value};
+
+/// The CBOR tag of a `COSE_Sign1` message.
+const TAG_SIGN1: u64 = 18;
+
+// header labels
+const LABEL_ALG: i64 = 1;
+const LABEL_KID: i64 = 4;
+
+// key labels
+const KEY_KTY: i64 = 1;
+const KEY_KID: i64 = 2;
+const KEY_ALG: i64 = 3;
+const KEY_CRV: i64 = -1;
+const KEY_X: i64 = -2;
+#[cfg(feature = "p256")]
+const KEY_Y: i64 = -3;
+
+const KTY_OKP: i64 = 1;
+#[cfg(feature = "p256")]
+const KTY_EC2: i64 = 2;
+const ALG_EDDSA: i64 = -8;
+#[cfg(feature = "p256")]
+const ALG_ES256: i64 = -7;
+#[cfg(feature = "p256")]
+const CRV_P256: i64 = 1;
+#[cfg(feature = "cipher")]
+const CRV_X25519: i64 = 4;
+const CRV_ED25519: i64 = 6;
+
+impl PublicKey {
+	/// Returns the key as a CBOR encoded `COSE_Key` with `alg` set to
+	/// `EdDSA`.
+	pub fn to_cose_key(&self) -> Vec<u8> {
+		encode(&okp_key(CRV_ED25519, Some(ALG_EDDSA), &self.to_bytes()))
+	}
+
+	/// Parses a CBOR encoded `COSE_Key`, if `alg` is set it needs to be
+	/// `EdDSA`.
+	pub fn from_cose_key(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let x = parse_okp_key(bytes, CRV_ED25519, Some(ALG_EDDSA))?;
+		Self::try_from_slice(&x).map_err(|_| DecodeError::InvalidBytes)
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl crate::cipher::PublicKey {
+	/// Returns the key as a CBOR encoded `COSE_Key` with the curve
+	/// `X25519`.
+	pub fn to_cose_key(&self) -> Vec<u8> {
+		encode(&okp_key(CRV_X25519, None, &self.to_bytes()))
+	}
+
+	/// Parses a CBOR encoded `COSE_Key` with the curve `X25519`.
+	pub fn from_cose_key(bytes: &[u8]) -> Result<Self, DecodeError> {
+		parse_okp_key(bytes, CRV_X25519, None).map(Self::from)
+	}
+}
+
+#[cfg(feature = "p256")]
+impl crate::signature::p256::PublicKey {
+	/// Returns the key as a CBOR encoded `EC2` `COSE_Key` with `alg` set to
+	/// `ES256`.
+	pub fn to_cose_key(&self) -> Vec<u8> {
+		let point = self.to_uncompressed();
+
+		encode(&Value::Map(vec![
+			(int(KEY_KTY), int(KTY_EC2)),
+			(int(KEY_ALG), int(ALG_ES256)),
+			(int(KEY_CRV), int(CRV_P256)),
+			(int(KEY_X), Value::Bytes(point[1..33].to_vec())),
+			(int(KEY_Y), Value::Bytes(point[33..].to_vec())),
+		]))
+	}
+
+	/// Parses a CBOR encoded `EC2` `COSE_Key` with the curve `P-256`, if
+	/// `alg` is set it needs to be `ES256`.
+	///
+	/// Compressed points are not supported.
+	pub fn from_cose_key(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let entries = map_entries(decode(bytes)?)?;
+		check_key(&entries, KTY_EC2, CRV_P256, Some(ALG_ES256))?;
+
+		let mut point = vec![0x04];
+		for label in [KEY_X, KEY_Y] {
+			match get(&entries, label) {
+				Some(Value::Bytes(c)) if c.len() == 32 => {
+					point.extend_from_slice(c)
+				}
+				Some(Value::Bytes(_)) => {
+					return Err(DecodeError::InvalidLength)
+				}
+				_ => return Err(DecodeError::InvalidBytes),
+			}
+		}
+
+		Self::try_from_slice(&point).map_err(|_| DecodeError::InvalidBytes)
+	}
+}
+
+/// A `COSE_Sign1` message signed with `EdDSA`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoseSign1 {
+	/// The serialized protected header.
+	protected: Vec<u8>,
+	kid: Option<Vec<u8>>,
+	payload: Option<Vec<u8>>,
+	signature: Signature,
+}
+
+impl CoseSign1 {
+	/// Signs the payload, `external_aad` is signed but not included in the
+	/// message.
+	pub fn sign(
+		keypair: &Keypair,
+		payload: &[u8],
+		external_aad: &[u8],
+	) -> Self {
+		let protected = encode(&map([(LABEL_ALG, int(ALG_EDDSA))]));
+		let signature =
+			keypair.sign(sig_structure(&protected, external_aad, payload));
+
+		Self {
+			protected,
+			kid: None,
+			payload: Some(payload.to_vec()),
+			signature,
+		}
+	}
+
+	/// The key id in the unprotected header.
+	pub fn kid(&self) -> Option<&[u8]> {
+		self.kid.as_deref()
+	}
+
+	/// Sets the key id, which is not covered by the signature.
+	pub fn set_kid(&mut self, kid: Option<Vec<u8>>) {
+		self.kid = kid;
+	}
+
+	/// Returns `None` if the payload is detached.
+	pub fn payload(&self) -> Option<&[u8]> {
+		self.payload.as_deref()
+	}
+
+	/// Removes the payload from the message, it then needs to be passed to
+	/// `verify_detached`.
+	pub fn detach(&mut self) -> Option<Vec<u8>> {
+		self.payload.take()
+	}
+
+	/// Returns false if the payload is detached.
+	pub fn verify(&self, public_key: &PublicKey, external_aad: &[u8]) -> bool {
+		match &self.payload {
+			Some(payload) => {
+				self.verify_inner(public_key, payload, external_aad)
+			}
+			None => false,
+		}
+	}
+
+	/// Verifies the message with a detached payload.
+	pub fn verify_detached(
+		&self,
+		public_key: &PublicKey,
+		payload: &[u8],
+		external_aad: &[u8],
+	) -> bool {
+		self.payload.is_none()
+			&& self.verify_inner(public_key, payload, external_aad)
+	}
+
+	fn verify_inner(
+		&self,
+		public_key: &PublicKey,
+		payload: &[u8],
+		external_aad: &[u8],
+	) -> bool {
+		public_key.verify(
+			sig_structure(&self.protected, external_aad, payload),
+			&self.signature,
+		)
+	}
+
+	/// Returns the tagged CBOR encoding.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let unprotected = match &self.kid {
+			Some(kid) => map([(LABEL_KID, Value::Bytes(kid.clone()))]),
+			None => Value::Map(vec![]),
+		};
+		let payload = match &self.payload {
+			Some(payload) => Value::Bytes(payload.clone()),
+			None => Value::Null,
+		};
+
+		let msg = Value::Array(vec![
+			Value::Bytes(self.protected.clone()),
+			unprotected,
+			payload,
+			Value::Bytes(self.signature.to_bytes().to_vec()),
+		]);
+
+		encode(&Value::Tag(TAG_SIGN1, Box::new(msg)))
+	}
+
+	/// Parses a tagged or untagged `COSE_Sign1` message.
+	///
+	/// Returns an error if the algorithm is not `EdDSA`.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let value = match decode(bytes)? {
+			Value::Tag(TAG_SIGN1, value) => *value,
+			Value::Tag(..) => return Err(DecodeError::InvalidBytes),
+			value => value,
+		};
+
+		let Value::Array(parts) = value else {
+			return Err(DecodeError::InvalidBytes);
+		};
+		let [protected, unprotected, payload, signature]: [Value; 4] =
+			parts.try_into().map_err(|_| DecodeError::InvalidBytes)?;
+
+		let protected = into_bytes(protected)?;
+		let header = map_entries(decode(&protected)?)?;
+		if get_int(&header, LABEL_ALG) != Some(ALG_EDDSA) {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		let unprotected = map_entries(unprotected)?;
+		let kid = match get(&unprotected, LABEL_KID) {
+			Some(Value::Bytes(kid)) => Some(kid.clone()),
+			Some(_) => return Err(DecodeError::InvalidBytes),
+			None => None,
+		};
+
+		let payload = match payload {
+			Value::Null => None,
+			payload => Some(into_bytes(payload)?),
+		};
+
+		let signature = Signature::try_from_slice(&into_bytes(signature)?)
+			.map_err(|_| DecodeError::InvalidLength)?;
+
+		Ok(Self {
+			protected,
+			kid,
+			payload,
+			signature,
+		})
+	}
+}
+
+/// The data which actually gets signed.
+fn sig_structure(
+	protected: &[u8],
+	external_aad: &[u8],
+	payload: &[u8],
+) -> Vec<u8> {
+	encode(&Value::Array(vec![
+		Value::Text("Signature1".into()),
+		Value::Bytes(protected.to_vec()),
+		Value::Bytes(external_aad.to_vec()),
+		Value::Bytes(payload.to_vec()),
+	]))
+}
+
+fn okp_key(crv: i64, alg: Option<i64>, x: &[u8]) -> Value {
+	let mut entries = vec![(int(KEY_KTY), int(KTY_OKP))];
+	if let Some(alg) = alg {
+		entries.push((int(KEY_ALG), int(alg)));
+	}
+	entries.push((int(KEY_CRV), int(crv)));
+	entries.push((int(KEY_X), Value::Bytes(x.to_vec())));
+
+	Value::Map(entries)
+}
+
+/// Returns `x`, checks `kty`, `crv` and if present and given `alg`.
+fn parse_okp_key(
+	bytes: &[u8],
+	crv: i64,
+	alg: Option<i64>,
+) -> Result<[u8; 32], DecodeError> {
+	let entries = map_entries(decode(bytes)?)?;
+	check_key(&entries, KTY_OKP, crv, alg)?;
+
+	match get(&entries, KEY_X) {
+		Some(Value::Bytes(x)) => x
+			.as_slice()
+			.try_into()
+			.map_err(|_| DecodeError::InvalidLength),
+		_ => Err(DecodeError::InvalidBytes),
+	}
+}
+
+/// Checks `kty`, `crv`, that `kid` is a byte string and if present and
+/// given `alg`.
+fn check_key(
+	entries: &[(Value, Value)],
+	kty: i64,
+	crv: i64,
+	alg: Option<i64>,
+) -> Result<(), DecodeError> {
+	if get_int(entries, KEY_KTY) != Some(kty)
+		|| get_int(entries, KEY_CRV) != Some(crv)
+	{
+		return Err(DecodeError::InvalidBytes);
+	}
+	if alg.is_some()
+		&& get(entries, KEY_ALG).is_some()
+		&& get_int(entries, KEY_ALG) != alg
+	{
+		return Err(DecodeError::InvalidBytes);
+	}
+	if !matches!(get(entries, KEY_KID), None | Some(Value::Bytes(_))) {
+		return Err(DecodeError::InvalidBytes);
+	}
+
+	Ok(())
+}
+
+fn int(i: i64) -> Value {
+	Value::Integer(i.into())
+}
+
+fn map<const N: usize>(entries: [(i64, Value); N]) -> Value {
+	Value::Map(entries.into_iter().map(|(k, v)| (int(k), v)).collect())
+}
+
+fn map_entries(value: Value) -> Result<Vec<(Value, Value)>, DecodeError> {
+	match value {
+		Value::Map(entries) => Ok(entries),
+		_ => Err(DecodeError::InvalidBytes),
+	}
+}
+
+fn get(entries: &[(Value, Value)], label: i64) -> Option<&Value> {
+	let label = Integer::from(label);
+	entries
+		.iter()
+		.find(|(k, _)| matches!(k, Value::Integer(i) if *i == label))
+		.map(|(_, v)| v)
+}
+
+fn get_int(entries: &[(Value, Value)], label: i64) -> Option<i64> {
+	match get(entries, label)? {
+		Value::Integer(i) => i64::try_from(*i).ok(),
+		_ => None,
+	}
+}
+
+fn into_bytes(value: Value) -> Result<Vec<u8>, DecodeError> {
+	match value {
+		Value::Bytes(bytes) => Ok(bytes),
+		_ => Err(DecodeError::InvalidBytes),
+	}
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+	let mut bytes = vec![];
+	// writing to a vec can't fail
+	ciborium::into_writer(value, &mut bytes).unwrap();
+
+	bytes
+}
+
+fn decode(bytes: &[u8]) -> Result<Value, DecodeError> {
+	ciborium::from_reader(bytes).map_err(|_| DecodeError::InvalidBytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn keypair() -> Keypair {
+		Keypair::from([7; 32])
+	}
+
+	#[test]
+	fn test_cose_key() {
+		let keypair = keypair();
+		let key = keypair.public().to_cose_key();
+
+		// {1: 1, 3: -8, -1: 6, -2: h'd75a...'}
+		assert_eq!(
+			&key[..10],
+			&[0xa4, 0x01, 0x01, 0x03, 0x27, 0x20, 0x06, 0x21, 0x58, 0x20]
+		);
+		assert_eq!(&key[10..], &keypair.public().to_bytes());
+
+		let public = PublicKey::from_cose_key(&key).unwrap();
+		assert_eq!(&public, keypair.public());
+
+		// the curve needs to match
+		let mut x25519 = key.clone();
+		x25519[6] = 0x04;
+		assert!(PublicKey::from_cose_key(&x25519).is_err());
+		assert!(PublicKey::from_cose_key(&key[..20]).is_err());
+	}
+
+	#[cfg(feature = "cipher")]
+	#[test]
+	fn test_x25519_cose_key() {
+		use crate::cipher;
+
+		let public = cipher::Keypair::new().public().clone();
+		let key = public.to_cose_key();
+		assert_eq!(cipher::PublicKey::from_cose_key(&key).unwrap(), public);
+		assert!(PublicKey::from_cose_key(&key).is_err());
+	}
+
+	#[cfg(feature = "p256")]
+	#[test]
+	fn test_p256_cose_key() {
+		use crate::signature::p256;
+
+		let public = p256::Keypair::new().public().clone();
+		let key = public.to_cose_key();
+		// {1: 2, 3: -7, -1: 1, -2: h'..', -3: h'..'}
+		assert_eq!(
+			&key[..10],
+			&[0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58, 0x20]
+		);
+		assert_eq!(p256::PublicKey::from_cose_key(&key).unwrap(), public);
+		assert!(PublicKey::from_cose_key(&key).is_err());
+
+		let ed25519 = keypair().public().to_cose_key();
+		assert!(p256::PublicKey::from_cose_key(&ed25519).is_err());
+	}
+
+	#[test]
+	fn test_sign1() {
+		let keypair = keypair();
+		let payload = b"This is the content.";
+
+		let mut msg = CoseSign1::sign(&keypair, payload, b"");
+		// cross checked with python cryptography
+		let expected = "d28443a10127a054546869732069732074686520636f6e74656e\
+			742e58402b67df9b75a134921f5feb0dca8f6f7e54e69eca37c27563e8150fac\
+			2690f12881e3432aa658f8e60463fe2229dbb749b345cd3a3100ba720c353ad7\
+			4f8a8805";
+		let hex: String =
+			msg.to_bytes().iter().map(|b| format!("{b:02x}")).collect();
+		assert_eq!(hex, expected);
+
+		msg.set_kid(Some(b"11".to_vec()));
+		let bytes = msg.to_bytes();
+
+		// tag 18, [h'a10127', {4: h'3131'}, payload, signature]
+		assert_eq!(
+			&bytes[..12],
+			&[
+				0xd2, 0x84, 0x43, 0xa1, 0x01, 0x27, 0xa1, 0x04, 0x42, 0x31,
+				0x31, 0x54
+			]
+		);
+
+		let msg = CoseSign1::from_bytes(&bytes).unwrap();
+		assert_eq!(msg.kid(), Some(&b"11"[..]));
+		assert!(msg.verify(keypair.public(), b""));
+		assert!(!msg.verify(keypair.public(), b"aad"));
+		assert!(!msg.verify(Keypair::new().public(), b""));
+
+		// untagged messages are accepted as well
+		assert_eq!(CoseSign1::from_bytes(&bytes[1..]).unwrap(), msg);
+
+		let mut detached = msg.clone();
+		assert_eq!(detached.detach().unwrap(), payload);
+		let detached = CoseSign1::from_bytes(&detached.to_bytes()).unwrap();
+		assert!(!detached.verify(keypair.public(), b""));
+		assert!(detached.verify_detached(keypair.public(), payload, b""));
+
+		// only EdDSA is accepted
+		let mut es256 = bytes.clone();
+		es256[5] = 0x26;
+		assert!(CoseSign1::from_bytes(&es256).is_err());
+	}
+}
@@ -0,0 +1,105 @@
+//! Constant time operations.
+//!
+//! Comparing secrets like tags, tokens or hashes with `==` on slices stops
+//! at the first difference, which leaks how many bytes matched. These
+//! functions always take the same time for inputs of the same length.
+//!
+//! The types of [`subtle`](https://docs.rs/subtle) are reexported for
+//! code which needs to combine multiple checks without branching.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::ct::{ct_eq, ct_select};
+//!
+//! assert!(ct_eq(b"secret", b"secret"));
+//! assert!(!ct_eq(b"secret", b"Secret"));
+//!
+//! assert_eq!(ct_select(&[1, 2], &[3, 4], true), [3, 4]);
+//! ```
+
+pub use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// Compares two slices in constant time.
+///
+/// Only the length of the slices might leak, slices of different lengths
+/// are never equal.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+	a.ct_eq(b).into()
+}
+
+/// Returns true if all bytes are zero, in constant time.
+pub fn ct_is_zero(bytes: &[u8]) -> bool {
+	let acc = bytes.iter().fold(0u8, |acc, b| acc | b);
+
+	acc.ct_eq(&0).into()
+}
+
+/// Returns `b` if `choice` is true else `a`, in constant time.
+pub fn ct_select<const N: usize>(
+	a: &[u8; N],
+	b: &[u8; N],
+	choice: bool,
+) -> [u8; N] {
+	let choice = Choice::from(choice as u8);
+
+	let mut out = [0u8; N];
+	for ((o, a), b) in out.iter_mut().zip(a).zip(b) {
+		*o = u8::conditional_select(a, b, choice);
+	}
+
+	out
+}
+
+/// Copies `src` into `dst` if `choice` is true, in constant time.
+///
+/// ## Panics
+/// When the slices don't have the same length.
+pub fn ct_assign(dst: &mut [u8], src: &[u8], choice: bool) {
+	assert_eq!(dst.len(), src.len());
+	let choice = Choice::from(choice as u8);
+
+	for (d, s) in dst.iter_mut().zip(src) {
+		d.conditional_assign(s, choice);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn eq() {
+		assert!(ct_eq(b"", b""));
+		assert!(ct_eq(b"abc", b"abc"));
+		assert!(!ct_eq(b"abc", b"abd"));
+		assert!(!ct_eq(b"abc", b"ab"));
+	}
+
+	#[test]
+	fn is_zero() {
+		assert!(ct_is_zero(&[]));
+		assert!(ct_is_zero(&[0; 32]));
+		let mut bytes = [0; 32];
+		bytes[31] = 1;
+		assert!(!ct_is_zero(&bytes));
+	}
+
+	#[test]
+	fn select() {
+		let a = [1u8; 4];
+		let b = [2u8; 4];
+		assert_eq!(ct_select(&a, &b, false), a);
+		assert_eq!(ct_select(&a, &b, true), b);
+
+		let mut dst = a;
+		ct_assign(&mut dst, &b, false);
+		assert_eq!(dst, a);
+		ct_assign(&mut dst, &b, true);
+		assert_eq!(dst, b);
+
+		let opt = CtOption::new(5u8, Choice::from(1));
+		assert_eq!(opt.unwrap_or(0), 5);
+		let none = CtOption::new(5u8, Choice::from(0));
+		assert_eq!(none.unwrap_or(0), 0);
+	}
+}
@@ -0,0 +1,57 @@
+//! Hex encoding without intermediate strings.
+//!
+//! The `hex` method on keys, signatures, tokens and hashes returns a [`Hex`]
+//! which writes the bytes as lowercase hex when formatted, the matching
+//! `from_hex` methods parse it again.
+//!
+//! ## Example
+//! ```
+//! # #[cfg(feature = "hash")]
+//! # {
+//! use chuchi_crypto::hash::{hash, Hash};
+//!
+//! let hash = hash(b"hello");
+//! let s = format!("{}", hash.hex());
+//! assert_eq!(s, hash.to_hex());
+//!
+//! let parsed = Hash::from_hex(&s).unwrap();
+//! assert_eq!(parsed, hash);
+//! # }
+//! ```
+
+use std::fmt;
+
+/// Formats a value as lowercase hex.
+///
+/// Returned by the `hex` method of the types which support hex.
+pub struct Hex<'a, T: ?Sized> {
+	inner: &'a T,
+}
+
+impl<'a, T: ?Sized> Hex<'a, T> {
+	pub(crate) fn new(inner: &'a T) -> Self {
+		Self { inner }
+	}
+
+	/// Returns the value which gets formatted.
+	pub fn get(&self) -> &'a T {
+		self.inner
+	}
+}
+
+impl<T: ?Sized> Clone for Hex<'_, T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T: ?Sized> Copy for Hex<'_, T> {}
+
+/// Writes the bytes as lowercase hex.
+pub(crate) fn fmt_hex(bytes: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+	for b in bytes {
+		write!(f, "{b:02x}")?;
+	}
+
+	Ok(())
+}
@@ -22,6 +22,24 @@ impl fmt::Display for TryFromError {
 
 impl Error for TryFromError {}
 
+/// The operating system could not provide random bytes
+#[derive(Debug, Copy, Clone)]
+pub struct RandomError(());
+
+impl RandomError {
+	pub(crate) fn new() -> Self {
+		Self(())
+	}
+}
+
+impl fmt::Display for RandomError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("failed to get random bytes from the operating system")
+	}
+}
+
+impl Error for RandomError {}
+
 /// Either the length or the format of a slice is incorrect
 #[derive(Debug, Copy, Clone)]
 #[non_exhaustive]
@@ -31,7 +49,16 @@ pub enum DecodeError {
 }
 
 impl DecodeError {
-	#[cfg(feature = "b64")]
+	#[cfg(any(
+		feature = "b64",
+		feature = "hex",
+		feature = "pkcs8",
+		feature = "mnemonic",
+		feature = "sshsig",
+		feature = "jwk",
+		feature = "webauthn",
+		feature = "rsa-verify"
+	))]
 	pub(crate) fn inv_bytes<T>(_: T) -> Self {
 		Self::InvalidBytes
 	}
@@ -44,3 +71,410 @@ impl fmt::Display for DecodeError {
 }
 
 impl Error for DecodeError {}
+
+/// Decrypting a message failed
+#[cfg(feature = "cipher")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecryptError {
+	/// The message is too short or malformed
+	InvalidFormat,
+	/// The message was encrypted with a key which is not known
+	UnknownKey,
+	/// The message was modified or the wrong key was used
+	MacNotEqual,
+}
+
+#[cfg(feature = "cipher")]
+impl fmt::Display for DecryptError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl Error for DecryptError {}
+
+/// A nonce sequence has no unused nonces left
+#[cfg(feature = "cipher")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NonceExhausted(());
+
+#[cfg(feature = "cipher")]
+impl NonceExhausted {
+	pub(crate) fn new() -> Self {
+		Self(())
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl fmt::Display for NonceExhausted {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("no unused nonces left, the key needs to be replaced")
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl Error for NonceExhausted {}
+
+/// More key material was requested than HKDF can derive
+#[cfg(feature = "hash")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OkmTooLong(());
+
+#[cfg(feature = "hash")]
+impl OkmTooLong {
+	pub(crate) fn new() -> Self {
+		Self(())
+	}
+}
+
+#[cfg(feature = "hash")]
+impl fmt::Display for OkmTooLong {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("hkdf can derive at most 8160 bytes")
+	}
+}
+
+#[cfg(feature = "hash")]
+impl Error for OkmTooLong {}
+
+/// The Diffie-Hellman output was all zeros because of a low order public
+/// key
+#[cfg(feature = "cipher")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LowOrderPublicKey(());
+
+#[cfg(feature = "cipher")]
+impl LowOrderPublicKey {
+	pub(crate) fn new() -> Self {
+		Self(())
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl fmt::Display for LowOrderPublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("the public key has a low order")
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl Error for LowOrderPublicKey {}
+
+/// Opening a session message failed
+#[cfg(feature = "cipher")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SessionError {
+	/// The message is too short or its counter is too far ahead
+	InvalidFormat,
+	/// The message was already received
+	Replayed,
+	/// The message is outside of the replay window or its key was already
+	/// erased
+	TooOld,
+	/// The message was modified or the wrong key was used
+	MacNotEqual,
+}
+
+#[cfg(feature = "cipher")]
+impl fmt::Display for SessionError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl Error for SessionError {}
+
+/// Encrypting or decrypting a Double Ratchet message failed
+#[cfg(feature = "cipher")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RatchetError {
+	/// The responder can't send a message before receiving one
+	NotReady,
+	/// The message is too short
+	InvalidFormat,
+	/// The Diffie-Hellman output was all zeros because of a low order
+	/// public key
+	InvalidPublicKey,
+	/// More than `MAX_SKIP` messages were skipped
+	TooManySkipped,
+	/// The message was already received or its key was discarded
+	Replayed,
+	/// The message was modified or the wrong key was used
+	MacNotEqual,
+}
+
+#[cfg(feature = "cipher")]
+impl fmt::Display for RatchetError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl Error for RatchetError {}
+
+/// Setting up an HPKE context or opening a message failed
+#[cfg(feature = "cipher")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HpkeError {
+	/// The Diffie-Hellman output was all zeros because of a low order
+	/// public key
+	InvalidPublicKey,
+	/// The message was modified or the wrong key was used
+	MacNotEqual,
+}
+
+#[cfg(feature = "cipher")]
+impl fmt::Display for HpkeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl Error for HpkeError {}
+
+/// Encapsulating or decapsulating a shared secret failed
+#[cfg(feature = "cipher")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KemError {
+	/// The public key has a low order
+	InvalidPublicKey,
+	/// The encapsulation is not valid
+	InvalidEncapsulation,
+}
+
+#[cfg(feature = "cipher")]
+impl fmt::Display for KemError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl Error for KemError {}
+
+/// Opening an envelope failed
+#[cfg(feature = "cipher")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EnvelopeError<E> {
+	/// The envelope is malformed
+	InvalidFormat,
+	/// The payload was modified or the aad is not the same
+	MacNotEqual,
+	/// The kek provider could not unwrap the data key
+	Kek(E),
+}
+
+#[cfg(feature = "cipher")]
+impl<E: fmt::Debug> fmt::Display for EnvelopeError<E> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl<E: Error + 'static> Error for EnvelopeError<E> {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::Kek(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+/// Encrypting or decrypting an age file failed
+#[cfg(feature = "age")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AgeError {
+	/// No recipient was given
+	NoRecipients,
+	/// The Diffie-Hellman output was all zeros because of a low order
+	/// public key
+	InvalidPublicKey,
+	/// The header or the payload is malformed
+	InvalidFormat,
+	/// The file was not encrypted to this identity or passphrase
+	NoMatchingKey,
+	/// The scrypt work factor is above the allowed maximum
+	WorkFactorTooHigh,
+	/// The header or the payload was modified
+	MacNotEqual,
+}
+
+#[cfg(feature = "age")]
+impl fmt::Display for AgeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "age")]
+impl Error for AgeError {}
+
+/// Hashing or verifying a password failed
+#[cfg(feature = "password")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PasswordError {
+	/// The parameters are outside of the allowed range
+	InvalidParams,
+	/// The hash is not a valid PHC string
+	InvalidHash,
+	/// The algorithm of the hash is not supported
+	UnsupportedAlgorithm,
+}
+
+#[cfg(feature = "password")]
+impl fmt::Display for PasswordError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "password")]
+impl Error for PasswordError {}
+
+/// Signing with an `AsyncSigner` failed
+///
+/// Contains the error of the signing backend.
+#[cfg(feature = "signature")]
+#[derive(Debug)]
+pub struct SignError(Box<dyn Error + Send + Sync>);
+
+#[cfg(feature = "signature")]
+impl SignError {
+	pub fn new(err: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+		Self(err.into())
+	}
+
+	pub fn into_inner(self) -> Box<dyn Error + Send + Sync> {
+		self.0
+	}
+}
+
+#[cfg(feature = "signature")]
+impl fmt::Display for SignError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+#[cfg(feature = "signature")]
+impl Error for SignError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		Some(&*self.0)
+	}
+}
+
+/// Decoding a minisign key or signature failed
+#[cfg(feature = "minisign")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MinisignError {
+	/// The file or the base64 line could not be parsed
+	InvalidFormat,
+	/// The signature or key algorithm is not supported
+	UnsupportedAlgorithm,
+	/// The secret key is encrypted and the password is missing or wrong
+	InvalidPassword,
+	/// The scrypt limits are invalid or larger than the ones of minisign
+	InvalidParams,
+}
+
+#[cfg(feature = "minisign")]
+impl fmt::Display for MinisignError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "minisign")]
+impl Error for MinisignError {}
+
+/// Verifying a WebAuthn assertion failed
+#[cfg(feature = "webauthn")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WebauthnError {
+	/// The authenticator data or the client data could not be parsed
+	InvalidFormat,
+	/// The client data is not of the type `webauthn.get`
+	InvalidType,
+	InvalidChallenge,
+	InvalidOrigin,
+	/// The authenticator data was created for another relying party
+	InvalidRpId,
+	UserNotPresent,
+	/// User verification was required but not performed
+	UserNotVerified,
+	InvalidSignature,
+}
+
+#[cfg(feature = "webauthn")]
+impl fmt::Display for WebauthnError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "webauthn")]
+impl Error for WebauthnError {}
+
+/// Verifying a token failed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TokenError {
+	/// The token could not be parsed
+	InvalidFormat,
+	/// The token was signed with another algorithm than the one of the key
+	InvalidAlgorithm,
+	InvalidSignature,
+	Expired,
+	/// The token is not valid yet
+	NotYetValid,
+	/// The token was issued for another audience
+	InvalidAudience,
+}
+
+impl fmt::Display for TokenError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+impl Error for TokenError {}
+
+/// Authenticating a request failed
+///
+/// `Missing` and `Invalid` map to 401 and `Forbidden` to 403.
+#[cfg(feature = "chuchi")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AuthError {
+	/// The header or cookie is missing
+	Missing,
+	/// The header or cookie could not be parsed
+	Invalid,
+	/// The signature or the token did not match
+	Forbidden,
+}
+
+#[cfg(feature = "chuchi")]
+impl fmt::Display for AuthError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "chuchi")]
+impl Error for AuthError {}
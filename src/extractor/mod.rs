@@ -0,0 +1,272 @@
+//! Extractors for the [chuchi](https://docs.rs/chuchi) web framework.
+//!
+//! - `Bearer` parses a `Token` from the `Authorization` header.
+//! - `SignedCookie` and `EncryptedCookie` read a cookie with the same name
+//!   as the handler parameter using the `CookieKey` resource.
+//! - `verify_body` reads a request body signed with an `HmacKey`.
+//!
+//! Every failure returns an `AuthError` which maps to 401 or 403.
+//!
+//! ## Example
+//! ```no_run
+//! use chuchi::get;
+//! use chuchi_crypto::extractor::SignedCookie;
+//!
+//! #[get("/")]
+//! fn index(user: SignedCookie) -> String {
+//!     format!("hello {}", user.0)
+//! }
+//! ```
+
+use crate::cookie::CookieKey;
+use crate::error::AuthError;
+use crate::hash::{HmacKey, Tag};
+use crate::token::Token;
+
+use std::str::FromStr;
+
+use ::chuchi::error::{ClientErrorKind, ErrorKind};
+use ::chuchi::extractor::{Extractor, ExtractorError};
+use ::chuchi::header::RequestHeader;
+use ::chuchi::{
+	extractor_extract, extractor_prepare, extractor_validate, Request,
+};
+
+/// The header `verify_body` reads the base64 encoded `Tag` from.
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+impl AuthError {
+	fn client_kind(&self) -> ClientErrorKind {
+		match self {
+			Self::Missing | Self::Invalid => ClientErrorKind::Unauthorized,
+			Self::Forbidden => ClientErrorKind::Forbidden,
+		}
+	}
+}
+
+impl ExtractorError for AuthError {
+	fn error_kind(&self) -> ErrorKind {
+		self.client_kind().into()
+	}
+
+	fn into_std(self) -> Box<dyn std::error::Error + Send + Sync> {
+		Box::new(self)
+	}
+}
+
+impl From<AuthError> for ::chuchi::Error {
+	fn from(e: AuthError) -> Self {
+		Self::new(e.client_kind(), e)
+	}
+}
+
+/// A token sent as `Authorization: Bearer <token>`.
+///
+/// The token is only parsed, use `verify` to compare it with a known
+/// token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bearer<const S: usize>(pub Token<S>);
+
+impl<const S: usize> Bearer<S> {
+	/// Compares the token in constant time.
+	pub fn verify(&self, token: &Token<S>) -> Result<(), AuthError> {
+		if self.0 == *token {
+			Ok(())
+		} else {
+			Err(AuthError::Forbidden)
+		}
+	}
+}
+
+impl<'a, R, const S: usize> Extractor<'a, R> for Bearer<S> {
+	type Error = AuthError;
+	type Prepared = Token<S>;
+
+	extractor_validate!();
+
+	extractor_prepare!(|prepare| { bearer(prepare.header) });
+
+	extractor_extract!(|extract| { Ok(Bearer(extract.prepared)) });
+}
+
+fn bearer<const S: usize>(
+	header: &RequestHeader,
+) -> Result<Token<S>, AuthError> {
+	let value = header.value("authorization").ok_or(AuthError::Missing)?;
+	parse_bearer(value)
+}
+
+fn parse_bearer<const S: usize>(value: &str) -> Result<Token<S>, AuthError> {
+	let (scheme, token) = value.split_once(' ').ok_or(AuthError::Invalid)?;
+	if !scheme.eq_ignore_ascii_case("bearer") {
+		return Err(AuthError::Invalid);
+	}
+
+	Token::from_str(token.trim()).map_err(|_| AuthError::Invalid)
+}
+
+/// A cookie signed with `CookieKey::sign_value`.
+///
+/// The name of the cookie is the name of the handler parameter and the
+/// `CookieKey` needs to be added as a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedCookie(pub String);
+
+impl<'a, R> Extractor<'a, R> for SignedCookie {
+	type Error = AuthError;
+	type Prepared = String;
+
+	extractor_validate!(|validate| {
+		assert!(
+			validate.resources.exists::<CookieKey>(),
+			"Resource CookieKey does not exist"
+		);
+	});
+
+	extractor_prepare!(|prepare| {
+		let key = prepare.resources.get::<CookieKey>().unwrap();
+		let value = cookie(prepare.header, prepare.name)?;
+
+		key.verify_value(prepare.name, value)
+			.map(String::from)
+			.map_err(|_| AuthError::Forbidden)
+	});
+
+	extractor_extract!(|extract| { Ok(SignedCookie(extract.prepared)) });
+}
+
+/// A cookie encrypted with `CookieKey::encrypt_value`.
+///
+/// The name of the cookie is the name of the handler parameter and the
+/// `CookieKey` needs to be added as a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedCookie(pub String);
+
+impl<'a, R> Extractor<'a, R> for EncryptedCookie {
+	type Error = AuthError;
+	type Prepared = String;
+
+	extractor_validate!(|validate| {
+		assert!(
+			validate.resources.exists::<CookieKey>(),
+			"Resource CookieKey does not exist"
+		);
+	});
+
+	extractor_prepare!(|prepare| {
+		let key = prepare.resources.get::<CookieKey>().unwrap();
+		let value = cookie(prepare.header, prepare.name)?;
+
+		key.decrypt_value(prepare.name, value)
+			.map_err(|_| AuthError::Forbidden)
+	});
+
+	extractor_extract!(|extract| { Ok(EncryptedCookie(extract.prepared)) });
+}
+
+fn cookie<'a>(
+	header: &'a RequestHeader,
+	name: &str,
+) -> Result<&'a str, AuthError> {
+	let cookies = header.value("cookie").ok_or(AuthError::Missing)?;
+	find_cookie(cookies, name).ok_or(AuthError::Missing)
+}
+
+fn find_cookie<'a>(cookies: &'a str, name: &str) -> Option<&'a str> {
+	cookies
+		.split(';')
+		.filter_map(|c| c.trim().split_once('='))
+		.find(|(n, _)| *n == name)
+		.map(|(_, v)| v.trim_matches('"'))
+}
+
+/// Reads the body and verifies the `Tag` in the `x-signature` header.
+///
+/// Returns the body if the signature is valid.
+pub async fn verify_body(
+	req: &mut Request,
+	mac: &HmacKey,
+) -> Result<Vec<u8>, ::chuchi::Error> {
+	let tag = req
+		.header()
+		.value(SIGNATURE_HEADER)
+		.ok_or(AuthError::Missing)?;
+	let tag = Tag::from_str(tag).map_err(|_| AuthError::Invalid)?;
+
+	let body = req
+		.take_body()
+		.into_bytes()
+		.await
+		.map_err(::chuchi::Error::from_client_io)?;
+
+	if mac.verify(&body, &tag) {
+		Ok(body.to_vec())
+	} else {
+		Err(AuthError::Forbidden.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use ::chuchi::header::Uri;
+
+	#[test]
+	fn test_bearer() {
+		let token = Token::<32>::new();
+
+		let parsed: Token<32> =
+			parse_bearer(&format!("Bearer {token}")).unwrap();
+		assert_eq!(parsed, token);
+		assert!(Bearer(parsed).verify(&token).is_ok());
+
+		assert_eq!(
+			parse_bearer::<32>(&format!("Basic {token}")).unwrap_err(),
+			AuthError::Invalid
+		);
+		assert_eq!(
+			parse_bearer::<16>(&format!("bearer {token}")).unwrap_err(),
+			AuthError::Invalid
+		);
+		assert_eq!(
+			Bearer(Token::<32>::new()).verify(&token).unwrap_err(),
+			AuthError::Forbidden
+		);
+	}
+
+	#[test]
+	fn test_find_cookie() {
+		let cookies = "theme=dark; user=abc.42; session=\"xyz\"";
+		assert_eq!(find_cookie(cookies, "user"), Some("abc.42"));
+		assert_eq!(find_cookie(cookies, "session"), Some("xyz"));
+		assert_eq!(find_cookie(cookies, "use"), None);
+	}
+
+	#[tokio::test]
+	async fn test_verify_body() {
+		let mac = HmacKey::new(b"webhook secret");
+		let body = b"{\"event\":\"ping\"}";
+		let tag = mac.sign(body);
+
+		let request = |tag: &str| {
+			Request::builder(Uri::from_static("/webhook"))
+				.header(SIGNATURE_HEADER, tag)
+				.body(body.to_vec())
+				.build()
+		};
+
+		let mut req = request(&tag.to_string());
+		let verified = verify_body(&mut req, &mac).await.unwrap();
+		assert_eq!(verified, body);
+
+		let mut req = request(&tag.to_string());
+		let other = HmacKey::new(b"another secret");
+		let err = verify_body(&mut req, &other).await.unwrap_err();
+		assert_eq!(err.status_code().as_u16(), 403);
+
+		let mut req = request("invalid");
+		let err = verify_body(&mut req, &mac).await.unwrap_err();
+		assert_eq!(err.status_code().as_u16(), 401);
+	}
+}
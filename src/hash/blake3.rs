@@ -0,0 +1,136 @@
+//! BLAKE3 hashing with the keyed and derive key modes.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::hash::blake3;
+//!
+//! let master = [1u8; 32];
+//! let enc_key = blake3::derive_key("my-app 2024 encryption", &master);
+//! let mac_key = blake3::derive_key("my-app 2024 mac", &master);
+//! assert_ne!(enc_key, mac_key);
+//!
+//! let tag = blake3::keyed_hash(&mac_key, b"message");
+//! assert_eq!(tag, blake3::keyed_hash(&mac_key, b"message"));
+//! ```
+
+use super::fixed_bytes;
+use crate::error::TryFromError;
+
+use std::convert::TryInto;
+use std::fmt;
+use std::io::{self, Read};
+
+/// Hashes some data.
+pub fn hash(data: impl AsRef<[u8]>) -> Hash {
+	let mut hasher = Hasher::new();
+	hasher.update(data);
+	hasher.finalize()
+}
+
+/// Hashes some data with a key, the result can be used as a mac.
+pub fn keyed_hash(key: &[u8; 32], data: impl AsRef<[u8]>) -> Hash {
+	let mut hasher = Hasher::new_keyed(key);
+	hasher.update(data);
+	hasher.finalize()
+}
+
+/// Derives a key from some key material.
+///
+/// The context should be hardcoded, globally unique and application
+/// specific, so that different purposes never get the same key.
+pub fn derive_key(context: &str, material: impl AsRef<[u8]>) -> [u8; 32] {
+	::blake3::derive_key(context, material.as_ref())
+}
+
+/// Hashes everything from the reader until it returns EOF.
+pub fn hash_reader(mut reader: impl Read) -> io::Result<Hash> {
+	let mut hasher = Hasher::new();
+	io::copy(&mut reader, &mut hasher)?;
+	Ok(hasher.finalize())
+}
+
+/// An incremental BLAKE3 hasher.
+#[derive(Clone)]
+pub struct Hasher {
+	inner: ::blake3::Hasher,
+}
+
+impl Hasher {
+	pub fn new() -> Self {
+		Self {
+			inner: ::blake3::Hasher::new(),
+		}
+	}
+
+	/// Creates a hasher in the keyed mode.
+	pub fn new_keyed(key: &[u8; 32]) -> Self {
+		Self {
+			inner: ::blake3::Hasher::new_keyed(key),
+		}
+	}
+
+	/// Creates a hasher in the derive key mode.
+	///
+	/// See `derive_key`.
+	pub fn new_derive_key(context: &str) -> Self {
+		Self {
+			inner: ::blake3::Hasher::new_derive_key(context),
+		}
+	}
+
+	pub fn update(&mut self, data: impl AsRef<[u8]>) {
+		self.inner.update(data.as_ref());
+	}
+
+	pub fn finalize(&self) -> Hash {
+		Hash {
+			bytes: self.inner.finalize().into(),
+		}
+	}
+}
+
+impl fmt::Debug for Hasher {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Hasher")
+	}
+}
+
+impl io::Write for Hasher {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// A BLAKE3 hash.
+///
+/// Comparing two hashes is done in constant time.
+#[derive(Clone, Eq)]
+pub struct Hash {
+	bytes: [u8; 32],
+}
+
+impl Hash {
+	pub const LEN: usize = 32;
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.bytes
+	}
+}
+
+impl PartialEq for Hash {
+	fn eq(&self, other: &Self) -> bool {
+		crate::ct::ct_eq(&self.bytes, &other.bytes)
+	}
+}
+
+fixed_bytes!(Hash, 32);
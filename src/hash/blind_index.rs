@@ -0,0 +1,68 @@
+use super::fixed_bytes;
+use crate::error::TryFromError;
+
+use std::convert::TryInto;
+
+use hmac::{Hmac, Mac as _};
+use sha2::Sha256;
+
+/// Creates a blind index of `N` bytes, `HMAC-SHA256(key, value)` truncated
+/// to `N` bytes.
+///
+/// A blind index allows to search for an encrypted value by storing the
+/// index in a separate column. Use a different key for every column and a
+/// key which is independent from the encryption key.
+///
+/// Shorter indexes cause more collisions which makes them harder to
+/// correlate, but the query then needs to filter out false positives
+/// after decrypting.
+///
+/// ## Panics
+/// If `N` is 0 or larger than 32.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::hash::{blind_index, BlindIndex};
+///
+/// let key = b"a random 32 byte key for emails!";
+/// let index: BlindIndex<16> = blind_index(key, "alice@example.com");
+///
+/// assert_eq!(index, blind_index(key, "alice@example.com"));
+/// ```
+pub fn blind_index<const N: usize>(
+	key: impl AsRef<[u8]>,
+	value: impl AsRef<[u8]>,
+) -> BlindIndex<N> {
+	assert!(N > 0 && N <= 32, "blind index length must be 1 to 32 bytes");
+
+	// hmac accepts keys of any length
+	let mut mac = Hmac::<Sha256>::new_from_slice(key.as_ref()).unwrap();
+	mac.update(value.as_ref());
+	let tag = mac.finalize().into_bytes();
+
+	let mut bytes = [0u8; N];
+	bytes.copy_from_slice(&tag[..N]);
+
+	BlindIndex { bytes }
+}
+
+/// A blind index created with [`blind_index`].
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BlindIndex<const N: usize> {
+	bytes: [u8; N],
+}
+
+impl<const N: usize> BlindIndex<N> {
+	pub const LEN: usize = N;
+
+	/// Returns an error if the slice is not `N` bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; N] {
+		self.bytes
+	}
+}
+
+fixed_bytes!(BlindIndex<const N: usize>);
@@ -0,0 +1,119 @@
+use super::{hash, Hash, Hasher};
+
+/// An append-only hash chain, every head commits to all previous entries.
+///
+/// Appending an entry computes `H(prev || H(entry))` where the first head
+/// is 64 zero bytes. The entry is hashed on its own, so the chain only
+/// needs to keep the entry hashes to create a [`ConsistencyProof`].
+///
+/// Storing a head somewhere else (for example in a second database or a
+/// signed checkpoint) allows to detect if the log has been rewritten.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::hash::Chain;
+///
+/// let mut chain = Chain::new();
+/// chain.append(b"user created");
+/// let old = chain.head().clone();
+///
+/// chain.append(b"user deleted");
+/// let proof = chain.prove(1).unwrap();
+/// assert!(proof.verify(&old, chain.head()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Chain {
+	head: Hash,
+	entries: Vec<Hash>,
+}
+
+impl Chain {
+	/// Creates an empty chain.
+	pub fn new() -> Self {
+		Self {
+			head: Self::genesis(),
+			entries: vec![],
+		}
+	}
+
+	/// The head of an empty chain.
+	pub fn genesis() -> Hash {
+		Hash::from([0u8; 64])
+	}
+
+	/// Appends an entry and returns the new head.
+	pub fn append(&mut self, entry: impl AsRef<[u8]>) -> Hash {
+		let entry = hash(entry);
+		self.head = link(&self.head, &entry);
+		self.entries.push(entry);
+
+		self.head.clone()
+	}
+
+	pub fn head(&self) -> &Hash {
+		&self.head
+	}
+
+	/// Returns the amount of entries.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Returns the head the chain had when it contained `len` entries.
+	///
+	/// Returns `None` if the chain has less than `len` entries.
+	pub fn head_at(&self, len: usize) -> Option<Hash> {
+		let entries = self.entries.get(..len)?;
+
+		Some(entries.iter().fold(Self::genesis(), |h, e| link(&h, e)))
+	}
+
+	/// Creates a proof that the head at `old_len` entries is a prefix of
+	/// the current head.
+	///
+	/// Returns `None` if the chain has less than `old_len` entries.
+	pub fn prove(&self, old_len: usize) -> Option<ConsistencyProof> {
+		self.entries.get(old_len..).map(|entries| ConsistencyProof {
+			entries: entries.to_vec(),
+		})
+	}
+}
+
+/// Proves that a chain head was extended to another head by appending
+/// entries, created with [`Chain::prove`].
+///
+/// The proof contains the hashes of the appended entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyProof {
+	entries: Vec<Hash>,
+}
+
+impl ConsistencyProof {
+	/// Creates a proof from the hashes of the appended entries.
+	pub fn new(entries: Vec<Hash>) -> Self {
+		Self { entries }
+	}
+
+	/// Returns the hashes of the appended entries.
+	pub fn entries(&self) -> &[Hash] {
+		&self.entries
+	}
+
+	/// Returns true if appending the entries to `old` results in `new`.
+	pub fn verify(&self, old: &Hash, new: &Hash) -> bool {
+		let head = self.entries.iter().fold(old.clone(), |h, e| link(&h, e));
+
+		head == *new
+	}
+}
+
+fn link(prev: &Hash, entry: &Hash) -> Hash {
+	let mut hasher = Hasher::new();
+	hasher.update(prev);
+	hasher.update(entry);
+	hasher.finalize()
+}
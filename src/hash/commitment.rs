@@ -0,0 +1,85 @@
+use super::fixed_bytes;
+use crate::error::TryFromError;
+use crate::token::Token;
+
+use std::convert::TryInto;
+
+use blake2::{digest::consts::U32, Blake2b, Digest};
+
+/// The random blinding factor of a [`Commitment`], needs to be kept
+/// secret until the value is revealed.
+pub type Opening = Token<32>;
+
+/// A hiding and binding commitment to a value.
+///
+/// The commitment is `BLAKE2b-256(opening || value)` where the opening
+/// is 32 random bytes. Without the opening the commitment doesn't reveal
+/// anything about the value, and after publishing it the value can't be
+/// changed anymore.
+///
+/// Comparing two commitments is done in constant time.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::hash::Commitment;
+///
+/// // publish the commitment before the draw
+/// let (commitment, opening) = Commitment::commit(b"ticket 17");
+///
+/// // reveal the value and the opening afterwards
+/// assert!(commitment.verify(b"ticket 17", &opening));
+/// assert!(!commitment.verify(b"ticket 18", &opening));
+/// ```
+#[derive(Clone)]
+pub struct Commitment {
+	bytes: [u8; 32],
+}
+
+impl Commitment {
+	pub const LEN: usize = 32;
+
+	/// Commits to a value with a new random opening.
+	pub fn commit(value: impl AsRef<[u8]>) -> (Self, Opening) {
+		let opening = Opening::new();
+
+		(Self::with_opening(value, &opening), opening)
+	}
+
+	/// Creates the commitment to a value with the given opening.
+	///
+	/// The opening must be random and never be reused.
+	pub fn with_opening(value: impl AsRef<[u8]>, opening: &Opening) -> Self {
+		let mut hasher = Blake2b::<U32>::new();
+		hasher.update(opening.as_ref());
+		hasher.update(value.as_ref());
+
+		Self {
+			bytes: hasher.finalize().into(),
+		}
+	}
+
+	/// Returns true if the commitment was created from the value and the
+	/// opening.
+	pub fn verify(&self, value: impl AsRef<[u8]>, opening: &Opening) -> bool {
+		Self::with_opening(value, opening) == *self
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.bytes
+	}
+}
+
+impl PartialEq for Commitment {
+	fn eq(&self, other: &Self) -> bool {
+		crate::ct::ct_eq(&self.bytes, &other.bytes)
+	}
+}
+
+impl Eq for Commitment {}
+
+fixed_bytes!(Commitment, 32);
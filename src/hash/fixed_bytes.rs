@@ -0,0 +1,338 @@
+/// Implements the shared surface of a `[u8; N]` wrapper with a `bytes`
+/// field: `Debug`, b64 `Display` and `FromStr`, `From<[u8; N]>`,
+/// `TryFrom<&[u8]>`, `AsRef<[u8]>`, hex and the serde, protobuf, postgres
+/// and sqlx integrations.
+///
+/// `LEN`, the constructors, `PartialEq` and `Hash` are left to the type,
+/// since only some of them need to compare in constant time.
+///
+/// Invoked as `fixed_bytes!(Id, 32)` or
+/// `fixed_bytes!(BlindIndex<const N: usize>)`.
+macro_rules! fixed_bytes {
+	($name:ident, $len:expr) => {
+		$crate::hash::fixed_bytes!(@impl [] $name [] $len);
+	};
+	($name:ident<const $n:ident: usize>) => {
+		$crate::hash::fixed_bytes!(@impl [const $n: usize] $name [$n] $n);
+	};
+	(@impl [$($gen:tt)*] $name:ident [$($arg:tt)*] $len:expr) => {
+		#[cfg(not(feature = "b64"))]
+		impl<$($gen)*> std::fmt::Debug for $name<$($arg)*> {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.debug_tuple(stringify!($name))
+					.field(&self.as_ref())
+					.finish()
+			}
+		}
+
+		#[cfg(feature = "b64")]
+		impl<$($gen)*> std::fmt::Debug for $name<$($arg)*> {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.debug_tuple(stringify!($name))
+					.field(&self.to_string())
+					.finish()
+			}
+		}
+
+		#[cfg(feature = "b64")]
+		impl<$($gen)*> std::fmt::Display for $name<$($arg)*> {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				let b64 = base64::display::Base64Display::new(
+					self.as_ref(),
+					&base64::engine::general_purpose::URL_SAFE_NO_PAD,
+				);
+				std::fmt::Display::fmt(&b64, f)
+			}
+		}
+
+		impl<$($gen)*> From<[u8; $len]> for $name<$($arg)*> {
+			fn from(bytes: [u8; $len]) -> Self {
+				Self { bytes }
+			}
+		}
+
+		impl<$($gen)*> TryFrom<&[u8]> for $name<$($arg)*> {
+			type Error = $crate::error::TryFromError;
+
+			fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+				<[u8; $len]>::try_from(v)
+					.map_err($crate::error::TryFromError::from_any)
+					.map(Self::from)
+			}
+		}
+
+		#[cfg(feature = "b64")]
+		impl<$($gen)*> std::str::FromStr for $name<$($arg)*> {
+			type Err = $crate::error::DecodeError;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+				if s.len() != $crate::calculate_b64_len($len) {
+					return Err($crate::error::DecodeError::InvalidLength);
+				}
+
+				let mut bytes = [0u8; $len];
+				URL_SAFE_NO_PAD
+					.decode_slice_unchecked(s, &mut bytes)
+					.map_err($crate::error::DecodeError::inv_bytes)
+					.map(|_| Self::from(bytes))
+			}
+		}
+
+		impl<$($gen)*> AsRef<[u8]> for $name<$($arg)*> {
+			fn as_ref(&self) -> &[u8] {
+				&self.bytes
+			}
+		}
+
+		#[cfg(feature = "hex")]
+		impl<$($gen)*> $name<$($arg)*> {
+			/// Returns an adapter which formats the bytes as lowercase hex.
+			pub fn hex(&self) -> $crate::encoding::Hex<'_, Self> {
+				$crate::encoding::Hex::new(self)
+			}
+
+			/// Returns the bytes as a lowercase hex string.
+			pub fn to_hex(&self) -> String {
+				self.hex().to_string()
+			}
+
+			/// Parses a hex string, upper and lowercase are both accepted.
+			pub fn from_hex(
+				s: &str,
+			) -> Result<Self, $crate::error::DecodeError> {
+				$crate::decode_hex::<{ $len }>(s).map(Self::from)
+			}
+		}
+
+		#[cfg(feature = "hex")]
+		impl<$($gen)*> std::fmt::Display
+			for $crate::encoding::Hex<'_, $name<$($arg)*>>
+		{
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				$crate::encoding::fmt_hex(self.get().as_ref(), f)
+			}
+		}
+
+		#[cfg(feature = "serde")]
+		mod impl_serde {
+			use super::*;
+
+			#[cfg(feature = "b64")]
+			use std::borrow::Cow;
+			#[cfg(feature = "b64")]
+			use std::str::FromStr;
+
+			#[cfg(feature = "b64")]
+			use _serde::de::Error;
+			use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+			impl<$($gen)*> Serialize for $name<$($arg)*> {
+				fn serialize<Se>(
+					&self,
+					serializer: Se,
+				) -> Result<Se::Ok, Se::Error>
+				where
+					Se: Serializer,
+				{
+					#[cfg(feature = "b64")]
+					if serializer.is_human_readable() {
+						return serializer.collect_str(&self);
+					}
+
+					serializer.serialize_bytes(self.as_ref())
+				}
+			}
+
+			impl<'de, $($gen)*> Deserialize<'de> for $name<$($arg)*> {
+				fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+				where
+					De: Deserializer<'de>,
+				{
+					#[cfg(feature = "b64")]
+					if deserializer.is_human_readable() {
+						let s: Cow<'_, str> =
+							Deserialize::deserialize(deserializer)?;
+						return Self::from_str(s.as_ref())
+							.map_err(De::Error::custom);
+					}
+
+					$crate::serde_bytes::deserialize::<_, { $len }>(
+						deserializer,
+					)
+					.map(Self::from)
+				}
+			}
+		}
+
+		#[cfg(feature = "protobuf")]
+		mod impl_protobuf {
+			use super::*;
+
+			use protopuffer::{
+				bytes::BytesWrite,
+				decode::{DecodeError, DecodeMessage, FieldKind},
+				encode::{
+					EncodeError, EncodeMessage, FieldOpt, MessageEncoder,
+					SizeBuilder,
+				},
+				WireType,
+			};
+
+			impl<$($gen)*> EncodeMessage for $name<$($arg)*> {
+				const WIRE_TYPE: WireType = WireType::Len;
+
+				fn is_default(&self) -> bool {
+					false
+				}
+
+				fn encoded_size(
+					&mut self,
+					field: Option<FieldOpt>,
+					builder: &mut SizeBuilder,
+				) -> Result<(), EncodeError> {
+					self.bytes.encoded_size(field, builder)
+				}
+
+				fn encode<B>(
+					&mut self,
+					field: Option<FieldOpt>,
+					encoder: &mut MessageEncoder<B>,
+				) -> Result<(), EncodeError>
+				where
+					B: BytesWrite,
+				{
+					self.bytes.encode(field, encoder)
+				}
+			}
+
+			impl<'m, $($gen)*> DecodeMessage<'m> for $name<$($arg)*> {
+				const WIRE_TYPE: WireType = WireType::Len;
+
+				fn decode_default() -> Self {
+					Self::from([0u8; $len])
+				}
+
+				fn merge(
+					&mut self,
+					kind: FieldKind<'m>,
+					is_field: bool,
+				) -> Result<(), DecodeError> {
+					self.bytes.merge(kind, is_field)
+				}
+			}
+		}
+
+		#[cfg(all(feature = "b64", feature = "postgres"))]
+		mod impl_postgres {
+			use super::*;
+
+			use bytes::BytesMut;
+			use chuchi_postgres::filter::ParamData;
+			use postgres_types::{
+				to_sql_checked, FromSql, IsNull, ToSql, Type,
+			};
+
+			impl<$($gen)*> ToSql for $name<$($arg)*> {
+				fn to_sql(
+					&self,
+					ty: &Type,
+					out: &mut BytesMut,
+				) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+				where
+					Self: Sized,
+				{
+					if *ty == Type::BYTEA {
+						return <&[u8] as ToSql>::to_sql(
+							&self.as_ref(),
+							ty,
+							out,
+						);
+					}
+
+					self.to_string().to_sql(ty, out)
+				}
+
+				fn accepts(ty: &Type) -> bool
+				where
+					Self: Sized,
+				{
+					*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
+				}
+
+				to_sql_checked!();
+			}
+
+			impl<'r, $($gen)*> FromSql<'r> for $name<$($arg)*> {
+				fn from_sql(
+					ty: &Type,
+					raw: &'r [u8],
+				) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+					if *ty == Type::BYTEA {
+						return Self::try_from(raw).map_err(Into::into);
+					}
+
+					let s = <&str as FromSql>::from_sql(ty, raw)?;
+					s.parse().map_err(Into::into)
+				}
+
+				fn accepts(ty: &Type) -> bool {
+					*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+				}
+			}
+
+			impl<$($gen)*> ParamData for $name<$($arg)*> {
+				fn is_null(&self) -> bool {
+					false
+				}
+			}
+		}
+
+		#[cfg(all(feature = "b64", feature = "sqlx"))]
+		mod impl_sqlx {
+			use super::*;
+
+			use sqlx::encode::IsNull;
+			use sqlx::error::BoxDynError;
+			use sqlx::{Database, Decode, Encode, Type};
+
+			impl<DB: Database, $($gen)*> Type<DB> for $name<$($arg)*>
+			where
+				String: Type<DB>,
+			{
+				fn type_info() -> DB::TypeInfo {
+					<String as Type<DB>>::type_info()
+				}
+
+				fn compatible(ty: &DB::TypeInfo) -> bool {
+					<String as Type<DB>>::compatible(ty)
+				}
+			}
+
+			impl<'q, DB: Database, $($gen)*> Encode<'q, DB> for $name<$($arg)*>
+			where
+				String: Encode<'q, DB>,
+			{
+				fn encode_by_ref(
+					&self,
+					buf: &mut DB::ArgumentBuffer<'q>,
+				) -> Result<IsNull, BoxDynError> {
+					self.to_string().encode(buf)
+				}
+			}
+
+			impl<'r, DB: Database, $($gen)*> Decode<'r, DB> for $name<$($arg)*>
+			where
+				&'r str: Decode<'r, DB>,
+			{
+				fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+					let s = <&str as Decode<DB>>::decode(value)?;
+					s.parse().map_err(Into::into)
+				}
+			}
+		}
+	};
+}
+
+pub(crate) use fixed_bytes;
@@ -0,0 +1,178 @@
+//! HKDF-SHA256 (RFC 5869).
+//!
+//! Derives any number of independent keys from one secret, the `info`
+//! distinguishes the keys. `extract` needs to be called once per secret,
+//! `expand` once per derived key.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::hash::{hkdf, HmacKey};
+//! use chuchi_crypto::token::Token;
+//!
+//! let prk = hkdf::extract(b"salt", b"master secret");
+//!
+//! let mac: HmacKey = prk.derive(b"mac key");
+//! let nonce_seed: Token<16> = prk.derive(b"nonce seed");
+//! let tenant_key: [u8; 32] = prk.derive(b"tenant 42");
+//!
+//! // or in one step
+//! let other: Token<16> =
+//!     hkdf::derive(b"salt", b"master secret", b"nonce seed");
+//! assert_eq!(nonce_seed, other);
+//! ```
+
+use super::HmacKey;
+use crate::error::OkmTooLong;
+use crate::token::Token;
+
+use std::fmt;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+/// The maximum amount of bytes which can be derived with one `info`.
+pub const MAX_LEN: usize = 255 * 32;
+
+/// Extracts a pseudorandom key from the input keying material.
+///
+/// The salt is optional, an empty salt is the same as no salt.
+pub fn extract(salt: &[u8], ikm: &[u8]) -> Prk {
+	let (prk, _) = Hkdf::<Sha256>::extract(Some(salt), ikm);
+
+	Prk { bytes: prk.into() }
+}
+
+/// Fills `okm` with key material derived from `prk` and `info`.
+///
+/// Returns an error if `okm` is longer than [`MAX_LEN`].
+pub fn expand(
+	prk: &Prk,
+	info: &[u8],
+	okm: &mut [u8],
+) -> Result<(), OkmTooLong> {
+	prk.expand(info, okm)
+}
+
+/// Extracts and expands in one step.
+///
+/// ## Panics
+/// If `T::LEN` is longer than [`MAX_LEN`].
+pub fn derive<T: FromOkm>(salt: &[u8], ikm: &[u8], info: &[u8]) -> T {
+	extract(salt, ikm).derive(info)
+}
+
+/// A pseudorandom key created by `extract`.
+#[derive(Clone)]
+pub struct Prk {
+	bytes: [u8; 32],
+}
+
+impl Prk {
+	pub const LEN: usize = 32;
+
+	/// Uses bytes which are already uniformly random as the pseudorandom
+	/// key, skipping `extract`.
+	pub fn from_bytes(bytes: [u8; 32]) -> Self {
+		Self { bytes }
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.bytes
+	}
+
+	/// Fills `okm` with key material derived from `info`.
+	///
+	/// Returns an error if `okm` is longer than [`MAX_LEN`].
+	pub fn expand(
+		&self,
+		info: &[u8],
+		okm: &mut [u8],
+	) -> Result<(), OkmTooLong> {
+		Hkdf::<Sha256>::from_prk(&self.bytes)
+			// the prk has the correct length
+			.unwrap()
+			.expand(info, okm)
+			.map_err(|_| OkmTooLong::new())
+	}
+
+	/// Derives a typed key from `info`.
+	///
+	/// ## Panics
+	/// If `T::LEN` is longer than [`MAX_LEN`].
+	pub fn derive<T: FromOkm>(&self, info: &[u8]) -> T {
+		let mut okm = vec![0u8; T::LEN];
+		self.expand(info, &mut okm).expect("output too long");
+		let t = T::from_okm(&okm);
+		#[cfg(feature = "zeroize")]
+		zeroize::Zeroize::zeroize(&mut okm);
+
+		t
+	}
+}
+
+impl fmt::Debug for Prk {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Prk")
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Prk {
+	fn drop(&mut self) {
+		zeroize::Zeroize::zeroize(&mut self.bytes);
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Prk {}
+
+/// A type which can be created from the output of HKDF.
+pub trait FromOkm: Sized {
+	/// The amount of bytes needed.
+	const LEN: usize;
+
+	/// Creates the type, `okm` is always `LEN` bytes long.
+	fn from_okm(okm: &[u8]) -> Self;
+}
+
+impl<const N: usize> FromOkm for [u8; N] {
+	const LEN: usize = N;
+
+	fn from_okm(okm: &[u8]) -> Self {
+		okm.try_into().unwrap()
+	}
+}
+
+impl<const S: usize> FromOkm for Token<S> {
+	const LEN: usize = S;
+
+	fn from_okm(okm: &[u8]) -> Self {
+		Token::try_from_slice(okm).unwrap()
+	}
+}
+
+impl FromOkm for HmacKey {
+	const LEN: usize = 32;
+
+	fn from_okm(okm: &[u8]) -> Self {
+		HmacKey::new(okm)
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl FromOkm for crate::cipher::Aead {
+	const LEN: usize = Self::LEN;
+
+	fn from_okm(okm: &[u8]) -> Self {
+		Self::try_from_slice(okm).unwrap()
+	}
+}
+
+#[cfg(feature = "cipher")]
+impl FromOkm for crate::cipher::XAead {
+	const LEN: usize = Self::LEN;
+
+	fn from_okm(okm: &[u8]) -> Self {
+		Self::try_from_slice(okm).unwrap()
+	}
+}
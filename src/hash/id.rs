@@ -0,0 +1,64 @@
+use super::fixed_bytes;
+use crate::error::TryFromError;
+
+use std::convert::TryInto;
+use std::io::{self, Read};
+
+use blake2::{digest::consts::U32, Blake2b, Digest};
+
+/// A content address, the BLAKE2b-256 hash of some content.
+///
+/// Unlike a [`Token`](crate::token::Token) an id is not secret, the same
+/// content always results in the same id. Comparing is therefore not done
+/// in constant time and ids can be ordered.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::hash::Id;
+///
+/// let id = Id::of(b"file contents");
+/// assert_eq!(id, Id::of(b"file contents"));
+///
+/// # #[cfg(feature = "b64")]
+/// # {
+/// let s = id.to_string();
+/// assert_eq!(s.parse::<Id>().unwrap(), id);
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id {
+	bytes: [u8; 32],
+}
+
+impl Id {
+	pub const LEN: usize = 32;
+
+	/// Returns the id of the content.
+	pub fn of(content: impl AsRef<[u8]>) -> Self {
+		Self {
+			bytes: Blake2b::<U32>::digest(content.as_ref()).into(),
+		}
+	}
+
+	/// Returns the id of everything from the reader until it returns EOF,
+	/// without buffering all data in memory.
+	pub fn of_reader(mut reader: impl Read) -> io::Result<Self> {
+		let mut hasher = Blake2b::<U32>::new();
+		io::copy(&mut reader, &mut hasher)?;
+
+		Ok(Self {
+			bytes: hasher.finalize().into(),
+		})
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.bytes
+	}
+}
+
+fixed_bytes!(Id, 32);
@@ -0,0 +1,98 @@
+use super::fixed_bytes;
+use crate::error::TryFromError;
+
+use std::convert::TryInto;
+use std::fmt;
+
+use hmac::{Hmac, Mac as _};
+use sha2::Sha256;
+
+/// A keyed message authentication code using HMAC-SHA256.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::hash::HmacKey;
+///
+/// let mac = HmacKey::new(b"my secret key");
+/// let tag = mac.sign(b"my message");
+///
+/// assert!(mac.verify(b"my message", &tag));
+/// assert!(!mac.verify(b"another message", &tag));
+/// ```
+#[derive(Clone)]
+pub struct HmacKey {
+	key: Vec<u8>,
+}
+
+impl HmacKey {
+	/// Creates a new HmacKey, the key can have any length but should be at
+	/// least 32 bytes long.
+	pub fn new(key: impl AsRef<[u8]>) -> Self {
+		Self {
+			key: key.as_ref().to_vec(),
+		}
+	}
+
+	/// Creates the tag of a message.
+	pub fn sign(&self, msg: impl AsRef<[u8]>) -> Tag {
+		// hmac accepts keys of any length
+		let mut inner = Hmac::<Sha256>::new_from_slice(&self.key).unwrap();
+		inner.update(msg.as_ref());
+
+		Tag {
+			bytes: inner.finalize().into_bytes().into(),
+		}
+	}
+
+	/// Verifies the tag of a message in constant time.
+	pub fn verify(&self, msg: impl AsRef<[u8]>, tag: &Tag) -> bool {
+		&self.sign(msg) == tag
+	}
+}
+
+impl fmt::Debug for HmacKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("HmacKey")
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for HmacKey {
+	fn drop(&mut self) {
+		zeroize::Zeroize::zeroize(&mut self.key);
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for HmacKey {}
+
+/// The tag created by an `HmacKey`.
+///
+/// Comparing two tags is done in constant time.
+#[derive(Clone)]
+pub struct Tag {
+	bytes: [u8; 32],
+}
+
+impl Tag {
+	pub const LEN: usize = 32;
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.bytes
+	}
+}
+
+impl PartialEq for Tag {
+	fn eq(&self, other: &Self) -> bool {
+		crate::ct::ct_eq(&self.bytes, &other.bytes)
+	}
+}
+
+impl Eq for Tag {}
+
+fixed_bytes!(Tag, 32);
@@ -6,11 +6,14 @@
 //! use any salt, it is vulnerable to a rainbow table
 //! attack.
 
-#[cfg(feature = "b64")]
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex"))]
 use crate::error::DecodeError;
 use crate::error::TryFromError;
 
 use std::convert::{TryFrom, TryInto};
+use std::io::{self, Read};
 use std::mem::ManuallyDrop;
 use std::{fmt, ptr};
 
@@ -20,10 +23,72 @@ use generic_array::{typenum::U64, GenericArray};
 #[cfg(feature = "b64")]
 use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
 
+mod fixed_bytes;
+use fixed_bytes::fixed_bytes;
+
+mod mac;
+pub use mac::{HmacKey, Tag};
+
+mod blind_index;
+pub use blind_index::{blind_index, BlindIndex};
+
+mod id;
+pub use id::Id;
+
+mod chain;
+pub use chain::{Chain, ConsistencyProof};
+
+mod commitment;
+pub use commitment::{Commitment, Opening};
+
+mod truncated;
+pub use truncated::TruncatedHash;
+
+pub mod sha2;
+
+#[cfg(feature = "sha3")]
+pub mod sha3;
+
+pub mod hkdf;
+
+#[cfg(feature = "password")]
+pub mod password;
+
+#[cfg(feature = "blake3")]
+pub mod blake3;
+
+#[cfg(feature = "multihash")]
+pub mod multihash;
+
+#[cfg(feature = "siphash")]
+pub mod siphash;
+
 pub fn hash(data: impl AsRef<[u8]>) -> Hash {
 	Hasher::hash(data)
 }
 
+/// Hashes every input on its own, spread across the rayon thread pool.
+///
+/// The hashes are returned in the same order as the inputs.
+#[cfg(feature = "rayon")]
+pub fn hash_many<T>(inputs: &[T]) -> Vec<Hash>
+where
+	T: AsRef<[u8]> + Sync,
+{
+	use rayon::prelude::*;
+
+	inputs.par_iter().map(hash).collect()
+}
+
+/// Hashes everything from the reader until it returns EOF, without
+/// buffering all data in memory.
+pub fn hash_reader(reader: impl Read) -> io::Result<Hash> {
+	Hasher::hash_reader(reader)
+}
+
+/// An incremental hasher, data can be added with `update` or by writing
+/// to it.
+#[derive(Clone)]
 pub struct Hasher {
 	inner: Blake2b512,
 }
@@ -51,6 +116,75 @@ impl Hasher {
 		hasher.update(data);
 		hasher.finalize()
 	}
+
+	pub fn hash_reader(mut reader: impl Read) -> io::Result<Hash> {
+		let mut hasher = Hasher::new();
+		io::copy(&mut reader, &mut hasher)?;
+		Ok(hasher.finalize())
+	}
+}
+
+impl fmt::Debug for Hasher {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Hasher")
+	}
+}
+
+impl io::Write for Hasher {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+#[cfg(feature = "rustcrypto-traits")]
+mod impl_digest {
+	use super::*;
+
+	use digest::{
+		FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser,
+		Reset, Update,
+	};
+
+	impl Default for Hasher {
+		fn default() -> Self {
+			Self::new()
+		}
+	}
+
+	impl HashMarker for Hasher {}
+
+	impl Update for Hasher {
+		fn update(&mut self, data: &[u8]) {
+			Digest::update(&mut self.inner, data);
+		}
+	}
+
+	impl OutputSizeUser for Hasher {
+		type OutputSize = U64;
+	}
+
+	impl FixedOutput for Hasher {
+		fn finalize_into(self, out: &mut Output<Self>) {
+			FixedOutput::finalize_into(self.inner, out);
+		}
+	}
+
+	impl Reset for Hasher {
+		fn reset(&mut self) {
+			Reset::reset(&mut self.inner);
+		}
+	}
+
+	impl FixedOutputReset for Hasher {
+		fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+			FixedOutputReset::finalize_into_reset(&mut self.inner, out);
+		}
+	}
 }
 
 fn convert_generic_array<T>(arr: GenericArray<T, U64>) -> [T; 64] {
@@ -63,7 +197,10 @@ fn convert_generic_array<T>(arr: GenericArray<T, U64>) -> [T; 64] {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// A BLAKE2b-512 hash.
+///
+/// Comparing two hashes is done in constant time.
+#[derive(Clone)]
 pub struct Hash {
 	bytes: [u8; 64],
 }
@@ -73,15 +210,29 @@ impl Hash {
 
 	/// ## Panics
 	/// if the slice is not 64 bytes long.
+	#[deprecated(note = "use `try_from_slice` instead")]
 	pub fn from_slice(slice: &[u8]) -> Self {
 		slice.try_into().unwrap()
 	}
 
+	/// Returns an error if the slice is not 64 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
 	pub fn to_bytes(&self) -> [u8; 64] {
 		self.bytes
 	}
 }
 
+impl PartialEq for Hash {
+	fn eq(&self, other: &Self) -> bool {
+		crate::ct::ct_eq(&self.bytes, &other.bytes)
+	}
+}
+
+impl Eq for Hash {}
+
 #[cfg(not(feature = "b64"))]
 impl fmt::Debug for Hash {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -145,14 +296,41 @@ impl AsRef<[u8]> for Hash {
 	}
 }
 
-#[cfg(all(feature = "b64", feature = "serde"))]
-mod impl_serde {
+#[cfg(feature = "hex")]
+impl Hash {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
 
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).map(Self::from)
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, Hash> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		crate::encoding::fmt_hex(self.get().as_ref(), f)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
 	use super::*;
 
+	#[cfg(feature = "b64")]
 	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
 	use std::str::FromStr;
 
+	#[cfg(feature = "b64")]
 	use _serde::de::Error;
 	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -161,7 +339,12 @@ mod impl_serde {
 		where
 			S: Serializer,
 		{
-			serializer.collect_str(&self)
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
 		}
 	}
 
@@ -170,8 +353,181 @@ mod impl_serde {
 		where
 			D: Deserializer<'de>,
 		{
-			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
-			Self::from_str(s.as_ref()).map_err(D::Error::custom)
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			Ok(Self::from(bytes))
+		}
+	}
+}
+
+#[cfg(feature = "protobuf")]
+mod impl_protobuf {
+	use super::*;
+
+	use protopuffer::{
+		bytes::BytesWrite,
+		decode::{DecodeError, DecodeMessage, FieldKind},
+		encode::{
+			EncodeError, EncodeMessage, FieldOpt, MessageEncoder, SizeBuilder,
+		},
+		WireType,
+	};
+
+	impl EncodeMessage for Hash {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn is_default(&self) -> bool {
+			false
+		}
+
+		fn encoded_size(
+			&mut self,
+			field: Option<FieldOpt>,
+			builder: &mut SizeBuilder,
+		) -> Result<(), EncodeError> {
+			self.to_bytes().encoded_size(field, builder)
+		}
+
+		fn encode<B>(
+			&mut self,
+			field: Option<FieldOpt>,
+			encoder: &mut MessageEncoder<B>,
+		) -> Result<(), EncodeError>
+		where
+			B: BytesWrite,
+		{
+			self.to_bytes().encode(field, encoder)
+		}
+	}
+
+	impl<'m> DecodeMessage<'m> for Hash {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn decode_default() -> Self {
+			Self::from([0u8; 64])
+		}
+
+		fn merge(
+			&mut self,
+			kind: FieldKind<'m>,
+			is_field: bool,
+		) -> Result<(), DecodeError> {
+			let mut t = self.to_bytes();
+			t.merge(kind, is_field)?;
+
+			*self = Self::from(t);
+
+			Ok(())
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "postgres"))]
+mod impl_postgres {
+	use super::*;
+
+	use bytes::BytesMut;
+	use chuchi_postgres::filter::ParamData;
+	use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+	impl ToSql for Hash {
+		fn to_sql(
+			&self,
+			ty: &Type,
+			out: &mut BytesMut,
+		) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+		where
+			Self: Sized,
+		{
+			if *ty == Type::BYTEA {
+				return <&[u8] as ToSql>::to_sql(&self.as_ref(), ty, out);
+			}
+
+			self.to_string().to_sql(ty, out)
+		}
+
+		fn accepts(ty: &Type) -> bool
+		where
+			Self: Sized,
+		{
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
+		}
+
+		to_sql_checked!();
+	}
+
+	impl<'r> FromSql<'r> for Hash {
+		fn from_sql(
+			ty: &Type,
+			raw: &'r [u8],
+		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Self::try_from(raw).map_err(Into::into);
+			}
+
+			let s = <&str as FromSql>::from_sql(ty, raw)?;
+			s.parse().map_err(Into::into)
+		}
+
+		fn accepts(ty: &Type) -> bool {
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+
+	impl ParamData for Hash {
+		fn is_null(&self) -> bool {
+			false
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "sqlx"))]
+mod impl_sqlx {
+	use super::*;
+
+	use sqlx::encode::IsNull;
+	use sqlx::error::BoxDynError;
+	use sqlx::{Database, Decode, Encode, Type};
+
+	impl<DB: Database> Type<DB> for Hash
+	where
+		String: Type<DB>,
+	{
+		fn type_info() -> DB::TypeInfo {
+			<String as Type<DB>>::type_info()
+		}
+
+		fn compatible(ty: &DB::TypeInfo) -> bool {
+			<String as Type<DB>>::compatible(ty)
+		}
+	}
+
+	impl<'q, DB: Database> Encode<'q, DB> for Hash
+	where
+		String: Encode<'q, DB>,
+	{
+		fn encode_by_ref(
+			&self,
+			buf: &mut DB::ArgumentBuffer<'q>,
+		) -> Result<IsNull, BoxDynError> {
+			self.to_string().encode(buf)
+		}
+	}
+
+	impl<'r, DB: Database> Decode<'r, DB> for Hash
+	where
+		&'r str: Decode<'r, DB>,
+	{
+		fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+			let s = <&str as Decode<DB>>::decode(value)?;
+			s.parse().map_err(Into::into)
 		}
 	}
 }
@@ -181,6 +537,292 @@ mod tests {
 
 	use super::*;
 
+	#[cfg(feature = "password")]
+	use crate::error::PasswordError;
+
+	#[cfg(all(feature = "b64", feature = "postgres"))]
+	#[test]
+	fn test_postgres() {
+		use bytes::BytesMut;
+		use postgres_types::{FromSql, ToSql, Type};
+
+		let hash = hash(b"audit event");
+
+		let mut buf = BytesMut::new();
+		hash.to_sql(&Type::BYTEA, &mut buf).unwrap();
+		assert_eq!(buf.as_ref(), hash.as_ref());
+		assert_eq!(Hash::from_sql(&Type::BYTEA, &buf).unwrap(), hash);
+
+		let mut buf = BytesMut::new();
+		hash.to_sql(&Type::TEXT, &mut buf).unwrap();
+		assert_eq!(buf.as_ref(), hash.to_string().as_bytes());
+		assert_eq!(Hash::from_sql(&Type::TEXT, &buf).unwrap(), hash);
+
+		assert!(Hash::from_sql(&Type::BYTEA, &buf).is_err());
+	}
+
+	#[test]
+	fn test_blind_index() {
+		// rfc 4231 test case 2
+		let index: BlindIndex<16> =
+			blind_index("Jefe", "what do ya want for nothing?");
+		assert_eq!(
+			index.to_bytes(),
+			[
+				0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04,
+				0x24, 0x26, 0x08, 0x95, 0x75, 0xc7
+			]
+		);
+
+		let short: BlindIndex<2> =
+			blind_index("Jefe", "what do ya want for nothing?");
+		assert_eq!(short.to_bytes(), [0x5b, 0xdc]);
+		assert_ne!(
+			index,
+			blind_index("other key", "what do ya want for nothing?")
+		);
+		assert!(BlindIndex::<16>::try_from_slice(&[0; 15]).is_err());
+	}
+
+	#[cfg(all(feature = "b64", feature = "postgres"))]
+	#[test]
+	fn test_blind_index_postgres() {
+		use bytes::BytesMut;
+		use postgres_types::{FromSql, ToSql, Type};
+
+		let index: BlindIndex<8> = blind_index("key", "alice@example.com");
+
+		let mut buf = BytesMut::new();
+		index.to_sql(&Type::BYTEA, &mut buf).unwrap();
+		assert_eq!(buf.as_ref(), index.as_ref());
+		assert_eq!(BlindIndex::from_sql(&Type::BYTEA, &buf).unwrap(), index);
+
+		let mut buf = BytesMut::new();
+		index.to_sql(&Type::TEXT, &mut buf).unwrap();
+		assert_eq!(BlindIndex::from_sql(&Type::TEXT, &buf).unwrap(), index);
+	}
+
+	#[cfg(all(feature = "b64", feature = "postgres"))]
+	#[test]
+	fn test_tag_postgres() {
+		use bytes::BytesMut;
+		use postgres_types::{FromSql, ToSql, Type};
+
+		let tag = HmacKey::new(b"key").sign(b"message");
+
+		let mut buf = BytesMut::new();
+		tag.to_sql(&Type::BYTEA, &mut buf).unwrap();
+		assert_eq!(buf.as_ref(), tag.as_ref());
+		assert_eq!(Tag::from_sql(&Type::BYTEA, &buf).unwrap(), tag);
+
+		let mut buf = BytesMut::new();
+		tag.to_sql(&Type::TEXT, &mut buf).unwrap();
+		assert_eq!(Tag::from_sql(&Type::TEXT, &buf).unwrap(), tag);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_tag_serde() {
+		let tag = HmacKey::new(b"key").sign(b"message");
+
+		let bin = bincode::serialize(&tag).unwrap();
+		assert_eq!(bin.len(), 8 + Tag::LEN);
+		assert_eq!(bincode::deserialize::<Tag>(&bin).unwrap(), tag);
+
+		#[cfg(feature = "b64")]
+		{
+			let json = serde_json::to_string(&tag).unwrap();
+			assert_eq!(json, format!("\"{tag}\""));
+			assert_eq!(serde_json::from_str::<Tag>(&json).unwrap(), tag);
+		}
+	}
+
+	#[cfg(feature = "protobuf")]
+	#[test]
+	fn test_protobuf() {
+		let hash = hash(b"message");
+
+		let bytes = protopuffer::to_vec(&mut hash.clone()).unwrap();
+		assert_eq!(protopuffer::from_slice::<Hash>(&bytes).unwrap(), hash);
+
+		let tag = HmacKey::new(b"key").sign(b"message");
+		let bytes = protopuffer::to_vec(&mut tag.clone()).unwrap();
+		assert_eq!(protopuffer::from_slice::<Tag>(&bytes).unwrap(), tag);
+	}
+
+	#[test]
+	fn id() {
+		// BLAKE2b-256
+		let id = Id::of(b"abc");
+		assert_eq!(
+			Id::of_reader(&b"abc"[..]).unwrap().to_bytes(),
+			id.to_bytes()
+		);
+		assert_ne!(id, Id::of(b"abd"));
+		assert!(Id::try_from_slice(&[0; 31]).is_err());
+
+		#[cfg(feature = "b64")]
+		{
+			let s = "vd2BPGNCOXIxce8_7phXm5SWTjuxyz5CcmLIwGjVIxk";
+			assert_eq!(id.to_string(), s);
+			assert_eq!(s.parse::<Id>().unwrap(), id);
+			assert!(s[1..].parse::<Id>().is_err());
+		}
+
+		#[cfg(feature = "protobuf")]
+		{
+			let bytes = protopuffer::to_vec(&mut id.clone()).unwrap();
+			assert_eq!(protopuffer::from_slice::<Id>(&bytes).unwrap(), id);
+		}
+	}
+
+	#[cfg(all(feature = "b64", feature = "postgres"))]
+	#[test]
+	fn test_id_postgres() {
+		use bytes::BytesMut;
+		use postgres_types::{FromSql, ToSql, Type};
+
+		let id = Id::of(b"content");
+
+		let mut buf = BytesMut::new();
+		id.to_sql(&Type::BYTEA, &mut buf).unwrap();
+		assert_eq!(buf.as_ref(), id.as_ref());
+		assert_eq!(Id::from_sql(&Type::BYTEA, &buf).unwrap(), id);
+
+		let mut buf = BytesMut::new();
+		id.to_sql(&Type::TEXT, &mut buf).unwrap();
+		assert_eq!(Id::from_sql(&Type::TEXT, &buf).unwrap(), id);
+	}
+
+	#[cfg(feature = "siphash")]
+	#[test]
+	fn siphash() {
+		use super::siphash::{self, BuildSipHasher13, SipKey};
+		use std::hash::{BuildHasher, Hasher as _};
+
+		// test vectors from the reference implementation
+		let key: Vec<u8> = (0..16).collect();
+		let key = SipKey::try_from_slice(&key).unwrap();
+		assert_eq!(siphash::siphash24(&key, b""), 0x726fdb47dd0e0e31);
+		let data: Vec<u8> = (0..15).collect();
+		assert_eq!(siphash::siphash24(&key, &data), 0xa129ca6149be45e5);
+		assert_eq!(
+			siphash::siphash24_token(&key, &data).to_bytes(),
+			0xa129ca6149be45e5u64.to_le_bytes()
+		);
+
+		let mut hasher = BuildSipHasher13::new(key.clone()).build_hasher();
+		hasher.write(&data);
+		assert_eq!(hasher.finish(), siphash::siphash13(&key, &data));
+		assert_ne!(
+			siphash::siphash13(&key, &data),
+			siphash::siphash24(&key, &data)
+		);
+		assert_ne!(
+			siphash::siphash13(&SipKey::new(), &data),
+			siphash::siphash13(&key, &data)
+		);
+	}
+
+	#[test]
+	fn chain() {
+		let mut chain = Chain::new();
+		assert!(chain.is_empty());
+		assert_eq!(*chain.head(), Chain::genesis());
+
+		let first = chain.append(b"first");
+		let old = chain.head().clone();
+		assert_eq!(first, old);
+		chain.append(b"second");
+		chain.append(b"third");
+		assert_eq!(chain.len(), 3);
+		assert_eq!(chain.head_at(1).unwrap(), old);
+		assert_eq!(chain.head_at(3).as_ref(), Some(chain.head()));
+		assert!(chain.head_at(4).is_none());
+
+		let proof = chain.prove(1).unwrap();
+		assert_eq!(proof.entries().len(), 2);
+		assert!(proof.verify(&old, chain.head()));
+		assert!(!proof.verify(&Chain::genesis(), chain.head()));
+		assert!(!chain.prove(0).unwrap().verify(&Chain::genesis(), &old));
+		assert!(chain.prove(3).unwrap().verify(chain.head(), chain.head()));
+		assert!(chain.prove(4).is_none());
+
+		// a rewritten entry changes every following head
+		let mut other = Chain::new();
+		other.append(b"first");
+		other.append(b"changed");
+		other.append(b"third");
+		assert_ne!(other.head(), chain.head());
+		assert!(!other.prove(1).unwrap().verify(&old, chain.head()));
+	}
+
+	#[test]
+	fn commitment() {
+		let (commitment, opening) = Commitment::commit(b"ticket 17");
+		assert!(commitment.verify(b"ticket 17", &opening));
+		assert!(!commitment.verify(b"ticket 18", &opening));
+		assert!(!commitment.verify(b"ticket 17", &Opening::new()));
+		assert_eq!(
+			Commitment::with_opening(b"ticket 17", &opening),
+			commitment
+		);
+
+		// the same value results in a different commitment
+		let (other, _) = Commitment::commit(b"ticket 17");
+		assert_ne!(other, commitment);
+
+		assert!(Commitment::try_from_slice(&[0; 31]).is_err());
+
+		#[cfg(feature = "b64")]
+		{
+			let s = commitment.to_string();
+			assert_eq!(s.parse::<Commitment>().unwrap(), commitment);
+		}
+	}
+
+	#[test]
+	fn truncated_hash() {
+		let hash = hash(b"truncate me");
+		let short: TruncatedHash<16> = hash.truncate();
+		assert_eq!(short.as_ref(), &hash.to_bytes()[..16]);
+		assert_eq!(hash.truncate::<64>().to_bytes(), hash.to_bytes());
+		assert_ne!(short, super::hash(b"other").truncate());
+		assert!(TruncatedHash::<16>::try_from_slice(&[0; 17]).is_err());
+
+		#[cfg(feature = "b64")]
+		{
+			let s = short.to_string();
+			assert_eq!(s.len(), 22);
+			assert_eq!(s.parse::<TruncatedHash<16>>().unwrap(), short);
+			assert!(s.parse::<TruncatedHash<32>>().is_err());
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn truncate_too_long() {
+		let _: TruncatedHash<65> = hash(b"").truncate();
+	}
+
+	#[cfg(feature = "rustcrypto-traits")]
+	#[test]
+	fn digest_traits() {
+		fn digest<D: Digest>(data: &[u8]) -> Vec<u8> {
+			D::digest(data).to_vec()
+		}
+
+		let bytes: Vec<u8> = (0..=255).collect();
+		assert_eq!(digest::<Hasher>(&bytes), hash(&bytes).to_bytes());
+		assert_eq!(digest::<Hasher>(&bytes), digest::<Blake2b512>(&bytes));
+
+		let mut hasher = <Hasher as Digest>::new();
+		Digest::update(&mut hasher, b"abc");
+		let first = hasher.finalize_reset();
+		Digest::update(&mut hasher, b"abc");
+		assert_eq!(first, Digest::finalize(hasher));
+	}
+
 	#[test]
 	fn hash_something() {
 		let bytes: Vec<u8> = (0..=255).collect();
@@ -210,4 +852,619 @@ mod tests {
 			U09dEESxlP3PdfetmZiBMWpv6W0YIH8EP2-eIT6XL-A"
 		);
 	}
+
+	#[cfg(feature = "hex")]
+	#[test]
+	fn test_hex() {
+		let hash = hash(b"");
+		assert_eq!(
+			hash.hex().to_string(),
+			"786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+			d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"
+		);
+		assert_eq!(hash.to_hex(), hash.hex().to_string());
+		assert_eq!(
+			Hash::from_hex(&hash.to_hex().to_uppercase()).unwrap(),
+			hash
+		);
+		assert!(Hash::from_hex(&hash.to_hex()[2..]).is_err());
+	}
+
+	// https://www.rfc-editor.org/rfc/rfc4231#section-4.3
+	#[test]
+	#[cfg(feature = "hex")]
+	fn mac_rfc4231() {
+		let mac = HmacKey::new(b"Jefe");
+		let tag = mac.sign(b"what do ya want for nothing?");
+
+		assert_eq!(
+			tag.to_hex(),
+			"5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+		);
+		assert!(mac.verify(b"what do ya want for nothing?", &tag));
+		assert!(!mac.verify(b"what do ya want for something?", &tag));
+		assert!(!HmacKey::new(b"Jeff")
+			.verify(b"what do ya want for nothing?", &tag));
+	}
+
+	#[test]
+	#[cfg(feature = "password")]
+	fn password() {
+		use password::*;
+
+		// keep the test fast
+		let params = Params {
+			memory_cost: 256,
+			time_cost: 1,
+			parallelism: 1,
+		};
+
+		let hash = hash_password_with("my password", &params).unwrap();
+		assert!(hash.as_str().starts_with("$argon2id$v=19$m=256,t=1,p=1$"));
+
+		let hash: PhcString = hash.to_string().parse().unwrap();
+		assert!(verify_password("my password", &hash).unwrap());
+		assert!(!verify_password("not my password", &hash).unwrap());
+
+		// the same password results in a different hash
+		let hash_2 = hash_password_with("my password", &params).unwrap();
+		assert_ne!(hash, hash_2);
+
+		assert!("not a hash".parse::<PhcString>().is_err());
+		let invalid = Params {
+			memory_cost: 0,
+			..params
+		};
+		assert_eq!(
+			hash_password_with("my password", &invalid).unwrap_err(),
+			PasswordError::InvalidParams
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "password")]
+	fn needs_rehash() {
+		use password::*;
+
+		let params = Params {
+			memory_cost: 256,
+			time_cost: 1,
+			parallelism: 1,
+		};
+
+		let hash = hash_password_with("my password", &params).unwrap();
+		assert!(!needs_rehash_with(&hash, &params));
+		assert!(needs_rehash(&hash));
+		let stronger = Params {
+			time_cost: 2,
+			..params.clone()
+		};
+		assert!(needs_rehash_with(&hash, &stronger));
+
+		let argon2i: PhcString = "$argon2i$v=19$m=256,t=1,p=1$c29tZXNhbHQ\
+			$iekCn0Y3spW+sCcFanM2xBT63UP2sghkUoHLIUpWRS8"
+			.parse()
+			.unwrap();
+		assert!(needs_rehash_with(&argon2i, &params));
+	}
+
+	#[test]
+	#[cfg(feature = "password")]
+	fn password_hasher() {
+		use password::*;
+
+		let params = Params {
+			memory_cost: 256,
+			time_cost: 1,
+			parallelism: 1,
+		};
+		let hasher = PasswordHasher::new(params.clone()).unwrap();
+
+		let hash = hasher.hash("my password");
+		assert_eq!(hash.scheme(), Some(Scheme::Argon2));
+		assert_eq!(
+			hasher.verify("my password", &hash).unwrap(),
+			Verification::Valid
+		);
+		let res = hasher.verify("not my password", &hash).unwrap();
+		assert_eq!(res, Verification::Invalid);
+		assert!(!res.is_valid());
+
+		// upgrade to stronger params
+		let stronger = PasswordHasher::new(Params {
+			time_cost: 2,
+			..params.clone()
+		})
+		.unwrap();
+		let Verification::Rehash(new_hash) =
+			stronger.verify("my password", &hash).unwrap()
+		else {
+			panic!("expected a rehash")
+		};
+		assert!(new_hash
+			.as_str()
+			.starts_with("$argon2id$v=19$m=256,t=2,p=1$"));
+		assert_eq!(
+			stronger.verify("my password", &new_hash).unwrap(),
+			Verification::Valid
+		);
+
+		let pbkdf2: PhcString = "$pbkdf2-sha256$i=1000,l=32\
+			$bGVnYWN5c2FsdHZhbHVlMQ\
+			$qCIiyAKx2Sy0Kbnxbf3SJlJkjEeRQc7Ajxw4m5KyJbQ"
+			.parse()
+			.unwrap();
+		assert_eq!(pbkdf2.scheme(), Some(Scheme::Pbkdf2));
+		#[cfg(feature = "pbkdf2")]
+		{
+			let res = hasher.verify("hunter2", &pbkdf2).unwrap();
+			assert!(res.is_valid());
+			let Verification::Rehash(new_hash) = res else {
+				panic!("expected a rehash")
+			};
+			assert_eq!(new_hash.scheme(), Some(Scheme::Argon2));
+			assert!(verify_password("hunter2", &new_hash).unwrap());
+		}
+		#[cfg(not(feature = "pbkdf2"))]
+		assert_eq!(
+			hasher.verify("hunter2", &pbkdf2).unwrap_err(),
+			PasswordError::UnsupportedAlgorithm
+		);
+
+		#[cfg(feature = "bcrypt")]
+		{
+			let bcrypt: PhcString =
+				"$2b$04$abcdefghijklmnopqrstuuV3duMsC0HpUex6N9qapiuOHHWkwRXVm"
+					.parse()
+					.unwrap();
+			assert_eq!(bcrypt.scheme(), Some(Scheme::Bcrypt));
+			assert!(matches!(
+				hasher.verify("hunter2", &bcrypt).unwrap(),
+				Verification::Rehash(_)
+			));
+		}
+
+		assert_eq!(
+			PasswordHasher::new(Params {
+				memory_cost: 0,
+				..params
+			})
+			.unwrap_err(),
+			PasswordError::InvalidParams
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "password")]
+	fn pbkdf2() {
+		use password::*;
+
+		// created with python's hashlib.pbkdf2_hmac
+		let sha256: PhcString = "$pbkdf2-sha256$i=1000,l=32\
+			$bGVnYWN5c2FsdHZhbHVlMQ\
+			$qCIiyAKx2Sy0Kbnxbf3SJlJkjEeRQc7Ajxw4m5KyJbQ"
+			.parse()
+			.unwrap();
+		let sha512: PhcString = "$pbkdf2-sha512$i=1000,l=64\
+			$bGVnYWN5c2FsdHZhbHVlMQ\
+			$SkAe1N9NAZX8rnF6ANlOsAhmJLpG984zd2oWuVTkOQ/VaJIwCbAax+JpoZbNGkZr\
+			b+ET2HsI1gsKDq93cpyCKw"
+			.parse()
+			.unwrap();
+
+		for hash in [&sha256, &sha512] {
+			assert!(needs_rehash(hash));
+
+			#[cfg(feature = "pbkdf2")]
+			{
+				assert!(verify_password("hunter2", hash).unwrap());
+				assert!(!verify_password("hunter3", hash).unwrap());
+			}
+
+			#[cfg(not(feature = "pbkdf2"))]
+			assert_eq!(
+				verify_password("hunter2", hash).unwrap_err(),
+				PasswordError::UnsupportedAlgorithm
+			);
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "bcrypt")]
+	fn bcrypt() {
+		use password::*;
+
+		// created with libxcrypt
+		let hash: PhcString =
+			"$2b$04$abcdefghijklmnopqrstuuV3duMsC0HpUex6N9qapiuOHHWkwRXVm"
+				.parse()
+				.unwrap();
+		assert!(verify_password("hunter2", &hash).unwrap());
+		assert!(!verify_password("hunter3", &hash).unwrap());
+		assert!(needs_rehash(&hash));
+
+		// from the OpenWall test vectors
+		let hash: PhcString =
+			"$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW"
+				.parse()
+				.unwrap();
+		assert!(verify_password("U*U", &hash).unwrap());
+
+		let hash = hash_password_bcrypt("my password", 4).unwrap();
+		assert!(hash.as_str().starts_with("$2b$04$"));
+		let hash: PhcString = hash.to_string().parse().unwrap();
+		assert!(verify_password("my password", &hash).unwrap());
+		assert!(!verify_password("not my password", &hash).unwrap());
+
+		assert_eq!(
+			hash_password_bcrypt("my password", 3).unwrap_err(),
+			PasswordError::InvalidParams
+		);
+		assert!("$2b$04$short".parse::<PhcString>().is_err());
+		assert!(
+			"$2b$99$abcdefghijklmnopqrstuuV3duMsC0HpUex6N9qapiuOHHWkwRXVm"
+				.parse::<PhcString>()
+				.is_err()
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "scrypt")]
+	fn scrypt() {
+		use password::*;
+
+		// created with python's hashlib.scrypt
+		let hash: PhcString = "$scrypt$ln=10,r=8,p=1$c2NyeXB0c2FsdHZhbHVlMQ\
+			$faX9wew92DpCrJqNoRc8mrvbn0y503kucvvDwy7VR6Q"
+			.parse()
+			.unwrap();
+		assert_eq!(hash.scheme(), Some(Scheme::Scrypt));
+		assert!(verify_password("hunter2", &hash).unwrap());
+		assert!(!verify_password("hunter3", &hash).unwrap());
+		assert!(needs_rehash(&hash));
+
+		let params = ScryptParams {
+			log_n: 10,
+			r: 8,
+			p: 1,
+		};
+		let hasher = PasswordHasher::new(params.clone()).unwrap();
+		assert!(!hasher.needs_rehash(&hash));
+		assert_eq!(
+			hasher.verify("hunter2", &hash).unwrap(),
+			Verification::Valid
+		);
+
+		let new_hash = hasher.hash("my password");
+		assert!(new_hash.as_str().starts_with("$scrypt$ln=10,r=8,p=1$"));
+		assert!(verify_password("my password", &new_hash).unwrap());
+
+		// upgrade from scrypt to argon2id
+		let argon2_hasher = PasswordHasher::new(Params {
+			memory_cost: 256,
+			time_cost: 1,
+			parallelism: 1,
+		})
+		.unwrap();
+		let Verification::Rehash(argon2) =
+			argon2_hasher.verify("my password", &new_hash).unwrap()
+		else {
+			panic!("expected a rehash")
+		};
+		assert_eq!(argon2.scheme(), Some(Scheme::Argon2));
+
+		// RFC 7914
+		let key = derive_key_scrypt(
+			"password",
+			b"NaCl",
+			&ScryptParams {
+				log_n: 10,
+				r: 8,
+				p: 16,
+			},
+		)
+		.unwrap();
+		assert_eq!(
+			key,
+			[
+				0xfd, 0xba, 0xbe, 0x1c, 0x9d, 0x34, 0x72, 0x00, 0x78, 0x56,
+				0xe7, 0x19, 0x0d, 0x01, 0xe9, 0xfe, 0x7c, 0x6a, 0xd7, 0xcb,
+				0xc8, 0x23, 0x78, 0x30, 0xe7, 0x73, 0x76, 0x63, 0x4b, 0x37,
+				0x31, 0x62
+			]
+		);
+
+		assert_eq!(
+			PasswordHasher::new(ScryptParams { p: 0, ..params }).unwrap_err(),
+			PasswordError::InvalidParams
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "password")]
+	fn key_params() {
+		use password::*;
+
+		let params = Params {
+			memory_cost: 256,
+			time_cost: 1,
+			parallelism: 1,
+		};
+
+		let key_params = KeyParams::new(b"some salt", &params).unwrap();
+		let s = key_params.to_string();
+		assert_eq!(s, "$argon2id$v=19$m=256,t=1,p=1$c29tZSBzYWx0");
+		assert_eq!(s.parse::<KeyParams>().unwrap(), key_params);
+
+		assert_ne!(KeyParams::generate(&params).unwrap(), key_params);
+
+		assert_eq!(
+			KeyParams::new(b"short", &params).unwrap_err(),
+			PasswordError::InvalidParams
+		);
+		// a password hash should not be accepted
+		let hash = hash_password_with("my password", &params).unwrap();
+		assert_eq!(
+			hash.as_str().parse::<KeyParams>().unwrap_err(),
+			PasswordError::InvalidHash
+		);
+		assert_eq!(
+			"$argon2i$v=19$m=256,t=1,p=1$c29tZSBzYWx0"
+				.parse::<KeyParams>()
+				.unwrap_err(),
+			PasswordError::UnsupportedAlgorithm
+		);
+	}
+
+	#[test]
+	fn hash_reader() {
+		use std::io::Write;
+
+		let bytes: Vec<u8> = (0..100_000u32).map(|i| i as u8).collect();
+		let hash = Hasher::hash(&bytes);
+
+		assert_eq!(super::hash_reader(bytes.as_slice()).unwrap(), hash);
+
+		let mut hasher = Hasher::new();
+		for chunk in bytes.chunks(1000) {
+			hasher.write_all(chunk).unwrap();
+		}
+		assert_eq!(hasher.finalize(), hash);
+	}
+
+	// hashes from the official test vectors
+	#[cfg(all(feature = "blake3", feature = "hex"))]
+	#[test]
+	fn blake3() {
+		let input: Vec<u8> = (0..1024u32).map(|i| (i % 251) as u8).collect();
+
+		assert_eq!(
+			blake3::hash(&input).to_hex(),
+			"42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7"
+		);
+
+		let key = *b"whats the Elvish word for friend";
+		assert_eq!(
+			blake3::keyed_hash(&key, &input).to_hex(),
+			"75c46f6f3d9eb4f55ecaaee480db732e6c2105546f1e675003687c31719c7ba4"
+		);
+
+		let context = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+		let key = blake3::derive_key(context, &input);
+		let mut hasher = blake3::Hasher::new_derive_key(context);
+		hasher.update(&input);
+		assert_eq!(hasher.finalize().to_bytes(), key);
+		assert_ne!(blake3::derive_key("other context", &input), key);
+	}
+
+	#[cfg(feature = "hex")]
+	#[test]
+	fn sha2() {
+		use super::sha2::{Hasher, Sha256, Sha512};
+
+		assert_eq!(
+			sha2::sha256(b"abc").to_hex(),
+			"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+		);
+		assert_eq!(
+			sha2::sha512(b"abc").to_hex(),
+			"ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+			2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+		);
+
+		let mut hasher = Hasher::<Sha256>::new();
+		hasher.update(b"a");
+		hasher.update(b"bc");
+		assert_eq!(hasher.finalize(), sha2::sha256(b"abc"));
+
+		let hash = Hasher::<Sha512>::hash_reader(&b"abc"[..]).unwrap();
+		assert_eq!(hash, sha2::sha512(b"abc"));
+	}
+
+	#[cfg(all(feature = "sha3", feature = "hex"))]
+	#[test]
+	fn sha3() {
+		use super::sha3::{self, Hasher, Sha3_256, Shake128, XofHasher};
+		use std::io::Read;
+
+		assert_eq!(
+			sha3::sha3_256(b"abc").to_hex(),
+			"3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+		);
+		assert_eq!(
+			sha3::sha3_512(b"abc").to_hex(),
+			"b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+			10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+		);
+
+		let mut hasher = Hasher::<Sha3_256>::new();
+		hasher.update(b"a");
+		hasher.update(b"bc");
+		assert_eq!(hasher.finalize(), sha3::sha3_256(b"abc"));
+
+		let mut out = [0u8; 32];
+		sha3::shake128(b"abc").squeeze(&mut out);
+		assert_eq!(
+			hex::encode(out),
+			"5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8"
+		);
+
+		let mut out = [0u8; 64];
+		sha3::shake256(b"abc").read_exact(&mut out).unwrap();
+		assert_eq!(
+			hex::encode(out),
+			"483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+			d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4"
+		);
+
+		// squeezing continues where it stopped
+		let mut hasher = XofHasher::<Shake128>::new();
+		hasher.update(b"ab");
+		hasher.update(b"c");
+		let mut reader = hasher.finalize();
+		let a: [u8; 10] = reader.squeeze_array();
+		let b: [u8; 22] = reader.squeeze_array();
+		assert_eq!(
+			hex::encode([a.as_slice(), b.as_slice()].concat()),
+			"5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8"
+		);
+	}
+
+	#[cfg(feature = "rayon")]
+	#[test]
+	fn hash_many() {
+		let inputs: Vec<Vec<u8>> =
+			(0..100).map(|i| vec![i; i as usize]).collect();
+		let hashes = super::hash_many(&inputs);
+		assert_eq!(hashes.len(), inputs.len());
+		for (input, h) in inputs.iter().zip(&hashes) {
+			assert_eq!(*h, super::hash(input));
+		}
+	}
+
+	#[test]
+	fn hkdf() {
+		// RFC 5869 test case 1
+		let ikm = [0x0b; 22];
+		let salt: Vec<u8> = (0x00..=0x0c).collect();
+		let info: Vec<u8> = (0xf0..=0xf9).collect();
+
+		let prk = hkdf::extract(&salt, &ikm);
+		assert_eq!(
+			prk.to_bytes(),
+			[
+				0x07, 0x77, 0x09, 0x36, 0x2c, 0x2e, 0x32, 0xdf, 0x0d, 0xdc,
+				0x3f, 0x0d, 0xc4, 0x7b, 0xba, 0x63, 0x90, 0xb6, 0xc7, 0x3b,
+				0xb5, 0x0f, 0x9c, 0x31, 0x22, 0xec, 0x84, 0x4a, 0xd7, 0xc2,
+				0xb3, 0xe5
+			]
+		);
+
+		let expected = [
+			0x3c, 0xb2, 0x5f, 0x25, 0xfa, 0xac, 0xd5, 0x7a, 0x90, 0x43, 0x4f,
+			0x64, 0xd0, 0x36, 0x2f, 0x2a, 0x2d, 0x2d, 0x0a, 0x90, 0xcf, 0x1a,
+			0x5a, 0x4c, 0x5d, 0xb0, 0x2d, 0x56, 0xec, 0xc4, 0xc5, 0xbf, 0x34,
+			0x00, 0x72, 0x08, 0xd5, 0xb8, 0x87, 0x18, 0x58, 0x65,
+		];
+		let mut okm = [0u8; 42];
+		hkdf::expand(&prk, &info, &mut okm).unwrap();
+		assert_eq!(okm, expected);
+
+		let okm: [u8; 42] = hkdf::derive(&salt, &ikm, &info);
+		assert_eq!(okm, expected);
+		let token: crate::token::Token<42> = prk.derive(&info);
+		assert_eq!(token.to_bytes(), expected);
+
+		// a shorter output is a prefix
+		let okm: [u8; 16] = prk.derive(&info);
+		assert_eq!(okm, expected[..16]);
+
+		let mut okm = vec![0u8; hkdf::MAX_LEN];
+		assert!(prk.expand(&info, &mut okm).is_ok());
+		okm.push(0);
+		assert!(prk.expand(&info, &mut okm).is_err());
+
+		let mac: HmacKey = prk.derive(b"mac");
+		assert!(HmacKey::new(prk.derive::<[u8; 32]>(b"mac"))
+			.verify(b"msg", &mac.sign(b"msg")));
+
+		#[cfg(feature = "cipher")]
+		{
+			use crate::cipher::XAead;
+
+			let key: XAead = prk.derive(b"key");
+			assert_eq!(key.to_bytes(), prk.derive::<[u8; 32]>(b"key"));
+		}
+	}
+
+	#[cfg(feature = "multihash")]
+	#[test]
+	fn multihash() {
+		use super::multihash::{self, code, Base, Multihash};
+
+		let hash = sha2::sha256(b"foo");
+		let mh = Multihash::from(&hash);
+		assert_eq!(mh.code(), code::SHA2_256);
+		assert_eq!(mh.to_bytes()[..2], [0x12, 32]);
+		assert_eq!(mh.to_bytes()[2..], *hash.as_ref());
+
+		let s = "bciqcyjvunnup7rup7gnukpa5gbatie2cfvygja57ud4yuxuimjtoplq";
+		assert_eq!(mh.to_multibase(Base::Base32), s);
+		assert_eq!(Multihash::from_multibase(s).unwrap(), mh);
+		assert_eq!(mh.to_hash::<sha2::Sha256Hash>().unwrap(), hash);
+		assert!(mh.to_hash::<sha2::Sha512Hash>().is_err());
+
+		// BLAKE2b-512 has a two byte varint code
+		let hash = super::hash(b"foo");
+		let mh = Multihash::from(&hash);
+		let s = "uwOQCQMoAIzDmnT5rhKRqVqZTP9edUdl6O7fK1sL_Q7NUGF1twecj-z20rgc34SA3hCTHFLuYLZ3Fu9egqzGCQN3Rj40";
+		assert_eq!(mh.to_multibase(Base::Base64Url), s);
+		assert_eq!(
+			Multihash::from_multibase(s)
+				.unwrap()
+				.to_hash::<Hash>()
+				.unwrap(),
+			hash
+		);
+
+		for base in
+			[Base::Base16, Base::Base32, Base::Base58Btc, Base::Base64Url]
+		{
+			let s = mh.to_multibase(base);
+			assert_eq!(multihash::decode_multibase(&s).unwrap().0, base);
+			assert_eq!(Multihash::from_multibase(&s).unwrap(), mh);
+		}
+
+		let id = Id::of(b"foo");
+		let mh = Multihash::from(&id);
+		assert_eq!(mh.to_bytes()[..4], [0xa0, 0xe4, 0x02, 32]);
+		assert_eq!(mh.to_hash::<Id>().unwrap(), id);
+
+		assert_eq!(
+			multihash::encode_multibase(Base::Base58Btc, b"Hello World!"),
+			"z2NEpo7TZRRrLZSi2U"
+		);
+		assert_eq!(
+			multihash::decode_multibase("f48656c6c6f").unwrap(),
+			(Base::Base16, b"Hello".to_vec())
+		);
+
+		assert_eq!(
+			multihash::decode_multibase("bmy").unwrap(),
+			(Base::Base32, b"f".to_vec())
+		);
+		// base32 only has a single lowercase encoding
+		assert!(multihash::decode_multibase("bMY").is_err());
+		assert!(multihash::decode_multibase("bmz").is_err());
+		assert!(multihash::decode_multibase("bmy======").is_err());
+
+		// unknown prefix, trailing bytes and non minimal varints
+		assert!(multihash::decode_multibase("xabc").is_err());
+		assert!(Multihash::from_bytes(&[0x12, 1, 0, 0]).is_err());
+		assert!(Multihash::from_bytes(&[0x92, 0, 0]).is_err());
+		assert_eq!(
+			Multihash::new(0x12, &[1]).unwrap().to_bytes(),
+			[0x12, 1, 1]
+		);
+	}
 }
@@ -0,0 +1,279 @@
+//! Multihash and multibase encoding for interoperability with IPFS and
+//! other multiformats tooling.
+//!
+//! A multihash prefixes the digest with the code of the algorithm and the
+//! length of the digest, both encoded as unsigned varints. A multibase
+//! string prefixes an encoded value with a character identifying the
+//! encoding.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::hash::multihash::{Base, Multihash};
+//! use chuchi_crypto::hash::sha2;
+//!
+//! let hash = sha2::sha256(b"foo");
+//! let multihash = Multihash::from(&hash);
+//! let s = multihash.to_multibase(Base::Base32);
+//! assert!(s.starts_with("bciqc"));
+//!
+//! let multihash = Multihash::from_multibase(&s).unwrap();
+//! assert_eq!(multihash.to_hash::<sha2::Sha256Hash>().unwrap(), hash);
+//! ```
+
+use super::sha2::{Sha256Hash, Sha512Hash};
+use crate::error::DecodeError;
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// The codes of the algorithms supported by this crate, from the
+/// multicodec table.
+pub mod code {
+	pub const SHA2_256: u64 = 0x12;
+	pub const SHA2_512: u64 = 0x13;
+	pub const SHA3_512: u64 = 0x14;
+	pub const SHA3_256: u64 = 0x16;
+	pub const BLAKE3: u64 = 0x1e;
+	pub const BLAKE2B_256: u64 = 0xb220;
+	pub const BLAKE2B_512: u64 = 0xb240;
+}
+
+/// A digest together with the code of the algorithm which created it.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Multihash {
+	code: u64,
+	digest: Vec<u8>,
+}
+
+impl Multihash {
+	/// Creates a multihash, returns an error if the digest is longer than
+	/// what a multihash can describe.
+	pub fn new(code: u64, digest: &[u8]) -> Result<Self, DecodeError> {
+		if code > MAX_VARINT || digest.len() as u64 > MAX_VARINT {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		Ok(Self {
+			code,
+			digest: digest.to_vec(),
+		})
+	}
+
+	/// Parses the binary representation of a multihash.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let (code, rest) = read_varint(bytes)?;
+		let (len, digest) = read_varint(rest)?;
+
+		if digest.len() as u64 != len {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		Ok(Self {
+			code,
+			digest: digest.to_vec(),
+		})
+	}
+
+	/// Parses a multibase encoded multihash.
+	pub fn from_multibase(s: &str) -> Result<Self, DecodeError> {
+		let (_, bytes) = decode_multibase(s)?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// The code of the algorithm, see [`code`].
+	pub fn code(&self) -> u64 {
+		self.code
+	}
+
+	pub fn digest(&self) -> &[u8] {
+		&self.digest
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(2 * 9 + self.digest.len());
+		write_varint(&mut bytes, self.code);
+		write_varint(&mut bytes, self.digest.len() as u64);
+		bytes.extend_from_slice(&self.digest);
+
+		bytes
+	}
+
+	pub fn to_multibase(&self, base: Base) -> String {
+		encode_multibase(base, &self.to_bytes())
+	}
+
+	/// Converts the multihash into a hash of this crate, returns an error
+	/// if the code or the length don't match.
+	pub fn to_hash<H: MultihashDigest>(&self) -> Result<H, DecodeError> {
+		if self.code != H::CODE {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		H::from_digest(&self.digest)
+	}
+}
+
+impl fmt::Debug for Multihash {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Multihash")
+			.field("code", &format_args!("{:#x}", self.code))
+			.field("digest", &self.digest)
+			.finish()
+	}
+}
+
+impl TryFrom<&[u8]> for Multihash {
+	type Error = DecodeError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		Self::from_bytes(v)
+	}
+}
+
+/// A hash which has a multihash code.
+pub trait MultihashDigest: Sized {
+	/// The code of the algorithm, see [`code`].
+	const CODE: u64;
+
+	/// Creates the hash from a digest, returns an error if the length
+	/// doesn't match.
+	fn from_digest(digest: &[u8]) -> Result<Self, DecodeError>;
+}
+
+macro_rules! impl_hash {
+	($hash:ty, $code:expr, $len:expr) => {
+		impl From<&$hash> for Multihash {
+			fn from(hash: &$hash) -> Self {
+				Self {
+					code: $code,
+					digest: hash.as_ref().to_vec(),
+				}
+			}
+		}
+
+		impl MultihashDigest for $hash {
+			const CODE: u64 = $code;
+
+			fn from_digest(digest: &[u8]) -> Result<Self, DecodeError> {
+				<[u8; $len]>::try_from(digest)
+					.map(Self::from)
+					.map_err(|_| DecodeError::InvalidLength)
+			}
+		}
+	};
+}
+
+impl_hash!(super::Hash, code::BLAKE2B_512, 64);
+impl_hash!(super::Id, code::BLAKE2B_256, 32);
+impl_hash!(Sha256Hash, code::SHA2_256, 32);
+impl_hash!(Sha512Hash, code::SHA2_512, 64);
+#[cfg(feature = "sha3")]
+impl_hash!(super::sha3::Sha3_256Hash, code::SHA3_256, 32);
+#[cfg(feature = "sha3")]
+impl_hash!(super::sha3::Sha3_512Hash, code::SHA3_512, 64);
+#[cfg(feature = "blake3")]
+impl_hash!(super::blake3::Hash, code::BLAKE3, 32);
+
+/// A multibase encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Base {
+	/// Lowercase hex, prefix `f`
+	Base16,
+	/// Lowercase RFC 4648 base32 without padding, prefix `b`
+	Base32,
+	/// Base58 with the bitcoin alphabet, prefix `z`
+	Base58Btc,
+	/// RFC 4648 base64url without padding, prefix `u`
+	Base64Url,
+}
+
+impl Base {
+	pub fn prefix(&self) -> char {
+		match self {
+			Self::Base16 => 'f',
+			Self::Base32 => 'b',
+			Self::Base58Btc => 'z',
+			Self::Base64Url => 'u',
+		}
+	}
+
+	pub fn from_prefix(prefix: char) -> Option<Self> {
+		match prefix {
+			'f' => Some(Self::Base16),
+			'b' => Some(Self::Base32),
+			'z' => Some(Self::Base58Btc),
+			'u' => Some(Self::Base64Url),
+			_ => None,
+		}
+	}
+}
+
+/// Encodes the bytes as a multibase string.
+pub fn encode_multibase(base: Base, bytes: &[u8]) -> String {
+	let mut s = String::new();
+	s.push(base.prefix());
+
+	match base {
+		Base::Base16 => s.push_str(&hex::encode(bytes)),
+		Base::Base32 => s.push_str(&crate::base32_encode_lower(bytes)),
+		Base::Base58Btc => s.push_str(&bs58::encode(bytes).into_string()),
+		Base::Base64Url => URL_SAFE_NO_PAD.encode_string(bytes, &mut s),
+	}
+
+	s
+}
+
+/// Decodes a multibase string, returning the base it was encoded with.
+pub fn decode_multibase(s: &str) -> Result<(Base, Vec<u8>), DecodeError> {
+	let mut chars = s.chars();
+	let base = chars
+		.next()
+		.and_then(Base::from_prefix)
+		.ok_or(DecodeError::InvalidBytes)?;
+	let s = chars.as_str();
+
+	let bytes = match base {
+		Base::Base16 => hex::decode(s).map_err(DecodeError::inv_bytes)?,
+		Base::Base32 => crate::base32_decode_lower(s)?,
+		Base::Base58Btc => {
+			bs58::decode(s).into_vec().map_err(DecodeError::inv_bytes)?
+		}
+		Base::Base64Url => {
+			URL_SAFE_NO_PAD.decode(s).map_err(DecodeError::inv_bytes)?
+		}
+	};
+
+	Ok((base, bytes))
+}
+
+// multiformats limits varints to 9 bytes
+const MAX_VARINT: u64 = (1 << 63) - 1;
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+	while n >= 0x80 {
+		out.push(n as u8 | 0x80);
+		n >>= 7;
+	}
+	out.push(n as u8);
+}
+
+fn read_varint(bytes: &[u8]) -> Result<(u64, &[u8]), DecodeError> {
+	let mut n = 0u64;
+	for (i, b) in bytes.iter().enumerate().take(9) {
+		n |= ((b & 0x7f) as u64) << (i * 7);
+
+		if b & 0x80 == 0 {
+			// the shortest encoding is required
+			if i > 0 && *b == 0 {
+				return Err(DecodeError::InvalidBytes);
+			}
+
+			return Ok((n, &bytes[i + 1..]));
+		}
+	}
+
+	Err(DecodeError::InvalidBytes)
+}
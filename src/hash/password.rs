@@ -0,0 +1,777 @@
+//! Password hashing using Argon2id.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::hash::password::{hash_password, verify_password};
+//!
+//! let hash = hash_password("my password");
+//! // the hash can be stored as a string
+//! let hash = hash.to_string().parse().unwrap();
+//!
+//! assert!(verify_password("my password", &hash).unwrap());
+//! assert!(!verify_password("not my password", &hash).unwrap());
+//! ```
+//!
+//! ## Legacy hashes
+//! With the `pbkdf2` feature `verify_password` also accepts
+//! PBKDF2-HMAC-SHA256 and PBKDF2-HMAC-SHA512 hashes, for example
+//! `$pbkdf2-sha256$i=600000,l=32$<salt>$<hash>`, with the `bcrypt`
+//! feature bcrypt hashes like `$2b$12$<salt><hash>` and with the `scrypt`
+//! feature scrypt hashes like `$scrypt$ln=17,r=8,p=1$<salt>$<hash>`. After a successful login
+//! `needs_rehash` tells if the password should be hashed again with the
+//! current Argon2id parameters.
+//!
+//! ```
+//! use chuchi_crypto::hash::password::{
+//!     hash_password, needs_rehash, verify_password, PhcString,
+//! };
+//!
+//! # fn store(_: &PhcString) {}
+//! fn login(password: &str, hash: &PhcString) -> bool {
+//!     if !verify_password(password, hash).unwrap_or(false) {
+//!         return false;
+//!     }
+//!
+//!     if needs_rehash(hash) {
+//!         store(&hash_password(password));
+//!     }
+//!
+//!     true
+//! }
+//!
+//! let hash = hash_password("my password");
+//! assert!(login("my password", &hash));
+//! assert!(!needs_rehash(&hash));
+//! ```
+//!
+//! [`PasswordHasher`] does the same in one step.
+
+use crate::error::PasswordError;
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::OsRng;
+
+use argon2::password_hash::{
+	self, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString,
+};
+use argon2::{Algorithm, Argon2, Version};
+
+/// The parameters used for Argon2id.
+///
+/// The defaults follow the OWASP recommendation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+	/// Memory size in KiB
+	pub memory_cost: u32,
+	/// Number of iterations
+	pub time_cost: u32,
+	/// Degree of parallelism
+	pub parallelism: u32,
+}
+
+impl Params {
+	fn to_argon2(&self) -> Result<Argon2<'static>, PasswordError> {
+		let params = argon2::Params::new(
+			self.memory_cost,
+			self.time_cost,
+			self.parallelism,
+			None,
+		)
+		.map_err(|_| PasswordError::InvalidParams)?;
+
+		Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+	}
+}
+
+impl Default for Params {
+	fn default() -> Self {
+		Self {
+			memory_cost: argon2::Params::DEFAULT_M_COST,
+			time_cost: argon2::Params::DEFAULT_T_COST,
+			parallelism: argon2::Params::DEFAULT_P_COST,
+		}
+	}
+}
+
+/// The parameters used for scrypt.
+///
+/// The defaults are N = 2^17, r = 8 and p = 1.
+#[cfg(feature = "scrypt")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScryptParams {
+	/// log2 of the CPU/memory cost N
+	pub log_n: u8,
+	/// Block size
+	pub r: u32,
+	/// Degree of parallelism
+	pub p: u32,
+}
+
+#[cfg(feature = "scrypt")]
+impl ScryptParams {
+	fn to_scrypt(&self, len: usize) -> Result<scrypt::Params, PasswordError> {
+		scrypt::Params::new(self.log_n, self.r, self.p, len)
+			.map_err(|_| PasswordError::InvalidParams)
+	}
+}
+
+#[cfg(feature = "scrypt")]
+impl Default for ScryptParams {
+	fn default() -> Self {
+		Self {
+			log_n: scrypt::Params::RECOMMENDED_LOG_N,
+			r: scrypt::Params::RECOMMENDED_R,
+			p: scrypt::Params::RECOMMENDED_P,
+		}
+	}
+}
+
+/// A password hash in the PHC string format.
+///
+/// For example: `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`
+///
+/// With the `bcrypt` feature bcrypt hashes like `$2b$12$<salt><hash>` are
+/// accepted as well.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PhcString {
+	inner: String,
+}
+
+impl PhcString {
+	pub fn as_str(&self) -> &str {
+		&self.inner
+	}
+
+	/// Returns `None` if this is a bcrypt hash.
+	pub(crate) fn password_hash(&self) -> Option<PasswordHash<'_>> {
+		if is_bcrypt(&self.inner) {
+			return None;
+		}
+
+		// the string was validated when it was created
+		Some(PasswordHash::new(&self.inner).unwrap())
+	}
+}
+
+impl PhcString {
+	/// Returns the scheme the hash was created with or `None` if it is
+	/// unknown.
+	pub fn scheme(&self) -> Option<Scheme> {
+		if is_bcrypt(&self.inner) {
+			return Some(Scheme::Bcrypt);
+		}
+
+		let hash = self.password_hash()?;
+		match hash.algorithm.as_str() {
+			"argon2d" | "argon2i" | "argon2id" => Some(Scheme::Argon2),
+			"scrypt" => Some(Scheme::Scrypt),
+			"pbkdf2" | "pbkdf2-sha256" | "pbkdf2-sha512" => {
+				Some(Scheme::Pbkdf2)
+			}
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Debug for PhcString {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PhcString").field(&self.inner).finish()
+	}
+}
+
+impl fmt::Display for PhcString {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.inner)
+	}
+}
+
+impl FromStr for PhcString {
+	type Err = PasswordError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		#[cfg(feature = "bcrypt")]
+		if is_bcrypt(s) {
+			let parts = bcrypt::HashParts::from_str(s)
+				.map_err(|_| PasswordError::InvalidHash)?;
+			if !(4..=31).contains(&parts.get_cost()) {
+				return Err(PasswordError::InvalidHash);
+			}
+
+			return Ok(Self { inner: s.into() });
+		}
+
+		PasswordHash::new(s).map_err(|_| PasswordError::InvalidHash)?;
+
+		Ok(Self { inner: s.into() })
+	}
+}
+
+impl AsRef<str> for PhcString {
+	fn as_ref(&self) -> &str {
+		&self.inner
+	}
+}
+
+/// The salt and parameters needed to derive the same key from a password
+/// again.
+///
+/// Encoded in the PHC string format without a hash, for example
+/// `$argon2id$v=19$m=19456,t=2,p=1$<salt>`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyParams {
+	salt: Vec<u8>,
+	params: Params,
+}
+
+impl KeyParams {
+	/// Returns an error if the salt is not between 8 and 48 bytes long or
+	/// the params are invalid.
+	pub fn new(salt: &[u8], params: &Params) -> Result<Self, PasswordError> {
+		if salt.len() < argon2::MIN_SALT_LEN || salt.len() > 48 {
+			return Err(PasswordError::InvalidParams);
+		}
+
+		params.to_argon2()?;
+
+		Ok(Self {
+			salt: salt.to_vec(),
+			params: params.clone(),
+		})
+	}
+
+	/// Creates params with a random 16 bytes salt.
+	pub fn generate(params: &Params) -> Result<Self, PasswordError> {
+		let mut salt = [0u8; 16];
+		crate::fill_random(&mut salt);
+
+		Self::new(&salt, params)
+	}
+
+	pub fn salt(&self) -> &[u8] {
+		&self.salt
+	}
+
+	pub fn params(&self) -> &Params {
+		&self.params
+	}
+
+	#[cfg(feature = "cipher")]
+	pub(crate) fn derive_key(
+		&self,
+		password: &[u8],
+		out: &mut [u8],
+	) -> Result<(), PasswordError> {
+		self.params
+			.to_argon2()?
+			.hash_password_into(password, &self.salt, out)
+			.map_err(|_| PasswordError::InvalidParams)
+	}
+}
+
+impl fmt::Debug for KeyParams {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("KeyParams").field(&self.to_string()).finish()
+	}
+}
+
+impl fmt::Display for KeyParams {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		// the length of the salt was checked in new
+		let salt = SaltString::encode_b64(&self.salt).unwrap();
+
+		write!(
+			f,
+			"$argon2id$v=19$m={},t={},p={}${}",
+			self.params.memory_cost,
+			self.params.time_cost,
+			self.params.parallelism,
+			salt.as_str()
+		)
+	}
+}
+
+impl FromStr for KeyParams {
+	type Err = PasswordError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let hash =
+			PasswordHash::new(s).map_err(|_| PasswordError::InvalidHash)?;
+
+		if hash.algorithm != argon2::ARGON2ID_IDENT {
+			return Err(PasswordError::UnsupportedAlgorithm);
+		}
+
+		if matches!(hash.version, Some(v) if v != 0x13) {
+			return Err(PasswordError::UnsupportedAlgorithm);
+		}
+
+		if hash.hash.is_some() {
+			return Err(PasswordError::InvalidHash);
+		}
+
+		let params = argon2::Params::try_from(&hash)
+			.map_err(|_| PasswordError::InvalidParams)?;
+
+		let mut buf = [0u8; 64];
+		let salt = hash
+			.salt
+			.ok_or(PasswordError::InvalidHash)?
+			.decode_b64(&mut buf)
+			.map_err(|_| PasswordError::InvalidHash)?;
+
+		Self::new(
+			salt,
+			&Params {
+				memory_cost: params.m_cost(),
+				time_cost: params.t_cost(),
+				parallelism: params.p_cost(),
+			},
+		)
+	}
+}
+
+/// The algorithm of a password hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Scheme {
+	Argon2,
+	Bcrypt,
+	Scrypt,
+	Pbkdf2,
+}
+
+/// Hashes new passwords with Argon2id and verifies hashes of any supported
+/// scheme, telling when a hash should be upgraded.
+///
+/// Which legacy schemes can be verified depends on the enabled features,
+/// see the [module](self) documentation.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::hash::password::{PasswordHasher, Verification};
+///
+/// let hasher = PasswordHasher::default();
+/// let hash = hasher.hash("my password");
+///
+/// match hasher.verify("my password", &hash).unwrap() {
+///     Verification::Valid => {}
+///     Verification::Rehash(new_hash) => {
+///         // store new_hash instead of hash
+///         # drop(new_hash);
+///     }
+///     Verification::Invalid => panic!("wrong password"),
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PasswordHasher {
+	params: HasherParams,
+}
+
+impl PasswordHasher {
+	/// Creates a hasher which uses `params` for new hashes, returns an
+	/// error if the params are invalid.
+	pub fn new(params: impl Into<HasherParams>) -> Result<Self, PasswordError> {
+		let params = params.into();
+		match &params {
+			HasherParams::Argon2id(p) => {
+				p.to_argon2()?;
+			}
+			#[cfg(feature = "scrypt")]
+			HasherParams::Scrypt(p) => {
+				p.to_scrypt(scrypt::Params::RECOMMENDED_LEN)?;
+			}
+		}
+
+		Ok(Self { params })
+	}
+
+	pub fn params(&self) -> &HasherParams {
+		&self.params
+	}
+
+	/// Hashes a password with the algorithm and params of this hasher.
+	pub fn hash(&self, password: impl AsRef<[u8]>) -> PhcString {
+		// the params were validated in new
+		match &self.params {
+			HasherParams::Argon2id(p) => {
+				hash_password_with(password, p).unwrap()
+			}
+			#[cfg(feature = "scrypt")]
+			HasherParams::Scrypt(p) => hash_password_scrypt(password, p).unwrap(),
+		}
+	}
+
+	/// Verifies the password, if it is valid but the hash was created with
+	/// another scheme or other params the password is hashed again.
+	///
+	/// Returns an error if the hash is malformed or its scheme is not
+	/// supported.
+	pub fn verify(
+		&self,
+		password: impl AsRef<[u8]>,
+		hash: &PhcString,
+	) -> Result<Verification, PasswordError> {
+		let password = password.as_ref();
+		if !verify_password(password, hash)? {
+			return Ok(Verification::Invalid);
+		}
+
+		if self.needs_rehash(hash) {
+			Ok(Verification::Rehash(self.hash(password)))
+		} else {
+			Ok(Verification::Valid)
+		}
+	}
+
+	/// Returns true if the hash was not created with the algorithm and the
+	/// params of this hasher.
+	pub fn needs_rehash(&self, hash: &PhcString) -> bool {
+		match &self.params {
+			HasherParams::Argon2id(p) => needs_rehash_with(hash, p),
+			#[cfg(feature = "scrypt")]
+			HasherParams::Scrypt(p) => needs_rehash_scrypt(hash, p),
+		}
+	}
+}
+
+/// The algorithm and params a `PasswordHasher` uses for new hashes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HasherParams {
+	Argon2id(Params),
+	#[cfg(feature = "scrypt")]
+	Scrypt(ScryptParams),
+}
+
+impl Default for HasherParams {
+	fn default() -> Self {
+		Self::Argon2id(Params::default())
+	}
+}
+
+impl From<Params> for HasherParams {
+	fn from(params: Params) -> Self {
+		Self::Argon2id(params)
+	}
+}
+
+#[cfg(feature = "scrypt")]
+impl From<ScryptParams> for HasherParams {
+	fn from(params: ScryptParams) -> Self {
+		Self::Scrypt(params)
+	}
+}
+
+/// The result of `PasswordHasher::verify`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+	/// The password does not match
+	Invalid,
+	/// The password matches
+	Valid,
+	/// The password matches and was hashed again with the current params,
+	/// the new hash should replace the stored one
+	Rehash(PhcString),
+}
+
+impl Verification {
+	/// Returns true if the password matches.
+	pub fn is_valid(&self) -> bool {
+		!matches!(self, Self::Invalid)
+	}
+}
+
+/// Hashes a password with Argon2id and the default parameters.
+pub fn hash_password(password: impl AsRef<[u8]>) -> PhcString {
+	// the default params are valid
+	hash_password_with(password, &Params::default()).unwrap()
+}
+
+/// Hashes a password with Argon2id and custom parameters.
+pub fn hash_password_with(
+	password: impl AsRef<[u8]>,
+	params: &Params,
+) -> Result<PhcString, PasswordError> {
+	let argon2 = params.to_argon2()?;
+	let salt = SaltString::generate(&mut OsRng);
+
+	let hash = argon2
+		.hash_password(password.as_ref(), &salt)
+		.map_err(|_| PasswordError::InvalidParams)?;
+
+	Ok(PhcString {
+		inner: hash.to_string(),
+	})
+}
+
+/// Hashes a password with bcrypt, `cost` needs to be between 4 and 31.
+///
+/// Only use this if a system requires bcrypt hashes, else use
+/// `hash_password`.
+///
+/// ## Warning
+/// bcrypt only uses the first 72 bytes of the password.
+#[cfg(feature = "bcrypt")]
+pub fn hash_password_bcrypt(
+	password: impl AsRef<[u8]>,
+	cost: u32,
+) -> Result<PhcString, PasswordError> {
+	let hash = bcrypt::hash(password.as_ref(), cost)
+		.map_err(|_| PasswordError::InvalidParams)?;
+
+	Ok(PhcString { inner: hash })
+}
+
+/// Hashes a password with scrypt.
+///
+/// Only use this if a system requires scrypt hashes, else use
+/// `hash_password`.
+#[cfg(feature = "scrypt")]
+pub fn hash_password_scrypt(
+	password: impl AsRef<[u8]>,
+	params: &ScryptParams,
+) -> Result<PhcString, PasswordError> {
+	let params = params.to_scrypt(scrypt::Params::RECOMMENDED_LEN)?;
+	let salt = SaltString::generate(&mut OsRng);
+
+	let hash = scrypt::Scrypt
+		.hash_password_customized(password.as_ref(), None, None, params, &salt)
+		.map_err(|_| PasswordError::InvalidParams)?;
+
+	Ok(PhcString {
+		inner: hash.to_string(),
+	})
+}
+
+/// Derives a key from a password with scrypt.
+///
+/// The salt should be random and at least 16 bytes long.
+#[cfg(feature = "scrypt")]
+pub fn derive_key_scrypt(
+	password: impl AsRef<[u8]>,
+	salt: &[u8],
+	params: &ScryptParams,
+) -> Result<[u8; 32], PasswordError> {
+	let params = params.to_scrypt(32)?;
+
+	let mut key = [0u8; 32];
+	// the output length is valid
+	scrypt::scrypt(password.as_ref(), salt, &params, &mut key).unwrap();
+
+	Ok(key)
+}
+
+/// Verifies a password against a hash.
+///
+/// Returns `Ok(false)` if the password does not match.
+pub fn verify_password(
+	password: impl AsRef<[u8]>,
+	hash: &PhcString,
+) -> Result<bool, PasswordError> {
+	#[cfg(feature = "bcrypt")]
+	if is_bcrypt(hash.as_str()) {
+		return bcrypt::verify(password.as_ref(), hash.as_str())
+			.map_err(|_| PasswordError::InvalidHash);
+	}
+
+	let Some(hash) = hash.password_hash() else {
+		return Err(PasswordError::UnsupportedAlgorithm);
+	};
+	let res = match hash.algorithm.as_str() {
+		#[cfg(feature = "pbkdf2")]
+		"pbkdf2-sha256" | "pbkdf2-sha512" => {
+			pbkdf2::Pbkdf2.verify_password(password.as_ref(), &hash)
+		}
+		#[cfg(feature = "scrypt")]
+		"scrypt" => scrypt::Scrypt.verify_password(password.as_ref(), &hash),
+		"argon2d" | "argon2i" | "argon2id" => {
+			Argon2::default().verify_password(password.as_ref(), &hash)
+		}
+		_ => return Err(PasswordError::UnsupportedAlgorithm),
+	};
+
+	match res {
+		Ok(()) => Ok(true),
+		Err(password_hash::Error::Password) => Ok(false),
+		Err(password_hash::Error::Algorithm) => {
+			Err(PasswordError::UnsupportedAlgorithm)
+		}
+		Err(_) => Err(PasswordError::InvalidHash),
+	}
+}
+
+/// Returns true if the hash was not created with Argon2id and the default
+/// parameters.
+///
+/// Should be checked after the password was verified, if it returns true
+/// the password should be hashed again with `hash_password`.
+pub fn needs_rehash(hash: &PhcString) -> bool {
+	needs_rehash_with(hash, &Params::default())
+}
+
+/// Returns true if the hash was not created with Argon2id and the given
+/// parameters.
+pub fn needs_rehash_with(hash: &PhcString, params: &Params) -> bool {
+	let Some(hash) = hash.password_hash() else {
+		return true;
+	};
+	if hash.algorithm != argon2::ARGON2ID_IDENT
+		|| hash.version != Some(Version::V0x13.into())
+	{
+		return true;
+	}
+
+	match argon2::Params::try_from(&hash) {
+		Ok(p) => {
+			p.m_cost() != params.memory_cost
+				|| p.t_cost() != params.time_cost
+				|| p.p_cost() != params.parallelism
+		}
+		Err(_) => true,
+	}
+}
+
+/// Returns true if the hash was not created with scrypt and the given
+/// parameters.
+#[cfg(feature = "scrypt")]
+fn needs_rehash_scrypt(hash: &PhcString, params: &ScryptParams) -> bool {
+	let Some(hash) = hash.password_hash() else {
+		return true;
+	};
+	if hash.algorithm != scrypt::ALG_ID {
+		return true;
+	}
+
+	match scrypt::Params::try_from(&hash) {
+		Ok(p) => {
+			p.log_n() != params.log_n || p.r() != params.r || p.p() != params.p
+		}
+		Err(_) => true,
+	}
+}
+
+/// Returns true if the string is in the bcrypt format.
+fn is_bcrypt(s: &str) -> bool {
+	["$2a$", "$2b$", "$2x$", "$2y$"]
+		.iter()
+		.any(|prefix| s.starts_with(prefix))
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	use std::borrow::Cow;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for PhcString {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			serializer.serialize_str(self.as_str())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for PhcString {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+			Self::from_str(s.as_ref()).map_err(D::Error::custom)
+		}
+	}
+}
+
+#[cfg(feature = "postgres")]
+mod impl_postgres {
+	use super::*;
+
+	use bytes::BytesMut;
+	use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+	impl ToSql for PhcString {
+		fn to_sql(
+			&self,
+			ty: &Type,
+			out: &mut BytesMut,
+		) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+		where
+			Self: Sized,
+		{
+			self.as_str().to_sql(ty, out)
+		}
+
+		fn accepts(ty: &Type) -> bool
+		where
+			Self: Sized,
+		{
+			<&str as ToSql>::accepts(ty)
+		}
+
+		to_sql_checked!();
+	}
+
+	impl<'r> FromSql<'r> for PhcString {
+		fn from_sql(
+			ty: &Type,
+			raw: &'r [u8],
+		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			let s = <&str as FromSql>::from_sql(ty, raw)?;
+			s.parse().map_err(Into::into)
+		}
+
+		fn accepts(ty: &Type) -> bool {
+			<&str as FromSql>::accepts(ty)
+		}
+	}
+}
+
+#[cfg(feature = "sqlx")]
+mod impl_sqlx {
+	use super::*;
+
+	use sqlx::encode::IsNull;
+	use sqlx::error::BoxDynError;
+	use sqlx::{Database, Decode, Encode, Type};
+
+	impl<DB: Database> Type<DB> for PhcString
+	where
+		String: Type<DB>,
+	{
+		fn type_info() -> DB::TypeInfo {
+			<String as Type<DB>>::type_info()
+		}
+
+		fn compatible(ty: &DB::TypeInfo) -> bool {
+			<String as Type<DB>>::compatible(ty)
+		}
+	}
+
+	impl<'q, DB: Database> Encode<'q, DB> for PhcString
+	where
+		String: Encode<'q, DB>,
+	{
+		fn encode_by_ref(
+			&self,
+			buf: &mut DB::ArgumentBuffer<'q>,
+		) -> Result<IsNull, BoxDynError> {
+			self.as_str().to_string().encode(buf)
+		}
+	}
+
+	impl<'r, DB: Database> Decode<'r, DB> for PhcString
+	where
+		&'r str: Decode<'r, DB>,
+	{
+		fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+			let s = <&str as Decode<DB>>::decode(value)?;
+			s.parse().map_err(Into::into)
+		}
+	}
+}
@@ -0,0 +1,163 @@
+//! SHA-256 and SHA-512 for interoperability with other systems.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::hash::sha2::{self, Hasher, Sha256};
+//!
+//! let mut hasher = Hasher::<Sha256>::new();
+//! hasher.update(b"hello ");
+//! hasher.update(b"world");
+//! assert_eq!(hasher.finalize(), sha2::sha256(b"hello world"));
+//! ```
+
+use super::fixed_bytes;
+use crate::error::TryFromError;
+
+use std::convert::TryInto;
+use std::fmt;
+use std::io::{self, Read};
+
+use ::sha2::Digest;
+
+mod sealed {
+	pub trait Sealed {}
+}
+
+/// A hash algorithm of the SHA-2 family.
+///
+/// This trait is sealed and implemented by `Sha256` and `Sha512`.
+pub trait Algorithm: sealed::Sealed {
+	/// The hash returned by this algorithm.
+	type Hash;
+
+	#[doc(hidden)]
+	type Digest: Digest + Clone;
+
+	#[doc(hidden)]
+	fn finalize(digest: Self::Digest) -> Self::Hash;
+}
+
+/// The SHA-256 algorithm.
+#[derive(Debug, Clone, Copy)]
+pub struct Sha256;
+
+impl sealed::Sealed for Sha256 {}
+
+impl Algorithm for Sha256 {
+	type Hash = Sha256Hash;
+	type Digest = ::sha2::Sha256;
+
+	fn finalize(digest: Self::Digest) -> Self::Hash {
+		Sha2Hash {
+			bytes: digest.finalize().into(),
+		}
+	}
+}
+
+/// The SHA-512 algorithm.
+#[derive(Debug, Clone, Copy)]
+pub struct Sha512;
+
+impl sealed::Sealed for Sha512 {}
+
+impl Algorithm for Sha512 {
+	type Hash = Sha512Hash;
+	type Digest = ::sha2::Sha512;
+
+	fn finalize(digest: Self::Digest) -> Self::Hash {
+		Sha2Hash {
+			bytes: digest.finalize().into(),
+		}
+	}
+}
+
+pub fn sha256(data: impl AsRef<[u8]>) -> Sha256Hash {
+	Hasher::<Sha256>::hash(data)
+}
+
+pub fn sha512(data: impl AsRef<[u8]>) -> Sha512Hash {
+	Hasher::<Sha512>::hash(data)
+}
+
+/// An incremental SHA-2 hasher.
+pub struct Hasher<A: Algorithm> {
+	inner: A::Digest,
+}
+
+impl<A: Algorithm> Hasher<A> {
+	pub fn new() -> Self {
+		Self {
+			inner: A::Digest::new(),
+		}
+	}
+
+	pub fn update(&mut self, data: impl AsRef<[u8]>) {
+		self.inner.update(data);
+	}
+
+	pub fn finalize(self) -> A::Hash {
+		A::finalize(self.inner)
+	}
+
+	pub fn hash(data: impl AsRef<[u8]>) -> A::Hash {
+		let mut hasher = Self::new();
+		hasher.update(data);
+		hasher.finalize()
+	}
+
+	pub fn hash_reader(mut reader: impl Read) -> io::Result<A::Hash> {
+		let mut hasher = Self::new();
+		io::copy(&mut reader, &mut hasher)?;
+		Ok(hasher.finalize())
+	}
+}
+
+impl<A: Algorithm> Clone for Hasher<A> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+		}
+	}
+}
+
+impl<A: Algorithm> fmt::Debug for Hasher<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Hasher")
+	}
+}
+
+impl<A: Algorithm> io::Write for Hasher<A> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+pub type Sha256Hash = Sha2Hash<32>;
+
+pub type Sha512Hash = Sha2Hash<64>;
+
+/// A hash created by a SHA-2 algorithm.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Sha2Hash<const S: usize> {
+	bytes: [u8; S],
+}
+
+impl<const S: usize> Sha2Hash<S> {
+	pub const LEN: usize = S;
+
+	/// Returns an error if the slice is not `S` bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; S] {
+		self.bytes
+	}
+}
+
+fixed_bytes!(Sha2Hash<const S: usize>);
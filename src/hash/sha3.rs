@@ -0,0 +1,335 @@
+//! SHA-3 and the SHAKE extendable output functions (FIPS 202).
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::hash::sha3::{self, Hasher, Sha3_256};
+//!
+//! let mut hasher = Hasher::<Sha3_256>::new();
+//! hasher.update(b"hello ");
+//! hasher.update(b"world");
+//! assert_eq!(hasher.finalize(), sha3::sha3_256(b"hello world"));
+//!
+//! // SHAKE can output any amount of bytes
+//! let mut reader = sha3::shake256(b"seed");
+//! let mut mask = [0u8; 100];
+//! reader.squeeze(&mut mask);
+//! ```
+
+use super::fixed_bytes;
+use crate::error::TryFromError;
+use crate::token::Token;
+
+use std::convert::TryInto;
+use std::fmt;
+use std::io::{self, Read};
+
+use ::sha3::digest::{ExtendableOutput, Update, XofReader as _};
+use ::sha3::Digest;
+
+mod sealed {
+	pub trait Sealed {}
+}
+
+/// A hash algorithm of the SHA-3 family.
+///
+/// This trait is sealed and implemented by `Sha3_256` and `Sha3_512`.
+pub trait Algorithm: sealed::Sealed {
+	/// The hash returned by this algorithm.
+	type Hash;
+
+	#[doc(hidden)]
+	type Digest: Digest + Clone;
+
+	#[doc(hidden)]
+	fn finalize(digest: Self::Digest) -> Self::Hash;
+}
+
+/// The SHA3-256 algorithm.
+#[derive(Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub struct Sha3_256;
+
+impl sealed::Sealed for Sha3_256 {}
+
+impl Algorithm for Sha3_256 {
+	type Hash = Sha3_256Hash;
+	type Digest = ::sha3::Sha3_256;
+
+	fn finalize(digest: Self::Digest) -> Self::Hash {
+		Sha3Hash {
+			bytes: digest.finalize().into(),
+		}
+	}
+}
+
+/// The SHA3-512 algorithm.
+#[derive(Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub struct Sha3_512;
+
+impl sealed::Sealed for Sha3_512 {}
+
+impl Algorithm for Sha3_512 {
+	type Hash = Sha3_512Hash;
+	type Digest = ::sha3::Sha3_512;
+
+	fn finalize(digest: Self::Digest) -> Self::Hash {
+		Sha3Hash {
+			bytes: digest.finalize().into(),
+		}
+	}
+}
+
+pub fn sha3_256(data: impl AsRef<[u8]>) -> Sha3_256Hash {
+	Hasher::<Sha3_256>::hash(data)
+}
+
+pub fn sha3_512(data: impl AsRef<[u8]>) -> Sha3_512Hash {
+	Hasher::<Sha3_512>::hash(data)
+}
+
+/// An incremental SHA-3 hasher.
+pub struct Hasher<A: Algorithm> {
+	inner: A::Digest,
+}
+
+impl<A: Algorithm> Hasher<A> {
+	pub fn new() -> Self {
+		Self {
+			inner: A::Digest::new(),
+		}
+	}
+
+	pub fn update(&mut self, data: impl AsRef<[u8]>) {
+		Digest::update(&mut self.inner, data);
+	}
+
+	pub fn finalize(self) -> A::Hash {
+		A::finalize(self.inner)
+	}
+
+	pub fn hash(data: impl AsRef<[u8]>) -> A::Hash {
+		let mut hasher = Self::new();
+		hasher.update(data);
+		hasher.finalize()
+	}
+
+	pub fn hash_reader(mut reader: impl Read) -> io::Result<A::Hash> {
+		let mut hasher = Self::new();
+		io::copy(&mut reader, &mut hasher)?;
+		Ok(hasher.finalize())
+	}
+}
+
+impl<A: Algorithm> Clone for Hasher<A> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+		}
+	}
+}
+
+impl<A: Algorithm> fmt::Debug for Hasher<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Hasher")
+	}
+}
+
+impl<A: Algorithm> io::Write for Hasher<A> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// An extendable output function of the SHA-3 family.
+///
+/// This trait is sealed and implemented by `Shake128` and `Shake256`.
+pub trait XofAlgorithm: sealed::Sealed {
+	#[doc(hidden)]
+	type Xof: ExtendableOutput + Default + Clone;
+}
+
+/// The SHAKE128 extendable output function.
+#[derive(Debug, Clone, Copy)]
+pub struct Shake128;
+
+impl sealed::Sealed for Shake128 {}
+
+impl XofAlgorithm for Shake128 {
+	type Xof = ::sha3::Shake128;
+}
+
+/// The SHAKE256 extendable output function.
+#[derive(Debug, Clone, Copy)]
+pub struct Shake256;
+
+impl sealed::Sealed for Shake256 {}
+
+impl XofAlgorithm for Shake256 {
+	type Xof = ::sha3::Shake256;
+}
+
+pub fn shake128(data: impl AsRef<[u8]>) -> XofReader<Shake128> {
+	XofHasher::<Shake128>::hash(data)
+}
+
+pub fn shake256(data: impl AsRef<[u8]>) -> XofReader<Shake256> {
+	XofHasher::<Shake256>::hash(data)
+}
+
+/// Derives any amount of output from `material` with SHAKE256, the
+/// `context` separates outputs of the same material used for different
+/// purposes.
+///
+/// The output is only unguessable if the material contains a secret.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::hash::sha3;
+/// use chuchi_crypto::token::Token;
+///
+/// let secret = b"server secret";
+/// let material = [secret.as_slice(), b"user@example.com"].concat();
+///
+/// let id: Token<16> = sha3::derive("user id", &material).squeeze_token();
+/// let mut mask = [0u8; 100];
+/// sha3::derive("mask", &material).squeeze(&mut mask);
+/// ```
+pub fn derive(
+	context: &str,
+	material: impl AsRef<[u8]>,
+) -> XofReader<Shake256> {
+	let mut hasher = XofHasher::<Shake256>::new();
+	hasher.update((context.len() as u64).to_be_bytes());
+	hasher.update(context);
+	hasher.update(material);
+	hasher.finalize()
+}
+
+/// An incremental SHAKE hasher.
+pub struct XofHasher<A: XofAlgorithm> {
+	inner: A::Xof,
+}
+
+impl<A: XofAlgorithm> XofHasher<A> {
+	pub fn new() -> Self {
+		Self {
+			inner: A::Xof::default(),
+		}
+	}
+
+	pub fn update(&mut self, data: impl AsRef<[u8]>) {
+		self.inner.update(data.as_ref());
+	}
+
+	/// Returns a reader from which any amount of output can be squeezed.
+	pub fn finalize(self) -> XofReader<A> {
+		XofReader {
+			inner: self.inner.finalize_xof(),
+		}
+	}
+
+	pub fn hash(data: impl AsRef<[u8]>) -> XofReader<A> {
+		let mut hasher = Self::new();
+		hasher.update(data);
+		hasher.finalize()
+	}
+}
+
+impl<A: XofAlgorithm> Clone for XofHasher<A> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+		}
+	}
+}
+
+impl<A: XofAlgorithm> fmt::Debug for XofHasher<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("XofHasher")
+	}
+}
+
+impl<A: XofAlgorithm> io::Write for XofHasher<A> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// The output of a SHAKE hasher.
+///
+/// Squeezing twice continues where the last call stopped, so squeezing 16
+/// and then 16 bytes returns the same as squeezing 32 bytes at once.
+pub struct XofReader<A: XofAlgorithm> {
+	inner: <A::Xof as ExtendableOutput>::Reader,
+}
+
+impl<A: XofAlgorithm> XofReader<A> {
+	/// Fills `buf` with the next bytes of the output.
+	pub fn squeeze(&mut self, buf: &mut [u8]) {
+		self.inner.read(buf);
+	}
+
+	/// Returns the next `N` bytes of the output.
+	pub fn squeeze_array<const N: usize>(&mut self) -> [u8; N] {
+		let mut buf = [0u8; N];
+		self.squeeze(&mut buf);
+		buf
+	}
+
+	/// Returns a token from the next `S` bytes of the output.
+	pub fn squeeze_token<const S: usize>(&mut self) -> Token<S> {
+		Token::from(self.squeeze_array::<S>())
+	}
+}
+
+impl<A: XofAlgorithm> fmt::Debug for XofReader<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("XofReader")
+	}
+}
+
+/// Never ends, `read` always fills the whole buffer.
+impl<A: XofAlgorithm> Read for XofReader<A> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.squeeze(buf);
+		Ok(buf.len())
+	}
+}
+
+#[allow(non_camel_case_types)]
+pub type Sha3_256Hash = Sha3Hash<32>;
+
+#[allow(non_camel_case_types)]
+pub type Sha3_512Hash = Sha3Hash<64>;
+
+/// A hash created by a SHA-3 algorithm.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Sha3Hash<const S: usize> {
+	bytes: [u8; S],
+}
+
+impl<const S: usize> Sha3Hash<S> {
+	pub const LEN: usize = S;
+
+	/// Returns an error if the slice is not `S` bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; S] {
+		self.bytes
+	}
+}
+
+fixed_bytes!(Sha3Hash<const S: usize>);
@@ -0,0 +1,83 @@
+//! Keyed SipHash for hash tables and request coalescing.
+//!
+//! SipHash is a fast keyed hash with a 64 bit output. As long as the key
+//! stays secret an attacker can't predict which inputs collide, which
+//! protects hash tables against flooding.
+//!
+//! ## Note
+//! SipHash is **not** collision resistant, with 64 bits collisions are
+//! found after about 2^32 inputs. Don't use it as a MAC, for integrity or
+//! as an identifier, use [`HmacKey`](super::HmacKey) or [`Id`](super::Id) instead.
+//!
+//! SipHash-1-3 is faster and is what the standard library uses,
+//! SipHash-2-4 is the variant from the original paper.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::hash::siphash::{self, SipKey};
+//! use std::collections::HashMap;
+//!
+//! let key = SipKey::new();
+//! let shard = siphash::siphash13(&key, b"user 42") % 16;
+//!
+//! let mut map = HashMap::with_hasher(siphash::BuildSipHasher13::new(key));
+//! map.insert("user 42", shard);
+//! ```
+
+use crate::token::Token;
+
+use std::fmt;
+use std::hash::BuildHasher;
+
+pub use siphasher::sip::{SipHasher13, SipHasher24};
+
+/// The 16 byte key used by SipHash.
+pub type SipKey = Token<16>;
+
+/// Hashes the data with SipHash-1-3.
+pub fn siphash13(key: &SipKey, data: impl AsRef<[u8]>) -> u64 {
+	SipHasher13::new_with_key(&key.to_bytes()).hash(data.as_ref())
+}
+
+/// Hashes the data with SipHash-2-4.
+pub fn siphash24(key: &SipKey, data: impl AsRef<[u8]>) -> u64 {
+	SipHasher24::new_with_key(&key.to_bytes()).hash(data.as_ref())
+}
+
+/// Hashes the data with SipHash-2-4 and returns the little endian bytes
+/// as a token.
+pub fn siphash24_token(key: &SipKey, data: impl AsRef<[u8]>) -> Token<8> {
+	Token::from(siphash24(key, data).to_le_bytes())
+}
+
+/// Builds [`SipHasher13`] instances with the same key, for use with
+/// `HashMap` and `HashSet`.
+#[derive(Clone)]
+pub struct BuildSipHasher13 {
+	key: SipKey,
+}
+
+impl BuildSipHasher13 {
+	pub fn new(key: SipKey) -> Self {
+		Self { key }
+	}
+
+	/// Creates a builder with a random key.
+	pub fn random() -> Self {
+		Self::new(SipKey::new())
+	}
+}
+
+impl BuildHasher for BuildSipHasher13 {
+	type Hasher = SipHasher13;
+
+	fn build_hasher(&self) -> SipHasher13 {
+		SipHasher13::new_with_key(&self.key.to_bytes())
+	}
+}
+
+impl fmt::Debug for BuildSipHasher13 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("BuildSipHasher13")
+	}
+}
@@ -0,0 +1,64 @@
+use super::fixed_bytes;
+use super::Hash;
+
+use crate::error::TryFromError;
+
+use std::convert::TryInto;
+
+impl Hash {
+	/// Returns the first `N` bytes of the hash.
+	///
+	/// ## Panics
+	/// If `N` is 0 or larger than 64.
+	///
+	/// ## Example
+	/// ```
+	/// use chuchi_crypto::hash::{hash, TruncatedHash};
+	///
+	/// let short: TruncatedHash<16> = hash(b"data").truncate();
+	/// assert_eq!(short.as_ref(), &hash(b"data").to_bytes()[..16]);
+	/// ```
+	pub fn truncate<const N: usize>(&self) -> TruncatedHash<N> {
+		assert!(N > 0 && N <= 64, "truncated hash must be 1 to 64 bytes");
+
+		let mut bytes = [0u8; N];
+		bytes.copy_from_slice(&self.bytes[..N]);
+
+		TruncatedHash { bytes }
+	}
+}
+
+/// A [`Hash`](struct@Hash) truncated to the first `N` bytes, created with
+/// [`Hash::truncate`].
+///
+/// A truncated hash has at most `N * 4` bits of collision resistance, so
+/// `N` should not be smaller than 16 if collisions could be exploited.
+///
+/// Comparing two truncated hashes is done in constant time.
+#[derive(Clone)]
+pub struct TruncatedHash<const N: usize> {
+	bytes: [u8; N],
+}
+
+impl<const N: usize> TruncatedHash<N> {
+	pub const LEN: usize = N;
+
+	/// Returns an error if the slice is not `N` bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; N] {
+		self.bytes
+	}
+}
+
+impl<const N: usize> PartialEq for TruncatedHash<N> {
+	fn eq(&self, other: &Self) -> bool {
+		crate::ct::ct_eq(&self.bytes, &other.bytes)
+	}
+}
+
+impl<const N: usize> Eq for TruncatedHash<N> {}
+
+fixed_bytes!(TruncatedHash<const N: usize>);
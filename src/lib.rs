@@ -2,12 +2,17 @@
 #![allow(clippy::new_without_default)]
 
 use rand::rngs::OsRng;
-use rand::RngCore;
+use rand::{CryptoRng, RngCore};
+
+use error::RandomError;
 
 /// used internally when b64
 #[cfg(feature = "b64")]
 use std::str::FromStr;
 
+#[cfg(feature = "hex")]
+use error::DecodeError;
+
 #[cfg(feature = "cipher")]
 pub mod cipher;
 
@@ -17,10 +22,44 @@ pub mod signature;
 #[cfg(feature = "hash")]
 pub mod hash;
 
+#[cfg(feature = "otp")]
+pub mod otp;
+
+#[cfg(feature = "cookie")]
+pub mod cookie;
+
+#[cfg(feature = "chuchi")]
+pub mod extractor;
+
+#[cfg(feature = "webhook")]
+pub mod webhook;
+
+#[cfg(feature = "minisign")]
+pub mod minisign;
+
+#[cfg(feature = "sshsig")]
+pub mod sshsig;
+
+#[cfg(feature = "cose")]
+pub mod cose;
+
+#[cfg(feature = "webauthn")]
+pub mod webauthn;
+
 pub mod token;
 
+#[cfg(feature = "hex")]
+pub mod encoding;
+
+pub mod ct;
+
+pub mod checksum;
+
 pub mod error;
 
+#[cfg(feature = "serde")]
+mod serde_bytes;
+
 // from https://docs.rs/crate/chacha20/0.3.4/source/src/cipher.rs
 /// Xors two buffers. Both buffers need to have the same length.
 ///
@@ -35,10 +74,110 @@ pub fn xor(buf: &mut [u8], key: &[u8]) {
 }
 
 /// Fills a slice with random bytes.
+///
+/// ## Panics
+/// If the operating system cannot provide random bytes.
 pub fn fill_random(buf: &mut [u8]) {
 	OsRng.fill_bytes(buf)
 }
 
+/// Fills a slice with random bytes, returning an error if the operating
+/// system cannot provide them.
+pub fn try_fill_random(buf: &mut [u8]) -> Result<(), RandomError> {
+	OsRng.try_fill_bytes(buf).map_err(|_| RandomError::new())
+}
+
+/// An OsRng which does not panic but remembers if it failed.
+struct TryOsRng {
+	failed: bool,
+}
+
+impl RngCore for TryOsRng {
+	fn next_u32(&mut self) -> u32 {
+		let mut bytes = [0u8; 4];
+		self.fill_bytes(&mut bytes);
+		u32::from_le_bytes(bytes)
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		let mut bytes = [0u8; 8];
+		self.fill_bytes(&mut bytes);
+		u64::from_le_bytes(bytes)
+	}
+
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		if OsRng.try_fill_bytes(dest).is_err() {
+			self.failed = true;
+		}
+	}
+
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+		self.fill_bytes(dest);
+		Ok(())
+	}
+}
+
+impl CryptoRng for TryOsRng {}
+
+/// Calls `f` with an rng that does not panic, if the rng failed to get
+/// random bytes the value is discarded and an error returned.
+fn try_with_os_rng<T>(
+	f: impl FnOnce(&mut TryOsRng) -> T,
+) -> Result<T, RandomError> {
+	let mut rng = TryOsRng { failed: false };
+	let v = f(&mut rng);
+
+	if rng.failed {
+		Err(RandomError::new())
+	} else {
+		Ok(v)
+	}
+}
+
+/// Derives a 32 byte secret from a seed using HKDF-SHA256.
+///
+/// `kind` separates the secrets of different key types and `context` the
+/// secrets of the same type.
+///
+/// ## Panics
+/// If the seed is shorter than 32 bytes.
+#[cfg(any(feature = "cipher", feature = "signature"))]
+fn derive_from_seed(seed: &[u8], kind: &str, context: &str) -> [u8; 32] {
+	assert!(seed.len() >= 32, "seed too short");
+
+	let salt = format!("chuchi-crypto {kind}");
+	let hkdf = hkdf::Hkdf::<sha2::Sha256>::new(Some(salt.as_bytes()), seed);
+
+	let mut secret = [0u8; 32];
+	hkdf.expand(context.as_bytes(), &mut secret)
+		// 32 bytes is way below the max size of 255 * 32
+		.unwrap();
+
+	secret
+}
+
+/// Returns the current time.
+///
+/// `SystemTime::now` panics on wasm32 in the browser, so the time is read
+/// from javascript there.
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+fn now() -> std::time::SystemTime {
+	std::time::SystemTime::now()
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+fn now() -> std::time::SystemTime {
+	let millis = js_sys::Date::now() as u64;
+	std::time::UNIX_EPOCH + std::time::Duration::from_millis(millis)
+}
+
+/// Returns the seconds since the unix epoch, times before it return 0.
+pub(crate) fn unix_secs(time: std::time::SystemTime) -> u64 {
+	time.duration_since(std::time::UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or(0)
+}
+
 /// todo replace when rust #88582 get's stabilized
 ///
 /// Since this function multiplies s with 4
@@ -57,3 +196,156 @@ const fn calculate_b64_len(s: usize) -> usize {
 
 	d
 }
+
+/// Decodes a hex string which needs to be exactly `S` bytes long.
+#[cfg(feature = "hex")]
+fn decode_hex<const S: usize>(s: &str) -> Result<[u8; S], DecodeError> {
+	if s.len() != S * 2 {
+		return Err(DecodeError::InvalidLength);
+	}
+
+	let mut bytes = [0u8; S];
+	hex::decode_to_slice(s, &mut bytes).map_err(DecodeError::inv_bytes)?;
+
+	Ok(bytes)
+}
+
+#[cfg(feature = "hash")]
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[cfg(feature = "multihash")]
+const BASE32_ALPHABET_LOWER: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Encodes bytes as unpadded uppercase base32 (RFC 4648).
+#[cfg(feature = "hash")]
+fn base32_encode(bytes: &[u8]) -> String {
+	let mut out = String::with_capacity((bytes.len() * 8).div_ceil(5));
+	base32_encode_to(bytes, BASE32_ALPHABET, &mut out);
+
+	out
+}
+
+/// Encodes bytes as unpadded lowercase base32 (RFC 4648).
+#[cfg(feature = "multihash")]
+fn base32_encode_lower(bytes: &[u8]) -> String {
+	let mut out = String::with_capacity((bytes.len() * 8).div_ceil(5));
+	base32_encode_to(bytes, BASE32_ALPHABET_LOWER, &mut out);
+
+	out
+}
+
+/// Appends the bytes as unpadded base32 with the given alphabet.
+#[cfg(feature = "hash")]
+fn base32_encode_to(bytes: &[u8], alphabet: &[u8; 32], out: &mut String) {
+	let mut buffer = 0u16;
+	let mut bits = 0;
+
+	for &b in bytes {
+		buffer = (buffer << 8) | b as u16;
+		bits += 8;
+
+		while bits >= 5 {
+			bits -= 5;
+			out.push(alphabet[(buffer >> bits) as usize & 31] as char);
+		}
+	}
+
+	if bits > 0 {
+		out.push(alphabet[(buffer << (5 - bits)) as usize & 31] as char);
+	}
+}
+
+/// Decodes base32 (RFC 4648), lowercase letters, spaces and padding are
+/// accepted.
+#[cfg(feature = "hash")]
+fn base32_decode(s: &str) -> Result<Vec<u8>, error::DecodeError> {
+	let chars = s
+		.trim_end_matches('=')
+		.bytes()
+		.filter(|c| *c != b' ')
+		.map(|c| c.to_ascii_uppercase());
+
+	base32_decode_with(chars, BASE32_ALPHABET, false)
+}
+
+/// Decodes unpadded lowercase base32 strictly, padding, uppercase letters
+/// and non zero trailing bits are rejected so every value has exactly one
+/// encoding.
+#[cfg(feature = "multihash")]
+fn base32_decode_lower(s: &str) -> Result<Vec<u8>, error::DecodeError> {
+	base32_decode_with(s.bytes(), BASE32_ALPHABET_LOWER, true)
+}
+
+#[cfg(feature = "hash")]
+fn base32_decode_with(
+	chars: impl Iterator<Item = u8>,
+	alphabet: &[u8; 32],
+	strict: bool,
+) -> Result<Vec<u8>, error::DecodeError> {
+	let (len, _) = chars.size_hint();
+	let mut out = Vec::with_capacity(len * 5 / 8);
+	let mut buffer = 0u16;
+	let mut bits = 0;
+
+	for c in chars {
+		let v = alphabet
+			.iter()
+			.position(|a| *a == c)
+			.ok_or(error::DecodeError::InvalidBytes)?;
+		buffer = (buffer << 5) | v as u16;
+		bits += 5;
+
+		if bits >= 8 {
+			bits -= 8;
+			out.push((buffer >> bits) as u8);
+		}
+	}
+
+	// the remaining bits are padding and need to be zero
+	if strict && (bits >= 5 || buffer & ((1 << bits) - 1) != 0) {
+		return Err(error::DecodeError::InvalidBytes);
+	}
+
+	Ok(out)
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+	use wasm_bindgen_test::wasm_bindgen_test;
+
+	#[wasm_bindgen_test]
+	fn random() {
+		let mut buf = [0u8; 32];
+		super::try_fill_random(&mut buf).unwrap();
+		assert_ne!(buf, [0u8; 32]);
+	}
+
+	#[wasm_bindgen_test]
+	fn now() {
+		let now = super::now();
+		assert!(now > std::time::UNIX_EPOCH);
+	}
+
+	#[cfg(feature = "cipher")]
+	#[wasm_bindgen_test]
+	fn cipher_keypair() {
+		use crate::cipher::Keypair;
+
+		let alice = Keypair::new();
+		let bob = Keypair::new();
+
+		let alice_ssk = alice.diffie_hellman(bob.public());
+		let bob_ssk = bob.diffie_hellman(alice.public());
+		assert_eq!(alice_ssk, bob_ssk);
+	}
+
+	#[cfg(feature = "signature")]
+	#[wasm_bindgen_test]
+	fn signature_keypair() {
+		use crate::signature::Keypair;
+
+		let key = Keypair::new();
+		let sig = key.sign(b"message");
+		assert!(key.public().verify(b"message", &sig));
+	}
+}
@@ -0,0 +1,731 @@
+//! Reading and writing keys and signatures in the
+//! [minisign](https://jedisct1.github.io/minisign/) format.
+//!
+//! Signatures are always created with the prehashed `ED` algorithm, legacy
+//! `Ed` signatures can still be verified. Secret keys are encrypted with
+//! scrypt like the minisign binary does.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::minisign::{PublicKey, SecretKey, Signature};
+//!
+//! let secret = SecretKey::new();
+//! let public = secret.public_key();
+//!
+//! let sig = secret.sign(b"release.tar.gz", "file:release.tar.gz");
+//! let sig = Signature::decode(&sig.encode()).unwrap();
+//!
+//! let public = PublicKey::decode(&public.encode()).unwrap();
+//! assert!(public.verify(b"release.tar.gz", &sig));
+//! assert_eq!(sig.trusted_comment(), "file:release.tar.gz");
+//! ```
+
+use crate::error::MinisignError;
+use crate::signature::{self, Keypair};
+
+use std::fmt;
+use std::io;
+
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Blake2b512, Digest};
+
+use base64::engine::{general_purpose::STANDARD, Engine};
+
+use zeroize::Zeroize;
+
+const ALG_LEGACY: [u8; 2] = *b"Ed";
+const ALG_PREHASHED: [u8; 2] = *b"ED";
+const KDF_NONE: [u8; 2] = [0, 0];
+const KDF_SCRYPT: [u8; 2] = *b"Sc";
+const CHK_BLAKE2B: [u8; 2] = *b"B2";
+
+/// The limits libsodium calls sensitive, which minisign uses.
+const OPSLIMIT: u64 = 33_554_432;
+const MEMLIMIT: u64 = 1_073_741_824;
+
+const UNTRUSTED_PREFIX: &str = "untrusted comment: ";
+const TRUSTED_PREFIX: &str = "trusted comment: ";
+
+/// The random id which links a signature to its key.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyId([u8; 8]);
+
+impl KeyId {
+	pub const LEN: usize = 8;
+
+	/// Creates a new random key id.
+	///
+	/// ## Panics
+	/// If the OsRng fails to return random bytes.
+	pub fn new() -> Self {
+		let mut bytes = [0u8; Self::LEN];
+		crate::fill_random(&mut bytes);
+
+		Self(bytes)
+	}
+
+	pub fn to_bytes(&self) -> [u8; 8] {
+		self.0
+	}
+}
+
+impl From<[u8; 8]> for KeyId {
+	fn from(bytes: [u8; 8]) -> Self {
+		Self(bytes)
+	}
+}
+
+/// Formats the id like minisign does, as an uppercase hex number.
+impl fmt::Display for KeyId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:016X}", u64::from_le_bytes(self.0))
+	}
+}
+
+impl fmt::Debug for KeyId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("KeyId")
+			.field(&format_args!("{self}"))
+			.finish()
+	}
+}
+
+/// A minisign public key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+	key_id: KeyId,
+	key: signature::PublicKey,
+}
+
+impl PublicKey {
+	pub fn new(key_id: KeyId, key: signature::PublicKey) -> Self {
+		Self { key_id, key }
+	}
+
+	pub fn key_id(&self) -> KeyId {
+		self.key_id
+	}
+
+	pub fn key(&self) -> &signature::PublicKey {
+		&self.key
+	}
+
+	/// Returns the single line printed by `minisign -R`.
+	pub fn to_base64(&self) -> String {
+		let mut bytes = Vec::with_capacity(42);
+		bytes.extend_from_slice(&ALG_LEGACY);
+		bytes.extend_from_slice(&self.key_id.0);
+		bytes.extend_from_slice(&self.key.to_bytes());
+
+		STANDARD.encode(bytes)
+	}
+
+	pub fn from_base64(s: &str) -> Result<Self, MinisignError> {
+		let bytes = decode_b64(s)?;
+		if bytes.len() != 42 {
+			return Err(MinisignError::InvalidFormat);
+		}
+		if bytes[..2] != ALG_LEGACY {
+			return Err(MinisignError::UnsupportedAlgorithm);
+		}
+
+		Ok(Self {
+			key_id: key_id(&bytes[2..10]),
+			key: signature::PublicKey::try_from_slice(&bytes[10..])
+				.map_err(|_| MinisignError::InvalidFormat)?,
+		})
+	}
+
+	/// Returns the content of a `minisign.pub` file.
+	pub fn encode(&self) -> String {
+		format!(
+			"{UNTRUSTED_PREFIX}minisign public key {}\n{}\n",
+			self.key_id,
+			self.to_base64()
+		)
+	}
+
+	/// Decodes the content of a `minisign.pub` file, the comment line is
+	/// optional.
+	pub fn decode(s: &str) -> Result<Self, MinisignError> {
+		let mut lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
+		let mut line = lines.next().ok_or(MinisignError::InvalidFormat)?;
+		if line.starts_with(UNTRUSTED_PREFIX) {
+			line = lines.next().ok_or(MinisignError::InvalidFormat)?;
+		}
+
+		Self::from_base64(line)
+	}
+
+	/// Verifies the signature and the trusted comment.
+	///
+	/// Returns false if the signature was created by another key.
+	pub fn verify(&self, msg: impl AsRef<[u8]>, sig: &Signature) -> bool {
+		let msg = msg.as_ref();
+
+		if sig.prehashed {
+			self.verify_inner(&Blake2b512::digest(msg), sig)
+		} else {
+			self.verify_inner(msg, sig)
+		}
+	}
+
+	/// Like `verify` but reads the message from a reader.
+	///
+	/// For legacy signatures the whole message needs to be read into
+	/// memory.
+	pub fn verify_reader<R: io::Read + ?Sized>(
+		&self,
+		reader: &mut R,
+		sig: &Signature,
+	) -> io::Result<bool> {
+		if sig.prehashed {
+			let hash = blake2b512_reader(reader)?;
+			Ok(self.verify_inner(&hash, sig))
+		} else {
+			let mut msg = vec![];
+			reader.read_to_end(&mut msg)?;
+			Ok(self.verify_inner(&msg, sig))
+		}
+	}
+
+	fn verify_inner(&self, msg: &[u8], sig: &Signature) -> bool {
+		sig.key_id == self.key_id
+			&& self.key.verify(msg, &sig.signature)
+			&& self.key.verify(
+				global_message(&sig.signature, &sig.trusted_comment),
+				&sig.global_signature,
+			)
+	}
+}
+
+/// A minisign secret key.
+pub struct SecretKey {
+	key_id: KeyId,
+	keypair: Keypair,
+}
+
+impl SecretKey {
+	/// Creates a new random key.
+	///
+	/// ## Panics
+	/// If the OsRng fails to return random bytes.
+	pub fn new() -> Self {
+		Self::from_keypair(KeyId::new(), Keypair::new())
+	}
+
+	pub fn from_keypair(key_id: KeyId, keypair: Keypair) -> Self {
+		Self { key_id, keypair }
+	}
+
+	pub fn key_id(&self) -> KeyId {
+		self.key_id
+	}
+
+	pub fn keypair(&self) -> &Keypair {
+		&self.keypair
+	}
+
+	pub fn public_key(&self) -> PublicKey {
+		PublicKey::new(self.key_id, self.keypair.public().clone())
+	}
+
+	/// Signs the message with the prehashed `ED` algorithm.
+	///
+	/// ## Panics
+	/// If the trusted comment contains a newline.
+	pub fn sign(
+		&self,
+		msg: impl AsRef<[u8]>,
+		trusted_comment: &str,
+	) -> Signature {
+		self.sign_hash(&Blake2b512::digest(msg.as_ref()), trusted_comment)
+	}
+
+	/// Like `sign` but reads the message from a reader.
+	///
+	/// ## Panics
+	/// If the trusted comment contains a newline.
+	pub fn sign_reader<R: io::Read + ?Sized>(
+		&self,
+		reader: &mut R,
+		trusted_comment: &str,
+	) -> io::Result<Signature> {
+		let hash = blake2b512_reader(reader)?;
+		Ok(self.sign_hash(&hash, trusted_comment))
+	}
+
+	fn sign_hash(&self, hash: &[u8], trusted_comment: &str) -> Signature {
+		assert_no_newline(trusted_comment);
+
+		let signature = self.keypair.sign(hash);
+		let global = global_message(&signature, trusted_comment);
+
+		Signature {
+			prehashed: true,
+			key_id: self.key_id,
+			signature,
+			untrusted_comment: "signature from minisign secret key".into(),
+			trusted_comment: trusted_comment.into(),
+			global_signature: self.keypair.sign(global),
+		}
+	}
+
+	/// Returns the content of a `minisign.key` file.
+	///
+	/// If a password is given the key is encrypted with the same scrypt
+	/// parameters as minisign uses, which needs 1 GiB of memory.
+	pub fn encode(&self, password: Option<&str>) -> String {
+		// the limits are valid
+		self.encode_with(password, OPSLIMIT, MEMLIMIT).unwrap()
+	}
+
+	fn encode_with(
+		&self,
+		password: Option<&str>,
+		opslimit: u64,
+		memlimit: u64,
+	) -> Result<String, MinisignError> {
+		let mut bytes = Vec::with_capacity(158);
+		bytes.extend_from_slice(&ALG_LEGACY);
+
+		let mut salt = [0u8; 32];
+		let (kdf, opslimit, memlimit) = match password {
+			Some(_) => {
+				crate::fill_random(&mut salt);
+				(KDF_SCRYPT, opslimit, memlimit)
+			}
+			None => (KDF_NONE, 0, 0),
+		};
+		bytes.extend_from_slice(&kdf);
+		bytes.extend_from_slice(&CHK_BLAKE2B);
+		bytes.extend_from_slice(&salt);
+		bytes.extend_from_slice(&opslimit.to_le_bytes());
+		bytes.extend_from_slice(&memlimit.to_le_bytes());
+
+		let secret_start = bytes.len();
+		bytes.extend_from_slice(&self.key_id.0);
+		let mut secret = self.keypair.to_bytes();
+		bytes.extend_from_slice(&secret);
+		secret.zeroize();
+		bytes.extend_from_slice(&self.keypair.public().to_bytes());
+		bytes.extend_from_slice(&checksum(&bytes[secret_start..]));
+
+		if let Some(password) = password {
+			let r = scrypt_xor(
+				password,
+				&salt,
+				opslimit,
+				memlimit,
+				&mut bytes[secret_start..],
+			);
+			if let Err(e) = r {
+				bytes.zeroize();
+				return Err(e);
+			}
+		}
+
+		let s = format!(
+			"{UNTRUSTED_PREFIX}minisign secret key\n{}\n",
+			STANDARD.encode(&bytes)
+		);
+		bytes.zeroize();
+
+		Ok(s)
+	}
+
+	/// Decodes the content of a `minisign.key` file.
+	///
+	/// Returns `MinisignError::InvalidPassword` if the key is encrypted and
+	/// the password is missing or wrong.
+	pub fn decode(
+		s: &str,
+		password: Option<&str>,
+	) -> Result<Self, MinisignError> {
+		let mut lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
+		let mut line = lines.next().ok_or(MinisignError::InvalidFormat)?;
+		if line.starts_with(UNTRUSTED_PREFIX) {
+			line = lines.next().ok_or(MinisignError::InvalidFormat)?;
+		}
+
+		let mut bytes = decode_b64(line)?;
+		let r = Self::decode_bytes(&mut bytes, password);
+		bytes.zeroize();
+
+		r
+	}
+
+	fn decode_bytes(
+		bytes: &mut [u8],
+		password: Option<&str>,
+	) -> Result<Self, MinisignError> {
+		if bytes.len() != 158 {
+			return Err(MinisignError::InvalidFormat);
+		}
+		if bytes[..2] != ALG_LEGACY || bytes[4..6] != CHK_BLAKE2B {
+			return Err(MinisignError::UnsupportedAlgorithm);
+		}
+
+		let (header, secret) = bytes.split_at_mut(54);
+		match [header[2], header[3]] {
+			KDF_NONE => {}
+			KDF_SCRYPT => {
+				let password =
+					password.ok_or(MinisignError::InvalidPassword)?;
+				let opslimit = u64_le(&header[38..46]);
+				let memlimit = u64_le(&header[46..54]);
+				scrypt_xor(
+					password,
+					&header[6..38],
+					opslimit,
+					memlimit,
+					secret,
+				)?;
+			}
+			_ => return Err(MinisignError::UnsupportedAlgorithm),
+		}
+
+		let (secret, chk) = secret.split_at(72);
+		let mut expected = checksum(secret);
+		let valid = subtle::ConstantTimeEq::ct_eq(&expected[..], chk);
+		expected.zeroize();
+		if !bool::from(valid) {
+			return Err(MinisignError::InvalidPassword);
+		}
+
+		let keypair = Keypair::try_from_slice(&secret[8..40])
+			.map_err(|_| MinisignError::InvalidFormat)?;
+		if keypair.public().to_bytes()[..] != secret[40..] {
+			return Err(MinisignError::InvalidFormat);
+		}
+
+		Ok(Self {
+			key_id: key_id(&secret[..8]),
+			keypair,
+		})
+	}
+}
+
+impl fmt::Debug for SecretKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SecretKey")
+			.field("key_id", &self.key_id)
+			.finish()
+	}
+}
+
+/// A minisign signature together with its comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+	prehashed: bool,
+	key_id: KeyId,
+	signature: signature::Signature,
+	untrusted_comment: String,
+	trusted_comment: String,
+	global_signature: signature::Signature,
+}
+
+impl Signature {
+	pub fn key_id(&self) -> KeyId {
+		self.key_id
+	}
+
+	/// Returns true if the message was hashed with BLAKE2b-512 before
+	/// signing, which is the case for every signature created by this
+	/// crate.
+	pub fn is_prehashed(&self) -> bool {
+		self.prehashed
+	}
+
+	/// The comment which is not covered by the signature.
+	pub fn untrusted_comment(&self) -> &str {
+		&self.untrusted_comment
+	}
+
+	/// ## Panics
+	/// If the comment contains a newline.
+	pub fn set_untrusted_comment(&mut self, comment: impl Into<String>) {
+		let comment = comment.into();
+		assert_no_newline(&comment);
+		self.untrusted_comment = comment;
+	}
+
+	/// The comment which is covered by the signature.
+	pub fn trusted_comment(&self) -> &str {
+		&self.trusted_comment
+	}
+
+	/// Returns the content of a `.minisig` file.
+	pub fn encode(&self) -> String {
+		let alg = if self.prehashed {
+			ALG_PREHASHED
+		} else {
+			ALG_LEGACY
+		};
+
+		let mut bytes = Vec::with_capacity(74);
+		bytes.extend_from_slice(&alg);
+		bytes.extend_from_slice(&self.key_id.0);
+		bytes.extend_from_slice(&self.signature.to_bytes());
+
+		format!(
+			"{UNTRUSTED_PREFIX}{}\n{}\n{TRUSTED_PREFIX}{}\n{}\n",
+			self.untrusted_comment,
+			STANDARD.encode(bytes),
+			self.trusted_comment,
+			STANDARD.encode(self.global_signature.to_bytes())
+		)
+	}
+
+	/// Decodes the content of a `.minisig` file.
+	pub fn decode(s: &str) -> Result<Self, MinisignError> {
+		let mut lines = s.lines().map(|l| l.trim_end_matches('\r'));
+		let mut next = || lines.next().ok_or(MinisignError::InvalidFormat);
+
+		let untrusted_comment = next()?
+			.strip_prefix(UNTRUSTED_PREFIX)
+			.ok_or(MinisignError::InvalidFormat)?;
+		let bytes = decode_b64(next()?)?;
+		let trusted_comment = next()?
+			.strip_prefix(TRUSTED_PREFIX)
+			.ok_or(MinisignError::InvalidFormat)?;
+		let global = decode_b64(next()?)?;
+
+		if bytes.len() != 74 {
+			return Err(MinisignError::InvalidFormat);
+		}
+		let prehashed = match [bytes[0], bytes[1]] {
+			ALG_PREHASHED => true,
+			ALG_LEGACY => false,
+			_ => return Err(MinisignError::UnsupportedAlgorithm),
+		};
+
+		Ok(Self {
+			prehashed,
+			key_id: key_id(&bytes[2..10]),
+			signature: signature::Signature::try_from_slice(&bytes[10..])
+				.map_err(|_| MinisignError::InvalidFormat)?,
+			untrusted_comment: untrusted_comment.into(),
+			trusted_comment: trusted_comment.into(),
+			global_signature: signature::Signature::try_from_slice(&global)
+				.map_err(|_| MinisignError::InvalidFormat)?,
+		})
+	}
+}
+
+/// The global signature covers the signature and the trusted comment.
+fn global_message(
+	signature: &signature::Signature,
+	trusted_comment: &str,
+) -> Vec<u8> {
+	let mut msg = signature.to_bytes().to_vec();
+	msg.extend_from_slice(trusted_comment.as_bytes());
+
+	msg
+}
+
+fn assert_no_newline(comment: &str) {
+	assert!(
+		!comment.contains(['\r', '\n']),
+		"comment contains a newline"
+	);
+}
+
+fn decode_b64(s: &str) -> Result<Vec<u8>, MinisignError> {
+	STANDARD
+		.decode(s.trim())
+		.map_err(|_| MinisignError::InvalidFormat)
+}
+
+fn key_id(bytes: &[u8]) -> KeyId {
+	// all callers pass 8 bytes
+	KeyId(bytes.try_into().unwrap())
+}
+
+fn u64_le(bytes: &[u8]) -> u64 {
+	// all callers pass 8 bytes
+	u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+fn checksum(secret: &[u8]) -> [u8; 32] {
+	let mut hasher = Blake2b::<U32>::new();
+	hasher.update(ALG_LEGACY);
+	hasher.update(secret);
+
+	hasher.finalize().into()
+}
+
+fn blake2b512_reader<R: io::Read + ?Sized>(
+	reader: &mut R,
+) -> io::Result<[u8; 64]> {
+	let mut hasher = Blake2b512::new();
+	let mut buf = [0u8; 8192];
+	loop {
+		match reader.read(&mut buf) {
+			Ok(0) => break,
+			Ok(n) => hasher.update(&buf[..n]),
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+			Err(e) => return Err(e),
+		}
+	}
+
+	Ok(hasher.finalize().into())
+}
+
+/// Xors the secret with an scrypt stream, converting the limits to scrypt
+/// parameters like libsodium does.
+fn scrypt_xor(
+	password: &str,
+	salt: &[u8],
+	opslimit: u64,
+	memlimit: u64,
+	secret: &mut [u8],
+) -> Result<(), MinisignError> {
+	// don't allow a key file to use more resources than minisign does
+	if opslimit > OPSLIMIT || memlimit > MEMLIMIT {
+		return Err(MinisignError::InvalidParams);
+	}
+
+	let opslimit = opslimit.max(32_768);
+	let r = 8u64;
+	let (log_n, p) = if opslimit < memlimit / 32 {
+		(max_log_n(opslimit / (r * 4)), 1)
+	} else {
+		let log_n = max_log_n(memlimit / (r * 128));
+		let max_rp = ((opslimit / 4) >> log_n).min(0x3fff_ffff);
+		(log_n, max_rp / r)
+	};
+
+	let params = scrypt::Params::new(log_n, r as u32, p as u32, 64)
+		.map_err(|_| MinisignError::InvalidParams)?;
+
+	let mut stream = vec![0u8; secret.len()];
+	scrypt::scrypt(password.as_bytes(), salt, &params, &mut stream)
+		.map_err(|_| MinisignError::InvalidParams)?;
+	crate::xor(secret, &stream);
+	stream.zeroize();
+
+	Ok(())
+}
+
+fn max_log_n(max_n: u64) -> u8 {
+	let mut log_n = 1;
+	while log_n < 63 && (1u64 << log_n) <= max_n / 2 {
+		log_n += 1;
+	}
+
+	log_n
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_sign_verify() {
+		let secret = SecretKey::new();
+		let public = secret.public_key();
+		let msg = b"chuchi-crypto 0.2.0";
+
+		let sig = secret.sign(msg, "timestamp:1700000000\tfile:release");
+		assert!(sig.is_prehashed());
+		assert!(public.verify(msg, &sig));
+		assert!(!public.verify(b"chuchi-crypto 0.2.1", &sig));
+		assert!(public.verify_reader(&mut &msg[..], &sig).unwrap());
+
+		let sig_2 = secret.sign_reader(&mut &msg[..], "a").unwrap();
+		assert!(public.verify(msg, &sig_2));
+
+		// the trusted comment can't be modified
+		let modified = sig.encode().replace("file:release", "file:evil");
+		let modified = Signature::decode(&modified).unwrap();
+		assert!(!public.verify(msg, &modified));
+
+		// the untrusted comment can
+		let mut sig = sig;
+		sig.set_untrusted_comment("anything");
+		let decoded = Signature::decode(&sig.encode()).unwrap();
+		assert_eq!(decoded, sig);
+		assert!(public.verify(msg, &decoded));
+
+		let other = SecretKey::from_keypair(secret.key_id(), Keypair::new());
+		assert!(!other.public_key().verify(msg, &sig));
+	}
+
+	#[test]
+	fn test_legacy_signature() {
+		// from the minisign-verify crate
+		let public = PublicKey::from_base64(
+			"RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3",
+		)
+		.unwrap();
+		let sig = Signature::decode(
+			"untrusted comment: signature from minisign secret key\n\
+			RWQf6LRCGA9i59SLOFxz6NxvASXDJeRtuZykwQepbDEGt87ig1BNpWaVWuNrm73YiIiJbq71Wi+dP9eKL8OC351vwIasSSbXxwA=\n\
+			trusted comment: timestamp:1555779966\tfile:test\n\
+			QtKMXWyYcwdpZAlPF7tE2ENJkRd1ujvKjlj1m9RtHTBnZPa5WKU5uWRs5GoP5M/VqE81QFuMKI5k/SfNQUaOAA==\n",
+		)
+		.unwrap();
+
+		assert!(!sig.is_prehashed());
+		assert_eq!(public.key_id().to_string(), "E7620F1842B4E81F");
+		assert!(public.verify(b"test", &sig));
+		assert!(!public.verify(b"tesT", &sig));
+	}
+
+	#[test]
+	fn test_public_key() {
+		let public = SecretKey::new().public_key();
+
+		let file = public.encode();
+		assert!(file.starts_with(&format!(
+			"untrusted comment: minisign public key {}\n",
+			public.key_id()
+		)));
+		assert_eq!(PublicKey::decode(&file).unwrap(), public);
+		assert_eq!(PublicKey::decode(&public.to_base64()).unwrap(), public);
+		assert_eq!(
+			PublicKey::decode("untrusted comment: \nRWQf").unwrap_err(),
+			MinisignError::InvalidFormat
+		);
+	}
+
+	#[test]
+	fn test_secret_key() {
+		let secret = SecretKey::new();
+
+		let file = secret.encode(None);
+		let decoded = SecretKey::decode(&file, None).unwrap();
+		assert_eq!(decoded.key_id(), secret.key_id());
+		assert_eq!(decoded.keypair().to_bytes(), secret.keypair().to_bytes());
+
+		// small limits to keep the test fast
+		let file = secret
+			.encode_with(Some("password"), 32_768, 16 * 1024 * 1024)
+			.unwrap();
+		let decoded = SecretKey::decode(&file, Some("password")).unwrap();
+		assert_eq!(decoded.keypair().to_bytes(), secret.keypair().to_bytes());
+
+		assert_eq!(
+			SecretKey::decode(&file, Some("wrong")).unwrap_err(),
+			MinisignError::InvalidPassword
+		);
+		assert_eq!(
+			SecretKey::decode(&file, None).unwrap_err(),
+			MinisignError::InvalidPassword
+		);
+		assert_eq!(
+			secret
+				.encode_with(Some("password"), OPSLIMIT, MEMLIMIT * 2)
+				.unwrap_err(),
+			MinisignError::InvalidParams
+		);
+	}
+
+	#[test]
+	fn test_scrypt_params() {
+		// the limits minisign uses result in N = 2^20, r = 8, p = 1
+		assert_eq!(max_log_n(MEMLIMIT / (8 * 128)), 20);
+		assert_eq!(((OPSLIMIT / 4) >> 20) / 8, 1);
+	}
+}
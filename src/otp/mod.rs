@@ -0,0 +1,521 @@
+//! One time passwords as used by authenticator apps.
+//!
+//! Implements HOTP ([RFC 4226](https://www.rfc-editor.org/rfc/rfc4226))
+//! and TOTP ([RFC 6238](https://www.rfc-editor.org/rfc/rfc6238)).
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::otp::{Secret, Totp};
+//!
+//! let totp = Totp::new(Secret::new());
+//! // show this as a qr code to the user
+//! let uri = totp.to_uri("Chuchi", "user@example.com");
+//! assert!(uri.starts_with("otpauth://totp/Chuchi:user%40example.com?"));
+//!
+//! let code = totp.generate();
+//! assert!(totp.verify(&code));
+//! ```
+
+use crate::error::{DecodeError, RandomError};
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+
+use subtle::ConstantTimeEq;
+
+/// The hash function used by the HMAC.
+///
+/// Most authenticator apps only support `Sha1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+	#[default]
+	Sha1,
+	Sha256,
+	Sha512,
+}
+
+impl Algorithm {
+	fn as_str(&self) -> &'static str {
+		match self {
+			Self::Sha1 => "SHA1",
+			Self::Sha256 => "SHA256",
+			Self::Sha512 => "SHA512",
+		}
+	}
+
+	fn hmac(&self, key: &[u8], msg: &[u8]) -> Vec<u8> {
+		// hmac accepts keys of any length
+		match self {
+			Self::Sha1 => {
+				let mut mac = Hmac::<sha1::Sha1>::new_from_slice(key).unwrap();
+				mac.update(msg);
+				mac.finalize().into_bytes().to_vec()
+			}
+			Self::Sha256 => {
+				let mut mac =
+					Hmac::<sha2::Sha256>::new_from_slice(key).unwrap();
+				mac.update(msg);
+				mac.finalize().into_bytes().to_vec()
+			}
+			Self::Sha512 => {
+				let mut mac =
+					Hmac::<sha2::Sha512>::new_from_slice(key).unwrap();
+				mac.update(msg);
+				mac.finalize().into_bytes().to_vec()
+			}
+		}
+	}
+}
+
+/// The parameters of a one time password.
+///
+/// The defaults are the ones every authenticator app supports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+	pub algorithm: Algorithm,
+	/// The amount of digits of a code, between 6 and 9
+	pub digits: u32,
+	/// The duration in seconds a TOTP code is valid
+	pub period: u64,
+	/// How many periods before and after the current one are accepted
+	/// by TOTP
+	pub skew: u64,
+}
+
+impl Params {
+	fn assert_valid(&self) {
+		assert!((6..=9).contains(&self.digits), "digits must be 6..=9");
+		assert!(self.period > 0, "period cannot be zero");
+	}
+}
+
+impl Default for Params {
+	fn default() -> Self {
+		Self {
+			algorithm: Algorithm::Sha1,
+			digits: 6,
+			period: 30,
+			skew: 1,
+		}
+	}
+}
+
+/// The shared secret of a one time password.
+///
+/// Comparing two secrets is done in constant time.
+#[derive(Clone)]
+pub struct Secret {
+	bytes: Vec<u8>,
+}
+
+impl Secret {
+	/// The length of a generated secret, as recommended by RFC 4226.
+	pub const LEN: usize = 20;
+
+	/// Creates a new random secret.
+	///
+	/// ## Panics
+	/// If the OsRng fails to return random bytes.
+	pub fn new() -> Self {
+		let mut bytes = vec![0; Self::LEN];
+		crate::fill_random(&mut bytes);
+
+		Self { bytes }
+	}
+
+	/// Creates a new random secret, returning an error if the OsRng fails.
+	pub fn try_new() -> Result<Self, RandomError> {
+		let mut bytes = vec![0; Self::LEN];
+		crate::try_fill_random(&mut bytes)?;
+
+		Ok(Self { bytes })
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Self {
+		Self {
+			bytes: bytes.to_vec(),
+		}
+	}
+
+	/// Returns the secret as an unpadded uppercase base32 string, the
+	/// format authenticator apps expect.
+	pub fn to_base32(&self) -> String {
+		crate::base32_encode(&self.bytes)
+	}
+
+	/// Parses a base32 string, lowercase letters, spaces and padding are
+	/// accepted.
+	pub fn from_base32(s: &str) -> Result<Self, DecodeError> {
+		crate::base32_decode(s).map(|bytes| Self { bytes })
+	}
+}
+
+impl fmt::Debug for Secret {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Secret")
+	}
+}
+
+impl PartialEq for Secret {
+	fn eq(&self, other: &Self) -> bool {
+		self.bytes.ct_eq(&other.bytes).into()
+	}
+}
+
+impl Eq for Secret {}
+
+impl AsRef<[u8]> for Secret {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Secret {
+	fn zeroize(&mut self) {
+		self.bytes.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Secret {
+	fn drop(&mut self) {
+		zeroize::Zeroize::zeroize(self);
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Secret {}
+
+/// A counter based one time password (HOTP).
+#[derive(Debug, Clone)]
+pub struct Hotp {
+	secret: Secret,
+	params: Params,
+}
+
+impl Hotp {
+	/// Creates a HOTP with the default parameters.
+	pub fn new(secret: Secret) -> Self {
+		Self::with_params(secret, Params::default())
+	}
+
+	/// ## Panics
+	/// if the digits are not between 6 and 9 or the period is zero.
+	pub fn with_params(secret: Secret, params: Params) -> Self {
+		params.assert_valid();
+
+		Self { secret, params }
+	}
+
+	pub fn secret(&self) -> &Secret {
+		&self.secret
+	}
+
+	pub fn params(&self) -> &Params {
+		&self.params
+	}
+
+	/// Generates the code for a counter.
+	pub fn generate(&self, counter: u64) -> String {
+		generate(&self.secret, &self.params, counter)
+	}
+
+	/// Verifies the code for a counter in constant time.
+	pub fn verify(&self, code: &str, counter: u64) -> bool {
+		ct_eq_code(&self.generate(counter), code)
+	}
+
+	/// Verifies the code against the counters `counter..=counter +
+	/// look_ahead`, returning the counter that matched.
+	///
+	/// The stored counter should then be set to the returned value plus
+	/// one.
+	pub fn verify_window(
+		&self,
+		code: &str,
+		counter: u64,
+		look_ahead: u64,
+	) -> Option<u64> {
+		find_counter(
+			&self.secret,
+			&self.params,
+			code,
+			counter..=counter.saturating_add(look_ahead),
+		)
+	}
+
+	/// Returns an `otpauth://hotp/` uri which can be shown as a qr code.
+	pub fn to_uri(&self, issuer: &str, account: &str, counter: u64) -> String {
+		let mut uri = uri("hotp", &self.secret, &self.params, issuer, account);
+		uri.push_str(&format!("&counter={counter}"));
+
+		uri
+	}
+}
+
+/// A time based one time password (TOTP).
+#[derive(Debug, Clone)]
+pub struct Totp {
+	secret: Secret,
+	params: Params,
+}
+
+impl Totp {
+	/// Creates a TOTP with the default parameters.
+	pub fn new(secret: Secret) -> Self {
+		Self::with_params(secret, Params::default())
+	}
+
+	/// ## Panics
+	/// if the digits are not between 6 and 9 or the period is zero.
+	pub fn with_params(secret: Secret, params: Params) -> Self {
+		params.assert_valid();
+
+		Self { secret, params }
+	}
+
+	pub fn secret(&self) -> &Secret {
+		&self.secret
+	}
+
+	pub fn params(&self) -> &Params {
+		&self.params
+	}
+
+	/// Returns the time step of a given time.
+	pub fn step_at(&self, time: SystemTime) -> u64 {
+		let secs = time
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_secs())
+			.unwrap_or(0);
+
+		secs / self.params.period
+	}
+
+	/// Generates the current code.
+	pub fn generate(&self) -> String {
+		self.generate_at(crate::now())
+	}
+
+	pub fn generate_at(&self, time: SystemTime) -> String {
+		generate(&self.secret, &self.params, self.step_at(time))
+	}
+
+	/// Verifies the code in constant time, allowing for `skew` periods
+	/// of clock drift.
+	pub fn verify(&self, code: &str) -> bool {
+		self.verify_at(code, crate::now())
+	}
+
+	pub fn verify_at(&self, code: &str, time: SystemTime) -> bool {
+		self.verify_step_at(code, time).is_some()
+	}
+
+	/// Verifies the code and returns the time step that matched.
+	///
+	/// To prevent a code from being used twice, store the step and reject
+	/// steps which are not greater than the stored one.
+	pub fn verify_step_at(&self, code: &str, time: SystemTime) -> Option<u64> {
+		let step = self.step_at(time);
+		let skew = self.params.skew;
+
+		find_counter(
+			&self.secret,
+			&self.params,
+			code,
+			step.saturating_sub(skew)..=step.saturating_add(skew),
+		)
+	}
+
+	/// Returns an `otpauth://totp/` uri which can be shown as a qr code.
+	pub fn to_uri(&self, issuer: &str, account: &str) -> String {
+		let mut uri = uri("totp", &self.secret, &self.params, issuer, account);
+		uri.push_str(&format!("&period={}", self.params.period));
+
+		uri
+	}
+}
+
+fn generate(secret: &Secret, params: &Params, counter: u64) -> String {
+	let hash = params.algorithm.hmac(&secret.bytes, &counter.to_be_bytes());
+
+	// dynamic truncation
+	let offset = (hash[hash.len() - 1] & 0xf) as usize;
+	let bin = u32::from_be_bytes([
+		hash[offset] & 0x7f,
+		hash[offset + 1],
+		hash[offset + 2],
+		hash[offset + 3],
+	]);
+
+	let code = bin % 10u32.pow(params.digits);
+	format!("{:0width$}", code, width = params.digits as usize)
+}
+
+/// Checks every counter so the time does not depend on which one matched.
+fn find_counter(
+	secret: &Secret,
+	params: &Params,
+	code: &str,
+	counters: std::ops::RangeInclusive<u64>,
+) -> Option<u64> {
+	let mut found = None;
+	for counter in counters {
+		if ct_eq_code(&generate(secret, params, counter), code)
+			&& found.is_none()
+		{
+			found = Some(counter);
+		}
+	}
+
+	found
+}
+
+fn ct_eq_code(expected: &str, code: &str) -> bool {
+	expected.as_bytes().ct_eq(code.as_bytes()).into()
+}
+
+fn uri(
+	kind: &str,
+	secret: &Secret,
+	params: &Params,
+	issuer: &str,
+	account: &str,
+) -> String {
+	let issuer = percent_encode(issuer);
+
+	format!(
+		"otpauth://{kind}/{issuer}:{}?secret={}&issuer={issuer}\
+		&algorithm={}&digits={}",
+		percent_encode(account),
+		secret.to_base32(),
+		params.algorithm.as_str(),
+		params.digits
+	)
+}
+
+fn percent_encode(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for b in s.bytes() {
+		match b {
+			b'A'..=b'Z'
+			| b'a'..=b'z'
+			| b'0'..=b'9'
+			| b'-'
+			| b'.'
+			| b'_'
+			| b'~' => out.push(b as char),
+			_ => out.push_str(&format!("%{b:02X}")),
+		}
+	}
+
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::time::Duration;
+
+	#[test]
+	fn test_hotp_vector() {
+		// RFC 4226 appendix D
+		let hotp = Hotp::new(Secret::from_bytes(b"12345678901234567890"));
+		let codes = [
+			"755224", "287082", "359152", "969429", "338314", "254676",
+			"287922", "162583", "399871", "520489",
+		];
+
+		for (counter, code) in codes.iter().enumerate() {
+			assert_eq!(hotp.generate(counter as u64), *code);
+			assert!(hotp.verify(code, counter as u64));
+		}
+
+		assert_eq!(hotp.verify_window("969429", 1, 5), Some(3));
+		assert_eq!(hotp.verify_window("969429", 4, 5), None);
+	}
+
+	#[test]
+	fn test_totp_vector() {
+		// RFC 6238 appendix B
+		let seed =
+			b"1234567890123456789012345678901234567890123456789012345678901234";
+		let totps = [
+			(Algorithm::Sha1, &seed[..20]),
+			(Algorithm::Sha256, &seed[..32]),
+			(Algorithm::Sha512, &seed[..]),
+		]
+		.map(|(algorithm, secret)| {
+			Totp::with_params(
+				Secret::from_bytes(secret),
+				Params {
+					algorithm,
+					digits: 8,
+					..Default::default()
+				},
+			)
+		});
+
+		let vectors: [(u64, [&str; 3]); 3] = [
+			(59, ["94287082", "46119246", "90693936"]),
+			(1111111109, ["07081804", "68084774", "25091201"]),
+			(20000000000, ["65353130", "77737706", "47863826"]),
+		];
+
+		for (time, codes) in vectors {
+			let time = UNIX_EPOCH + Duration::from_secs(time);
+			for (totp, code) in totps.iter().zip(codes) {
+				assert_eq!(totp.generate_at(time), code);
+				assert!(totp.verify_at(code, time));
+			}
+		}
+	}
+
+	#[test]
+	fn test_totp_skew() {
+		let totp = Totp::new(Secret::new());
+		let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+		let code = totp.generate_at(now);
+
+		let step = totp.step_at(now);
+		assert_eq!(totp.verify_step_at(&code, now), Some(step));
+		assert!(totp.verify_at(&code, now + Duration::from_secs(30)));
+		assert!(totp.verify_at(&code, now - Duration::from_secs(30)));
+		assert!(!totp.verify_at(&code, now + Duration::from_secs(90)));
+		assert!(!totp.verify_at("12345", now));
+	}
+
+	#[test]
+	fn test_base32() {
+		let secret = Secret::from_bytes(b"12345678901234567890");
+		let s = secret.to_base32();
+		assert_eq!(s, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+		assert_eq!(Secret::from_base32(&s).unwrap(), secret);
+		assert_eq!(
+			Secret::from_base32("gezd gnbv gy3t qojq gezd gnbv gy3t qojq")
+				.unwrap(),
+			secret
+		);
+
+		assert_eq!(crate::base32_encode(b"f"), "MY");
+		assert_eq!(crate::base32_decode("MY======").unwrap(), b"f");
+		assert_eq!(crate::base32_encode(b"foobar"), "MZXW6YTBOI");
+		assert_eq!(crate::base32_decode("MZXW6YTBOI").unwrap(), b"foobar");
+		assert!(Secret::from_base32("MZ1W").is_err());
+	}
+
+	#[test]
+	fn test_uri() {
+		let totp = Totp::new(Secret::from_bytes(b"12345678901234567890"));
+		assert_eq!(
+			totp.to_uri("ACME Co", "john@example.com"),
+			"otpauth://totp/ACME%20Co:john%40example.com\
+			?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=ACME%20Co\
+			&algorithm=SHA1&digits=6&period=30"
+		);
+	}
+}
@@ -0,0 +1,101 @@
+//! Helpers to deserialize fixed size byte arrays with serde.
+//!
+//! Binary formats (which are not human readable) store the raw bytes instead
+//! of a base64 string.
+
+use std::fmt;
+
+use _serde::de::{Error, SeqAccess, Visitor};
+use _serde::Deserializer;
+
+struct ArrayVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for ArrayVisitor<N> {
+	type Value = [u8; N];
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} bytes", N)
+	}
+
+	fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+	where
+		E: Error,
+	{
+		v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let mut bytes = [0u8; N];
+		for (i, b) in bytes.iter_mut().enumerate() {
+			*b = seq
+				.next_element()?
+				.ok_or_else(|| A::Error::invalid_length(i, &self))?;
+		}
+
+		if seq.next_element::<u8>()?.is_some() {
+			return Err(A::Error::invalid_length(N + 1, &self));
+		}
+
+		Ok(bytes)
+	}
+}
+
+struct VecVisitor;
+
+impl<'de> Visitor<'de> for VecVisitor {
+	type Value = Vec<u8>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("bytes")
+	}
+
+	fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+	where
+		E: Error,
+	{
+		Ok(v.to_vec())
+	}
+
+	fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+	where
+		E: Error,
+	{
+		Ok(v)
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+		while let Some(b) = seq.next_element()? {
+			bytes.push(b);
+		}
+
+		Ok(bytes)
+	}
+}
+
+/// Deserializes exactly `N` bytes, either from a byte buffer or a sequence.
+pub(crate) fn deserialize<'de, D, const N: usize>(
+	deserializer: D,
+) -> Result<[u8; N], D::Error>
+where
+	D: Deserializer<'de>,
+{
+	deserializer.deserialize_bytes(ArrayVisitor::<N>)
+}
+
+/// Deserializes any amount of bytes, either from a byte buffer or a
+/// sequence.
+pub(crate) fn deserialize_vec<'de, D>(
+	deserializer: D,
+) -> Result<Vec<u8>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	deserializer.deserialize_byte_buf(VecVisitor)
+}
@@ -0,0 +1,42 @@
+use super::{Keypair, PublicKey, Signature};
+use crate::error::SignError;
+
+use std::future::Future;
+use std::pin::Pin;
+
+/// The future returned by `AsyncSigner::sign`.
+pub type SignFuture<'a> =
+	Pin<Box<dyn Future<Output = Result<Signature, SignError>> + Send + 'a>>;
+
+/// A signer which might need to wait for a remote backend, for example
+/// a KMS or an HSM.
+///
+/// `Keypair` implements this trait so code which is generic over it can
+/// use an in-memory key during development.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::signature::{AsyncSigner, Keypair};
+///
+/// async fn sign_release(signer: &dyn AsyncSigner, release: &[u8]) {
+///     let signature = signer.sign(release).await.unwrap();
+///     assert!(signer.public().verify(release, &signature));
+/// }
+/// ```
+pub trait AsyncSigner: Send + Sync {
+	/// The public key of the signing key.
+	fn public(&self) -> &PublicKey;
+
+	fn sign<'a>(&'a self, msg: &'a [u8]) -> SignFuture<'a>;
+}
+
+impl AsyncSigner for Keypair {
+	fn public(&self) -> &PublicKey {
+		Keypair::public(self)
+	}
+
+	fn sign<'a>(&'a self, msg: &'a [u8]) -> SignFuture<'a> {
+		let signature = Keypair::sign(self, msg);
+		Box::pin(async move { Ok(signature) })
+	}
+}
@@ -0,0 +1,130 @@
+use crate::error::DecodeError;
+
+use std::fmt;
+use std::str::FromStr;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+
+use zeroize::Zeroize;
+
+const HARDENED: u32 = 1 << 31;
+
+/// A path of hardened indices like `m/44'/0'/1'`, used to derive child
+/// keys with SLIP-0010.
+///
+/// Ed25519 only supports hardened derivation, so every index is hardened.
+/// When parsing, `'`, `h` and `H` are accepted as the hardened marker.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct DerivationPath {
+	indices: Vec<u32>,
+}
+
+impl DerivationPath {
+	/// Creates an empty path which refers to the master key.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns an error if any index is `2^31` or larger.
+	pub fn from_indices(indices: &[u32]) -> Result<Self, DecodeError> {
+		if indices.iter().any(|i| *i >= HARDENED) {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		Ok(Self {
+			indices: indices.to_vec(),
+		})
+	}
+
+	/// Returns a new path with the index appended.
+	///
+	/// ## Panics
+	/// If the index is `2^31` or larger.
+	pub fn child(&self, index: u32) -> Self {
+		assert!(index < HARDENED, "index too large");
+
+		let mut indices = self.indices.clone();
+		indices.push(index);
+
+		Self { indices }
+	}
+
+	/// Returns the indices without the hardened bit.
+	pub fn indices(&self) -> &[u32] {
+		&self.indices
+	}
+}
+
+impl fmt::Display for DerivationPath {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("m")?;
+		for i in &self.indices {
+			write!(f, "/{i}'")?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromStr for DerivationPath {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.split('/');
+		if parts.next() != Some("m") {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		let indices = parts
+			.map(|part| {
+				let index = part
+					.strip_suffix(['\'', 'h', 'H'])
+					.ok_or(DecodeError::InvalidBytes)?;
+				// u32::from_str would accept a leading +
+				if !index.bytes().all(|b| b.is_ascii_digit()) {
+					return Err(DecodeError::InvalidBytes);
+				}
+
+				index.parse().map_err(|_| DecodeError::InvalidBytes)
+			})
+			.collect::<Result<Vec<u32>, _>>()?;
+
+		Self::from_indices(&indices)
+	}
+}
+
+/// Derives the secret key at `path` from `seed` as specified in SLIP-0010
+/// for ed25519.
+pub(super) fn derive_secret(seed: &[u8], path: &DerivationPath) -> [u8; 32] {
+	let (mut key, mut chain_code) = hmac_split(b"ed25519 seed", seed);
+
+	for index in path.indices() {
+		let mut data = [0u8; 37];
+		data[1..33].copy_from_slice(&key);
+		data[33..].copy_from_slice(&(index | HARDENED).to_be_bytes());
+
+		key.zeroize();
+		(key, chain_code) = hmac_split(&chain_code, &data);
+		data.zeroize();
+	}
+
+	chain_code.zeroize();
+
+	key
+}
+
+fn hmac_split(key: &[u8], data: &[u8]) -> ([u8; 32], [u8; 32]) {
+	// hmac accepts keys of any length
+	let mut mac = Hmac::<Sha512>::new_from_slice(key).unwrap();
+	mac.update(data);
+	let mut out: [u8; 64] = mac.finalize().into_bytes().into();
+
+	let mut left = [0u8; 32];
+	let mut right = [0u8; 32];
+	left.copy_from_slice(&out[..32]);
+	right.copy_from_slice(&out[32..]);
+	out.zeroize();
+
+	(left, right)
+}
@@ -0,0 +1,264 @@
+use super::{PublicKey448, Signature448};
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex"))]
+use crate::error::DecodeError;
+use crate::error::{RandomError, TryFromError};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use zeroize::Zeroize;
+
+use ed448_goldilocks::{
+	SecretKey, SigningKey, SECRET_KEY_LENGTH as SECRET_LEN,
+};
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// An Ed448 keypair, stored as the 57 byte secret key.
+#[derive(Clone)]
+pub struct Keypair448 {
+	secret: SigningKey,
+	public: PublicKey448,
+}
+
+impl Keypair448 {
+	pub const LEN: usize = SECRET_LEN;
+
+	pub fn new() -> Self {
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut secret = [0u8; SECRET_LEN];
+		rng.fill_bytes(&mut secret);
+		let this = Self::from(secret);
+		secret.zeroize();
+
+		this
+	}
+
+	/// Returns an error if the slice is not 57 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; SECRET_LEN] {
+		let mut bytes = [0u8; SECRET_LEN];
+		bytes.copy_from_slice(self.secret.as_bytes());
+
+		bytes
+	}
+
+	pub fn public(&self) -> &PublicKey448 {
+		&self.public
+	}
+
+	pub fn sign(&self, msg: impl AsRef<[u8]>) -> Signature448 {
+		self.sign_with_context("", msg)
+	}
+
+	pub fn verify(
+		&self,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature448,
+	) -> bool {
+		self.public().verify(msg, signature)
+	}
+
+	/// Signs a message with the Ed448 context string, the signature can
+	/// only be verified with `verify_with_context` and the same context.
+	///
+	/// ## Panics
+	/// If the context is longer than 255 bytes.
+	pub fn sign_with_context(
+		&self,
+		context: &str,
+		msg: impl AsRef<[u8]>,
+	) -> Signature448 {
+		let signature = self
+			.secret
+			.sign_ctx(context.as_bytes(), msg.as_ref())
+			.expect("context longer than 255 bytes");
+
+		Signature448::from(signature.to_bytes())
+	}
+
+	pub fn verify_with_context(
+		&self,
+		context: &str,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature448,
+	) -> bool {
+		self.public().verify_with_context(context, msg, signature)
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Keypair448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair448")
+			.field("secret", &self.to_bytes())
+			.field("public", self.public())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Keypair448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair448")
+			.field("secret", &self.to_string())
+			.field("public", self.public())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Keypair448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(
+			self.secret.as_bytes(),
+			&URL_SAFE_NO_PAD,
+		)
+		.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for Keypair448 {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<&[u8; SECRET_LEN]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(|secret| Self::from(*secret))
+	}
+}
+
+impl From<[u8; SECRET_LEN]> for Keypair448 {
+	fn from(secret: [u8; SECRET_LEN]) -> Self {
+		let secret = SigningKey::from(SecretKey::from(secret));
+		let public = PublicKey448::from_inner(secret.verifying_key());
+
+		Self { secret, public }
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Keypair448 {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = [0u8; Self::LEN];
+		let r = URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.map(|_| Self::from(bytes));
+		bytes.zeroize();
+
+		r
+	}
+}
+
+#[cfg(feature = "hex")]
+impl Keypair448 {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).map(|mut bytes| {
+			let this = Self::from(bytes);
+			bytes.zeroize();
+
+			this
+		})
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, Keypair448> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut bytes = self.get().to_bytes();
+		let r = crate::encoding::fmt_hex(&bytes, f);
+		bytes.zeroize();
+
+		r
+	}
+}
+
+// the signing key zeroizes itself on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Keypair448 {}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	#[cfg(feature = "b64")]
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Keypair448 {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.secret.as_bytes())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Keypair448 {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let mut bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			let r = Ok(Self::from(bytes));
+			bytes.zeroize();
+
+			r
+		}
+	}
+}
@@ -0,0 +1,197 @@
+//! Ed448 signatures as specified in RFC 8032, for a 224 bit security level
+//! instead of the 128 bits of ed25519.
+
+mod keypair;
+pub use keypair::Keypair448;
+
+mod public_key;
+pub use public_key::PublicKey448;
+
+mod signature;
+pub use signature::Signature448;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	fn to_hex(bytes: &[u8]) -> String {
+		bytes.iter().map(|b| format!("{b:02x}")).collect()
+	}
+
+	#[test]
+	fn python_vector() {
+		// generated with python cryptography from the same secret
+		let secret: [u8; 57] = std::array::from_fn(|i| i as u8);
+		let keypair = Keypair448::from(secret);
+		assert_eq!(
+			to_hex(keypair.public().as_ref()),
+			"18d0a70e42a742dfb561279893385061d7b4dad8f6feed4791eaab66b2f4a4f0\
+			2fc09462a8bfb1842d0bac60e8a1b3e55ba2407f33226f3800"
+		);
+
+		let sign = keypair.sign("Hello, World!");
+		assert_eq!(
+			to_hex(sign.as_ref()),
+			"291d667e0feb48fa22de44a114afdd7d06d20ff5b662819e3c0e8645fc07601e\
+			4caf6aae527bfcb0a46cb49f6d1922e3f71d52b4241a882500dd2c40f7d82209\
+			ab4f10b45cca8a114a74452bc9e320d7fc9469f9e19a8e36e9988934239512dd\
+			711c300a7f3cd95363e4eb1f8293bb483200"
+		);
+		assert!(keypair.verify("Hello, World!", &sign));
+	}
+
+	fn from_hex<const N: usize>(s: &str) -> [u8; N] {
+		let mut bytes = [0u8; N];
+		for (i, b) in bytes.iter_mut().enumerate() {
+			*b = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap();
+		}
+		bytes
+	}
+
+	#[test]
+	fn rfc8032_vectors() {
+		// section 7.4: (secret, public, message, context, signature)
+		let vectors: [(&str, &str, &[u8], &str, &str); 4] = [
+			(
+				"6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63\
+				c9960ef6e348a3528c8a3fcc2f044e39a3fc5b94492f8f032e75\
+				49a20098f95b",
+				"5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a\
+				0ea75d80e96778edf124769b46c7061bd6783df1e50f6cd1fa1a\
+				beafe8256180",
+				b"",
+				"",
+				"533a37f6bbe457251f023c0d88f976ae2dfb504a843e34d207\
+				4fd823d41a591f2b233f034f628281f2fd7a22ddd47d7828c59b\
+				d0a21bfd3980ff0d2028d4b18a9df63e006c5d1c2d345b925d8d\
+				c00b4104852db99ac5c7cdda8530a113a0f4dbb61149f05a7363\
+				268c71d95808ff2e652600",
+			),
+			(
+				"c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a\
+				1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e\
+				954e0a12274e",
+				"43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372b\
+				fa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160\
+				627b4c3a9480",
+				&[0x03],
+				"",
+				"26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb\
+				0bd0087810f4352541b143c4b981b7e18f62de8ccdf633fc1bf0\
+				37ab7cd779805e0dbcc0aae1cbcee1afb2e027df36bc04dcecbf\
+				154336c19f0af7e0a6472905e799f1953d2a0ff3348ab21aa4ad\
+				afd1d234441cf807c03a00",
+			),
+			(
+				"c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a\
+				1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e\
+				954e0a12274e",
+				"43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372b\
+				fa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160\
+				627b4c3a9480",
+				&[0x03],
+				"foo",
+				"d4f8f6131770dd46f40867d6fd5d5055de43541f8c5e35abbc\
+				d001b32a89f7d2151f7647f11d8ca2ae279fb842d607217fce6e\
+				042f6815ea000c85741de5c8da1144a6a1aba7f96de42505d7a7\
+				298524fda538fccbbb754f578c1cad10d54d0d5428407e85dcbc\
+				98a49155c13764e66c3c00",
+			),
+			(
+				"cd23d24f714274e744343237b93290f511f6425f98e64459ff\
+				203e8985083ffdf60500553abc0e05cd02184bdb89c4ccd67e18\
+				7951267eb328",
+				"dcea9e78f35a1bf3499a831b10b86c90aac01cd84b67a0109b\
+				55a36e9328b1e365fce161d71ce7131a543ea4cb5f7e9f1d8b00\
+				696447001400",
+				&[
+					0x0c, 0x3e, 0x54, 0x40, 0x74, 0xec, 0x63, 0xb0, 0x26, 0x5e,
+					0x0c,
+				],
+				"",
+				"1f0a8888ce25e8d458a21130879b840a9089d999aaba039eaf\
+				3e3afa090a09d389dba82c4ff2ae8ac5cdfb7c55e94d5d961a29\
+				fe0109941e00b8dbdeea6d3b051068df7254c0cdc129cbe62db2\
+				dc957dbb47b51fd3f213fb8698f064774250a5028961c9bf8ffd\
+				973fe5d5c206492b140e00",
+			),
+		];
+
+		for (secret, public, msg, context, signature) in vectors {
+			let keypair = Keypair448::from(from_hex::<57>(secret));
+			assert_eq!(keypair.public().to_bytes(), from_hex::<57>(public));
+
+			let sign = keypair.sign_with_context(context, msg);
+			assert_eq!(sign.to_bytes(), from_hex::<114>(signature));
+			assert!(keypair.verify_with_context(context, msg, &sign));
+			assert!(!keypair.verify_with_context("bar", msg, &sign));
+		}
+	}
+
+	#[test]
+	fn sign_verify() {
+		let keypair = Keypair448::new();
+		let sign = keypair.sign("Hello, World!");
+		assert!(keypair.verify("Hello, World!", &sign));
+		assert!(!keypair.verify("Hello, World?", &sign));
+		assert!(!keypair.verify_with_context("login", "Hello, World!", &sign));
+		assert!(!Keypair448::new().verify("Hello, World!", &sign));
+
+		let sign = keypair.sign_with_context("login", "Hello, World!");
+		assert!(keypair.verify_with_context("login", "Hello, World!", &sign));
+		assert!(!keypair.verify("Hello, World!", &sign));
+
+		let mut bytes = sign.to_bytes();
+		bytes[60] ^= 1;
+		let sign_2 = Signature448::try_from_slice(&bytes).unwrap();
+		assert!(!keypair.verify_with_context(
+			"login",
+			"Hello, World!",
+			&sign_2
+		));
+
+		let keypair_2 =
+			Keypair448::try_from_slice(&keypair.to_bytes()).unwrap();
+		assert_eq!(keypair.public(), keypair_2.public());
+
+		// not a point on the curve
+		let mut public = keypair.public().to_bytes();
+		public[56] = 1;
+		assert!(PublicKey448::try_from_slice(&public).is_err());
+	}
+
+	#[cfg(feature = "hex")]
+	#[test]
+	fn hex() {
+		let keypair = Keypair448::new();
+		let sign = keypair.sign("Hello, World!");
+
+		assert_eq!(keypair.hex().to_string(), to_hex(&keypair.to_bytes()));
+		let keypair_2 = Keypair448::from_hex(&keypair.to_hex()).unwrap();
+		assert_eq!(keypair.public(), keypair_2.public());
+		let public =
+			PublicKey448::from_hex(&keypair.public().hex().to_string())
+				.unwrap();
+		assert_eq!(&public, keypair.public());
+		let sign_2 = Signature448::from_hex(&sign.hex().to_string()).unwrap();
+		assert_eq!(sign, sign_2);
+	}
+
+	#[cfg(feature = "b64")]
+	#[test]
+	fn b64() {
+		let keypair = Keypair448::new();
+		let sign = keypair.sign("Hello, World!");
+
+		let keypair_2 = Keypair448::from_str(&keypair.to_string()).unwrap();
+		assert_eq!(keypair.to_bytes(), keypair_2.to_bytes());
+		let public =
+			PublicKey448::from_str(&keypair.public().to_string()).unwrap();
+		assert_eq!(&public, keypair.public());
+		let sign_2 = Signature448::from_str(&sign.to_string()).unwrap();
+		assert_eq!(sign, sign_2);
+	}
+}
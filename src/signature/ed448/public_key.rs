@@ -0,0 +1,212 @@
+use super::Signature448;
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex"))]
+use crate::error::DecodeError;
+use crate::error::TryFromError;
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use ed448_goldilocks::{VerifyingKey, PUBLIC_KEY_LENGTH as PUBLIC_LEN};
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// An Ed448 public key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PublicKey448 {
+	inner: VerifyingKey,
+}
+
+impl PublicKey448 {
+	pub const LEN: usize = PUBLIC_LEN;
+
+	pub(super) fn from_inner(inner: VerifyingKey) -> Self {
+		Self { inner }
+	}
+
+	/// Returns an error if the slice is not 57 bytes long or not a point
+	/// on the curve.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; PUBLIC_LEN] {
+		self.inner.to_bytes()
+	}
+
+	pub fn verify(
+		&self,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature448,
+	) -> bool {
+		self.verify_with_context("", msg, signature)
+	}
+
+	/// Verifies a signature created with `sign_with_context`.
+	///
+	/// Returns false if the context is longer than 255 bytes.
+	pub fn verify_with_context(
+		&self,
+		context: &str,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature448,
+	) -> bool {
+		// the context length is not checked when verifying
+		if context.len() > 255 {
+			return false;
+		}
+
+		self.inner
+			.verify_ctx(&signature.to_inner(), context.as_bytes(), msg.as_ref())
+			.is_ok()
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for PublicKey448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey448").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for PublicKey448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey448")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for PublicKey448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl Hash for PublicKey448 {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.as_ref().hash(state)
+	}
+}
+
+impl TryFrom<&[u8]> for PublicKey448 {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		let bytes =
+			<[u8; PUBLIC_LEN]>::try_from(v).map_err(TryFromError::from_any)?;
+
+		let inner =
+			VerifyingKey::from_bytes(&bytes).map_err(TryFromError::from_any)?;
+
+		// the unused bits of the last byte are ignored when decoding
+		if inner.to_edwards().to_affine().compress().to_bytes() != bytes {
+			return Err(TryFromError::from_any(()));
+		}
+
+		Ok(Self::from_inner(inner))
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for PublicKey448 {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = [0u8; Self::LEN];
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(|_| {
+				Self::try_from(bytes.as_ref()).map_err(DecodeError::inv_bytes)
+			})
+	}
+}
+
+impl AsRef<[u8]> for PublicKey448 {
+	fn as_ref(&self) -> &[u8] {
+		self.inner.as_bytes()
+	}
+}
+
+#[cfg(feature = "hex")]
+impl PublicKey448 {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).and_then(|bytes| {
+			Self::try_from(bytes.as_ref()).map_err(DecodeError::inv_bytes)
+		})
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, PublicKey448> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		crate::encoding::fmt_hex(self.get().as_ref(), f)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for PublicKey448 {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for PublicKey448 {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			Self::try_from(bytes.as_ref()).map_err(D::Error::custom)
+		}
+	}
+}
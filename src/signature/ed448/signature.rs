@@ -0,0 +1,168 @@
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex"))]
+use crate::error::DecodeError;
+use crate::error::TryFromError;
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use ed448_goldilocks::SIGNATURE_LENGTH as SIGNATURE_LEN;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// An Ed448 signature, stored as `R || S`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signature448 {
+	bytes: [u8; SIGNATURE_LEN],
+}
+
+impl Signature448 {
+	pub const LEN: usize = SIGNATURE_LEN;
+
+	/// Returns an error if the slice is not 114 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; SIGNATURE_LEN] {
+		self.bytes
+	}
+
+	pub(super) fn to_inner(&self) -> ed448_goldilocks::Signature {
+		ed448_goldilocks::Signature::from_bytes(&self.bytes)
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Signature448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Signature448").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Signature448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Signature448")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Signature448 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl From<[u8; SIGNATURE_LEN]> for Signature448 {
+	fn from(bytes: [u8; SIGNATURE_LEN]) -> Self {
+		Self { bytes }
+	}
+}
+
+impl TryFrom<&[u8]> for Signature448 {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; SIGNATURE_LEN]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Signature448 {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = [0u8; Self::LEN];
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.map(|_| Self::from(bytes))
+	}
+}
+
+impl AsRef<[u8]> for Signature448 {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+#[cfg(feature = "hex")]
+impl Signature448 {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).map(Self::from)
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, Signature448> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		crate::encoding::fmt_hex(self.get().as_ref(), f)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	#[cfg(feature = "b64")]
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Signature448 {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Signature448 {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			crate::serde_bytes::deserialize::<_, { Self::LEN }>(deserializer)
+				.map(Self::from)
+		}
+	}
+}
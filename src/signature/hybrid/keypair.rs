@@ -0,0 +1,238 @@
+use super::{PublicKey, Signature, CONTEXT};
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::{RandomError, TryFromError};
+use crate::signature::{self as ed25519, ml_dsa};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+#[cfg(any(feature = "b64", feature = "serde"))]
+use zeroize::Zeroize;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// An ed25519 and an ML-DSA-65 keypair which always sign together.
+pub struct Keypair {
+	ed25519: ed25519::Keypair,
+	ml_dsa: ml_dsa::Keypair,
+	public: PublicKey,
+}
+
+impl Keypair {
+	pub const LEN: usize = ed25519::Keypair::LEN + ml_dsa::Keypair::LEN;
+
+	pub fn new() -> Self {
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		Self::from_parts(
+			ed25519::Keypair::new_with_rng(rng),
+			ml_dsa::Keypair::new_with_rng(rng),
+		)
+	}
+
+	/// Combines two existing keypairs, which allows to add a post-quantum
+	/// key to an existing ed25519 key.
+	pub fn from_parts(
+		ed25519: ed25519::Keypair,
+		ml_dsa: ml_dsa::Keypair,
+	) -> Self {
+		let public = PublicKey::from_parts(
+			ed25519.public().clone(),
+			ml_dsa.public().clone(),
+		);
+
+		Self {
+			ed25519,
+			ml_dsa,
+			public,
+		}
+	}
+
+	/// Returns an error if the slice is not 64 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	/// Returns the ed25519 secret followed by the ML-DSA seed.
+	pub fn to_bytes(&self) -> [u8; 64] {
+		let mut bytes = [0u8; 64];
+		bytes[..32].copy_from_slice(&self.ed25519.to_bytes());
+		bytes[32..].copy_from_slice(&self.ml_dsa.to_bytes());
+
+		bytes
+	}
+
+	pub fn public(&self) -> &PublicKey {
+		&self.public
+	}
+
+	pub fn ed25519(&self) -> &ed25519::Keypair {
+		&self.ed25519
+	}
+
+	pub fn ml_dsa(&self) -> &ml_dsa::Keypair {
+		&self.ml_dsa
+	}
+
+	/// Signs the message with both keys.
+	pub fn sign(&self, msg: impl AsRef<[u8]>) -> Signature {
+		let msg = msg.as_ref();
+		Signature::from_parts(
+			self.ed25519.sign_with_context(CONTEXT, msg),
+			self.ml_dsa.sign_with_context(CONTEXT, msg),
+		)
+	}
+
+	pub fn verify(&self, msg: impl AsRef<[u8]>, signature: &Signature) -> bool {
+		self.public().verify(msg, signature)
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("secret", &self.to_bytes())
+			.field("public", self.public())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("secret", &self.to_string())
+			.field("public", self.public())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut bytes = self.to_bytes();
+		let r = base64::display::Base64Display::new(&bytes, &URL_SAFE_NO_PAD)
+			.fmt(f);
+		bytes.zeroize();
+
+		r
+	}
+}
+
+impl TryFrom<&[u8]> for Keypair {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		if v.len() != Self::LEN {
+			return Err(TryFromError::from_any(()));
+		}
+
+		let (ed25519, ml_dsa) = v.split_at(ed25519::Keypair::LEN);
+		Ok(Self::from_parts(ed25519.try_into()?, ml_dsa.try_into()?))
+	}
+}
+
+impl From<[u8; 64]> for Keypair {
+	fn from(bytes: [u8; 64]) -> Self {
+		// the length is always correct
+		Self::try_from(bytes.as_slice()).unwrap()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Keypair {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = [0u8; Self::LEN];
+		let r = URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.map(|_| Self::from(bytes));
+		bytes.zeroize();
+
+		r
+	}
+}
+
+impl Clone for Keypair {
+	fn clone(&self) -> Self {
+		Self::from_parts(self.ed25519.clone(), self.ml_dsa.clone())
+	}
+}
+
+// both keypairs zeroize themselves on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Keypair {}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	#[cfg(feature = "b64")]
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Keypair {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			let mut bytes = self.to_bytes();
+			let r = serializer.serialize_bytes(&bytes);
+			bytes.zeroize();
+
+			r
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Keypair {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let mut bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			let r = Ok(Self::from(bytes));
+			bytes.zeroize();
+
+			r
+		}
+	}
+}
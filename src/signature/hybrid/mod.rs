@@ -0,0 +1,75 @@
+//! Hybrid signatures which combine ed25519 and ML-DSA-65.
+//!
+//! A signature is only valid if both signatures are valid, so it stays
+//! secure as long as one of the two schemes is not broken. Both parts sign
+//! the message bound to a context, which means they cannot be stripped and
+//! used as a plain ed25519 or ML-DSA signature.
+
+mod keypair;
+pub use keypair::Keypair;
+
+mod public_key;
+pub use public_key::PublicKey;
+
+#[allow(clippy::module_inception)]
+mod signature;
+pub use signature::Signature;
+
+const CONTEXT: &str = "chuchi-crypto hybrid ed25519 ml-dsa-65";
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	#[test]
+	fn sign_verify() {
+		let keypair = Keypair::new();
+		let sign = keypair.sign("Hello, World!");
+		assert!(keypair.verify("Hello, World!", &sign));
+		assert!(!keypair.verify("Hello, World?", &sign));
+
+		// both parts need to be valid
+		let other = Keypair::new();
+		let other_sign = other.sign("Hello, World!");
+		let mixed = Signature::from_parts(
+			sign.ed25519().clone(),
+			other_sign.ml_dsa().clone(),
+		);
+		assert!(!keypair.verify("Hello, World!", &mixed));
+		let mixed = Signature::from_parts(
+			other_sign.ed25519().clone(),
+			sign.ml_dsa().clone(),
+		);
+		assert!(!keypair.verify("Hello, World!", &mixed));
+
+		// the parts are not valid on their own
+		assert!(!keypair.ed25519().verify("Hello, World!", sign.ed25519()));
+		assert!(!keypair.ml_dsa().verify("Hello, World!", sign.ml_dsa()));
+
+		let keypair_2 = Keypair::try_from_slice(&keypair.to_bytes()).unwrap();
+		assert_eq!(keypair.public(), keypair_2.public());
+		let public =
+			PublicKey::try_from_slice(&keypair.public().to_bytes()).unwrap();
+		assert_eq!(&public, keypair.public());
+		let sign_2 = Signature::try_from_slice(&sign.to_bytes()).unwrap();
+		assert_eq!(sign, sign_2);
+	}
+
+	#[cfg(feature = "b64")]
+	#[test]
+	fn b64() {
+		let keypair = Keypair::new();
+		let sign = keypair.sign("Hello, World!");
+
+		let keypair_2 = Keypair::from_str(&keypair.to_string()).unwrap();
+		assert_eq!(keypair.to_bytes(), keypair_2.to_bytes());
+		let public =
+			PublicKey::from_str(&keypair.public().to_string()).unwrap();
+		assert_eq!(&public, keypair.public());
+		let sign_2 = Signature::from_str(&sign.to_string()).unwrap();
+		assert_eq!(sign, sign_2);
+	}
+}
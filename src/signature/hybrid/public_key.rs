@@ -0,0 +1,176 @@
+use super::{Signature, CONTEXT};
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::TryFromError;
+use crate::signature::{self as ed25519, ml_dsa};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// An ed25519 and an ML-DSA-65 public key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PublicKey {
+	ed25519: ed25519::PublicKey,
+	ml_dsa: ml_dsa::PublicKey,
+}
+
+impl PublicKey {
+	pub const LEN: usize = ed25519::PublicKey::LEN + ml_dsa::PublicKey::LEN;
+
+	pub fn from_parts(
+		ed25519: ed25519::PublicKey,
+		ml_dsa: ml_dsa::PublicKey,
+	) -> Self {
+		Self { ed25519, ml_dsa }
+	}
+
+	/// Returns an error if the slice is not 1984 bytes long or the ed25519
+	/// key is not valid.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	/// Returns the ed25519 key followed by the ML-DSA key.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(Self::LEN);
+		bytes.extend_from_slice(self.ed25519.as_ref());
+		bytes.extend_from_slice(self.ml_dsa.as_ref());
+
+		bytes
+	}
+
+	pub fn ed25519(&self) -> &ed25519::PublicKey {
+		&self.ed25519
+	}
+
+	pub fn ml_dsa(&self) -> &ml_dsa::PublicKey {
+		&self.ml_dsa
+	}
+
+	/// Returns true if both signatures are valid.
+	pub fn verify(&self, msg: impl AsRef<[u8]>, signature: &Signature) -> bool {
+		let msg = msg.as_ref();
+		let ed25519 =
+			self.ed25519
+				.verify_with_context(CONTEXT, msg, signature.ed25519());
+		let ml_dsa =
+			self.ml_dsa
+				.verify_with_context(CONTEXT, msg, signature.ml_dsa());
+
+		ed25519 & ml_dsa
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PublicKey")
+			.field("ed25519", &self.ed25519)
+			.field("ml_dsa", &self.ml_dsa)
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey").field(&self.to_string()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.to_bytes(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl Hash for PublicKey {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.ed25519.hash(state);
+		self.ml_dsa.hash(state);
+	}
+}
+
+impl TryFrom<&[u8]> for PublicKey {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		if v.len() != Self::LEN {
+			return Err(TryFromError::from_any(()));
+		}
+
+		let (ed25519, ml_dsa) = v.split_at(ed25519::PublicKey::LEN);
+		Ok(Self {
+			ed25519: ed25519.try_into()?,
+			ml_dsa: ml_dsa.try_into()?,
+		})
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for PublicKey {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = vec![0u8; Self::LEN];
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(|_| {
+				Self::try_from(bytes.as_slice()).map_err(DecodeError::inv_bytes)
+			})
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for PublicKey {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.to_bytes())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for PublicKey {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::try_from(bytes.as_slice()).map_err(D::Error::custom)
+		}
+	}
+}
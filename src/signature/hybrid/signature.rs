@@ -0,0 +1,153 @@
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::TryFromError;
+use crate::signature::{self as ed25519, ml_dsa};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// An ed25519 and an ML-DSA-65 signature over the same message.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signature {
+	ed25519: ed25519::Signature,
+	ml_dsa: ml_dsa::Signature,
+}
+
+impl Signature {
+	pub const LEN: usize = ed25519::Signature::LEN + ml_dsa::Signature::LEN;
+
+	pub(super) fn from_parts(
+		ed25519: ed25519::Signature,
+		ml_dsa: ml_dsa::Signature,
+	) -> Self {
+		Self { ed25519, ml_dsa }
+	}
+
+	/// Returns an error if the slice is not 3373 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	/// Returns the ed25519 signature followed by the ML-DSA signature.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(Self::LEN);
+		bytes.extend_from_slice(&self.ed25519.to_bytes());
+		bytes.extend_from_slice(&self.ml_dsa.to_bytes());
+
+		bytes
+	}
+
+	pub fn ed25519(&self) -> &ed25519::Signature {
+		&self.ed25519
+	}
+
+	pub fn ml_dsa(&self) -> &ml_dsa::Signature {
+		&self.ml_dsa
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Signature")
+			.field("ed25519", &self.ed25519)
+			.field("ml_dsa", &self.ml_dsa)
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Signature").field(&self.to_string()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.to_bytes(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for Signature {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		if v.len() != Self::LEN {
+			return Err(TryFromError::from_any(()));
+		}
+
+		let (ed25519, ml_dsa) = v.split_at(ed25519::Signature::LEN);
+		Ok(Self {
+			ed25519: ed25519.try_into()?,
+			ml_dsa: ml_dsa.try_into()?,
+		})
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Signature {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = vec![0u8; Self::LEN];
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(|_| {
+				Self::try_from(bytes.as_slice()).map_err(DecodeError::inv_bytes)
+			})
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Signature {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.to_bytes())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Signature {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::try_from(bytes.as_slice()).map_err(D::Error::custom)
+		}
+	}
+}
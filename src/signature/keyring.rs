@@ -0,0 +1,110 @@
+use super::{PublicKey, Signature};
+
+/// A set of public keys each tagged with a key id.
+///
+/// Useful during key rotation, where a signature might have been created
+/// by any of the currently trusted keys.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::signature::{Keypair, Keyring};
+///
+/// let old = Keypair::new();
+/// let new = Keypair::new();
+///
+/// let mut keyring = Keyring::new();
+/// keyring.insert("2023", old.public().clone());
+/// keyring.insert("2024", new.public().clone());
+///
+/// let sig = old.sign(b"msg");
+/// assert!(keyring.verify("2023", b"msg", &sig));
+/// assert!(!keyring.verify("2024", b"msg", &sig));
+/// assert_eq!(keyring.verify_any(b"msg", &sig), Some("2023"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Keyring {
+	keys: Vec<(String, PublicKey)>,
+}
+
+impl Keyring {
+	pub fn new() -> Self {
+		Self { keys: vec![] }
+	}
+
+	/// Adds a key, returning the previous key if the id was already used.
+	pub fn insert(
+		&mut self,
+		id: impl Into<String>,
+		key: PublicKey,
+	) -> Option<PublicKey> {
+		let id = id.into();
+		match self.keys.iter_mut().find(|(i, _)| *i == id) {
+			Some((_, k)) => Some(std::mem::replace(k, key)),
+			None => {
+				self.keys.push((id, key));
+				None
+			}
+		}
+	}
+
+	/// Removes a key returning it if it existed.
+	pub fn remove(&mut self, id: &str) -> Option<PublicKey> {
+		let pos = self.keys.iter().position(|(i, _)| i == id)?;
+		Some(self.keys.remove(pos).1)
+	}
+
+	pub fn get(&self, id: &str) -> Option<&PublicKey> {
+		self.keys.iter().find(|(i, _)| i == id).map(|(_, k)| k)
+	}
+
+	pub fn len(&self) -> usize {
+		self.keys.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+
+	/// Returns the ids and keys in the order they were inserted.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &PublicKey)> {
+		self.keys.iter().map(|(i, k)| (i.as_str(), k))
+	}
+
+	/// Verifies the signature with the key with the given id.
+	///
+	/// Returns false if no key with that id exists.
+	pub fn verify(
+		&self,
+		id: &str,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature,
+	) -> bool {
+		self.get(id)
+			.map(|k| k.verify(msg, signature))
+			.unwrap_or(false)
+	}
+
+	/// Tries every key in insertion order and returns the id of the first
+	/// one which could verify the signature.
+	pub fn verify_any(
+		&self,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature,
+	) -> Option<&str> {
+		let msg = msg.as_ref();
+		self.iter()
+			.find(|(_, k)| k.verify(msg, signature))
+			.map(|(i, _)| i)
+	}
+}
+
+impl<I: Into<String>> FromIterator<(I, PublicKey)> for Keyring {
+	fn from_iter<T: IntoIterator<Item = (I, PublicKey)>>(iter: T) -> Self {
+		let mut keyring = Self::new();
+		for (id, key) in iter {
+			keyring.insert(id, key);
+		}
+
+		keyring
+	}
+}
@@ -0,0 +1,255 @@
+use super::{PublicKey, Signature};
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::{RandomError, TryFromError};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use zeroize::Zeroize;
+
+use ml_dsa::{ExpandedSigningKey, MlDsa65, B32};
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// An ML-DSA-65 keypair, stored as the 32 byte seed from which the keys
+/// are expanded.
+pub struct Keypair {
+	seed: [u8; 32],
+	secret: Box<ExpandedSigningKey<MlDsa65>>,
+	public: PublicKey,
+}
+
+impl Keypair {
+	pub const LEN: usize = 32;
+
+	pub fn new() -> Self {
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let mut seed = [0u8; 32];
+		rng.fill_bytes(&mut seed);
+		let this = Self::from(seed);
+		seed.zeroize();
+
+		this
+	}
+
+	/// Returns an error if the slice is not 32 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	/// Returns the seed.
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.seed
+	}
+
+	pub fn public(&self) -> &PublicKey {
+		&self.public
+	}
+
+	/// Signs the message with fresh randomness (hedged signing).
+	pub fn sign(&self, msg: impl AsRef<[u8]>) -> Signature {
+		self.sign_with_context("", msg)
+	}
+
+	pub fn verify(&self, msg: impl AsRef<[u8]>, signature: &Signature) -> bool {
+		self.public().verify(msg, signature)
+	}
+
+	/// Signs a message with the ML-DSA context string, the signature can
+	/// only be verified with `verify_with_context` and the same context.
+	///
+	/// ## Panics
+	/// If the context is longer than 255 bytes.
+	pub fn sign_with_context(
+		&self,
+		context: &str,
+		msg: impl AsRef<[u8]>,
+	) -> Signature {
+		let mut rnd = [0u8; 32];
+		OsRng.fill_bytes(&mut rnd);
+		let sign = self.sign_with_rnd(context, msg.as_ref(), &rnd);
+		rnd.zeroize();
+
+		sign
+	}
+
+	pub(super) fn sign_with_rnd(
+		&self,
+		context: &str,
+		msg: &[u8],
+		rnd: &[u8; 32],
+	) -> Signature {
+		let context_len: u8 = context
+			.len()
+			.try_into()
+			.expect("context longer than 255 bytes");
+
+		// ML-DSA.Sign_internal with the formatted message M' from FIPS 204
+		let sign = self.secret.sign_internal(
+			&[&[0, context_len], context.as_bytes(), msg],
+			&B32::from(*rnd),
+		);
+
+		Signature::from_raw(Box::new(sign.encode().into()))
+	}
+
+	pub fn verify_with_context(
+		&self,
+		context: &str,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature,
+	) -> bool {
+		self.public().verify_with_context(context, msg, signature)
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("secret", &self.to_bytes())
+			.field("public", self.public())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("secret", &self.to_string())
+			.field("public", self.public())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.seed, &URL_SAFE_NO_PAD).fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for Keypair {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		<&[u8; 32]>::try_from(v)
+			.map_err(TryFromError::from_any)
+			.map(|seed| Self::from(*seed))
+	}
+}
+
+impl From<[u8; 32]> for Keypair {
+	fn from(seed: [u8; 32]) -> Self {
+		let secret = Box::new(ExpandedSigningKey::<MlDsa65>::from_seed(
+			&B32::from(seed),
+		));
+		let public = secret.verifying_key().encode().into();
+		Self {
+			seed,
+			secret,
+			public: PublicKey::from_raw(Box::new(public)),
+		}
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Keypair {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = [0u8; Self::LEN];
+		let r = URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.map(|_| Self::from(bytes));
+		bytes.zeroize();
+
+		r
+	}
+}
+
+impl Clone for Keypair {
+	fn clone(&self) -> Self {
+		Self::from(self.seed)
+	}
+}
+
+impl Drop for Keypair {
+	fn drop(&mut self) {
+		self.seed.zeroize();
+	}
+}
+
+// the expanded secret key zeroizes itself on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Keypair {}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	#[cfg(feature = "b64")]
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Keypair {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.seed)
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Keypair {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let mut bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			let r = Ok(Self::from(bytes));
+			bytes.zeroize();
+
+			r
+		}
+	}
+}
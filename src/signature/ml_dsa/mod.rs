@@ -0,0 +1,93 @@
+//! Post-quantum signatures with ML-DSA-65 as specified in FIPS 204.
+//!
+//! The signatures are 3309 bytes long and the public keys 1952 bytes, so
+//! prefer ed25519 unless the signatures need to stay valid against a
+//! quantum computer. [`hybrid`](super::hybrid) combines both.
+//!
+//! The algorithm itself is implemented by the `ml-dsa` crate.
+
+mod keypair;
+pub use keypair::Keypair;
+
+mod public_key;
+pub use public_key::PublicKey;
+
+#[allow(clippy::module_inception)]
+mod signature;
+pub use signature::Signature;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use sha2::{Digest, Sha256};
+
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	fn sha256_hex(bytes: &[u8]) -> String {
+		Sha256::digest(bytes)
+			.iter()
+			.map(|b| format!("{b:02x}"))
+			.collect()
+	}
+
+	#[test]
+	fn openssl_vector() {
+		// generated with openssl 3.5 using `-pkeyopt deterministic:1`
+		let seed: [u8; 32] = std::array::from_fn(|i| i as u8);
+		let keypair = Keypair::from(seed);
+		assert_eq!(
+			sha256_hex(keypair.public().as_ref()),
+			"d666806e11cee19a7c989f7445f90dd419cf4d2d51db8c0fdb4c0f0a542238c9"
+		);
+
+		let sign = keypair.sign_with_rnd("", b"Hello, World!", &[0; 32]);
+		assert_eq!(
+			sha256_hex(&sign.to_bytes()),
+			"f5ff82a69cdc7ee35ba8a62524d98d531078595f94dc4d43cbbc4ffabd2dcfc1"
+		);
+		assert!(keypair.verify("Hello, World!", &sign));
+
+		let sign = keypair.sign_with_rnd("login", b"Hello, World!", &[0; 32]);
+		assert_eq!(
+			sha256_hex(&sign.to_bytes()),
+			"c4f21bf351f53de476ed83eb3ba3d95e3fdd7af4ade4f678b758fa4bfccba4d1"
+		);
+		assert!(keypair.verify_with_context("login", "Hello, World!", &sign));
+	}
+
+	#[test]
+	fn sign_verify() {
+		let keypair = Keypair::new();
+		let sign = keypair.sign("Hello, World!");
+		assert!(keypair.verify("Hello, World!", &sign));
+		assert!(!keypair.verify("Hello, World?", &sign));
+		assert!(!keypair.verify_with_context("login", "Hello, World!", &sign));
+		assert!(!Keypair::new().verify("Hello, World!", &sign));
+
+		let mut bytes = sign.to_bytes();
+		bytes[10] ^= 1;
+		let sign_2 = Signature::try_from_slice(&bytes).unwrap();
+		assert!(!keypair.verify("Hello, World!", &sign_2));
+
+		let keypair_2 = Keypair::try_from_slice(&keypair.to_bytes()).unwrap();
+		assert_eq!(keypair.public(), keypair_2.public());
+		assert!(Signature::try_from_slice(&bytes[1..]).is_err());
+	}
+
+	#[cfg(feature = "b64")]
+	#[test]
+	fn b64() {
+		let keypair = Keypair::new();
+		let sign = keypair.sign("Hello, World!");
+
+		let keypair_2 = Keypair::from_str(&keypair.to_string()).unwrap();
+		assert_eq!(keypair.to_bytes(), keypair_2.to_bytes());
+		let public =
+			PublicKey::from_str(&keypair.public().to_string()).unwrap();
+		assert_eq!(&public, keypair.public());
+		let sign_2 = Signature::from_str(&sign.to_string()).unwrap();
+		assert_eq!(sign, sign_2);
+	}
+}
@@ -0,0 +1,173 @@
+use super::Signature;
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::TryFromError;
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use ml_dsa::{
+	EncodedSignature, EncodedVerifyingKey, MlDsa65,
+	Signature as MlDsaSignature, VerifyingKey,
+};
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+const PUBLIC_KEY_LEN: usize = 1952;
+
+/// An ML-DSA-65 public key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PublicKey {
+	bytes: Box<[u8; PUBLIC_KEY_LEN]>,
+}
+
+impl PublicKey {
+	pub const LEN: usize = PUBLIC_KEY_LEN;
+
+	pub(super) fn from_raw(bytes: Box<[u8; PUBLIC_KEY_LEN]>) -> Self {
+		Self { bytes }
+	}
+
+	/// Returns an error if the slice is not 1952 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; PUBLIC_KEY_LEN] {
+		*self.bytes
+	}
+
+	pub fn verify(&self, msg: impl AsRef<[u8]>, signature: &Signature) -> bool {
+		self.verify_with_context("", msg, signature)
+	}
+
+	/// Verifies a signature created with `sign_with_context`.
+	///
+	/// Returns false if the context is longer than 255 bytes.
+	pub fn verify_with_context(
+		&self,
+		context: &str,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature,
+	) -> bool {
+		let public = VerifyingKey::<MlDsa65>::decode(&EncodedVerifyingKey::<
+			MlDsa65,
+		>::from(*self.bytes));
+
+		// a signature with an invalid hint encoding is rejected here
+		let Some(signature) = MlDsaSignature::<MlDsa65>::decode(
+			&EncodedSignature::<MlDsa65>::from(*signature.as_array()),
+		) else {
+			return false;
+		};
+
+		public.verify_with_context(msg.as_ref(), context.as_bytes(), &signature)
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PublicKey").field(&self.to_string()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for PublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl Hash for PublicKey {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.as_ref().hash(state)
+	}
+}
+
+impl TryFrom<&[u8]> for PublicKey {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		// every encoding of the right length is a valid public key
+		<Box<[u8; PUBLIC_KEY_LEN]>>::try_from(v.to_vec().into_boxed_slice())
+			.map_err(TryFromError::from_any)
+			.map(Self::from_raw)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for PublicKey {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = Box::new([0u8; Self::LEN]);
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, bytes.as_mut_slice())
+			.map_err(DecodeError::inv_bytes)
+			.map(|_| Self::from_raw(bytes))
+	}
+}
+
+impl AsRef<[u8]> for PublicKey {
+	fn as_ref(&self) -> &[u8] {
+		self.bytes.as_slice()
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for PublicKey {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for PublicKey {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::try_from(bytes.as_slice()).map_err(D::Error::custom)
+		}
+	}
+}
@@ -0,0 +1,137 @@
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::TryFromError;
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+const SIGNATURE_LEN: usize = 3309;
+
+/// An ML-DSA-65 signature.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signature {
+	bytes: Box<[u8; SIGNATURE_LEN]>,
+}
+
+impl Signature {
+	pub const LEN: usize = SIGNATURE_LEN;
+
+	pub(super) fn from_raw(bytes: Box<[u8; SIGNATURE_LEN]>) -> Self {
+		Self { bytes }
+	}
+
+	pub(super) fn as_array(&self) -> &[u8; SIGNATURE_LEN] {
+		&self.bytes
+	}
+
+	/// Returns an error if the slice is not 3309 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; SIGNATURE_LEN] {
+		*self.bytes
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Signature").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Signature").field(&self.to_string()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for Signature {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		// the encoding is checked when verifying
+		<Box<[u8; SIGNATURE_LEN]>>::try_from(v.to_vec().into_boxed_slice())
+			.map_err(TryFromError::from_any)
+			.map(Self::from_raw)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Signature {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = Box::new([0u8; Self::LEN]);
+		URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, bytes.as_mut_slice())
+			.map_err(DecodeError::inv_bytes)
+			.map(|_| Self::from_raw(bytes))
+	}
+}
+
+impl AsRef<[u8]> for Signature {
+	fn as_ref(&self) -> &[u8] {
+		self.bytes.as_slice()
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Signature {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Signature {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::try_from(bytes.as_slice()).map_err(D::Error::custom)
+		}
+	}
+}
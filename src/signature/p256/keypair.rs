@@ -0,0 +1,401 @@
+use super::{PublicKey, Signature};
+#[cfg(any(feature = "b64", feature = "hex"))]
+use crate::error::DecodeError;
+use crate::error::{RandomError, TryFromError};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use ::p256::ecdsa::{self, signature::Signer};
+
+#[cfg(any(
+	feature = "b64",
+	feature = "hex",
+	feature = "serde",
+	feature = "protobuf"
+))]
+use zeroize::Zeroize;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// A P-256 keypair which creates ES256 signatures.
+pub struct Keypair {
+	secret: ecdsa::SigningKey,
+	public: PublicKey,
+}
+
+impl Keypair {
+	pub const LEN: usize = 32;
+
+	pub fn new() -> Self {
+		Self::new_with_rng(&mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new() -> Result<Self, RandomError> {
+		crate::try_with_os_rng(Self::new_with_rng)
+	}
+
+	/// Creates a new keypair using the given rng.
+	pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		Self::from_keypair(ecdsa::SigningKey::random(rng))
+	}
+
+	fn from_keypair(secret: ecdsa::SigningKey) -> Self {
+		let public = PublicKey::from_raw(*secret.verifying_key());
+		Self { secret, public }
+	}
+
+	/// Returns an error if the slice is not 32 bytes long or is not a valid
+	/// scalar.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
+	pub fn to_bytes(&self) -> [u8; 32] {
+		self.secret.to_bytes().into()
+	}
+
+	pub fn public(&self) -> &PublicKey {
+		&self.public
+	}
+
+	/// Signs the message using ES256, the message is hashed with SHA-256
+	/// and the nonce is derived as specified in RFC 6979.
+	pub fn sign(&self, msg: impl AsRef<[u8]>) -> Signature {
+		let sign: ecdsa::Signature = self.secret.sign(msg.as_ref());
+		Signature::from_sign(sign)
+	}
+
+	pub fn verify(&self, msg: impl AsRef<[u8]>, signature: &Signature) -> bool {
+		self.public().verify(msg, signature)
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("secret", &self.to_bytes())
+			.field("public", self.public())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("secret", &self.to_string())
+			.field("public", self.public())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for Keypair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut bytes = self.to_bytes();
+		let r = base64::display::Base64Display::new(&bytes, &URL_SAFE_NO_PAD)
+			.fmt(f);
+		bytes.zeroize();
+
+		r
+	}
+}
+
+impl TryFrom<&[u8]> for Keypair {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		// from_slice would also accept shorter slices
+		if v.len() != Self::LEN {
+			return Err(TryFromError::from_any(()));
+		}
+
+		ecdsa::SigningKey::from_slice(v)
+			.map_err(TryFromError::from_any)
+			.map(Self::from_keypair)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for Keypair {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.len() != crate::calculate_b64_len(Self::LEN) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let mut bytes = [0u8; Self::LEN];
+		let r = URL_SAFE_NO_PAD
+			.decode_slice_unchecked(s, &mut bytes)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(|_| {
+				Self::try_from(bytes.as_ref()).map_err(DecodeError::inv_bytes)
+			});
+		bytes.zeroize();
+
+		r
+	}
+}
+
+impl Clone for Keypair {
+	fn clone(&self) -> Self {
+		Self::from_keypair(self.secret.clone())
+	}
+}
+
+// the signing key zeroizes itself on drop
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Keypair {}
+
+#[cfg(feature = "hex")]
+impl Keypair {
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		let mut bytes = self.to_bytes();
+		let s = hex::encode(bytes);
+		bytes.zeroize();
+
+		s
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).and_then(|mut bytes| {
+			let r =
+				Self::try_from(bytes.as_ref()).map_err(DecodeError::inv_bytes);
+			bytes.zeroize();
+
+			r
+		})
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for Keypair {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			let mut bytes = self.to_bytes();
+			let r = serializer.serialize_bytes(&bytes);
+			bytes.zeroize();
+
+			r
+		}
+	}
+
+	impl<'de> Deserialize<'de> for Keypair {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let mut bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			let r = Self::try_from(bytes.as_ref()).map_err(D::Error::custom);
+			bytes.zeroize();
+
+			r
+		}
+	}
+}
+
+#[cfg(feature = "protobuf")]
+mod impl_protobuf {
+	use super::*;
+
+	use protopuffer::{
+		bytes::BytesWrite,
+		decode::{DecodeError, DecodeMessage, FieldKind},
+		encode::{
+			EncodeError, EncodeMessage, FieldOpt, MessageEncoder, SizeBuilder,
+		},
+		WireType,
+	};
+
+	impl EncodeMessage for Keypair {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn is_default(&self) -> bool {
+			false
+		}
+
+		fn encoded_size(
+			&mut self,
+			field: Option<FieldOpt>,
+			builder: &mut SizeBuilder,
+		) -> Result<(), EncodeError> {
+			self.to_bytes().encoded_size(field, builder)
+		}
+
+		fn encode<B>(
+			&mut self,
+			field: Option<FieldOpt>,
+			encoder: &mut MessageEncoder<B>,
+		) -> Result<(), EncodeError>
+		where
+			B: BytesWrite,
+		{
+			self.to_bytes().encode(field, encoder)
+		}
+	}
+
+	impl<'m> DecodeMessage<'m> for Keypair {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn decode_default() -> Self {
+			// zero is not a valid scalar
+			let mut bytes = [0u8; 32];
+			bytes[31] = 1;
+			Self::try_from(bytes.as_ref()).unwrap()
+		}
+
+		fn merge(
+			&mut self,
+			kind: FieldKind<'m>,
+			is_field: bool,
+		) -> Result<(), DecodeError> {
+			let mut t = self.to_bytes();
+			let r = t.merge(kind, is_field).and_then(|_| {
+				Self::try_from(t.as_ref())
+					.map_err(|e| DecodeError::Other(e.to_string()))
+			});
+			t.zeroize();
+
+			*self = r?;
+
+			Ok(())
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "postgres"))]
+mod impl_postgres {
+	use super::*;
+
+	use bytes::BytesMut;
+	use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+	impl ToSql for Keypair {
+		fn to_sql(
+			&self,
+			ty: &Type,
+			out: &mut BytesMut,
+		) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+		where
+			Self: Sized,
+		{
+			if *ty == Type::BYTEA {
+				let mut bytes = self.to_bytes();
+				let r = <&[u8] as ToSql>::to_sql(&bytes.as_ref(), ty, out);
+				bytes.zeroize();
+
+				return r;
+			}
+
+			self.to_string().to_sql(ty, out)
+		}
+
+		fn accepts(ty: &Type) -> bool
+		where
+			Self: Sized,
+		{
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
+		}
+
+		to_sql_checked!();
+	}
+
+	impl<'r> FromSql<'r> for Keypair {
+		fn from_sql(
+			ty: &Type,
+			raw: &'r [u8],
+		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Self::try_from(raw).map_err(Into::into);
+			}
+
+			let s = <&str as FromSql>::from_sql(ty, raw)?;
+			s.parse().map_err(Into::into)
+		}
+
+		fn accepts(ty: &Type) -> bool {
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "sqlx"))]
+mod impl_sqlx {
+	use super::*;
+
+	use sqlx::encode::IsNull;
+	use sqlx::error::BoxDynError;
+	use sqlx::{Database, Decode, Encode, Type};
+
+	impl<DB: Database> Type<DB> for Keypair
+	where
+		String: Type<DB>,
+	{
+		fn type_info() -> DB::TypeInfo {
+			<String as Type<DB>>::type_info()
+		}
+
+		fn compatible(ty: &DB::TypeInfo) -> bool {
+			<String as Type<DB>>::compatible(ty)
+		}
+	}
+
+	impl<'q, DB: Database> Encode<'q, DB> for Keypair
+	where
+		String: Encode<'q, DB>,
+	{
+		fn encode_by_ref(
+			&self,
+			buf: &mut DB::ArgumentBuffer<'q>,
+		) -> Result<IsNull, BoxDynError> {
+			self.to_string().encode(buf)
+		}
+	}
+
+	impl<'r, DB: Database> Decode<'r, DB> for Keypair
+	where
+		&'r str: Decode<'r, DB>,
+	{
+		fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+			let s = <&str as Decode<DB>>::decode(value)?;
+			s.parse().map_err(Into::into)
+		}
+	}
+}
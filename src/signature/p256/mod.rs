@@ -0,0 +1,101 @@
+//! ECDSA signatures over the NIST P-256 curve with SHA-256 (ES256).
+//!
+//! Prefer the ed25519 types in [`signature`](crate::signature) unless a
+//! partner only accepts ES256.
+
+mod keypair;
+pub use keypair::Keypair;
+
+mod public_key;
+pub use public_key::PublicKey;
+
+#[allow(clippy::module_inception)]
+mod signature;
+pub use signature::Signature;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	#[test]
+	fn sign_verify() {
+		let keypair = Keypair::new();
+		let sign = keypair.sign("Hello, World!");
+		assert!(keypair.verify("Hello, World!", &sign));
+		assert!(!keypair.verify("Hello, World?", &sign));
+
+		let other = Keypair::new();
+		assert!(!other.public().verify("Hello, World!", &sign));
+
+		let public =
+			PublicKey::try_from_slice(&keypair.public().to_bytes()).unwrap();
+		assert_eq!(&public, keypair.public());
+		let public =
+			PublicKey::try_from_slice(&keypair.public().to_uncompressed())
+				.unwrap();
+		assert_eq!(&public, keypair.public());
+
+		let der = sign.to_der();
+		assert_eq!(Signature::from_der(&der).unwrap(), sign);
+
+		assert!(Keypair::try_from_slice(&[0u8; 32]).is_err());
+		assert!(Keypair::try_from_slice(&[1u8; 31]).is_err());
+		assert!(Signature::try_from_slice(&[0u8; 64]).is_err());
+	}
+
+	#[cfg(feature = "hex")]
+	#[test]
+	fn rfc6979_vector() {
+		// RFC 6979 A.2.5 with SHA-256 and the message "sample"
+		let keypair = Keypair::from_hex(
+			"c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+		)
+		.unwrap();
+		assert_eq!(
+			hex::encode(&keypair.public().to_uncompressed()[1..]),
+			"60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6\
+			7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"
+		);
+
+		let sign = keypair.sign("sample");
+		assert_eq!(
+			sign.to_hex(),
+			"efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+			f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"
+		);
+		assert!(keypair.public().verify("sample", &sign));
+	}
+
+	#[cfg(feature = "b64")]
+	#[test]
+	fn b64() {
+		let keypair = Keypair::new();
+		let sign = keypair.sign("Hello, World!");
+
+		let keypair_2 = Keypair::from_str(&keypair.to_string()).unwrap();
+		assert_eq!(keypair.to_bytes(), keypair_2.to_bytes());
+		let public =
+			PublicKey::from_str(&keypair.public().to_string()).unwrap();
+		assert_eq!(&public, keypair.public());
+		let sign_2 = Signature::from_str(&sign.to_string()).unwrap();
+		assert_eq!(sign, sign_2);
+	}
+
+	#[cfg(all(feature = "b64", feature = "serde"))]
+	#[test]
+	fn serde() {
+		let keypair = Keypair::new();
+		let sign = keypair.sign("Hello, World!");
+
+		let json = serde_json::to_string(keypair.public()).unwrap();
+		let public: PublicKey = serde_json::from_str(&json).unwrap();
+		assert_eq!(&public, keypair.public());
+
+		let json = serde_json::to_string(&sign).unwrap();
+		let sign_2: Signature = serde_json::from_str(&json).unwrap();
+		assert_eq!(sign, sign_2);
+	}
+}
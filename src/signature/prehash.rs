@@ -0,0 +1,48 @@
+use std::fmt;
+use std::io;
+
+use ed25519_dalek::{Digest, Sha512};
+
+/// A SHA-512 hasher which allows to sign or verify a message with Ed25519ph
+/// (RFC 8032) without having the whole message in memory.
+///
+/// ## Note
+/// A signature created with `sign_prehashed` is not the same as one created
+/// with `sign` and can only be verified with `verify_prehashed`.
+#[derive(Clone)]
+pub struct Prehash {
+	inner: Sha512,
+}
+
+impl Prehash {
+	pub fn new() -> Self {
+		Self {
+			inner: Sha512::new(),
+		}
+	}
+
+	pub fn update(&mut self, data: impl AsRef<[u8]>) {
+		self.inner.update(data);
+	}
+
+	pub(crate) fn into_inner(self) -> Sha512 {
+		self.inner
+	}
+}
+
+impl fmt::Debug for Prehash {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Prehash")
+	}
+}
+
+impl io::Write for Prehash {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
@@ -1,5 +1,7 @@
-use super::Signature;
-#[cfg(feature = "b64")]
+use super::{Prehash, Signature};
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex", feature = "pkcs8"))]
 use crate::error::DecodeError;
 use crate::error::TryFromError;
 
@@ -32,10 +34,16 @@ impl PublicKey {
 
 	/// ## Panics
 	/// if the slice is not 32 bytes long.
+	#[deprecated(note = "use `try_from_slice` instead")]
 	pub fn from_slice(slice: &[u8]) -> Self {
 		slice.try_into().unwrap()
 	}
 
+	/// Returns an error if the slice is not 32 bytes long or not a valid public key.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
 	pub fn to_bytes(&self) -> [u8; 32] {
 		self.inner.to_bytes()
 	}
@@ -45,6 +53,52 @@ impl PublicKey {
 			.verify_strict(msg.as_ref(), signature.inner())
 			.is_ok()
 	}
+
+	/// Verifies a signature created with `sign_with_context`.
+	pub fn verify_with_context(
+		&self,
+		context: &str,
+		msg: impl AsRef<[u8]>,
+		signature: &Signature,
+	) -> bool {
+		self.verify(super::context_message(context, msg.as_ref()), signature)
+	}
+
+	/// Verifies a signature created with `sign_prehashed`.
+	pub fn verify_prehashed(
+		&self,
+		prehash: Prehash,
+		signature: &Signature,
+	) -> bool {
+		self.inner
+			.verify_prehashed_strict(
+				prehash.into_inner(),
+				None,
+				signature.inner(),
+			)
+			.is_ok()
+	}
+
+	/// Converts the public key to an X25519 public key using the
+	/// birational map from edwards25519 to curve25519.
+	#[cfg(feature = "cipher")]
+	pub fn to_cipher_public(&self) -> crate::cipher::PublicKey {
+		self.inner.to_montgomery().to_bytes().into()
+	}
+
+	/// Returns the SHA-256 hash of the public key bytes, which can be used
+	/// as a stable identifier for the key.
+	#[cfg(feature = "hash")]
+	pub fn fingerprint(&self) -> crate::hash::sha2::Sha256Hash {
+		crate::hash::sha2::sha256(self.to_bytes())
+	}
+
+	/// Returns the first 8 bytes of the fingerprint as base64, useful in
+	/// logs.
+	#[cfg(all(feature = "hash", feature = "b64"))]
+	pub fn short_fingerprint(&self) -> String {
+		URL_SAFE_NO_PAD.encode(&self.fingerprint().as_ref()[..8])
+	}
 }
 
 #[cfg(not(feature = "b64"))]
@@ -110,12 +164,83 @@ impl AsRef<[u8]> for PublicKey {
 	}
 }
 
-#[cfg(all(feature = "b64", feature = "serde"))]
-mod impl_serde {
+#[cfg(feature = "hex")]
+impl PublicKey {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).and_then(|bytes| {
+			Self::try_from(bytes.as_ref()).map_err(DecodeError::inv_bytes)
+		})
+	}
+}
+
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, PublicKey> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		crate::encoding::fmt_hex(self.get().as_ref(), f)
+	}
+}
+
+#[cfg(feature = "pkcs8")]
+impl PublicKey {
+	/// Returns the public key as a SubjectPublicKeyInfo DER document.
+	pub fn to_public_key_der(&self) -> Vec<u8> {
+		use ed::pkcs8::EncodePublicKey;
+
+		self.inner
+			.to_public_key_der()
+			// encoding 32 bytes cannot fail
+			.unwrap()
+			.into_vec()
+	}
+
+	/// Parses a SubjectPublicKeyInfo DER document.
+	pub fn from_public_key_der(der: &[u8]) -> Result<Self, DecodeError> {
+		use ed::pkcs8::DecodePublicKey;
+
+		ed::VerifyingKey::from_public_key_der(der)
+			.map(Self::from_raw)
+			.map_err(DecodeError::inv_bytes)
+	}
+
+	/// Returns the public key as a SubjectPublicKeyInfo PEM document
+	/// (`-----BEGIN PUBLIC KEY-----`).
+	pub fn to_public_key_pem(&self) -> String {
+		use ed::pkcs8::{spki::der::pem::LineEnding, EncodePublicKey};
+
+		self.inner
+			.to_public_key_pem(LineEnding::LF)
+			// encoding 32 bytes cannot fail
+			.unwrap()
+	}
 
+	/// Parses a SubjectPublicKeyInfo PEM document as produced by OpenSSL.
+	pub fn from_public_key_pem(pem: &str) -> Result<Self, DecodeError> {
+		use ed::pkcs8::DecodePublicKey;
+
+		ed::VerifyingKey::from_public_key_pem(pem)
+			.map(Self::from_raw)
+			.map_err(DecodeError::inv_bytes)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
 	use super::*;
 
+	#[cfg(feature = "b64")]
 	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
 	use std::str::FromStr;
 
 	use _serde::de::Error;
@@ -126,7 +251,12 @@ mod impl_serde {
 		where
 			S: Serializer,
 		{
-			serializer.collect_str(&self)
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
 		}
 	}
 
@@ -135,8 +265,16 @@ mod impl_serde {
 		where
 			D: Deserializer<'de>,
 		{
-			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
-			Self::from_str(s.as_ref()).map_err(D::Error::custom)
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			Self::try_from(bytes.as_ref()).map_err(D::Error::custom)
 		}
 	}
 }
@@ -222,6 +360,14 @@ mod impl_postgres {
 		where
 			Self: Sized,
 		{
+			if *ty == Type::BYTEA {
+				return <&[u8] as ToSql>::to_sql(
+					&self.to_bytes().as_ref(),
+					ty,
+					out,
+				);
+			}
+
 			self.to_string().to_sql(ty, out)
 		}
 
@@ -229,7 +375,7 @@ mod impl_postgres {
 		where
 			Self: Sized,
 		{
-			<&str as ToSql>::accepts(ty)
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
 		}
 
 		to_sql_checked!();
@@ -240,12 +386,79 @@ mod impl_postgres {
 			ty: &Type,
 			raw: &'r [u8],
 		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Self::try_from(raw).map_err(Into::into);
+			}
+
 			let s = <&str as FromSql>::from_sql(ty, raw)?;
 			s.parse().map_err(Into::into)
 		}
 
 		fn accepts(ty: &Type) -> bool {
-			<&str as FromSql>::accepts(ty)
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "sqlx"))]
+mod impl_sqlx {
+	use super::*;
+
+	use sqlx::encode::IsNull;
+	use sqlx::error::BoxDynError;
+	use sqlx::{Database, Decode, Encode, Type};
+
+	impl<DB: Database> Type<DB> for PublicKey
+	where
+		String: Type<DB>,
+	{
+		fn type_info() -> DB::TypeInfo {
+			<String as Type<DB>>::type_info()
+		}
+
+		fn compatible(ty: &DB::TypeInfo) -> bool {
+			<String as Type<DB>>::compatible(ty)
+		}
+	}
+
+	impl<'q, DB: Database> Encode<'q, DB> for PublicKey
+	where
+		String: Encode<'q, DB>,
+	{
+		fn encode_by_ref(
+			&self,
+			buf: &mut DB::ArgumentBuffer<'q>,
+		) -> Result<IsNull, BoxDynError> {
+			self.to_string().encode(buf)
+		}
+	}
+
+	impl<'r, DB: Database> Decode<'r, DB> for PublicKey
+	where
+		&'r str: Decode<'r, DB>,
+	{
+		fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+			let s = <&str as Decode<DB>>::decode(value)?;
+			s.parse().map_err(Into::into)
+		}
+	}
+}
+
+#[cfg(feature = "rustcrypto-traits")]
+mod impl_rustcrypto {
+	use super::*;
+
+	impl signature::Verifier<Signature> for PublicKey {
+		fn verify(
+			&self,
+			msg: &[u8],
+			signature: &Signature,
+		) -> Result<(), signature::Error> {
+			if PublicKey::verify(self, msg, signature) {
+				Ok(())
+			} else {
+				Err(signature::Error::new())
+			}
 		}
 	}
 }
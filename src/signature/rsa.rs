@@ -0,0 +1,195 @@
+use crate::error::DecodeError;
+
+use std::fmt;
+
+use rsa::pkcs1::{DecodeRsaPublicKey, EncodeRsaPublicKey};
+use rsa::pkcs8::{DecodePublicKey, EncodePublicKey, LineEnding};
+use rsa::traits::PublicKeyParts;
+use rsa::{Pkcs1v15Sign, Pss};
+
+use sha2::{Digest, Sha256};
+
+/// An RSA public key which can only verify signatures.
+///
+/// Only keys between 2048 and 4096 bits are accepted, messages are hashed
+/// with SHA-256.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RsaPublicKey {
+	inner: rsa::RsaPublicKey,
+}
+
+impl RsaPublicKey {
+	pub const MIN_BITS: usize = 2048;
+	pub const MAX_BITS: usize = 4096;
+
+	fn from_raw(inner: rsa::RsaPublicKey) -> Result<Self, DecodeError> {
+		let bits = inner.n().bits();
+		if !(Self::MIN_BITS..=Self::MAX_BITS).contains(&bits) {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		Ok(Self { inner })
+	}
+
+	/// Parses a SubjectPublicKeyInfo DER document.
+	pub fn from_public_key_der(der: &[u8]) -> Result<Self, DecodeError> {
+		rsa::RsaPublicKey::from_public_key_der(der)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(Self::from_raw)
+	}
+
+	/// Parses a SubjectPublicKeyInfo PEM document
+	/// (`-----BEGIN PUBLIC KEY-----`).
+	pub fn from_public_key_pem(pem: &str) -> Result<Self, DecodeError> {
+		rsa::RsaPublicKey::from_public_key_pem(pem)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(Self::from_raw)
+	}
+
+	/// Parses a PKCS#1 DER document.
+	pub fn from_pkcs1_der(der: &[u8]) -> Result<Self, DecodeError> {
+		rsa::RsaPublicKey::from_pkcs1_der(der)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(Self::from_raw)
+	}
+
+	/// Parses a PKCS#1 PEM document (`-----BEGIN RSA PUBLIC KEY-----`).
+	pub fn from_pkcs1_pem(pem: &str) -> Result<Self, DecodeError> {
+		rsa::RsaPublicKey::from_pkcs1_pem(pem)
+			.map_err(DecodeError::inv_bytes)
+			.and_then(Self::from_raw)
+	}
+
+	/// Returns the public key as a SubjectPublicKeyInfo DER document.
+	pub fn to_public_key_der(&self) -> Vec<u8> {
+		self.inner
+			.to_public_key_der()
+			// encoding a valid key cannot fail
+			.unwrap()
+			.into_vec()
+	}
+
+	/// Returns the public key as a SubjectPublicKeyInfo PEM document.
+	pub fn to_public_key_pem(&self) -> String {
+		self.inner
+			.to_public_key_pem(LineEnding::LF)
+			// encoding a valid key cannot fail
+			.unwrap()
+	}
+
+	/// Returns the public key as a PKCS#1 DER document.
+	pub fn to_pkcs1_der(&self) -> Vec<u8> {
+		self.inner
+			.to_pkcs1_der()
+			// encoding a valid key cannot fail
+			.unwrap()
+			.into_vec()
+	}
+
+	/// Returns the size of the modulus in bits.
+	pub fn bits(&self) -> usize {
+		self.inner.n().bits()
+	}
+
+	/// Verifies a PKCS#1 v1.5 signature with SHA-256 (RS256).
+	pub fn verify_pkcs1v15(
+		&self,
+		msg: impl AsRef<[u8]>,
+		signature: &[u8],
+	) -> bool {
+		let hashed = Sha256::digest(msg.as_ref());
+		self.inner
+			.verify(Pkcs1v15Sign::new::<Sha256>(), &hashed, signature)
+			.is_ok()
+	}
+
+	/// Verifies a PSS signature with SHA-256, MGF1 with SHA-256 and a 32
+	/// byte salt (PS256).
+	pub fn verify_pss(&self, msg: impl AsRef<[u8]>, signature: &[u8]) -> bool {
+		let hashed = Sha256::digest(msg.as_ref());
+		self.inner
+			.verify(Pss::new::<Sha256>(), &hashed, signature)
+			.is_ok()
+	}
+}
+
+impl fmt::Debug for RsaPublicKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("RsaPublicKey")
+			.field("bits", &self.bits())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+#[cfg(feature = "b64")]
+mod tests {
+	use super::*;
+
+	use base64::engine::{general_purpose::STANDARD, Engine};
+
+	// generated with python cryptography
+	const PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEApN/C2khZf694p1YWW5Et
+zqVerknpN3EPkAMFyqN1m/MU2DUrtwxGPSYH4vg1d+aUXdqpEpwL4UfAzuYfB8Nm
+f0JkH00DdPHiAv2egX2RMwZ4Uk8+iA/Yah0Co9W6svYbYRJnCT1TkzW2m8Jpfeix
+c1oaUQEwIwlIP/pwYEBAQuwJRrEqKjmTAswI7rTLBZvaxGV+7uRiR7NyNMdWolIi
+NabeHaKwtqHc0fEpdU26rd5lZVYelCZLo30FlQxA7y7mPXpMec1CdnftdCNw4b1g
+JSt6bYxVCpzV3SzENejYhIs7XnABlELowHAx/FLi6hpsMwI0fu6xLjlnvRFPsDZo
+4wIDAQAB
+-----END PUBLIC KEY-----
+";
+
+	const SMALL_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDJGNpU1m8B9by9+5aqG+sTZFvX
+Mfew7T9FnmJSbbchtRI8qk9pmRhQQG9b5Xe3dclRM4ICZYsSRNcTonJBRJ5Vk7J1
+IK/y8mQbp/Ro44iZ9lN+WhCfXChpUPkgANBjif1BUnNBAG091WU7OMAjZLg1mNf4
+AxC1ake5dCreFcHDeQIDAQAB
+-----END PUBLIC KEY-----
+";
+
+	const MSG: &str = "license: chuchi-crypto";
+
+	const PKCS1V15_SIGNATURE: &str =
+		"H/QaFnFOaDeSj613q7Bdm5m7Np83cyj3dMG8RWTlPTYiDB/trHcYmtBH/9I+\
+		fltW6H//sJ6HZVhiK1Jb+h3sbgrHm8SN7jVdF8EwImPi758d/llZL4xNpDfr\
+		yZDLZMSTEuWJHOFk6tC7O3W7j3PNH4tt/WHPcOnwD1j9PsX+/n8rtRvZH8wk\
+		dZ4ZODa8/GrDg6n3CUimXir8oyTGv4Sy+eLh1vI3/Nip6hfU/uohsFdfHdDP\
+		NA+2RngNtvQaGz+n+9U6fg0XGVj1qVL+ISd93981lhjDyJ6iIFz/8kyQR8qB\
+		A0BjfvGwzT0GZymTEcYAizI9TUnAV/j3w5mLYa0ZBw==";
+
+	const PSS_SIGNATURE: &str =
+		"ef4JSA/AlxVAhOSIeVGEK85Evb5O/ILLs49KXq/wZktoHStYxeifYrfarTkD\
+		srwImAVVvFHcOQMPVsF4tMUEnZoskQjgDdhtCesOEIuEgkQAFSbH8DUsnt14\
+		+kzUWdMiPDf6qvPSsZmW4kOvNNnc3OHyObC8DZpXHST+KEYA0E5e/ajteqN2\
+		y1yuhlI92kOSTCVkDkPsRZKclbV5QmEJz9yDb8zsvkopd+xiSKoLPWPkJtUu\
+		LF1Uc95gP9exbqyz5oR1sslp1qQ/XD6DExLTjT5y+0rcBdOdg0FxiZAgU0UI\
+		46ggFut1EQ02UWCwnADItVbgEw9SC9JgoJJyFHnb9A==";
+
+	#[test]
+	fn verify() {
+		let key = RsaPublicKey::from_public_key_pem(PUBLIC_KEY).unwrap();
+		assert_eq!(key.bits(), 2048);
+
+		let pkcs1v15 = STANDARD.decode(PKCS1V15_SIGNATURE).unwrap();
+		let pss = STANDARD.decode(PSS_SIGNATURE).unwrap();
+		assert!(key.verify_pkcs1v15(MSG, &pkcs1v15));
+		assert!(key.verify_pss(MSG, &pss));
+
+		assert!(!key.verify_pkcs1v15("license: other", &pkcs1v15));
+		assert!(!key.verify_pss("license: other", &pss));
+		assert!(!key.verify_pkcs1v15(MSG, &pss));
+		assert!(!key.verify_pss(MSG, &pkcs1v15));
+
+		let key_2 = RsaPublicKey::from_public_key_der(&key.to_public_key_der())
+			.unwrap();
+		assert_eq!(key, key_2);
+		let key_2 = RsaPublicKey::from_pkcs1_der(&key.to_pkcs1_der()).unwrap();
+		assert_eq!(key, key_2);
+
+		assert!(matches!(
+			RsaPublicKey::from_public_key_pem(SMALL_PUBLIC_KEY),
+			Err(DecodeError::InvalidLength)
+		));
+	}
+}
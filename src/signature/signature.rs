@@ -1,4 +1,6 @@
-#[cfg(feature = "b64")]
+#[cfg(feature = "hex")]
+use crate::encoding::Hex;
+#[cfg(any(feature = "b64", feature = "hex"))]
 use crate::error::DecodeError;
 use crate::error::TryFromError;
 
@@ -24,10 +26,16 @@ impl Signature {
 
 	/// ## Panics
 	/// if the slice is not 64 bytes long.
+	#[deprecated(note = "use `try_from_slice` instead")]
 	pub fn from_slice(slice: &[u8]) -> Self {
 		slice.try_into().unwrap()
 	}
 
+	/// Returns an error if the slice is not 64 bytes long.
+	pub fn try_from_slice(slice: &[u8]) -> Result<Self, TryFromError> {
+		slice.try_into()
+	}
+
 	pub fn to_bytes(&self) -> [u8; 64] {
 		self.inner.to_bytes()
 	}
@@ -88,12 +96,40 @@ impl crate::FromStr for Signature {
 	}
 }
 
-#[cfg(all(feature = "b64", feature = "serde"))]
-mod impl_serde {
+#[cfg(feature = "hex")]
+impl Signature {
+	/// Returns an adapter which formats the bytes as lowercase hex.
+	pub fn hex(&self) -> Hex<'_, Self> {
+		Hex::new(self)
+	}
+
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		self.hex().to_string()
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		crate::decode_hex::<{ Self::LEN }>(s).and_then(|bytes| {
+			Self::try_from(bytes.as_ref()).map_err(DecodeError::inv_bytes)
+		})
+	}
+}
 
+#[cfg(feature = "hex")]
+impl fmt::Display for Hex<'_, Signature> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		crate::encoding::fmt_hex(&self.get().to_bytes(), f)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
 	use super::*;
 
+	#[cfg(feature = "b64")]
 	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
 	use std::str::FromStr;
 
 	use _serde::de::Error;
@@ -104,7 +140,12 @@ mod impl_serde {
 		where
 			S: Serializer,
 		{
-			serializer.collect_str(&self)
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.to_bytes())
 		}
 	}
 
@@ -113,8 +154,16 @@ mod impl_serde {
 		where
 			D: Deserializer<'de>,
 		{
-			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
-			Self::from_str(s.as_ref()).map_err(D::Error::custom)
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize::<_, { Self::LEN }>(
+				deserializer,
+			)?;
+			Self::try_from(bytes.as_ref()).map_err(D::Error::custom)
 		}
 	}
 }
@@ -163,7 +212,7 @@ mod impl_protobuf {
 		const WIRE_TYPE: WireType = WireType::Len;
 
 		fn decode_default() -> Self {
-			Self::from_slice(&[0u8; 32])
+			Self::from_sign(ed::Signature::from_bytes(&[0u8; 64]))
 		}
 
 		fn merge(
@@ -198,6 +247,14 @@ mod impl_postgres {
 		where
 			Self: Sized,
 		{
+			if *ty == Type::BYTEA {
+				return <&[u8] as ToSql>::to_sql(
+					&self.to_bytes().as_ref(),
+					ty,
+					out,
+				);
+			}
+
 			self.to_string().to_sql(ty, out)
 		}
 
@@ -205,7 +262,7 @@ mod impl_postgres {
 		where
 			Self: Sized,
 		{
-			<&str as ToSql>::accepts(ty)
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
 		}
 
 		to_sql_checked!();
@@ -216,12 +273,77 @@ mod impl_postgres {
 			ty: &Type,
 			raw: &'r [u8],
 		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Self::try_from(raw).map_err(Into::into);
+			}
+
 			let s = <&str as FromSql>::from_sql(ty, raw)?;
 			s.parse().map_err(Into::into)
 		}
 
 		fn accepts(ty: &Type) -> bool {
-			<&str as FromSql>::accepts(ty)
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "sqlx"))]
+mod impl_sqlx {
+	use super::*;
+
+	use sqlx::encode::IsNull;
+	use sqlx::error::BoxDynError;
+	use sqlx::{Database, Decode, Encode, Type};
+
+	impl<DB: Database> Type<DB> for Signature
+	where
+		String: Type<DB>,
+	{
+		fn type_info() -> DB::TypeInfo {
+			<String as Type<DB>>::type_info()
+		}
+
+		fn compatible(ty: &DB::TypeInfo) -> bool {
+			<String as Type<DB>>::compatible(ty)
+		}
+	}
+
+	impl<'q, DB: Database> Encode<'q, DB> for Signature
+	where
+		String: Encode<'q, DB>,
+	{
+		fn encode_by_ref(
+			&self,
+			buf: &mut DB::ArgumentBuffer<'q>,
+		) -> Result<IsNull, BoxDynError> {
+			self.to_string().encode(buf)
+		}
+	}
+
+	impl<'r, DB: Database> Decode<'r, DB> for Signature
+	where
+		&'r str: Decode<'r, DB>,
+	{
+		fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+			let s = <&str as Decode<DB>>::decode(value)?;
+			s.parse().map_err(Into::into)
 		}
 	}
 }
+
+#[cfg(feature = "rustcrypto-traits")]
+mod impl_rustcrypto {
+	use super::*;
+
+	use ::signature::SignatureEncoding;
+
+	impl From<Signature> for [u8; 64] {
+		fn from(signature: Signature) -> Self {
+			signature.to_bytes()
+		}
+	}
+
+	impl SignatureEncoding for Signature {
+		type Repr = [u8; 64];
+	}
+}
@@ -0,0 +1,396 @@
+use super::{AsyncSigner, Keypair, Keyring, PublicKey, Signature};
+use crate::error::{DecodeError, SignError, TryFromError};
+use crate::unix_secs;
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+const VERSION: u8 = 1;
+
+const KIND_PUBLIC_KEY: u8 = 1;
+const KIND_KEY_ID: u8 = 2;
+
+/// Identifies the key which signed a `SignedMessage`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerId {
+	PublicKey(PublicKey),
+	/// The id of a key in a `Keyring`.
+	KeyId(String),
+}
+
+/// A payload together with the signer, the time it was signed at and the
+/// signature.
+///
+/// The signature covers all other fields.
+///
+/// ## Layout
+/// `version (1 byte) | kind (1 byte) | signer | timestamp (8 bytes) |
+/// payload | signature (64 bytes)`
+///
+/// The signer is either a public key (32 bytes) or a key id prefixed with
+/// its length (2 bytes). All integers are big endian.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::signature::{Keypair, Keyring, SignedMessage};
+///
+/// let keypair = Keypair::new();
+/// let mut keyring = Keyring::new();
+/// keyring.insert("2024", keypair.public().clone());
+///
+/// let msg = SignedMessage::sign_with_key_id(&keypair, "2024", b"hello");
+///
+/// let msg = SignedMessage::from_bytes(&msg.to_bytes()).unwrap();
+/// assert!(msg.verify(&keyring));
+/// assert_eq!(msg.payload(), b"hello");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct SignedMessage {
+	signer: SignerId,
+	timestamp: u64,
+	payload: Vec<u8>,
+	signature: Signature,
+}
+
+impl SignedMessage {
+	/// Signs a payload and includes the public key of the keypair.
+	pub fn sign(keypair: &Keypair, payload: impl Into<Vec<u8>>) -> Self {
+		let signer = SignerId::PublicKey(keypair.public().clone());
+		Self::sign_with_signer(keypair, signer, payload.into())
+	}
+
+	/// Signs a payload and includes the key id instead of the public key.
+	///
+	/// ## Panics
+	/// if the key id is longer than `u16::MAX` bytes.
+	pub fn sign_with_key_id(
+		keypair: &Keypair,
+		key_id: impl Into<String>,
+		payload: impl Into<Vec<u8>>,
+	) -> Self {
+		let signer = SignerId::KeyId(key_id.into());
+		Self::sign_with_signer(keypair, signer, payload.into())
+	}
+
+	fn sign_with_signer(
+		keypair: &Keypair,
+		signer: SignerId,
+		payload: Vec<u8>,
+	) -> Self {
+		let timestamp = unix_secs(crate::now());
+		let msg = encode(&signer, timestamp, &payload);
+
+		Self {
+			signer,
+			timestamp,
+			payload,
+			signature: keypair.sign(msg),
+		}
+	}
+
+	/// Like `sign` but signs with an `AsyncSigner`.
+	pub async fn sign_async<K: AsyncSigner + ?Sized>(
+		signer: &K,
+		payload: impl Into<Vec<u8>>,
+	) -> Result<Self, SignError> {
+		let payload = payload.into();
+		let timestamp = unix_secs(crate::now());
+		let signer_id = SignerId::PublicKey(signer.public().clone());
+		let signature = signer
+			.sign(&encode(&signer_id, timestamp, &payload))
+			.await?;
+
+		Ok(Self {
+			signer: signer_id,
+			timestamp,
+			payload,
+			signature,
+		})
+	}
+
+	/// Verifies the signature with the matching key from the keyring.
+	///
+	/// If the message contains a public key, the key needs to be in the
+	/// keyring.
+	pub fn verify(&self, keyring: &Keyring) -> bool {
+		let msg = self.signed_bytes();
+
+		match &self.signer {
+			SignerId::PublicKey(key) => {
+				keyring.iter().any(|(_, k)| k == key)
+					&& key.verify(msg, &self.signature)
+			}
+			SignerId::KeyId(id) => keyring.verify(id, msg, &self.signature),
+		}
+	}
+
+	/// Verifies the signature with the given public key, ignoring the
+	/// signer stored in the message.
+	pub fn verify_with(&self, key: &PublicKey) -> bool {
+		key.verify(self.signed_bytes(), &self.signature)
+	}
+
+	pub fn signer(&self) -> &SignerId {
+		&self.signer
+	}
+
+	/// The time the message was signed at in seconds since the unix epoch.
+	pub fn timestamp(&self) -> u64 {
+		self.timestamp
+	}
+
+	/// Returns the payload without verifying the signature.
+	pub fn payload(&self) -> &[u8] {
+		&self.payload
+	}
+
+	pub fn into_payload(self) -> Vec<u8> {
+		self.payload
+	}
+
+	pub fn signature(&self) -> &Signature {
+		&self.signature
+	}
+
+	fn signed_bytes(&self) -> Vec<u8> {
+		encode(&self.signer, self.timestamp, &self.payload)
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = self.signed_bytes();
+		bytes.extend_from_slice(&self.signature.to_bytes());
+
+		bytes
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let (&version, rest) =
+			bytes.split_first().ok_or(DecodeError::InvalidLength)?;
+		if version != VERSION {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		let (&kind, rest) =
+			rest.split_first().ok_or(DecodeError::InvalidLength)?;
+		let (signer, rest) = match kind {
+			KIND_PUBLIC_KEY => {
+				let (key, rest) = split(rest, PublicKey::LEN)?;
+				let key = PublicKey::try_from_slice(key)
+					.map_err(|_| DecodeError::InvalidBytes)?;
+				(SignerId::PublicKey(key), rest)
+			}
+			KIND_KEY_ID => {
+				let (len, rest) = split(rest, 2)?;
+				let len = u16::from_be_bytes(len.try_into().unwrap());
+				let (id, rest) = split(rest, len as usize)?;
+				let id = std::str::from_utf8(id)
+					.map_err(|_| DecodeError::InvalidBytes)?;
+				(SignerId::KeyId(id.into()), rest)
+			}
+			_ => return Err(DecodeError::InvalidBytes),
+		};
+
+		let (timestamp, rest) = split(rest, 8)?;
+		let timestamp = u64::from_be_bytes(timestamp.try_into().unwrap());
+
+		if rest.len() < Signature::LEN {
+			return Err(DecodeError::InvalidLength);
+		}
+		let (payload, signature) = rest.split_at(rest.len() - Signature::LEN);
+
+		Ok(Self {
+			signer,
+			timestamp,
+			payload: payload.to_vec(),
+			signature: Signature::try_from_slice(signature).unwrap(),
+		})
+	}
+}
+
+fn encode(signer: &SignerId, timestamp: u64, payload: &[u8]) -> Vec<u8> {
+	let mut bytes = vec![VERSION];
+	match signer {
+		SignerId::PublicKey(key) => {
+			bytes.push(KIND_PUBLIC_KEY);
+			bytes.extend_from_slice(key.as_ref());
+		}
+		SignerId::KeyId(id) => {
+			let len = u16::try_from(id.len()).expect("key id too long");
+			bytes.push(KIND_KEY_ID);
+			bytes.extend_from_slice(&len.to_be_bytes());
+			bytes.extend_from_slice(id.as_bytes());
+		}
+	}
+	bytes.extend_from_slice(&timestamp.to_be_bytes());
+	bytes.extend_from_slice(payload);
+
+	bytes
+}
+
+fn split(bytes: &[u8], at: usize) -> Result<(&[u8], &[u8]), DecodeError> {
+	if bytes.len() < at {
+		return Err(DecodeError::InvalidLength);
+	}
+
+	Ok(bytes.split_at(at))
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for SignedMessage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SignedMessage")
+			.field("signer", &self.signer)
+			.field("timestamp", &self.timestamp)
+			.field("payload", &self.payload)
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for SignedMessage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("SignedMessage")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for SignedMessage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.to_bytes(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl TryFrom<&[u8]> for SignedMessage {
+	type Error = TryFromError;
+
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		Self::from_bytes(v).map_err(TryFromError::from_any)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for SignedMessage {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let bytes =
+			URL_SAFE_NO_PAD.decode(s).map_err(DecodeError::inv_bytes)?;
+		Self::from_bytes(&bytes)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for SignedMessage {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(&self.to_bytes())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for SignedMessage {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			let bytes = crate::serde_bytes::deserialize_vec(deserializer)?;
+			Self::from_bytes(&bytes).map_err(D::Error::custom)
+		}
+	}
+}
+
+#[cfg(feature = "protobuf")]
+mod impl_protobuf {
+	use super::*;
+
+	use protopuffer::{
+		bytes::BytesWrite,
+		decode::{DecodeMessage, FieldKind},
+		encode::{
+			EncodeError, EncodeMessage, FieldOpt, MessageEncoder, SizeBuilder,
+		},
+		WireType,
+	};
+
+	impl EncodeMessage for SignedMessage {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn is_default(&self) -> bool {
+			false
+		}
+
+		fn encoded_size(
+			&mut self,
+			field: Option<FieldOpt>,
+			builder: &mut SizeBuilder,
+		) -> Result<(), EncodeError> {
+			self.to_bytes().encoded_size(field, builder)
+		}
+
+		fn encode<B>(
+			&mut self,
+			field: Option<FieldOpt>,
+			encoder: &mut MessageEncoder<B>,
+		) -> Result<(), EncodeError>
+		where
+			B: BytesWrite,
+		{
+			self.to_bytes().encode(field, encoder)
+		}
+	}
+
+	impl<'m> DecodeMessage<'m> for SignedMessage {
+		const WIRE_TYPE: WireType = WireType::Len;
+
+		fn decode_default() -> Self {
+			Self {
+				signer: SignerId::KeyId(String::new()),
+				timestamp: 0,
+				payload: vec![],
+				signature: Signature::try_from_slice(&[0u8; 64]).unwrap(),
+			}
+		}
+
+		fn merge(
+			&mut self,
+			kind: FieldKind<'m>,
+			_is_field: bool,
+		) -> Result<(), protopuffer::decode::DecodeError> {
+			let bytes = kind.try_unwrap_len()?;
+			*self = Self::from_bytes(bytes).map_err(|e| {
+				protopuffer::decode::DecodeError::Other(e.to_string())
+			})?;
+
+			Ok(())
+		}
+	}
+}
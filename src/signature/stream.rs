@@ -0,0 +1,126 @@
+use super::{Keypair, Prehash, PublicKey, Signature};
+
+use std::fmt;
+use std::io;
+
+/// Signs a message in chunks using Ed25519ph.
+///
+/// The signature can be verified with `Verifier` or `verify_prehashed`.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::signature::{Keypair, Signer, Verifier};
+///
+/// let keypair = Keypair::new();
+///
+/// let mut signer = Signer::new(&keypair);
+/// signer.read_from(&mut &b"firmware image"[..]).unwrap();
+/// let signature = signer.finalize();
+///
+/// let mut verifier = Verifier::new(keypair.public());
+/// verifier.update(b"firmware ");
+/// verifier.update(b"image");
+/// assert!(verifier.finalize(&signature));
+/// ```
+#[derive(Clone)]
+pub struct Signer<'a> {
+	keypair: &'a Keypair,
+	prehash: Prehash,
+}
+
+impl<'a> Signer<'a> {
+	pub fn new(keypair: &'a Keypair) -> Self {
+		Self {
+			keypair,
+			prehash: Prehash::new(),
+		}
+	}
+
+	pub fn update(&mut self, data: impl AsRef<[u8]>) {
+		self.prehash.update(data);
+	}
+
+	/// Reads until the end of the reader, returning the number of bytes
+	/// read.
+	pub fn read_from<R: io::Read + ?Sized>(
+		&mut self,
+		reader: &mut R,
+	) -> io::Result<u64> {
+		io::copy(reader, &mut self.prehash)
+	}
+
+	pub fn finalize(self) -> Signature {
+		self.keypair.sign_prehashed(self.prehash)
+	}
+}
+
+impl fmt::Debug for Signer<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Signer")
+			.field("public", self.keypair.public())
+			.finish()
+	}
+}
+
+impl io::Write for Signer<'_> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Verifies a message in chunks which was signed using Ed25519ph.
+#[derive(Clone)]
+pub struct Verifier<'a> {
+	public_key: &'a PublicKey,
+	prehash: Prehash,
+}
+
+impl<'a> Verifier<'a> {
+	pub fn new(public_key: &'a PublicKey) -> Self {
+		Self {
+			public_key,
+			prehash: Prehash::new(),
+		}
+	}
+
+	pub fn update(&mut self, data: impl AsRef<[u8]>) {
+		self.prehash.update(data);
+	}
+
+	/// Reads until the end of the reader, returning the number of bytes
+	/// read.
+	pub fn read_from<R: io::Read + ?Sized>(
+		&mut self,
+		reader: &mut R,
+	) -> io::Result<u64> {
+		io::copy(reader, &mut self.prehash)
+	}
+
+	pub fn finalize(self, signature: &Signature) -> bool {
+		self.public_key.verify_prehashed(self.prehash, signature)
+	}
+}
+
+impl fmt::Debug for Verifier<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Verifier")
+			.field("public_key", self.public_key)
+			.finish()
+	}
+}
+
+impl io::Write for Verifier<'_> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
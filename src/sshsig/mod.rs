@@ -0,0 +1,437 @@
+//! Creating and verifying signatures in the OpenSSH `sshsig` format.
+//!
+//! The signatures are compatible with `ssh-keygen -Y sign` and
+//! `ssh-keygen -Y verify`. Every signature contains a namespace, so a
+//! signature for one purpose can't be used for another.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::signature::Keypair;
+//! use chuchi_crypto::sshsig::{self, SshSignature};
+//!
+//! let keypair = Keypair::new();
+//! let pem = sshsig::sign(&keypair, "file", b"artifact").to_pem();
+//!
+//! let sig = SshSignature::from_pem(&pem).unwrap();
+//! assert!(sig.verify(keypair.public(), "file", b"artifact"));
+//! assert!(!sig.verify(keypair.public(), "git", b"artifact"));
+//! ```
+
+use crate::error::DecodeError;
+use crate::signature::{Keypair, PublicKey, Signature};
+
+use std::io;
+
+use base64::engine::{general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256, Sha512};
+
+const MAGIC: &[u8; 6] = b"SSHSIG";
+const VERSION: u32 = 1;
+const KEY_TYPE: &str = "ssh-ed25519";
+
+const PEM_BEGIN: &str = "-----BEGIN SSH SIGNATURE-----";
+const PEM_END: &str = "-----END SSH SIGNATURE-----";
+/// The line length `ssh-keygen` uses.
+const PEM_LINE_LEN: usize = 70;
+
+/// The hash algorithm applied to the message before signing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HashAlgorithm {
+	Sha256,
+	/// The default of `ssh-keygen`.
+	#[default]
+	Sha512,
+}
+
+impl HashAlgorithm {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Sha256 => "sha256",
+			Self::Sha512 => "sha512",
+		}
+	}
+
+	fn from_name(name: &[u8]) -> Result<Self, DecodeError> {
+		match name {
+			b"sha256" => Ok(Self::Sha256),
+			b"sha512" => Ok(Self::Sha512),
+			_ => Err(DecodeError::InvalidBytes),
+		}
+	}
+
+	fn hash(&self, msg: &[u8]) -> Vec<u8> {
+		match self {
+			Self::Sha256 => Sha256::digest(msg).to_vec(),
+			Self::Sha512 => Sha512::digest(msg).to_vec(),
+		}
+	}
+
+	fn hash_reader<R: io::Read + ?Sized>(
+		&self,
+		reader: &mut R,
+	) -> io::Result<Vec<u8>> {
+		match self {
+			Self::Sha256 => read_digest::<Sha256, R>(reader),
+			Self::Sha512 => read_digest::<Sha512, R>(reader),
+		}
+	}
+}
+
+/// Signs the message with SHA-512 like `ssh-keygen -Y sign -n <namespace>`.
+///
+/// ## Panics
+/// If the namespace is empty.
+pub fn sign(
+	keypair: &Keypair,
+	namespace: &str,
+	msg: impl AsRef<[u8]>,
+) -> SshSignature {
+	let algorithm = HashAlgorithm::default();
+	sign_hash(keypair, namespace, algorithm, algorithm.hash(msg.as_ref()))
+}
+
+/// Like `sign` but reads the message from a reader.
+///
+/// ## Panics
+/// If the namespace is empty.
+pub fn sign_reader<R: io::Read + ?Sized>(
+	keypair: &Keypair,
+	namespace: &str,
+	reader: &mut R,
+) -> io::Result<SshSignature> {
+	let algorithm = HashAlgorithm::default();
+	let hash = algorithm.hash_reader(reader)?;
+
+	Ok(sign_hash(keypair, namespace, algorithm, hash))
+}
+
+fn sign_hash(
+	keypair: &Keypair,
+	namespace: &str,
+	hash_algorithm: HashAlgorithm,
+	hash: Vec<u8>,
+) -> SshSignature {
+	assert!(!namespace.is_empty(), "namespace is empty");
+
+	let signature = keypair.sign(signed_data(namespace, hash_algorithm, &hash));
+
+	SshSignature {
+		public_key: keypair.public().clone(),
+		namespace: namespace.into(),
+		hash_algorithm,
+		signature,
+	}
+}
+
+/// A signature in the `sshsig` format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshSignature {
+	public_key: PublicKey,
+	namespace: String,
+	hash_algorithm: HashAlgorithm,
+	signature: Signature,
+}
+
+impl SshSignature {
+	/// The key which created the signature.
+	///
+	/// Make sure to check that the key is trusted, `verify` does this by
+	/// comparing it with the expected key.
+	pub fn public_key(&self) -> &PublicKey {
+		&self.public_key
+	}
+
+	pub fn namespace(&self) -> &str {
+		&self.namespace
+	}
+
+	pub fn hash_algorithm(&self) -> HashAlgorithm {
+		self.hash_algorithm
+	}
+
+	/// Verifies that the signature was created by `public_key` in the
+	/// given namespace.
+	pub fn verify(
+		&self,
+		public_key: &PublicKey,
+		namespace: &str,
+		msg: impl AsRef<[u8]>,
+	) -> bool {
+		let hash = self.hash_algorithm.hash(msg.as_ref());
+		self.verify_hash(public_key, namespace, &hash)
+	}
+
+	/// Like `verify` but reads the message from a reader.
+	pub fn verify_reader<R: io::Read + ?Sized>(
+		&self,
+		public_key: &PublicKey,
+		namespace: &str,
+		reader: &mut R,
+	) -> io::Result<bool> {
+		let hash = self.hash_algorithm.hash_reader(reader)?;
+		Ok(self.verify_hash(public_key, namespace, &hash))
+	}
+
+	fn verify_hash(
+		&self,
+		public_key: &PublicKey,
+		namespace: &str,
+		hash: &[u8],
+	) -> bool {
+		self.public_key == *public_key
+			&& self.namespace == namespace
+			&& public_key.verify(
+				signed_data(namespace, self.hash_algorithm, hash),
+				&self.signature,
+			)
+	}
+
+	/// Returns the binary signature blob.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend_from_slice(&VERSION.to_be_bytes());
+		put_string(&mut bytes, &public_key_blob(&self.public_key));
+		put_string(&mut bytes, self.namespace.as_bytes());
+		// reserved
+		put_string(&mut bytes, b"");
+		put_string(&mut bytes, self.hash_algorithm.as_str().as_bytes());
+
+		let mut signature = vec![];
+		put_string(&mut signature, KEY_TYPE.as_bytes());
+		put_string(&mut signature, &self.signature.to_bytes());
+		put_string(&mut bytes, &signature);
+
+		bytes
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+		let mut reader = Reader(bytes);
+
+		if reader.take(MAGIC.len())? != MAGIC {
+			return Err(DecodeError::InvalidBytes);
+		}
+		if reader.u32()? != VERSION {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		let public_key = parse_public_key_blob(reader.string()?)?;
+		let namespace = std::str::from_utf8(reader.string()?)
+			.map_err(DecodeError::inv_bytes)?;
+		// the reserved field is ignored
+		reader.string()?;
+		let hash_algorithm = HashAlgorithm::from_name(reader.string()?)?;
+
+		let mut signature = Reader(reader.string()?);
+		if signature.string()? != KEY_TYPE.as_bytes() {
+			return Err(DecodeError::InvalidBytes);
+		}
+		let signature = Signature::try_from_slice(signature.string()?)
+			.map_err(|_| DecodeError::InvalidLength)?;
+
+		if !reader.0.is_empty() || namespace.is_empty() {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		Ok(Self {
+			public_key,
+			namespace: namespace.into(),
+			hash_algorithm,
+			signature,
+		})
+	}
+
+	/// Returns the armored signature like `ssh-keygen` writes it.
+	pub fn to_pem(&self) -> String {
+		let b64 = STANDARD.encode(self.to_bytes());
+
+		let mut s = format!("{PEM_BEGIN}\n");
+		for line in b64.as_bytes().chunks(PEM_LINE_LEN) {
+			// base64 is always ascii
+			s.push_str(std::str::from_utf8(line).unwrap());
+			s.push('\n');
+		}
+		s.push_str(PEM_END);
+		s.push('\n');
+
+		s
+	}
+
+	pub fn from_pem(pem: &str) -> Result<Self, DecodeError> {
+		let b64: String = pem
+			.trim()
+			.strip_prefix(PEM_BEGIN)
+			.and_then(|s| s.strip_suffix(PEM_END))
+			.ok_or(DecodeError::InvalidBytes)?
+			.split_whitespace()
+			.collect();
+
+		let bytes = STANDARD.decode(b64).map_err(DecodeError::inv_bytes)?;
+		Self::from_bytes(&bytes)
+	}
+}
+
+/// Returns the public key as a line of an `authorized_keys` or
+/// `allowed_signers` file, for example `ssh-ed25519 AAAA... comment`.
+pub fn encode_public_key(public_key: &PublicKey, comment: &str) -> String {
+	let b64 = STANDARD.encode(public_key_blob(public_key));
+
+	if comment.is_empty() {
+		format!("{KEY_TYPE} {b64}")
+	} else {
+		format!("{KEY_TYPE} {b64} {comment}")
+	}
+}
+
+/// Parses a public key in the OpenSSH format, like the content of
+/// `id_ed25519.pub`. The comment is ignored.
+pub fn decode_public_key(s: &str) -> Result<PublicKey, DecodeError> {
+	let mut parts = s.split_whitespace();
+	if parts.next() != Some(KEY_TYPE) {
+		return Err(DecodeError::InvalidBytes);
+	}
+
+	let b64 = parts.next().ok_or(DecodeError::InvalidBytes)?;
+	let blob = STANDARD.decode(b64).map_err(DecodeError::inv_bytes)?;
+
+	parse_public_key_blob(&blob)
+}
+
+/// The data which actually gets signed.
+fn signed_data(
+	namespace: &str,
+	hash_algorithm: HashAlgorithm,
+	hash: &[u8],
+) -> Vec<u8> {
+	let mut data = MAGIC.to_vec();
+	put_string(&mut data, namespace.as_bytes());
+	// reserved
+	put_string(&mut data, b"");
+	put_string(&mut data, hash_algorithm.as_str().as_bytes());
+	put_string(&mut data, hash);
+
+	data
+}
+
+fn public_key_blob(public_key: &PublicKey) -> Vec<u8> {
+	let mut blob = vec![];
+	put_string(&mut blob, KEY_TYPE.as_bytes());
+	put_string(&mut blob, &public_key.to_bytes());
+
+	blob
+}
+
+fn parse_public_key_blob(blob: &[u8]) -> Result<PublicKey, DecodeError> {
+	let mut reader = Reader(blob);
+	if reader.string()? != KEY_TYPE.as_bytes() {
+		return Err(DecodeError::InvalidBytes);
+	}
+
+	let key = reader.string()?;
+	if !reader.0.is_empty() {
+		return Err(DecodeError::InvalidBytes);
+	}
+
+	PublicKey::try_from_slice(key).map_err(|_| DecodeError::InvalidBytes)
+}
+
+/// Appends a string as specified in RFC 4251, prefixed by its length.
+fn put_string(buf: &mut Vec<u8>, s: &[u8]) {
+	buf.extend_from_slice(&(s.len() as u32).to_be_bytes());
+	buf.extend_from_slice(s);
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+	fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+		if self.0.len() < len {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let (bytes, rest) = self.0.split_at(len);
+		self.0 = rest;
+
+		Ok(bytes)
+	}
+
+	fn u32(&mut self) -> Result<u32, DecodeError> {
+		// take returns exactly 4 bytes
+		Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+	}
+
+	fn string(&mut self) -> Result<&'a [u8], DecodeError> {
+		let len = self.u32()?;
+		self.take(len as usize)
+	}
+}
+
+fn read_digest<D: Digest, R: io::Read + ?Sized>(
+	reader: &mut R,
+) -> io::Result<Vec<u8>> {
+	let mut hasher = D::new();
+	let mut buf = [0u8; 8192];
+	loop {
+		match reader.read(&mut buf) {
+			Ok(0) => break,
+			Ok(n) => hasher.update(&buf[..n]),
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+			Err(e) => return Err(e),
+		}
+	}
+
+	Ok(hasher.finalize().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// created with `ssh-keygen -Y sign -n file -f id_ed25519`
+	const PUBLIC_KEY: &str = "ssh-ed25519 \
+		AAAAC3NzaC1lZDI1NTE5AAAAIB5kCtkVAI2Ey2MJBw5P9adEciRAXHlpaGQaGnzJeRdP test";
+	const SIGNATURE: &str = "-----BEGIN SSH SIGNATURE-----\n\
+		U1NIU0lHAAAAAQAAADMAAAALc3NoLWVkMjU1MTkAAAAgHmQK2RUAjYTLYwkHDk/1p0RyJE\n\
+		BceWloZBoafMl5F08AAAAEZmlsZQAAAAAAAAAGc2hhNTEyAAAAUwAAAAtzc2gtZWQyNTUx\n\
+		OQAAAEBmQcrX0TmGVwq+Jkz62DsVsqPB6XvkO9xflS5DOLB+4xAashpq55BTHiK+vQTZ/e\n\
+		/vqg47+nuEQxE/XkGbjSsH\n\
+		-----END SSH SIGNATURE-----\n";
+
+	#[test]
+	fn test_openssh_signature() {
+		let public_key = decode_public_key(PUBLIC_KEY).unwrap();
+		assert_eq!(encode_public_key(&public_key, "test"), PUBLIC_KEY);
+
+		let sig = SshSignature::from_pem(SIGNATURE).unwrap();
+		assert_eq!(sig.namespace(), "file");
+		assert_eq!(sig.hash_algorithm(), HashAlgorithm::Sha512);
+		assert_eq!(sig.to_pem(), SIGNATURE);
+
+		assert!(sig.verify(&public_key, "file", b"hello world\n"));
+		assert!(!sig.verify(&public_key, "git", b"hello world\n"));
+		assert!(!sig.verify(&public_key, "file", b"hello world"));
+	}
+
+	#[test]
+	fn test_sign_verify() {
+		let keypair = Keypair::new();
+		let msg = b"artifact";
+
+		let sig = sign(&keypair, "file", msg);
+		assert!(sig.verify(keypair.public(), "file", msg));
+		assert!(sig
+			.verify_reader(keypair.public(), "file", &mut &msg[..])
+			.unwrap());
+		assert!(!sig.verify(Keypair::new().public(), "file", msg));
+
+		let sig_2 = sign_reader(&keypair, "file", &mut &msg[..]).unwrap();
+		assert_eq!(sig_2, sig);
+
+		let decoded = SshSignature::from_pem(&sig.to_pem()).unwrap();
+		assert_eq!(decoded, sig);
+
+		let mut bytes = sig.to_bytes();
+		bytes.push(0);
+		assert!(SshSignature::from_bytes(&bytes).is_err());
+		assert!(SshSignature::from_bytes(&bytes[..40]).is_err());
+	}
+}
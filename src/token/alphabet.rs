@@ -0,0 +1,201 @@
+use crate::error::{DecodeError, RandomError};
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+/// The characters a human-friendly code is made of, for codes which are
+/// read aloud or typed, for example `7ZQ4-K2MX`.
+///
+/// Every character is chosen with rejection sampling, so each one is
+/// equally likely. A code of `len` characters contains
+/// `len * log2(alphabet.size())` random bits, 8 Crockford characters are
+/// 40 bits.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::token::Alphabet;
+///
+/// let alphabet = Alphabet::CROCKFORD;
+/// let code = alphabet.generate(8);
+/// let shown = Alphabet::group(&code, 4);
+/// assert_eq!(shown.len(), 9);
+///
+/// // what the user typed
+/// let input = shown.to_lowercase().replace('0', "o");
+/// assert_eq!(alphabet.parse(&input).unwrap(), code);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet {
+	chars: &'static str,
+	/// Lowercase input is accepted.
+	uppercase: bool,
+	/// Characters which are accepted instead of another one.
+	aliases: &'static [(u8, u8)],
+}
+
+impl Alphabet {
+	/// Crockford's base32 `0123456789ABCDEFGHJKMNPQRSTVWXYZ`.
+	///
+	/// When parsing, lowercase letters are accepted, `O` is read as `0`
+	/// and `I` and `L` as `1`.
+	pub const CROCKFORD: Self = Self {
+		chars: "0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+		uppercase: true,
+		aliases: &[(b'O', b'0'), (b'I', b'1'), (b'L', b'1')],
+	};
+
+	/// Digits and uppercase letters without the easily confused
+	/// `0`, `1`, `I` and `O`.
+	///
+	/// When parsing, lowercase letters are accepted.
+	pub const UNAMBIGUOUS: Self = Self {
+		chars: "23456789ABCDEFGHJKLMNPQRSTUVWXYZ",
+		uppercase: true,
+		aliases: &[],
+	};
+
+	/// Only the digits `0` to `9`.
+	pub const DIGITS: Self = Self {
+		chars: "0123456789",
+		uppercase: false,
+		aliases: &[],
+	};
+
+	/// Creates a custom alphabet, if it has no lowercase letters
+	/// lowercase input is accepted when parsing.
+	///
+	/// ## Panics
+	/// If the alphabet has less than 2 or more than 256 characters,
+	/// contains non ascii characters, separators or duplicates.
+	pub fn new(chars: &'static str) -> Self {
+		let bytes = chars.as_bytes();
+		assert!(
+			bytes.len() >= 2 && bytes.len() <= 256,
+			"alphabet must have 2 to 256 characters"
+		);
+		assert!(
+			bytes
+				.iter()
+				.all(|c| c.is_ascii_graphic() && !is_separator(*c)),
+			"alphabet must only contain printable ascii and no separators"
+		);
+		for (i, c) in bytes.iter().enumerate() {
+			assert!(!bytes[..i].contains(c), "alphabet contains duplicates");
+		}
+
+		Self {
+			chars,
+			uppercase: !bytes.iter().any(u8::is_ascii_lowercase),
+			aliases: &[],
+		}
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		self.chars
+	}
+
+	/// Returns the amount of characters.
+	pub fn size(&self) -> usize {
+		self.chars.len()
+	}
+
+	/// Creates a random code of `len` characters.
+	///
+	/// ## Panics
+	/// If the OsRng fails to return random bytes.
+	pub fn generate(&self, len: usize) -> String {
+		self.generate_with_rng(len, &mut OsRng)
+	}
+
+	/// Like `generate` but returns an error instead of panicking if no
+	/// random bytes are available.
+	pub fn try_generate(&self, len: usize) -> Result<String, RandomError> {
+		crate::try_with_os_rng(|rng| self.generate_with_rng(len, rng))
+	}
+
+	/// Creates a random code of `len` characters using the given rng.
+	pub fn generate_with_rng(
+		&self,
+		len: usize,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> String {
+		let chars = self.chars.as_bytes();
+		// the largest multiple of the alphabet length which fits in a byte,
+		// bytes above are rejected so every character is equally likely
+		let limit = 256 - 256 % chars.len();
+
+		let mut code = String::with_capacity(len);
+		let mut buf = [0u8; 32];
+		while code.len() < len {
+			rng.fill_bytes(&mut buf);
+
+			for &b in buf.iter().filter(|b| (**b as usize) < limit) {
+				if code.len() == len {
+					break;
+				}
+				code.push(chars[b as usize % chars.len()] as char);
+			}
+		}
+
+		code
+	}
+
+	/// Creates a random code of `len` characters, split into groups of
+	/// `group_len` characters by dashes.
+	pub fn generate_grouped(&self, len: usize, group_len: usize) -> String {
+		Self::group(&self.generate(len), group_len)
+	}
+
+	/// Splits a code into groups of `group_len` characters separated by
+	/// dashes, for example `XXXX-XXXX`.
+	///
+	/// ## Panics
+	/// If `group_len` is 0.
+	pub fn group(code: &str, group_len: usize) -> String {
+		assert!(group_len > 0, "group_len must not be 0");
+
+		let mut s = String::with_capacity(code.len() + code.len() / group_len);
+		for (i, c) in code.chars().enumerate() {
+			if i > 0 && i % group_len == 0 {
+				s.push('-');
+			}
+			s.push(c);
+		}
+
+		s
+	}
+
+	/// Parses a code entered by a user, dashes and whitespace are removed
+	/// and the characters normalized.
+	///
+	/// Returns an error if a character is not part of the alphabet.
+	pub fn parse(&self, s: &str) -> Result<String, DecodeError> {
+		s.bytes()
+			.filter(|c| !is_separator(*c))
+			.map(|c| {
+				self.normalize(c)
+					.map(char::from)
+					.ok_or(DecodeError::InvalidBytes)
+			})
+			.collect()
+	}
+
+	fn normalize(&self, c: u8) -> Option<u8> {
+		let c = if self.uppercase {
+			c.to_ascii_uppercase()
+		} else {
+			c
+		};
+		let c = self
+			.aliases
+			.iter()
+			.find(|(from, _)| *from == c)
+			.map_or(c, |(_, to)| *to);
+
+		self.chars.as_bytes().contains(&c).then_some(c)
+	}
+}
+
+fn is_separator(c: u8) -> bool {
+	c == b'-' || c.is_ascii_whitespace()
+}
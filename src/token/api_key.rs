@@ -0,0 +1,176 @@
+use super::Token;
+use crate::checksum::{Crc32, Hasher};
+use crate::error::DecodeError;
+
+use crate::FromStr;
+use std::fmt;
+
+use subtle::ConstantTimeEq;
+
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+// the base64 encoded crc32
+const CHECKSUM_LEN: usize = 6;
+
+/// A prefixed API key with a checksum, for example `sk_live_<token><crc>`.
+///
+/// The prefix makes the key easy to recognize, for secret scanners
+/// and humans alike. The checksum allows to reject typos and random
+/// strings without a database lookup.
+///
+/// The prefix can only contain ascii letters, digits and underscores.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::token::ApiKey;
+///
+/// let key = ApiKey::new("sk_live");
+/// let s = key.to_string();
+/// assert!(s.starts_with("sk_live_"));
+///
+/// assert_eq!(ApiKey::parse_prefix(&s).unwrap(), "sk_live");
+/// let key_2: ApiKey = s.parse().unwrap();
+/// assert_eq!(key, key_2);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ApiKey {
+	prefix: String,
+	token: Token<32>,
+}
+
+impl ApiKey {
+	/// Creates a new random API key.
+	///
+	/// ## Panics
+	/// if the prefix is empty or contains invalid characters.
+	pub fn new(prefix: impl Into<String>) -> Self {
+		Self::from_token(prefix, Token::new())
+	}
+
+	/// ## Panics
+	/// if the prefix is empty or contains invalid characters.
+	pub fn from_token(prefix: impl Into<String>, token: Token<32>) -> Self {
+		let prefix = prefix.into();
+		assert!(valid_prefix(&prefix), "invalid prefix");
+
+		Self { prefix, token }
+	}
+
+	pub fn prefix(&self) -> &str {
+		&self.prefix
+	}
+
+	pub fn token(&self) -> &Token<32> {
+		&self.token
+	}
+
+	/// Validates the format and the checksum of an API key and returns the
+	/// prefix.
+	///
+	/// The secret part is not returned.
+	pub fn parse_prefix(s: &str) -> Result<&str, DecodeError> {
+		let (prefix, body, checksum) = split(s)?;
+
+		let expected = checksum_str(prefix, body);
+		if !bool::from(expected.as_bytes().ct_eq(checksum.as_bytes())) {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		Ok(prefix)
+	}
+}
+
+fn valid_prefix(prefix: &str) -> bool {
+	!prefix.is_empty()
+		&& prefix
+			.bytes()
+			.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Returns the prefix, the base64 token and the checksum.
+fn split(s: &str) -> Result<(&str, &str, &str), DecodeError> {
+	let tail_len = 1 + Token::<32>::STR_LEN + CHECKSUM_LEN;
+	if s.len() <= tail_len || !s.is_ascii() {
+		return Err(DecodeError::InvalidLength);
+	}
+
+	let (prefix, tail) = s.split_at(s.len() - tail_len);
+	let tail = tail.strip_prefix('_').ok_or(DecodeError::InvalidBytes)?;
+	let (body, checksum) = tail.split_at(Token::<32>::STR_LEN);
+
+	if !valid_prefix(prefix) {
+		return Err(DecodeError::InvalidBytes);
+	}
+
+	Ok((prefix, body, checksum))
+}
+
+fn checksum_str(prefix: &str, body: &str) -> String {
+	let mut hasher = Hasher::<Crc32>::new();
+	hasher.update(prefix);
+	hasher.update("_");
+	hasher.update(body);
+
+	URL_SAFE_NO_PAD.encode(hasher.finalize().to_bytes())
+}
+
+/// Only shows the prefix.
+impl fmt::Debug for ApiKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ApiKey")
+			.field("prefix", &self.prefix)
+			.finish_non_exhaustive()
+	}
+}
+
+impl fmt::Display for ApiKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let body = self.token.to_string();
+		let checksum = checksum_str(&self.prefix, &body);
+
+		write!(f, "{}_{}{}", self.prefix, body, checksum)
+	}
+}
+
+impl FromStr for ApiKey {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let prefix = Self::parse_prefix(s)?;
+		let (_, body, _) = split(s)?;
+
+		Ok(Self {
+			prefix: prefix.into(),
+			token: body.parse()?,
+		})
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	use std::borrow::Cow;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for ApiKey {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			serializer.collect_str(&self)
+		}
+	}
+
+	impl<'de> Deserialize<'de> for ApiKey {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+			s.parse().map_err(D::Error::custom)
+		}
+	}
+}
@@ -0,0 +1,215 @@
+//! The registered claims shared by JWT and PASETO tokens.
+
+use crate::error::TokenError;
+use crate::unix_secs;
+
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+use std::time::Duration;
+use std::time::SystemTime;
+
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+use _serde::de::DeserializeOwned;
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+use _serde::Serialize;
+
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+use serde_json::{Map, Value};
+
+/// Checks that `now` is before `expires_at` and not before `not_before`.
+pub(crate) fn validate_lifetime(
+	expires_at: Option<u64>,
+	not_before: Option<u64>,
+	now: SystemTime,
+) -> Result<(), TokenError> {
+	let now = unix_secs(now);
+
+	if matches!(expires_at, Some(exp) if now >= exp) {
+		return Err(TokenError::Expired);
+	}
+
+	if matches!(not_before, Some(nbf) if now < nbf) {
+		return Err(TokenError::NotYetValid);
+	}
+
+	Ok(())
+}
+
+/// The claims of a JWT or PASETO token.
+///
+/// The timestamps are in seconds since the unix epoch, `custom` contains
+/// all other claims and needs to serialize to a json object (or `()`).
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claims<T = ()> {
+	/// `iss`
+	pub issuer: Option<String>,
+	/// `sub`
+	pub subject: Option<String>,
+	/// `aud`, a single audience is stored as a string
+	pub audience: Vec<String>,
+	/// `exp`
+	pub expires_at: Option<u64>,
+	/// `nbf`
+	pub not_before: Option<u64>,
+	/// `iat`
+	pub issued_at: Option<u64>,
+	/// `jti`
+	pub token_id: Option<String>,
+	pub custom: T,
+}
+
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+impl Claims<()> {
+	/// Creates claims which are valid for the given duration.
+	pub fn new(valid_for: Duration) -> Self {
+		Self::with_custom((), valid_for)
+	}
+}
+
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+impl<T> Claims<T> {
+	/// Creates claims with custom claims which are valid for the given
+	/// duration.
+	pub fn with_custom(custom: T, valid_for: Duration) -> Self {
+		let now = unix_secs(crate::now());
+
+		Self {
+			issuer: None,
+			subject: None,
+			audience: vec![],
+			expires_at: Some(now + valid_for.as_secs()),
+			not_before: None,
+			issued_at: Some(now),
+			token_id: None,
+			custom,
+		}
+	}
+
+	/// Checks `exp`, `nbf` and if `audience` is some, that `aud` contains
+	/// it.
+	pub fn validate_at(
+		&self,
+		audience: Option<&str>,
+		now: SystemTime,
+	) -> Result<(), TokenError> {
+		validate_lifetime(self.expires_at, self.not_before, now)?;
+
+		if let Some(aud) = audience {
+			if !self.audience.iter().any(|a| a == aud) {
+				return Err(TokenError::InvalidAudience);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+impl<T: Serialize> Claims<T> {
+	/// Serializes the claims, `format_time` encodes `exp`, `nbf` and `iat`.
+	pub(crate) fn to_json(
+		&self,
+		format_time: fn(u64) -> Value,
+	) -> Result<Vec<u8>, TokenError> {
+		let mut map = match serde_json::to_value(&self.custom) {
+			Ok(Value::Object(map)) => map,
+			Ok(Value::Null) => Map::new(),
+			_ => return Err(TokenError::InvalidFormat),
+		};
+
+		let strings = [
+			("iss", &self.issuer),
+			("sub", &self.subject),
+			("jti", &self.token_id),
+		];
+		for (key, value) in strings {
+			if let Some(v) = value {
+				map.insert(key.into(), Value::String(v.clone()));
+			}
+		}
+
+		match self.audience.as_slice() {
+			[] => {}
+			[aud] => {
+				map.insert("aud".into(), Value::String(aud.clone()));
+			}
+			auds => {
+				let auds = auds.iter().cloned().map(Value::String).collect();
+				map.insert("aud".into(), Value::Array(auds));
+			}
+		}
+
+		let times = [
+			("exp", self.expires_at),
+			("nbf", self.not_before),
+			("iat", self.issued_at),
+		];
+		for (key, value) in times {
+			if let Some(v) = value {
+				map.insert(key.into(), format_time(v));
+			}
+		}
+
+		serde_json::to_vec(&map).map_err(|_| TokenError::InvalidFormat)
+	}
+}
+
+#[cfg(any(feature = "jwt", feature = "paseto"))]
+impl<T: DeserializeOwned> Claims<T> {
+	/// Deserializes the claims, `parse_time` decodes `exp`, `nbf` and `iat`.
+	pub(crate) fn from_json(
+		json: &[u8],
+		parse_time: fn(Value) -> Option<u64>,
+	) -> Result<Self, TokenError> {
+		let mut map: Map<String, Value> = serde_json::from_slice(json)
+			.map_err(|_| TokenError::InvalidFormat)?;
+
+		let mut string = |key: &str| match map.remove(key) {
+			Some(Value::String(s)) => Ok(Some(s)),
+			Some(_) => Err(TokenError::InvalidFormat),
+			None => Ok(None),
+		};
+
+		let issuer = string("iss")?;
+		let subject = string("sub")?;
+		let token_id = string("jti")?;
+
+		let audience = match map.remove("aud") {
+			Some(Value::String(s)) => vec![s],
+			Some(Value::Array(auds)) => auds
+				.into_iter()
+				.map(|v| match v {
+					Value::String(s) => Ok(s),
+					_ => Err(TokenError::InvalidFormat),
+				})
+				.collect::<Result<_, _>>()?,
+			Some(_) => return Err(TokenError::InvalidFormat),
+			None => vec![],
+		};
+
+		let mut time = |key: &str| match map.remove(key) {
+			Some(v) => parse_time(v).map(Some).ok_or(TokenError::InvalidFormat),
+			None => Ok(None),
+		};
+
+		let expires_at = time("exp")?;
+		let not_before = time("nbf")?;
+		let issued_at = time("iat")?;
+
+		// falling back to null allows T to be () and ignore unknown claims
+		let custom = serde_json::from_value(Value::Object(map))
+			.or_else(|_| serde_json::from_value(Value::Null))
+			.map_err(|_| TokenError::InvalidFormat)?;
+
+		Ok(Self {
+			issuer,
+			subject,
+			audience,
+			expires_at,
+			not_before,
+			issued_at,
+			token_id,
+			custom,
+		})
+	}
+}
@@ -0,0 +1,341 @@
+use super::Token;
+
+#[cfg(any(feature = "b64", feature = "hex"))]
+use crate::error::DecodeError;
+use crate::error::RandomError;
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// A random token where the length is chosen at runtime.
+///
+/// Use [`Token`] if the length is known at compile time.
+///
+/// Comparing two tokens is done in constant time, only the length might
+/// leak.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::token::DynToken;
+///
+/// let token = DynToken::new(24);
+/// assert_eq!(token.len(), 24);
+///
+/// # #[cfg(feature = "b64")]
+/// # {
+/// let s = token.to_string();
+/// assert_eq!(s.parse::<DynToken>().unwrap(), token);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DynToken {
+	bytes: Vec<u8>,
+}
+
+impl DynToken {
+	/// Creates a new random token of `len` bytes.
+	pub fn new(len: usize) -> Self {
+		Self::new_with_rng(len, &mut OsRng)
+	}
+
+	/// Like `new` but returns an error instead of panicking if no random
+	/// bytes are available.
+	pub fn try_new(len: usize) -> Result<Self, RandomError> {
+		crate::try_with_os_rng(|rng| Self::new_with_rng(len, rng))
+	}
+
+	/// Creates a new random token of `len` bytes using the given rng.
+	pub fn new_with_rng(
+		len: usize,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> Self {
+		let mut bytes = vec![0u8; len];
+
+		rng.fill_bytes(&mut bytes);
+
+		Self { bytes }
+	}
+
+	/// Returns the length in bytes.
+	pub fn len(&self) -> usize {
+		self.bytes.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.bytes.is_empty()
+	}
+
+	/// Returns the length of the base64 string.
+	pub fn str_len(&self) -> usize {
+		crate::calculate_b64_len(self.len())
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		self.bytes.clone()
+	}
+
+	/// Compares the token with some bytes in constant time.
+	///
+	/// Only the length of the slice might leak.
+	pub fn ct_eq(&self, other: &[u8]) -> bool {
+		crate::ct::ct_eq(&self.bytes, other)
+	}
+}
+
+impl PartialEq for DynToken {
+	fn eq(&self, other: &Self) -> bool {
+		self.ct_eq(&other.bytes)
+	}
+}
+
+impl Eq for DynToken {}
+
+impl Hash for DynToken {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.bytes.hash(state)
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for DynToken {
+	fn zeroize(&mut self) {
+		self.bytes.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for DynToken {
+	fn drop(&mut self) {
+		zeroize::Zeroize::zeroize(self);
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for DynToken {}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for DynToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("DynToken").field(&self.as_ref()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for DynToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("DynToken").field(&self.to_string()).finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for DynToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(self.as_ref(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+impl From<Vec<u8>> for DynToken {
+	fn from(bytes: Vec<u8>) -> Self {
+		Self { bytes }
+	}
+}
+
+impl From<&[u8]> for DynToken {
+	fn from(bytes: &[u8]) -> Self {
+		Self {
+			bytes: bytes.to_vec(),
+		}
+	}
+}
+
+impl<const S: usize> From<Token<S>> for DynToken {
+	fn from(token: Token<S>) -> Self {
+		Self::from(token.as_ref())
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for DynToken {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		URL_SAFE_NO_PAD
+			.decode(s)
+			.map_err(DecodeError::inv_bytes)
+			.map(Self::from)
+	}
+}
+
+impl AsRef<[u8]> for DynToken {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+#[cfg(feature = "hex")]
+impl DynToken {
+	/// Returns the bytes as a lowercase hex string.
+	pub fn to_hex(&self) -> String {
+		hex::encode(self.as_ref())
+	}
+
+	/// Parses a hex string, upper and lowercase are both accepted.
+	pub fn from_hex(s: &str) -> Result<Self, DecodeError> {
+		hex::decode(s)
+			.map_err(DecodeError::inv_bytes)
+			.map(Self::from)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	#[cfg(feature = "b64")]
+	use std::borrow::Cow;
+	#[cfg(feature = "b64")]
+	use std::str::FromStr;
+
+	#[cfg(feature = "b64")]
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for DynToken {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			#[cfg(feature = "b64")]
+			if serializer.is_human_readable() {
+				return serializer.collect_str(&self);
+			}
+
+			serializer.serialize_bytes(self.as_ref())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for DynToken {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			#[cfg(feature = "b64")]
+			if deserializer.is_human_readable() {
+				let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+				return Self::from_str(s.as_ref()).map_err(D::Error::custom);
+			}
+
+			crate::serde_bytes::deserialize_vec(deserializer).map(Self::from)
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "postgres"))]
+mod impl_postgres {
+	use super::*;
+
+	use bytes::BytesMut;
+	use chuchi_postgres::filter::ParamData;
+	use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+	impl ToSql for DynToken {
+		fn to_sql(
+			&self,
+			ty: &Type,
+			out: &mut BytesMut,
+		) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+		where
+			Self: Sized,
+		{
+			if *ty == Type::BYTEA {
+				return <&[u8] as ToSql>::to_sql(&self.as_ref(), ty, out);
+			}
+
+			self.to_string().to_sql(ty, out)
+		}
+
+		fn accepts(ty: &Type) -> bool
+		where
+			Self: Sized,
+		{
+			*ty == Type::BYTEA || <&str as ToSql>::accepts(ty)
+		}
+
+		to_sql_checked!();
+	}
+
+	impl<'r> FromSql<'r> for DynToken {
+		fn from_sql(
+			ty: &Type,
+			raw: &'r [u8],
+		) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+			if *ty == Type::BYTEA {
+				return Ok(Self::from(raw));
+			}
+
+			let s = <&str as FromSql>::from_sql(ty, raw)?;
+			s.parse().map_err(Into::into)
+		}
+
+		fn accepts(ty: &Type) -> bool {
+			*ty == Type::BYTEA || <&str as FromSql>::accepts(ty)
+		}
+	}
+
+	impl ParamData for DynToken {
+		fn is_null(&self) -> bool {
+			false
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "sqlx"))]
+mod impl_sqlx {
+	use super::*;
+
+	use sqlx::encode::IsNull;
+	use sqlx::error::BoxDynError;
+	use sqlx::{Database, Decode, Encode, Type};
+
+	impl<DB: Database> Type<DB> for DynToken
+	where
+		String: Type<DB>,
+	{
+		fn type_info() -> DB::TypeInfo {
+			<String as Type<DB>>::type_info()
+		}
+
+		fn compatible(ty: &DB::TypeInfo) -> bool {
+			<String as Type<DB>>::compatible(ty)
+		}
+	}
+
+	impl<'q, DB: Database> Encode<'q, DB> for DynToken
+	where
+		String: Encode<'q, DB>,
+	{
+		fn encode_by_ref(
+			&self,
+			buf: &mut DB::ArgumentBuffer<'q>,
+		) -> Result<IsNull, BoxDynError> {
+			self.to_string().encode(buf)
+		}
+	}
+
+	impl<'r, DB: Database> Decode<'r, DB> for DynToken
+	where
+		&'r str: Decode<'r, DB>,
+	{
+		fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+			let s = <&str as Decode<DB>>::decode(value)?;
+			s.parse().map_err(Into::into)
+		}
+	}
+}
@@ -0,0 +1,485 @@
+//! JSON Web Keys (RFC 7517) for ed25519 and x25519 keys.
+//!
+//! Both key types are encoded as `OKP` keys (RFC 8037) with the curves
+//! `Ed25519` and `X25519`, the public key is stored in `x` and the secret
+//! key in `d`.
+//!
+//! ## Example
+//! ```
+//! # #[cfg(feature = "signature")]
+//! # {
+//! use chuchi_crypto::signature::{Keypair, PublicKey};
+//! use chuchi_crypto::token::jwk::{Jwk, JwkSet};
+//!
+//! let key = Keypair::new();
+//! let mut jwk = key.public().to_jwk();
+//! jwk.kid = Some(jwk.thumbprint());
+//!
+//! let jwks = JwkSet { keys: vec![jwk] }.to_json();
+//!
+//! let jwks = JwkSet::from_json(&jwks).unwrap();
+//! let public = PublicKey::from_jwk(&jwks.keys[0]).unwrap();
+//! assert_eq!(&public, key.public());
+//! # }
+//! ```
+
+use crate::error::DecodeError;
+
+use std::fmt;
+
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use _serde::de::{Deserializer, Error as _};
+use _serde::ser::Serializer;
+use _serde::{Deserialize, Serialize};
+
+use serde_json::{Map, Value};
+
+use sha2::{Digest, Sha256};
+
+use zeroize::Zeroize;
+
+/// The key type of ed25519 and x25519 keys.
+pub const KTY_OKP: &str = "OKP";
+pub const CRV_ED25519: &str = "Ed25519";
+pub const CRV_X25519: &str = "X25519";
+
+/// A JSON Web Key.
+///
+/// Members which are not listed here are ignored when parsing.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Jwk {
+	/// `kty`
+	pub key_type: String,
+	/// `crv`
+	pub curve: String,
+	/// `x`, the base64url encoded public key
+	pub x: String,
+	/// `d`, the base64url encoded secret key
+	pub d: Option<String>,
+	/// `kid`
+	pub kid: Option<String>,
+	/// `alg`
+	pub alg: Option<String>,
+	/// `use`
+	pub key_use: Option<String>,
+}
+
+impl Jwk {
+	#[cfg(any(feature = "signature", feature = "cipher"))]
+	fn new(curve: &str, x: &[u8], d: Option<&[u8]>) -> Self {
+		Self {
+			key_type: KTY_OKP.into(),
+			curve: curve.into(),
+			x: URL_SAFE_NO_PAD.encode(x),
+			d: d.map(|d| URL_SAFE_NO_PAD.encode(d)),
+			kid: None,
+			alg: (curve == CRV_ED25519).then(|| "EdDSA".into()),
+			key_use: None,
+		}
+	}
+
+	/// Returns the key without the secret key.
+	pub fn to_public(&self) -> Self {
+		let mut jwk = self.clone();
+		jwk.d.zeroize();
+		jwk.d = None;
+
+		jwk
+	}
+
+	/// Returns the base64url encoded SHA-256 thumbprint (RFC 7638).
+	///
+	/// The thumbprint only covers the required members, so the public and
+	/// the secret key have the same thumbprint. It is a good choice for
+	/// `kid`.
+	pub fn thumbprint(&self) -> String {
+		// the members need to be sorted and without whitespace, formatting a
+		// string Value escapes it as json
+		let json = format!(
+			r#"{{"crv":{},"kty":{},"x":{}}}"#,
+			Value::from(self.curve.as_str()),
+			Value::from(self.key_type.as_str()),
+			Value::from(self.x.as_str())
+		);
+
+		URL_SAFE_NO_PAD.encode(Sha256::digest(json))
+	}
+
+	pub fn to_json(&self) -> String {
+		self.to_value().to_string()
+	}
+
+	pub fn from_json(json: &str) -> Result<Self, DecodeError> {
+		let value =
+			serde_json::from_str(json).map_err(DecodeError::inv_bytes)?;
+		Self::from_value(value)
+	}
+
+	fn to_value(&self) -> Value {
+		let mut map = Map::new();
+		map.insert("kty".into(), Value::String(self.key_type.clone()));
+		map.insert("crv".into(), Value::String(self.curve.clone()));
+		map.insert("x".into(), Value::String(self.x.clone()));
+
+		let optional = [
+			("d", &self.d),
+			("kid", &self.kid),
+			("alg", &self.alg),
+			("use", &self.key_use),
+		];
+		for (key, value) in optional {
+			if let Some(v) = value {
+				map.insert(key.into(), Value::String(v.clone()));
+			}
+		}
+
+		Value::Object(map)
+	}
+
+	fn from_value(value: Value) -> Result<Self, DecodeError> {
+		let Value::Object(mut map) = value else {
+			return Err(DecodeError::InvalidBytes);
+		};
+
+		let mut take = |key: &str| match map.remove(key) {
+			Some(Value::String(s)) => Ok(Some(s)),
+			None => Ok(None),
+			Some(_) => Err(DecodeError::InvalidBytes),
+		};
+
+		Ok(Self {
+			key_type: take("kty")?.ok_or(DecodeError::InvalidBytes)?,
+			curve: take("crv")?.ok_or(DecodeError::InvalidBytes)?,
+			x: take("x")?.ok_or(DecodeError::InvalidBytes)?,
+			d: take("d")?,
+			kid: take("kid")?,
+			alg: take("alg")?,
+			key_use: take("use")?,
+		})
+	}
+
+	#[cfg(any(feature = "signature", feature = "cipher"))]
+	/// Returns the decoded `x`, checking `kty` and `crv`.
+	fn public_bytes(&self, curve: &str) -> Result<[u8; 32], DecodeError> {
+		if self.key_type != KTY_OKP || self.curve != curve {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		decode_32(&self.x)
+	}
+
+	#[cfg(any(feature = "signature", feature = "cipher"))]
+	/// Returns the decoded `d`, checking `kty` and `crv`.
+	fn secret_bytes(&self, curve: &str) -> Result<[u8; 32], DecodeError> {
+		if self.key_type != KTY_OKP || self.curve != curve {
+			return Err(DecodeError::InvalidBytes);
+		}
+
+		decode_32(self.d.as_ref().ok_or(DecodeError::InvalidBytes)?)
+	}
+}
+
+impl fmt::Debug for Jwk {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Jwk")
+			.field("key_type", &self.key_type)
+			.field("curve", &self.curve)
+			.field("x", &self.x)
+			.field("d", &self.d.as_ref().map(|_| "..."))
+			.field("kid", &self.kid)
+			.field("alg", &self.alg)
+			.field("key_use", &self.key_use)
+			.finish()
+	}
+}
+
+impl Drop for Jwk {
+	fn drop(&mut self) {
+		self.d.zeroize();
+	}
+}
+
+impl Serialize for Jwk {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		self.to_value().serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for Jwk {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let value = Value::deserialize(deserializer)?;
+		Self::from_value(value).map_err(D::Error::custom)
+	}
+}
+
+/// A JWKS document like `{"keys":[...]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JwkSet {
+	pub keys: Vec<Jwk>,
+}
+
+impl JwkSet {
+	/// Returns the key with the given `kid`.
+	pub fn find(&self, kid: &str) -> Option<&Jwk> {
+		self.keys.iter().find(|k| k.kid.as_deref() == Some(kid))
+	}
+
+	pub fn to_json(&self) -> String {
+		self.to_value().to_string()
+	}
+
+	/// Keys with another `kty` than `OKP` are skipped, so a document which
+	/// also contains RSA or EC keys can be read.
+	pub fn from_json(json: &str) -> Result<Self, DecodeError> {
+		let value =
+			serde_json::from_str(json).map_err(DecodeError::inv_bytes)?;
+		Self::from_value(value)
+	}
+
+	fn to_value(&self) -> Value {
+		let keys = self.keys.iter().map(Jwk::to_value).collect();
+
+		let mut map = Map::new();
+		map.insert("keys".into(), Value::Array(keys));
+
+		Value::Object(map)
+	}
+
+	fn from_value(value: Value) -> Result<Self, DecodeError> {
+		let Some(Value::Array(keys)) = value.get("keys") else {
+			return Err(DecodeError::InvalidBytes);
+		};
+
+		let keys = keys
+			.iter()
+			.filter(|k| k.get("kty").and_then(Value::as_str) == Some(KTY_OKP))
+			.map(|k| Jwk::from_value(k.clone()))
+			.collect::<Result<_, _>>()?;
+
+		Ok(Self { keys })
+	}
+}
+
+impl Serialize for JwkSet {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		self.to_value().serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for JwkSet {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let value = Value::deserialize(deserializer)?;
+		Self::from_value(value).map_err(D::Error::custom)
+	}
+}
+
+#[cfg(any(feature = "signature", feature = "cipher"))]
+fn decode_32(s: &str) -> Result<[u8; 32], DecodeError> {
+	let mut bytes =
+		URL_SAFE_NO_PAD.decode(s).map_err(DecodeError::inv_bytes)?;
+	let r = bytes.as_slice().try_into();
+	bytes.zeroize();
+
+	r.map_err(|_| DecodeError::InvalidLength)
+}
+
+#[cfg(feature = "signature")]
+mod impl_signature {
+	use super::*;
+	use crate::signature::{Keypair, PublicKey};
+
+	impl PublicKey {
+		/// Returns the key as an `Ed25519` JWK with `alg` set to `EdDSA`.
+		pub fn to_jwk(&self) -> Jwk {
+			Jwk::new(CRV_ED25519, &self.to_bytes(), None)
+		}
+
+		pub fn from_jwk(jwk: &Jwk) -> Result<Self, DecodeError> {
+			let bytes = jwk.public_bytes(CRV_ED25519)?;
+			Self::try_from_slice(&bytes).map_err(|_| DecodeError::InvalidBytes)
+		}
+	}
+
+	impl Keypair {
+		/// Returns the key as an `Ed25519` JWK including the secret key.
+		pub fn to_jwk(&self) -> Jwk {
+			let mut secret = self.to_bytes();
+			let jwk =
+				Jwk::new(CRV_ED25519, &self.public().to_bytes(), Some(&secret));
+			secret.zeroize();
+
+			jwk
+		}
+
+		/// Returns an error if `d` is missing or does not match `x`.
+		pub fn from_jwk(jwk: &Jwk) -> Result<Self, DecodeError> {
+			let public = jwk.public_bytes(CRV_ED25519)?;
+			let mut secret = jwk.secret_bytes(CRV_ED25519)?;
+			let keypair = Self::from(secret);
+			secret.zeroize();
+
+			if keypair.public().to_bytes() != public {
+				return Err(DecodeError::InvalidBytes);
+			}
+
+			Ok(keypair)
+		}
+	}
+}
+
+#[cfg(feature = "cipher")]
+mod impl_cipher {
+	use super::*;
+	use crate::cipher::{Keypair, PublicKey};
+
+	impl PublicKey {
+		/// Returns the key as an `X25519` JWK.
+		pub fn to_jwk(&self) -> Jwk {
+			Jwk::new(CRV_X25519, &self.to_bytes(), None)
+		}
+
+		pub fn from_jwk(jwk: &Jwk) -> Result<Self, DecodeError> {
+			jwk.public_bytes(CRV_X25519).map(Self::from)
+		}
+	}
+
+	impl Keypair {
+		/// Returns the key as an `X25519` JWK including the secret key.
+		pub fn to_jwk(&self) -> Jwk {
+			let mut secret = self.to_bytes();
+			let jwk =
+				Jwk::new(CRV_X25519, &self.public().to_bytes(), Some(&secret));
+			secret.zeroize();
+
+			jwk
+		}
+
+		/// Returns an error if `d` is missing or does not match `x`.
+		pub fn from_jwk(jwk: &Jwk) -> Result<Self, DecodeError> {
+			let public = jwk.public_bytes(CRV_X25519)?;
+			let mut secret = jwk.secret_bytes(CRV_X25519)?;
+			let keypair = Self::from(secret);
+			secret.zeroize();
+
+			if keypair.public().to_bytes() != public {
+				return Err(DecodeError::InvalidBytes);
+			}
+
+			Ok(keypair)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// RFC 8037 appendix A.1
+	#[cfg(feature = "signature")]
+	const ED25519_JWK: &str = r#"{"kty":"OKP","crv":"Ed25519",
+		"d":"nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A",
+		"x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#;
+
+	#[cfg(feature = "signature")]
+	#[test]
+	fn test_ed25519() {
+		use crate::signature::{Keypair, PublicKey};
+
+		let jwk = Jwk::from_json(ED25519_JWK).unwrap();
+		// RFC 8037 appendix A.3
+		assert_eq!(
+			jwk.thumbprint(),
+			"kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k"
+		);
+
+		let keypair = Keypair::from_jwk(&jwk).unwrap();
+		let public = PublicKey::from_jwk(&jwk.to_public()).unwrap();
+		assert_eq!(keypair.public(), &public);
+		assert!(Keypair::from_jwk(&jwk.to_public()).is_err());
+
+		let jwk_2 = keypair.to_jwk();
+		assert_eq!(jwk_2.x, jwk.x);
+		assert_eq!(jwk_2.d, jwk.d);
+		assert_eq!(jwk_2.alg.as_deref(), Some("EdDSA"));
+		assert_eq!(public.to_jwk().thumbprint(), jwk.thumbprint());
+		assert!(public.to_jwk().d.is_none());
+
+		// d does not belong to x
+		let mut other = Keypair::new().to_jwk();
+		other.x = jwk.x.clone();
+		assert!(Keypair::from_jwk(&other).is_err());
+	}
+
+	#[cfg(feature = "cipher")]
+	#[test]
+	fn test_x25519() {
+		use crate::cipher::{Keypair, PublicKey};
+
+		// RFC 8037 appendix A.6
+		let jwk = Jwk::from_json(
+			r#"{"kty":"OKP","crv":"X25519",
+			"d":"dwdtCnMYpX08FsFyUbJmRd9ML4frwJkqsXf7pR25LCo",
+			"x":"hSDwCYkwp1R0i33ctD73Wg2_Og0mOBr066SpjqqbTmo"}"#,
+		)
+		.unwrap();
+
+		let keypair = Keypair::from_jwk(&jwk).unwrap();
+		assert_eq!(keypair.to_jwk(), jwk);
+		let public = PublicKey::from_jwk(&jwk).unwrap();
+		assert_eq!(keypair.public(), &public);
+
+		#[cfg(feature = "signature")]
+		{
+			let ed = Jwk::from_json(ED25519_JWK).unwrap();
+			assert!(PublicKey::from_jwk(&ed).is_err());
+		}
+	}
+
+	#[test]
+	fn test_thumbprint() {
+		// RFC 8037 appendix A.3, the optional members are ignored
+		let jwk = Jwk::from_json(
+			r#"{"kty":"OKP","crv":"Ed25519","kid":"1","use":"sig",
+			"x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#,
+		)
+		.unwrap();
+		assert_eq!(
+			jwk.thumbprint(),
+			"kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k"
+		);
+	}
+
+	#[test]
+	fn test_jwk_set() {
+		let json = r#"{"keys":[
+			{"kty":"RSA","n":"0vx7","e":"AQAB"},
+			{"kty":"OKP","crv":"Ed25519","kid":"1",
+			"x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}
+		]}"#;
+
+		let set = JwkSet::from_json(json).unwrap();
+		assert_eq!(set.keys.len(), 1);
+		let jwk = set.find("1").unwrap();
+		assert_eq!(jwk.curve, CRV_ED25519);
+		assert!(set.find("2").is_none());
+
+		let set_2: JwkSet = serde_json::from_str(&set.to_json()).unwrap();
+		assert_eq!(set_2, set);
+		assert_eq!(serde_json::to_string(&set).unwrap(), set.to_json());
+
+		assert!(JwkSet::from_json(r#"{"keys":[{"kty":"OKP"}]}"#).is_err());
+	}
+}
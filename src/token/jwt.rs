@@ -0,0 +1,176 @@
+//! JSON Web Tokens signed with EdDSA or HS256.
+//!
+//! Tokens can be signed with a `signature::Keypair` (`EdDSA`) or a
+//! `hash::HmacKey` (`HS256`), any key implementing `TokenSigner` or
+//! `TokenVerifier` can be used. The `alg` header needs to match the key,
+//! `none` and other algorithms are always rejected.
+//!
+//! When verifying a token `exp` and `nbf` are validated and if an audience
+//! is expected, `aud` needs to contain it.
+//!
+//! ## Example
+//! ```
+//! # #[cfg(feature = "signature")]
+//! # {
+//! use chuchi_crypto::signature::Keypair;
+//! use chuchi_crypto::token::jwt::{self, Claims};
+//! use std::time::Duration;
+//!
+//! let key = Keypair::new();
+//! let mut claims = Claims::new(Duration::from_secs(60 * 60));
+//! claims.subject = Some("user-1".into());
+//! claims.audience = vec!["my-api".into()];
+//!
+//! let token = jwt::sign(&key, &claims).unwrap();
+//!
+//! let claims_2: Claims =
+//!     jwt::verify(key.public(), &token, Some("my-api")).unwrap();
+//! assert_eq!(claims, claims_2);
+//! # }
+//! ```
+
+pub use super::claims::Claims;
+use super::{TokenSigner, TokenVerifier};
+#[cfg(feature = "signature")]
+use crate::error::SignError;
+use crate::error::TokenError;
+#[cfg(feature = "signature")]
+use crate::signature::AsyncSigner;
+
+use std::time::SystemTime;
+
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use _serde::de::DeserializeOwned;
+use _serde::Serialize;
+
+use serde_json::{Map, Value};
+
+/// Creates a JWT signed with the given key.
+pub fn sign<K: TokenSigner, T: Serialize>(
+	key: &K,
+	claims: &Claims<T>,
+) -> Result<String, TokenError> {
+	let alg =
+		algorithm_name(K::ALGORITHM).ok_or(TokenError::InvalidAlgorithm)?;
+
+	let mut token = signing_input(alg, claims)?;
+	let signature = key.sign_token(token.as_bytes());
+	token.push('.');
+	URL_SAFE_NO_PAD.encode_string(signature, &mut token);
+
+	Ok(token)
+}
+
+/// Creates a JWT signed with an `AsyncSigner` using `EdDSA`.
+#[cfg(feature = "signature")]
+pub async fn sign_async<K: AsyncSigner + ?Sized, T: Serialize>(
+	key: &K,
+	claims: &Claims<T>,
+) -> Result<String, SignError> {
+	let mut token = signing_input("EdDSA", claims).map_err(SignError::new)?;
+	let signature = key.sign(token.as_bytes()).await?;
+	token.push('.');
+	URL_SAFE_NO_PAD.encode_string(signature.to_bytes(), &mut token);
+
+	Ok(token)
+}
+
+/// Verifies a JWT and validates the claims.
+///
+/// If `audience` is some, the `aud` claim needs to contain it.
+pub fn verify<K: TokenVerifier, T: DeserializeOwned>(
+	key: &K,
+	token: &str,
+	audience: Option<&str>,
+) -> Result<Claims<T>, TokenError> {
+	verify_at(key, token, audience, crate::now())
+}
+
+/// Like `verify` but validates the claims against `now`.
+pub fn verify_at<K: TokenVerifier, T: DeserializeOwned>(
+	key: &K,
+	token: &str,
+	audience: Option<&str>,
+	now: SystemTime,
+) -> Result<Claims<T>, TokenError> {
+	let (msg, signature) =
+		token.rsplit_once('.').ok_or(TokenError::InvalidFormat)?;
+	let (header, payload) =
+		msg.split_once('.').ok_or(TokenError::InvalidFormat)?;
+
+	let header = decode_json(header)?;
+	match header.get("alg") {
+		Some(Value::String(alg))
+			if Some(alg.as_str()) == algorithm_name(K::ALGORITHM) => {}
+		Some(Value::String(_)) => return Err(TokenError::InvalidAlgorithm),
+		_ => return Err(TokenError::InvalidFormat),
+	}
+
+	let signature = URL_SAFE_NO_PAD
+		.decode(signature)
+		.map_err(|_| TokenError::InvalidFormat)?;
+	if !key.verify_token(msg.as_bytes(), &signature) {
+		return Err(TokenError::InvalidSignature);
+	}
+
+	let payload = URL_SAFE_NO_PAD
+		.decode(payload)
+		.map_err(|_| TokenError::InvalidFormat)?;
+	let claims = Claims::from_json(&payload, parse_numeric_date)?;
+	claims.validate_at(audience, now)?;
+
+	Ok(claims)
+}
+
+/// Returns the base64 encoded header and payload separated by a dot.
+fn signing_input<T: Serialize>(
+	alg: &str,
+	claims: &Claims<T>,
+) -> Result<String, TokenError> {
+	let header = format!(r#"{{"alg":"{alg}","typ":"JWT"}}"#);
+	let payload = claims.to_json(Value::from)?;
+
+	let mut token = URL_SAFE_NO_PAD.encode(header);
+	token.push('.');
+	URL_SAFE_NO_PAD.encode_string(payload, &mut token);
+
+	Ok(token)
+}
+
+/// Returns the header of a token without verifying it.
+///
+/// This can be used to find out which key should be used, for example
+/// by reading the `kid`.
+pub fn unverified_header(
+	token: &str,
+) -> Result<Map<String, Value>, TokenError> {
+	let header = token.split('.').next().unwrap_or_default();
+	decode_json(header)
+}
+
+fn decode_json(part: &str) -> Result<Map<String, Value>, TokenError> {
+	let bytes = URL_SAFE_NO_PAD
+		.decode(part)
+		.map_err(|_| TokenError::InvalidFormat)?;
+	serde_json::from_slice(&bytes).map_err(|_| TokenError::InvalidFormat)
+}
+
+// a NumericDate is allowed to contain fractions of a second
+fn parse_numeric_date(value: Value) -> Option<u64> {
+	match value {
+		Value::Number(n) => n
+			.as_u64()
+			.or_else(|| n.as_f64().filter(|f| *f >= 0.0).map(|f| f as u64)),
+		_ => None,
+	}
+}
+
+/// Maps the algorithm identifier of a `TokenSigner` to the `alg` header.
+fn algorithm_name(algorithm: u8) -> Option<&'static str> {
+	match algorithm {
+		1 => Some("HS256"),
+		2 => Some("EdDSA"),
+		_ => None,
+	}
+}
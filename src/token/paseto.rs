@@ -0,0 +1,433 @@
+//! PASETO v4 tokens.
+//!
+//! `v4.local` tokens are encrypted with a symmetric key (XChaCha20 with
+//! a BLAKE2b mac as described in the specification) and `v4.public`
+//! tokens are signed with an Ed25519 `Keypair`.
+//!
+//! The claims are stored as json, the registered claims `exp` and `nbf`
+//! are validated when decrypting or verifying a token. Implicit
+//! assertions are not supported and always empty.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::cipher::XAead;
+//! use chuchi_crypto::token::paseto::{self, Claims};
+//! use std::time::Duration;
+//!
+//! let key = XAead::new();
+//! let mut claims = Claims::new(Duration::from_secs(60 * 60));
+//! claims.subject = Some("user-1".into());
+//!
+//! let token = paseto::encrypt(&key, &claims, b"").unwrap();
+//! assert!(token.starts_with("v4.local."));
+//!
+//! let claims_2: Claims = paseto::decrypt(&key, &token, b"").unwrap();
+//! assert_eq!(claims, claims_2);
+//! ```
+
+pub use super::claims::Claims;
+use crate::cipher::XAead;
+use crate::error::{SignError, TokenError};
+use crate::signature::{AsyncSigner, Keypair, PublicKey, Signature};
+
+use std::time::SystemTime;
+
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use blake2::digest::consts::{U32, U56};
+use blake2::digest::{KeyInit, Mac};
+use blake2::Blake2bMac;
+
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::XChaCha20;
+
+use _serde::de::DeserializeOwned;
+use _serde::Serialize;
+
+use serde_json::Value;
+
+use subtle::ConstantTimeEq;
+
+const LOCAL_HEADER: &str = "v4.local.";
+const PUBLIC_HEADER: &str = "v4.public.";
+
+const NONCE_LEN: usize = 32;
+const TAG_LEN: usize = 32;
+
+/// Creates a `v4.local` token.
+///
+/// The footer is stored unencrypted but authenticated, pass an empty slice
+/// if you don't need one.
+pub fn encrypt<T: Serialize>(
+	key: &XAead,
+	claims: &Claims<T>,
+	footer: &[u8],
+) -> Result<String, TokenError> {
+	let mut nonce = [0u8; NONCE_LEN];
+	crate::fill_random(&mut nonce);
+
+	let msg = claims.to_json(rfc3339_value)?;
+	Ok(encrypt_with_nonce(key, &msg, footer, &nonce))
+}
+
+fn encrypt_with_nonce(
+	key: &XAead,
+	msg: &[u8],
+	footer: &[u8],
+	nonce: &[u8; NONCE_LEN],
+) -> String {
+	let (mut cipher, auth_key) = local_keys(key, nonce);
+
+	let mut payload = Vec::with_capacity(NONCE_LEN + msg.len() + TAG_LEN);
+	payload.extend_from_slice(nonce);
+	payload.extend_from_slice(msg);
+	cipher.apply_keystream(&mut payload[NONCE_LEN..]);
+
+	let tag = local_tag(&auth_key, nonce, &payload[NONCE_LEN..], footer);
+	payload.extend_from_slice(&tag);
+
+	encode_token(LOCAL_HEADER, &payload, footer)
+}
+
+/// Decrypts a `v4.local` token and validates the claims.
+///
+/// Returns `TokenError::InvalidSignature` if the token was modified or the
+/// footer does not match.
+pub fn decrypt<T: DeserializeOwned>(
+	key: &XAead,
+	token: &str,
+	footer: &[u8],
+) -> Result<Claims<T>, TokenError> {
+	decrypt_at(key, token, footer, crate::now())
+}
+
+/// Like `decrypt` but validates the claims against `now`.
+pub fn decrypt_at<T: DeserializeOwned>(
+	key: &XAead,
+	token: &str,
+	footer: &[u8],
+	now: SystemTime,
+) -> Result<Claims<T>, TokenError> {
+	let payload = decode_token(LOCAL_HEADER, token, footer)?;
+	if payload.len() < NONCE_LEN + TAG_LEN {
+		return Err(TokenError::InvalidFormat);
+	}
+
+	let (nonce, rest) = payload.split_at(NONCE_LEN);
+	let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+	let nonce: &[u8; NONCE_LEN] = nonce.try_into().unwrap();
+
+	let (mut cipher, auth_key) = local_keys(key, nonce);
+
+	let expected = local_tag(&auth_key, nonce, ciphertext, footer);
+	if !bool::from(expected.ct_eq(tag)) {
+		return Err(TokenError::InvalidSignature);
+	}
+
+	let mut msg = ciphertext.to_vec();
+	cipher.apply_keystream(&mut msg);
+
+	let claims = Claims::from_json(&msg, parse_rfc3339_value)?;
+	claims.validate_at(None, now)?;
+
+	Ok(claims)
+}
+
+/// Creates a `v4.public` token.
+///
+/// The claims and footer are not encrypted.
+pub fn sign<T: Serialize>(
+	key: &Keypair,
+	claims: &Claims<T>,
+	footer: &[u8],
+) -> Result<String, TokenError> {
+	let msg = claims.to_json(rfc3339_value)?;
+	let signature =
+		key.sign(pae(&[PUBLIC_HEADER.as_bytes(), &msg, footer, b""]));
+
+	let mut payload = msg;
+	payload.extend_from_slice(&signature.to_bytes());
+
+	Ok(encode_token(PUBLIC_HEADER, &payload, footer))
+}
+
+/// Creates a `v4.public` token signed with an `AsyncSigner`.
+pub async fn sign_async<K: AsyncSigner + ?Sized, T: Serialize>(
+	key: &K,
+	claims: &Claims<T>,
+	footer: &[u8],
+) -> Result<String, SignError> {
+	let msg = claims.to_json(rfc3339_value).map_err(SignError::new)?;
+	let signature = key
+		.sign(&pae(&[PUBLIC_HEADER.as_bytes(), &msg, footer, b""]))
+		.await?;
+
+	let mut payload = msg;
+	payload.extend_from_slice(&signature.to_bytes());
+
+	Ok(encode_token(PUBLIC_HEADER, &payload, footer))
+}
+
+/// Verifies a `v4.public` token and validates the claims.
+pub fn verify<T: DeserializeOwned>(
+	key: &PublicKey,
+	token: &str,
+	footer: &[u8],
+) -> Result<Claims<T>, TokenError> {
+	verify_at(key, token, footer, crate::now())
+}
+
+/// Like `verify` but validates the claims against `now`.
+pub fn verify_at<T: DeserializeOwned>(
+	key: &PublicKey,
+	token: &str,
+	footer: &[u8],
+	now: SystemTime,
+) -> Result<Claims<T>, TokenError> {
+	let payload = decode_token(PUBLIC_HEADER, token, footer)?;
+	if payload.len() < Signature::LEN {
+		return Err(TokenError::InvalidFormat);
+	}
+
+	let (msg, signature) = payload.split_at(payload.len() - Signature::LEN);
+	let signature = Signature::try_from_slice(signature)
+		.map_err(|_| TokenError::InvalidSignature)?;
+
+	let m2 = pae(&[PUBLIC_HEADER.as_bytes(), msg, footer, b""]);
+	if !key.verify(m2, &signature) {
+		return Err(TokenError::InvalidSignature);
+	}
+
+	let claims = Claims::from_json(msg, parse_rfc3339_value)?;
+	claims.validate_at(None, now)?;
+
+	Ok(claims)
+}
+
+/// Returns the footer of a token without verifying it.
+///
+/// This can be used to find out which key should be used, for example
+/// if the footer contains a key id.
+pub fn unverified_footer(token: &str) -> Result<Vec<u8>, TokenError> {
+	match token.splitn(4, '.').nth(3) {
+		Some(footer) => URL_SAFE_NO_PAD
+			.decode(footer)
+			.map_err(|_| TokenError::InvalidFormat),
+		None => Ok(vec![]),
+	}
+}
+
+fn local_keys(key: &XAead, nonce: &[u8; NONCE_LEN]) -> (XChaCha20, [u8; 32]) {
+	let mut key_bytes = key.to_bytes();
+
+	let mut mac = <Blake2bMac<U56> as KeyInit>::new_from_slice(&key_bytes)
+		.expect("32 bytes is a valid key");
+	mac.update(b"paseto-encryption-key");
+	mac.update(nonce);
+	let mut tmp: [u8; 56] = mac.finalize().into_bytes().into();
+
+	let mut mac = <Blake2bMac<U32> as KeyInit>::new_from_slice(&key_bytes)
+		.expect("32 bytes is a valid key");
+	mac.update(b"paseto-auth-key-for-aead");
+	mac.update(nonce);
+	let auth_key = mac.finalize().into_bytes().into();
+
+	let (enc_key, nonce_2) = tmp.split_at(32);
+	let cipher = XChaCha20::new(enc_key.into(), nonce_2.into());
+
+	zeroize::Zeroize::zeroize(&mut key_bytes);
+	zeroize::Zeroize::zeroize(&mut tmp);
+
+	(cipher, auth_key)
+}
+
+fn local_tag(
+	auth_key: &[u8; 32],
+	nonce: &[u8],
+	ciphertext: &[u8],
+	footer: &[u8],
+) -> [u8; TAG_LEN] {
+	let pre_auth =
+		pae(&[LOCAL_HEADER.as_bytes(), nonce, ciphertext, footer, b""]);
+
+	let mut mac = <Blake2bMac<U32> as KeyInit>::new_from_slice(auth_key)
+		.expect("32 bytes is a valid key");
+	mac.update(&pre_auth);
+	mac.finalize().into_bytes().into()
+}
+
+/// Pre-Authentication Encoding
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+	let mut out = Vec::new();
+	out.extend_from_slice(&le64(pieces.len() as u64));
+	for piece in pieces {
+		out.extend_from_slice(&le64(piece.len() as u64));
+		out.extend_from_slice(piece);
+	}
+
+	out
+}
+
+fn le64(n: u64) -> [u8; 8] {
+	// the most significant bit needs to be cleared
+	(n & (u64::MAX >> 1)).to_le_bytes()
+}
+
+fn encode_token(header: &str, payload: &[u8], footer: &[u8]) -> String {
+	let mut token = header.to_string();
+	URL_SAFE_NO_PAD.encode_string(payload, &mut token);
+
+	if !footer.is_empty() {
+		token.push('.');
+		URL_SAFE_NO_PAD.encode_string(footer, &mut token);
+	}
+
+	token
+}
+
+/// Returns the decoded payload, if the footer does not match
+/// `TokenError::InvalidSignature` is returned.
+fn decode_token(
+	header: &str,
+	token: &str,
+	footer: &[u8],
+) -> Result<Vec<u8>, TokenError> {
+	let rest = token
+		.strip_prefix(header)
+		.ok_or(TokenError::InvalidAlgorithm)?;
+
+	let (payload, token_footer) = match rest.split_once('.') {
+		Some((payload, footer)) => (payload, footer),
+		None => (rest, ""),
+	};
+
+	let token_footer = URL_SAFE_NO_PAD
+		.decode(token_footer)
+		.map_err(|_| TokenError::InvalidFormat)?;
+	if !bool::from(token_footer.ct_eq(footer)) {
+		return Err(TokenError::InvalidSignature);
+	}
+
+	URL_SAFE_NO_PAD
+		.decode(payload)
+		.map_err(|_| TokenError::InvalidFormat)
+}
+
+fn rfc3339_value(secs: u64) -> Value {
+	Value::String(format_rfc3339(secs))
+}
+
+fn parse_rfc3339_value(value: Value) -> Option<u64> {
+	match value {
+		Value::String(s) => parse_rfc3339(&s),
+		_ => None,
+	}
+}
+
+// see http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+	let y = if m <= 2 { y - 1 } else { y };
+	let era = if y >= 0 { y } else { y - 399 } / 400;
+	let yoe = y - era * 400;
+	let m = m as i64;
+	let doy =
+		(153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d as i64 - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+	era * 146097 + doe - 719468
+}
+
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+	let z = z + 719468;
+	let era = if z >= 0 { z } else { z - 146096 } / 146097;
+	let doe = z - era * 146097;
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+	let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+	let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+	(y, m, d)
+}
+
+/// Formats the seconds as `YYYY-MM-DDTHH:MM:SS+00:00`.
+fn format_rfc3339(secs: u64) -> String {
+	let days = (secs / 86400) as i64;
+	let rem = secs % 86400;
+	let (y, m, d) = civil_from_days(days);
+
+	format!(
+		"{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+		y,
+		m,
+		d,
+		rem / 3600,
+		rem / 60 % 60,
+		rem % 60
+	)
+}
+
+/// Parses `YYYY-MM-DDTHH:MM:SS[.fraction](Z|+HH:MM|-HH:MM)`, the fraction
+/// is ignored.
+fn parse_rfc3339(s: &str) -> Option<u64> {
+	let b = s.as_bytes();
+	if b.len() < 20
+		|| b[4] != b'-'
+		|| b[7] != b'-'
+		|| !matches!(b[10], b'T' | b't')
+		|| b[13] != b':'
+		|| b[16] != b':'
+	{
+		return None;
+	}
+
+	let num = |r: std::ops::Range<usize>| -> Option<u32> {
+		let s = s.get(r)?;
+		if !s.bytes().all(|c| c.is_ascii_digit()) {
+			return None;
+		}
+		s.parse().ok()
+	};
+
+	let (y, mo, d) = (num(0..4)?, num(5..7)?, num(8..10)?);
+	let (h, mi, sec) = (num(11..13)?, num(14..16)?, num(17..19)?);
+	if !(1..=12).contains(&mo)
+		|| !(1..=31).contains(&d)
+		|| h > 23
+		|| mi > 59
+		|| sec > 60
+	{
+		return None;
+	}
+
+	let mut rest = &s[19..];
+	if let Some(r) = rest.strip_prefix('.') {
+		let digits = r.bytes().take_while(|c| c.is_ascii_digit()).count();
+		if digits == 0 {
+			return None;
+		}
+		rest = &r[digits..];
+	}
+
+	let offset: i64 = match rest.as_bytes() {
+		[b'Z' | b'z'] => 0,
+		[sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+			let base = s.len() - rest.len();
+			let oh = num(base + 1..base + 3)?;
+			let om = num(base + 4..base + 6)?;
+			let offset = (oh * 3600 + om * 60) as i64;
+			if *sign == b'+' {
+				offset
+			} else {
+				-offset
+			}
+		}
+		_ => return None,
+	};
+
+	let days = days_from_civil(y as i64, mo, d);
+	let secs = days * 86400 + (h * 3600 + mi * 60 + sec) as i64 - offset;
+
+	secs.try_into().ok()
+}
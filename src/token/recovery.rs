@@ -0,0 +1,163 @@
+use crate::error::DecodeError;
+use crate::hash::sha2::{sha256, Sha256Hash};
+
+use std::fmt;
+
+use subtle::{Choice, ConstantTimeEq};
+
+/// A recovery code which is easy to type, for example
+/// `ABCD-EFGH-IJKL-MNOP`.
+///
+/// A code contains 80 random bits, encoded as base32 in groups of four.
+/// Only the hash of a code should be stored.
+///
+/// Comparing two codes is done in constant time.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::token::RecoveryCode;
+///
+/// let codes = RecoveryCode::batch(10);
+/// // show the codes to the user and store the hashes
+/// let hashes: Vec<_> = codes.iter().map(|(_, hash)| hash.clone()).collect();
+///
+/// let input = codes[3].0.to_string().to_lowercase();
+/// let code: RecoveryCode = input.parse().unwrap();
+/// assert_eq!(code.verify_any(&hashes), Some(3));
+/// ```
+#[derive(Clone)]
+pub struct RecoveryCode {
+	bytes: [u8; 10],
+}
+
+impl RecoveryCode {
+	pub const LEN: usize = 10;
+
+	/// The length of the string without the dashes.
+	const CHARS: usize = 16;
+
+	/// Creates a new random recovery code.
+	///
+	/// ## Panics
+	/// If the OsRng fails to return random bytes.
+	pub fn new() -> Self {
+		let mut bytes = [0u8; Self::LEN];
+		crate::fill_random(&mut bytes);
+
+		Self { bytes }
+	}
+
+	/// Creates `count` recovery codes and returns them together with
+	/// their hashes.
+	///
+	/// ## Panics
+	/// If the OsRng fails to return random bytes.
+	pub fn batch(count: usize) -> Vec<(Self, Sha256Hash)> {
+		(0..count)
+			.map(|_| {
+				let code = Self::new();
+				let hash = code.hash();
+				(code, hash)
+			})
+			.collect()
+	}
+
+	/// Returns the hash which should be stored.
+	pub fn hash(&self) -> Sha256Hash {
+		sha256(self.bytes)
+	}
+
+	/// Checks in constant time if the code matches the hash.
+	pub fn verify(&self, hash: &Sha256Hash) -> bool {
+		self.hash().as_ref().ct_eq(hash.as_ref()).into()
+	}
+
+	/// Returns the index of the hash matching this code.
+	///
+	/// All hashes are compared, so the time does not depend on which one
+	/// matched.
+	pub fn verify_any(&self, hashes: &[Sha256Hash]) -> Option<usize> {
+		let hash = self.hash();
+
+		let mut found = None;
+		for (i, h) in hashes.iter().enumerate() {
+			if bool::from(hash.as_ref().ct_eq(h.as_ref())) && found.is_none() {
+				found = Some(i);
+			}
+		}
+
+		found
+	}
+}
+
+impl ConstantTimeEq for RecoveryCode {
+	fn ct_eq(&self, other: &Self) -> Choice {
+		self.bytes.ct_eq(&other.bytes)
+	}
+}
+
+impl PartialEq for RecoveryCode {
+	fn eq(&self, other: &Self) -> bool {
+		self.ct_eq(other).into()
+	}
+}
+
+impl Eq for RecoveryCode {}
+
+impl fmt::Debug for RecoveryCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("RecoveryCode")
+	}
+}
+
+/// Formats the code as `ABCD-EFGH-IJKL-MNOP`.
+impl fmt::Display for RecoveryCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let s = crate::base32_encode(&self.bytes);
+
+		for (i, group) in s.as_bytes().chunks(4).enumerate() {
+			if i > 0 {
+				f.write_str("-")?;
+			}
+			// base32 is always ascii
+			f.write_str(std::str::from_utf8(group).unwrap())?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Dashes, spaces and lowercase letters are accepted.
+impl std::str::FromStr for RecoveryCode {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s: String = s.chars().filter(|c| *c != '-' && *c != ' ').collect();
+		if s.len() != Self::CHARS {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		let bytes = crate::base32_decode(&s)?;
+
+		Ok(Self {
+			bytes: bytes.try_into().map_err(|_| DecodeError::InvalidLength)?,
+		})
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for RecoveryCode {
+	fn zeroize(&mut self) {
+		self.bytes.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for RecoveryCode {
+	fn drop(&mut self) {
+		zeroize::Zeroize::zeroize(self);
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for RecoveryCode {}
@@ -0,0 +1,357 @@
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::error::TokenError;
+use crate::unix_secs;
+
+use super::claims::validate_lifetime;
+use super::Token;
+
+use std::convert::TryInto;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "b64")]
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+const VERSION: u8 = 1;
+
+// version + algorithm + id + issued_at + expires_at + subject len
+const HEADER_LEN: usize = 1 + 1 + 16 + 8 + 8 + 2;
+
+/// A key which can sign a `SignedToken`.
+pub trait TokenSigner {
+	/// The algorithm identifier which gets stored in the token.
+	const ALGORITHM: u8;
+
+	fn sign_token(&self, msg: &[u8]) -> Vec<u8>;
+}
+
+/// A key which can verify a `SignedToken`.
+pub trait TokenVerifier {
+	/// The algorithm identifier which needs to match the one in the token.
+	const ALGORITHM: u8;
+
+	fn verify_token(&self, msg: &[u8], signature: &[u8]) -> bool;
+}
+
+/// The claims contained in a `SignedToken`.
+///
+/// The timestamps are in seconds since the unix epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claims {
+	/// A random id which can be used to revoke the token.
+	pub id: Token<16>,
+	pub subject: String,
+	pub issued_at: u64,
+	pub expires_at: u64,
+}
+
+impl Claims {
+	/// ## Panics
+	/// if the subject is longer than `u16::MAX` bytes.
+	fn new(subject: String, ttl: Duration) -> Self {
+		assert!(subject.len() <= u16::MAX as usize, "subject too long");
+
+		let now = unix_secs(crate::now());
+		Self {
+			id: Token::new(),
+			subject,
+			issued_at: now,
+			expires_at: now.saturating_add(ttl.as_secs()),
+		}
+	}
+
+	fn encode(&self, algorithm: u8) -> Vec<u8> {
+		let subject = self.subject.as_bytes();
+		let mut bytes = Vec::with_capacity(HEADER_LEN + subject.len());
+		bytes.push(VERSION);
+		bytes.push(algorithm);
+		bytes.extend_from_slice(self.id.as_ref());
+		bytes.extend_from_slice(&self.issued_at.to_be_bytes());
+		bytes.extend_from_slice(&self.expires_at.to_be_bytes());
+		bytes.extend_from_slice(&(subject.len() as u16).to_be_bytes());
+		bytes.extend_from_slice(subject);
+
+		bytes
+	}
+}
+
+/// A token which contains an expiry date and is signed with either
+/// a `hash::HmacKey` or a `signature::Keypair`.
+///
+/// ## Example
+/// ```
+/// # #[cfg(feature = "hash")]
+/// # {
+/// use chuchi_crypto::hash::HmacKey;
+/// use chuchi_crypto::token::SignedToken;
+/// use std::time::Duration;
+///
+/// let key = HmacKey::new(b"my secret key");
+///
+/// let ttl = Duration::from_secs(60 * 60);
+/// let token = SignedToken::issue_with_subject(&key, "user-1", ttl);
+///
+/// let claims = token.verify(&key).unwrap();
+/// assert_eq!(claims.subject, "user-1");
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct SignedToken {
+	claims: Claims,
+	algorithm: u8,
+	signature: Vec<u8>,
+}
+
+impl SignedToken {
+	/// Issues a new token without a subject which is valid for `ttl`.
+	pub fn issue<K: TokenSigner>(key: &K, ttl: Duration) -> Self {
+		Self::issue_with_subject(key, String::new(), ttl)
+	}
+
+	/// Issues a new token which is valid for `ttl`.
+	///
+	/// ## Panics
+	/// if the subject is longer than `u16::MAX` bytes.
+	pub fn issue_with_subject<K: TokenSigner>(
+		key: &K,
+		subject: impl Into<String>,
+		ttl: Duration,
+	) -> Self {
+		let claims = Claims::new(subject.into(), ttl);
+
+		let signature = key.sign_token(&claims.encode(K::ALGORITHM));
+
+		Self {
+			claims,
+			algorithm: K::ALGORITHM,
+			signature,
+		}
+	}
+
+	/// Returns the claims without verifying the signature or the expiry.
+	pub fn unverified_claims(&self) -> &Claims {
+		&self.claims
+	}
+
+	/// Verifies the signature and makes sure the token has not expired.
+	pub fn verify<K: TokenVerifier>(
+		&self,
+		key: &K,
+	) -> Result<Claims, TokenError> {
+		self.verify_at(key, crate::now())
+	}
+
+	/// Verifies the signature and makes sure the token was not expired at
+	/// the given time.
+	pub fn verify_at<K: TokenVerifier>(
+		&self,
+		key: &K,
+		now: SystemTime,
+	) -> Result<Claims, TokenError> {
+		if self.algorithm != K::ALGORITHM {
+			return Err(TokenError::InvalidAlgorithm);
+		}
+
+		let msg = self.claims.encode(self.algorithm);
+		if !key.verify_token(&msg, &self.signature) {
+			return Err(TokenError::InvalidSignature);
+		}
+
+		validate_lifetime(Some(self.claims.expires_at), None, now)?;
+
+		Ok(self.claims.clone())
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = self.claims.encode(self.algorithm);
+		bytes.extend_from_slice(&self.signature);
+
+		bytes
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, TokenError> {
+		if bytes.len() < HEADER_LEN || bytes[0] != VERSION {
+			return Err(TokenError::InvalidFormat);
+		}
+
+		let algorithm = bytes[1];
+		let id = Token::try_from_slice(&bytes[2..18]).unwrap();
+		let issued_at = u64::from_be_bytes(bytes[18..26].try_into().unwrap());
+		let expires_at = u64::from_be_bytes(bytes[26..34].try_into().unwrap());
+		let subject_len =
+			u16::from_be_bytes(bytes[34..36].try_into().unwrap()) as usize;
+
+		let rest = &bytes[HEADER_LEN..];
+		if rest.len() < subject_len {
+			return Err(TokenError::InvalidFormat);
+		}
+
+		let (subject, signature) = rest.split_at(subject_len);
+		let subject = std::str::from_utf8(subject)
+			.map_err(|_| TokenError::InvalidFormat)?
+			.to_string();
+
+		Ok(Self {
+			claims: Claims {
+				id,
+				subject,
+				issued_at,
+				expires_at,
+			},
+			algorithm,
+			signature: signature.to_vec(),
+		})
+	}
+}
+
+#[cfg(not(feature = "b64"))]
+impl fmt::Debug for SignedToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SignedToken")
+			.field("claims", &self.claims)
+			.field("algorithm", &self.algorithm)
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Debug for SignedToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("SignedToken")
+			.field(&self.to_string())
+			.finish()
+	}
+}
+
+#[cfg(feature = "b64")]
+impl fmt::Display for SignedToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		base64::display::Base64Display::new(&self.to_bytes(), &URL_SAFE_NO_PAD)
+			.fmt(f)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for SignedToken {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let bytes =
+			URL_SAFE_NO_PAD.decode(s).map_err(DecodeError::inv_bytes)?;
+
+		Self::from_bytes(&bytes).map_err(DecodeError::inv_bytes)
+	}
+}
+
+#[cfg(feature = "hash")]
+mod impl_mac {
+	use super::*;
+
+	use crate::hash::{HmacKey, Tag};
+
+	impl TokenSigner for HmacKey {
+		const ALGORITHM: u8 = 1;
+
+		fn sign_token(&self, msg: &[u8]) -> Vec<u8> {
+			self.sign(msg).to_bytes().to_vec()
+		}
+	}
+
+	impl TokenVerifier for HmacKey {
+		const ALGORITHM: u8 = 1;
+
+		fn verify_token(&self, msg: &[u8], signature: &[u8]) -> bool {
+			Tag::try_from_slice(signature)
+				.map(|tag| self.verify(msg, &tag))
+				.unwrap_or(false)
+		}
+	}
+}
+
+#[cfg(feature = "signature")]
+mod impl_signature {
+	use super::*;
+
+	use crate::error::SignError;
+	use crate::signature::{AsyncSigner, Keypair, PublicKey, Signature};
+
+	impl SignedToken {
+		/// Like `issue_with_subject` but the token is signed with an
+		/// `AsyncSigner` and can be verified with its public key.
+		///
+		/// ## Panics
+		/// if the subject is longer than `u16::MAX` bytes.
+		pub async fn issue_async<K: AsyncSigner + ?Sized>(
+			key: &K,
+			subject: impl Into<String>,
+			ttl: Duration,
+		) -> Result<Self, SignError> {
+			let algorithm = <Keypair as TokenSigner>::ALGORITHM;
+			let claims = Claims::new(subject.into(), ttl);
+			let signature = key.sign(&claims.encode(algorithm)).await?;
+
+			Ok(Self {
+				claims,
+				algorithm,
+				signature: signature.to_bytes().to_vec(),
+			})
+		}
+	}
+
+	impl TokenSigner for Keypair {
+		const ALGORITHM: u8 = 2;
+
+		fn sign_token(&self, msg: &[u8]) -> Vec<u8> {
+			self.sign(msg).to_bytes().to_vec()
+		}
+	}
+
+	impl TokenVerifier for Keypair {
+		const ALGORITHM: u8 = 2;
+
+		fn verify_token(&self, msg: &[u8], signature: &[u8]) -> bool {
+			self.public().verify_token(msg, signature)
+		}
+	}
+
+	impl TokenVerifier for PublicKey {
+		const ALGORITHM: u8 = 2;
+
+		fn verify_token(&self, msg: &[u8], signature: &[u8]) -> bool {
+			Signature::try_from_slice(signature)
+				.map(|sign| self.verify(msg, &sign))
+				.unwrap_or(false)
+		}
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "serde"))]
+mod impl_serde {
+	use super::*;
+
+	use std::borrow::Cow;
+	use std::str::FromStr;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for SignedToken {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			serializer.collect_str(&self)
+		}
+	}
+
+	impl<'de> Deserialize<'de> for SignedToken {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+			Self::from_str(s.as_ref()).map_err(D::Error::custom)
+		}
+	}
+}
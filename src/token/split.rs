@@ -0,0 +1,146 @@
+use super::Token;
+#[cfg(feature = "b64")]
+use crate::error::DecodeError;
+use crate::hash::sha2::{sha256, Sha256Hash};
+
+use std::fmt;
+
+use subtle::ConstantTimeEq;
+
+/// A token split into a public selector and a secret verifier.
+///
+/// The selector is used to look up the token in the database, only the
+/// hash of the verifier is stored next to it. If the database leaks the
+/// tokens can't be used and since the lookup does not depend on the secret
+/// part, the comparison does not leak timing information.
+///
+/// This is the pattern recommended for password reset or remember me
+/// tokens.
+///
+/// ## Example
+/// ```
+/// use chuchi_crypto::token::SplitToken;
+///
+/// let token = SplitToken::new();
+/// // store those two in the database
+/// let selector = token.selector().clone();
+/// let verifier_hash = token.verifier_hash();
+///
+/// # #[cfg(feature = "b64")]
+/// # {
+/// // send this to the user
+/// let s = token.to_string();
+///
+/// // when the user comes back
+/// let token: SplitToken = s.parse().unwrap();
+/// assert_eq!(token.selector(), &selector);
+/// assert!(token.verify(&verifier_hash));
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SplitToken {
+	selector: Token<16>,
+	verifier: Token<32>,
+}
+
+impl SplitToken {
+	/// Creates a new random split token.
+	pub fn new() -> Self {
+		Self {
+			selector: Token::new(),
+			verifier: Token::new(),
+		}
+	}
+
+	pub fn from_parts(selector: Token<16>, verifier: Token<32>) -> Self {
+		Self { selector, verifier }
+	}
+
+	/// Returns the public part, which can be used to look up the token.
+	pub fn selector(&self) -> &Token<16> {
+		&self.selector
+	}
+
+	/// Returns the secret part, this should never be stored.
+	pub fn verifier(&self) -> &Token<32> {
+		&self.verifier
+	}
+
+	/// Returns the hash of the verifier which should be stored.
+	pub fn verifier_hash(&self) -> Sha256Hash {
+		sha256(&self.verifier)
+	}
+
+	/// Checks in constant time if the verifier matches the stored hash.
+	pub fn verify(&self, verifier_hash: &Sha256Hash) -> bool {
+		let hash = self.verifier_hash();
+		hash.as_ref().ct_eq(verifier_hash.as_ref()).into()
+	}
+}
+
+impl Default for SplitToken {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Only shows the selector.
+impl fmt::Debug for SplitToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SplitToken")
+			.field("selector", &self.selector)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Formats the token as `<selector>.<verifier>`.
+#[cfg(feature = "b64")]
+impl fmt::Display for SplitToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}.{}", self.selector, self.verifier)
+	}
+}
+
+#[cfg(feature = "b64")]
+impl crate::FromStr for SplitToken {
+	type Err = DecodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (selector, verifier) =
+			s.split_once('.').ok_or(DecodeError::InvalidBytes)?;
+
+		Ok(Self {
+			selector: selector.parse()?,
+			verifier: verifier.parse()?,
+		})
+	}
+}
+
+#[cfg(all(feature = "b64", feature = "serde"))]
+mod impl_serde {
+	use super::*;
+
+	use std::borrow::Cow;
+
+	use _serde::de::Error;
+	use _serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	impl Serialize for SplitToken {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			serializer.collect_str(&self)
+		}
+	}
+
+	impl<'de> Deserialize<'de> for SplitToken {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			let s: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
+			s.parse().map_err(D::Error::custom)
+		}
+	}
+}
@@ -0,0 +1,436 @@
+//! Verifying WebAuthn assertions.
+//!
+//! The authenticator signs `authenticatorData || SHA-256(clientDataJSON)`
+//! with the credential key which was registered as a `COSE_Key`, Ed25519
+//! (`EdDSA`) and P-256 (`ES256`) keys are supported.
+//! `verify_assertion` checks the signature together with the type, the
+//! challenge and the origin in the client data and the relying party id
+//! and the flags in the authenticator data.
+//!
+//! The signature counter is returned in `AuthenticatorData` and needs to be
+//! compared with the stored one by the caller.
+//!
+//! ## Example
+//! ```no_run
+//! use chuchi_crypto::webauthn::{
+//!     self, Assertion, CredentialPublicKey, Expected,
+//! };
+//!
+//! # let stored_cose_key = vec![];
+//! # let (auth_data, client_data, sig) = (vec![], vec![], vec![]);
+//! let key = CredentialPublicKey::from_cose_key(&stored_cose_key).unwrap();
+//!
+//! let assertion = Assertion {
+//!     authenticator_data: &auth_data,
+//!     client_data_json: &client_data,
+//!     signature: &sig,
+//! };
+//! let expected = Expected {
+//!     challenge: b"random challenge",
+//!     origin: "https://example.com",
+//!     rp_id: "example.com",
+//!     require_user_verification: true,
+//! };
+//!
+//! let auth_data =
+//!     webauthn::verify_assertion(&key, &assertion, &expected).unwrap();
+//! // compare auth_data.sign_count with the stored counter
+//! ```
+
+use crate::error::{DecodeError, WebauthnError};
+use crate::signature::{p256, PublicKey, Signature};
+
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use serde_json::Value;
+
+use sha2::{Digest, Sha256};
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+const FLAG_EXTENSION_DATA: u8 = 0x80;
+
+/// The fixed part of the authenticator data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatorData {
+	/// SHA-256 of the relying party id
+	pub rp_id_hash: [u8; 32],
+	pub flags: u8,
+	pub sign_count: u32,
+}
+
+impl AuthenticatorData {
+	/// The length of the fixed part.
+	pub const MIN_LEN: usize = 37;
+
+	/// Parses the fixed part, attested credential data and extensions are
+	/// not parsed.
+	pub fn parse(bytes: &[u8]) -> Result<Self, DecodeError> {
+		if bytes.len() < Self::MIN_LEN {
+			return Err(DecodeError::InvalidLength);
+		}
+
+		Ok(Self {
+			// the length is checked above
+			rp_id_hash: bytes[..32].try_into().unwrap(),
+			flags: bytes[32],
+			sign_count: u32::from_be_bytes(bytes[33..37].try_into().unwrap()),
+		})
+	}
+
+	pub fn user_present(&self) -> bool {
+		self.flags & FLAG_USER_PRESENT != 0
+	}
+
+	pub fn user_verified(&self) -> bool {
+		self.flags & FLAG_USER_VERIFIED != 0
+	}
+
+	pub fn has_attested_credential_data(&self) -> bool {
+		self.flags & FLAG_ATTESTED_CREDENTIAL_DATA != 0
+	}
+
+	pub fn has_extension_data(&self) -> bool {
+		self.flags & FLAG_EXTENSION_DATA != 0
+	}
+
+	/// Returns true if `rp_id_hash` is the hash of the relying party id.
+	pub fn is_rp_id(&self, rp_id: &str) -> bool {
+		Sha256::digest(rp_id.as_bytes())[..] == self.rp_id_hash
+	}
+}
+
+/// The parsed `clientDataJSON`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientData {
+	/// `type`, `webauthn.get` for assertions
+	pub kind: String,
+	/// The decoded `challenge`
+	pub challenge: Vec<u8>,
+	pub origin: String,
+	/// `crossOrigin`
+	pub cross_origin: bool,
+}
+
+impl ClientData {
+	pub fn parse(json: &[u8]) -> Result<Self, DecodeError> {
+		let value: Value =
+			serde_json::from_slice(json).map_err(DecodeError::inv_bytes)?;
+
+		let string = |key: &str| {
+			value
+				.get(key)
+				.and_then(Value::as_str)
+				.ok_or(DecodeError::InvalidBytes)
+		};
+
+		let challenge = URL_SAFE_NO_PAD
+			.decode(string("challenge")?)
+			.map_err(DecodeError::inv_bytes)?;
+
+		let cross_origin = match value.get("crossOrigin") {
+			Some(Value::Bool(b)) => *b,
+			None | Some(Value::Null) => false,
+			Some(_) => return Err(DecodeError::InvalidBytes),
+		};
+
+		Ok(Self {
+			kind: string("type")?.into(),
+			challenge,
+			origin: string("origin")?.into(),
+			cross_origin,
+		})
+	}
+}
+
+/// A credential public key stored during registration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CredentialPublicKey {
+	/// COSE algorithm `EdDSA` (-8)
+	Ed25519(PublicKey),
+	/// COSE algorithm `ES256` (-7)
+	Es256(p256::PublicKey),
+}
+
+impl CredentialPublicKey {
+	/// Parses the `COSE_Key` from the attested credential data.
+	///
+	/// Returns an error if the key type is not supported.
+	pub fn from_cose_key(bytes: &[u8]) -> Result<Self, DecodeError> {
+		PublicKey::from_cose_key(bytes)
+			.map(Self::Ed25519)
+			.or_else(|_| p256::PublicKey::from_cose_key(bytes).map(Self::Es256))
+	}
+
+	/// Verifies a signature over `signed_payload`.
+	///
+	/// `ES256` signatures are expected to be DER encoded like
+	/// authenticators return them.
+	pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+		match self {
+			Self::Ed25519(key) => Signature::try_from_slice(signature)
+				.map(|sig| key.verify(msg, &sig))
+				.unwrap_or(false),
+			Self::Es256(key) => p256::Signature::from_der(signature)
+				.map(|sig| key.verify(msg, &sig))
+				.unwrap_or(false),
+		}
+	}
+}
+
+/// The response of `navigator.credentials.get()`.
+#[derive(Debug, Clone, Copy)]
+pub struct Assertion<'a> {
+	pub authenticator_data: &'a [u8],
+	pub client_data_json: &'a [u8],
+	pub signature: &'a [u8],
+}
+
+/// The values an assertion needs to match.
+#[derive(Debug, Clone, Copy)]
+pub struct Expected<'a> {
+	/// The challenge sent to the client
+	pub challenge: &'a [u8],
+	/// The origin of the website, for example `https://example.com`
+	pub origin: &'a str,
+	/// The relying party id, for example `example.com`
+	pub rp_id: &'a str,
+	pub require_user_verification: bool,
+}
+
+/// Returns the data the authenticator signs,
+/// `authenticatorData || SHA-256(clientDataJSON)`.
+pub fn signed_payload(
+	authenticator_data: &[u8],
+	client_data_json: &[u8],
+) -> Vec<u8> {
+	let mut payload = authenticator_data.to_vec();
+	payload.extend_from_slice(&Sha256::digest(client_data_json));
+
+	payload
+}
+
+/// Verifies an assertion and returns the authenticator data.
+pub fn verify_assertion(
+	public_key: &CredentialPublicKey,
+	assertion: &Assertion,
+	expected: &Expected,
+) -> Result<AuthenticatorData, WebauthnError> {
+	let auth_data = AuthenticatorData::parse(assertion.authenticator_data)
+		.map_err(|_| WebauthnError::InvalidFormat)?;
+	let client_data = ClientData::parse(assertion.client_data_json)
+		.map_err(|_| WebauthnError::InvalidFormat)?;
+
+	if client_data.kind != "webauthn.get" {
+		return Err(WebauthnError::InvalidType);
+	}
+	if client_data.challenge != expected.challenge {
+		return Err(WebauthnError::InvalidChallenge);
+	}
+	if client_data.origin != expected.origin {
+		return Err(WebauthnError::InvalidOrigin);
+	}
+	if !auth_data.is_rp_id(expected.rp_id) {
+		return Err(WebauthnError::InvalidRpId);
+	}
+	if !auth_data.user_present() {
+		return Err(WebauthnError::UserNotPresent);
+	}
+	if expected.require_user_verification && !auth_data.user_verified() {
+		return Err(WebauthnError::UserNotVerified);
+	}
+
+	let payload = signed_payload(
+		assertion.authenticator_data,
+		assertion.client_data_json,
+	);
+	if !public_key.verify(&payload, assertion.signature) {
+		return Err(WebauthnError::InvalidSignature);
+	}
+
+	Ok(auth_data)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::signature::Keypair;
+
+	const ORIGIN: &str = "https://example.com";
+	const RP_ID: &str = "example.com";
+
+	fn auth_data(rp_id: &str, flags: u8, sign_count: u32) -> Vec<u8> {
+		let mut data = Sha256::digest(rp_id.as_bytes()).to_vec();
+		data.push(flags);
+		data.extend_from_slice(&sign_count.to_be_bytes());
+
+		data
+	}
+
+	fn client_data(kind: &str, challenge: &[u8], origin: &str) -> Vec<u8> {
+		format!(
+			r#"{{"type":"{kind}","challenge":"{}","origin":"{origin}","crossOrigin":false}}"#,
+			URL_SAFE_NO_PAD.encode(challenge)
+		)
+		.into_bytes()
+	}
+
+	#[test]
+	fn test_authenticator_data() {
+		let data = auth_data(RP_ID, 0x05, 42);
+		let parsed = AuthenticatorData::parse(&data).unwrap();
+		assert!(parsed.user_present());
+		assert!(parsed.user_verified());
+		assert!(!parsed.has_attested_credential_data());
+		assert_eq!(parsed.sign_count, 42);
+		assert!(parsed.is_rp_id(RP_ID));
+		assert!(!parsed.is_rp_id("example.org"));
+
+		assert!(matches!(
+			AuthenticatorData::parse(&data[..36]),
+			Err(DecodeError::InvalidLength)
+		));
+	}
+
+	#[cfg(feature = "hex")]
+	#[test]
+	fn test_es256_vector() {
+		// the key and signature from RFC 6979 A.2.5 with SHA-256 and the
+		// message "sample" as a COSE_Key and a DER signature
+		let cose_key = hex::decode(
+			"a5010203262001215820\
+			60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6\
+			225820\
+			7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299",
+		)
+		.unwrap();
+		let signature = hex::decode(
+			"3046022100\
+			efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+			022100\
+			f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+		)
+		.unwrap();
+
+		let key = CredentialPublicKey::from_cose_key(&cose_key).unwrap();
+		assert!(matches!(key, CredentialPublicKey::Es256(_)));
+		assert!(key.verify(b"sample", &signature));
+		assert!(!key.verify(b"sample!", &signature));
+		// the raw r || s encoding is not accepted
+		assert!(!key.verify(b"sample", &signature[6..]));
+	}
+
+	#[test]
+	fn test_verify_assertion_es256() {
+		let keypair = p256::Keypair::new();
+		let key =
+			CredentialPublicKey::from_cose_key(&keypair.public().to_cose_key())
+				.unwrap();
+
+		let expected = Expected {
+			challenge: b"challenge",
+			origin: ORIGIN,
+			rp_id: RP_ID,
+			require_user_verification: false,
+		};
+
+		let auth = auth_data(RP_ID, 0x01, 3);
+		let client = client_data("webauthn.get", b"challenge", ORIGIN);
+		let sig = keypair.sign(signed_payload(&auth, &client)).to_der();
+		let assertion = Assertion {
+			authenticator_data: &auth,
+			client_data_json: &client,
+			signature: &sig,
+		};
+		let data = verify_assertion(&key, &assertion, &expected).unwrap();
+		assert_eq!(data.sign_count, 3);
+
+		let other = p256::Keypair::new();
+		let sig = other.sign(signed_payload(&auth, &client)).to_der();
+		let assertion = Assertion {
+			signature: &sig,
+			..assertion
+		};
+		assert_eq!(
+			verify_assertion(&key, &assertion, &expected).unwrap_err(),
+			WebauthnError::InvalidSignature
+		);
+	}
+
+	#[test]
+	fn test_verify_assertion() {
+		let keypair = Keypair::new();
+		let key =
+			CredentialPublicKey::from_cose_key(&keypair.public().to_cose_key())
+				.unwrap();
+
+		let expected = Expected {
+			challenge: b"challenge",
+			origin: ORIGIN,
+			rp_id: RP_ID,
+			require_user_verification: true,
+		};
+
+		let verify = |auth_data: &[u8], client_data: &[u8]| {
+			let sig = keypair.sign(signed_payload(auth_data, client_data));
+			let assertion = Assertion {
+				authenticator_data: auth_data,
+				client_data_json: client_data,
+				signature: &sig.to_bytes(),
+			};
+			verify_assertion(&key, &assertion, &expected)
+		};
+
+		let client = client_data("webauthn.get", b"challenge", ORIGIN);
+		let data = verify(&auth_data(RP_ID, 0x05, 7), &client).unwrap();
+		assert_eq!(data.sign_count, 7);
+
+		assert_eq!(
+			verify(&auth_data(RP_ID, 0x01, 7), &client).unwrap_err(),
+			WebauthnError::UserNotVerified
+		);
+		assert_eq!(
+			verify(&auth_data(RP_ID, 0x04, 7), &client).unwrap_err(),
+			WebauthnError::UserNotPresent
+		);
+		assert_eq!(
+			verify(&auth_data("example.org", 0x05, 7), &client).unwrap_err(),
+			WebauthnError::InvalidRpId
+		);
+
+		let auth = auth_data(RP_ID, 0x05, 7);
+		let cases = [
+			(
+				client_data("webauthn.create", b"challenge", ORIGIN),
+				WebauthnError::InvalidType,
+			),
+			(
+				client_data("webauthn.get", b"other", ORIGIN),
+				WebauthnError::InvalidChallenge,
+			),
+			(
+				client_data("webauthn.get", b"challenge", "https://evil.com"),
+				WebauthnError::InvalidOrigin,
+			),
+			(b"{}".to_vec(), WebauthnError::InvalidFormat),
+		];
+		for (client, err) in cases {
+			assert_eq!(verify(&auth, &client).unwrap_err(), err);
+		}
+
+		// a signature from another key
+		let other = Keypair::new();
+		let sig = other.sign(signed_payload(&auth, &client));
+		let assertion = Assertion {
+			authenticator_data: &auth,
+			client_data_json: &client,
+			signature: &sig.to_bytes(),
+		};
+		assert_eq!(
+			verify_assertion(&key, &assertion, &expected).unwrap_err(),
+			WebauthnError::InvalidSignature
+		);
+	}
+}
@@ -0,0 +1,240 @@
+//! Signing and verifying webhooks with HMAC-SHA256.
+//!
+//! Supports the formats used by GitHub (`sha256=<hex>`), Stripe
+//! (`t=<timestamp>,v1=<hex>`) and a generic hex encoded tag. All
+//! comparisons are done in constant time.
+//!
+//! ## Example
+//! ```
+//! use chuchi_crypto::hash::HmacKey;
+//! use chuchi_crypto::webhook;
+//!
+//! let mac = HmacKey::new(b"It's a Secret to Everybody");
+//! let header = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+//!
+//! assert!(webhook::verify_github(&mac, b"Hello, World!", header).is_ok());
+//! ```
+
+use crate::error::TokenError;
+use crate::hash::{HmacKey, Tag};
+use crate::unix_secs;
+
+use std::time::{Duration, SystemTime};
+
+/// The tolerance Stripe uses by default.
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(5 * 60);
+
+/// Returns the hex encoded tag of the body.
+pub fn sign(mac: &HmacKey, body: &[u8]) -> String {
+	mac.sign(body).to_hex()
+}
+
+/// Verifies a hex encoded tag created with `sign`.
+pub fn verify(
+	mac: &HmacKey,
+	body: &[u8],
+	signature: &str,
+) -> Result<(), TokenError> {
+	let tag = Tag::from_hex(signature.trim())
+		.map_err(|_| TokenError::InvalidFormat)?;
+
+	if mac.verify(body, &tag) {
+		Ok(())
+	} else {
+		Err(TokenError::InvalidSignature)
+	}
+}
+
+/// Returns the value of the `X-Hub-Signature-256` header GitHub sends.
+pub fn sign_github(mac: &HmacKey, body: &[u8]) -> String {
+	format!("sha256={}", sign(mac, body))
+}
+
+/// Verifies the `X-Hub-Signature-256` header sent by GitHub.
+pub fn verify_github(
+	mac: &HmacKey,
+	body: &[u8],
+	header: &str,
+) -> Result<(), TokenError> {
+	let signature = header
+		.trim()
+		.strip_prefix("sha256=")
+		.ok_or(TokenError::InvalidFormat)?;
+
+	verify(mac, body, signature)
+}
+
+/// Returns the value of the `Stripe-Signature` header for the given time.
+pub fn sign_stripe(mac: &HmacKey, body: &[u8], time: SystemTime) -> String {
+	let timestamp = unix_secs(time);
+	let tag = mac.sign(stripe_payload(timestamp, body));
+
+	format!("t={timestamp},v1={}", tag.to_hex())
+}
+
+/// Verifies the `Stripe-Signature` header sent by Stripe.
+///
+/// The timestamp needs to be within `tolerance` of the current time, every
+/// `v1` signature is tried so secrets can be rolled.
+pub fn verify_stripe(
+	mac: &HmacKey,
+	body: &[u8],
+	header: &str,
+	tolerance: Duration,
+) -> Result<(), TokenError> {
+	verify_stripe_at(mac, body, header, tolerance, crate::now())
+}
+
+pub fn verify_stripe_at(
+	mac: &HmacKey,
+	body: &[u8],
+	header: &str,
+	tolerance: Duration,
+	now: SystemTime,
+) -> Result<(), TokenError> {
+	let mut timestamp = None;
+	let mut tags = vec![];
+
+	for part in header.split(',') {
+		let (key, value) = part
+			.trim()
+			.split_once('=')
+			.ok_or(TokenError::InvalidFormat)?;
+
+		match key {
+			"t" => {
+				let t = value.parse().map_err(|_| TokenError::InvalidFormat)?;
+				timestamp = Some(t);
+			}
+			// signatures we can't parse are ignored like other schemes
+			"v1" => tags.extend(Tag::from_hex(value).ok()),
+			_ => {}
+		}
+	}
+
+	let timestamp: u64 = timestamp.ok_or(TokenError::InvalidFormat)?;
+	if tags.is_empty() {
+		return Err(TokenError::InvalidFormat);
+	}
+
+	let expected = mac.sign(stripe_payload(timestamp, body));
+	let mut valid = false;
+	for tag in &tags {
+		valid |= expected == *tag;
+	}
+
+	if !valid {
+		return Err(TokenError::InvalidSignature);
+	}
+
+	let now = unix_secs(now);
+	let tolerance = tolerance.as_secs();
+	if timestamp > now.saturating_add(tolerance) {
+		Err(TokenError::NotYetValid)
+	} else if timestamp.saturating_add(tolerance) < now {
+		Err(TokenError::Expired)
+	} else {
+		Ok(())
+	}
+}
+
+fn stripe_payload(timestamp: u64, body: &[u8]) -> Vec<u8> {
+	let mut payload = format!("{timestamp}.").into_bytes();
+	payload.extend_from_slice(body);
+
+	payload
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::time::UNIX_EPOCH;
+
+	#[test]
+	fn test_github() {
+		// from the github documentation
+		let mac = HmacKey::new(b"It's a Secret to Everybody");
+		let body = b"Hello, World!";
+
+		let header = sign_github(&mac, body);
+		assert_eq!(
+			header,
+			"sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+		);
+		assert!(verify_github(&mac, body, &header).is_ok());
+
+		assert_eq!(
+			verify_github(&mac, b"Hello, World?", &header).unwrap_err(),
+			TokenError::InvalidSignature
+		);
+		assert_eq!(
+			verify_github(&mac, body, &header[7..]).unwrap_err(),
+			TokenError::InvalidFormat
+		);
+	}
+
+	#[test]
+	fn test_generic() {
+		let mac = HmacKey::new(b"secret");
+		let signature = sign(&mac, b"body");
+
+		assert!(verify(&mac, b"body", &signature).is_ok());
+		assert!(verify(&mac, b"body", &signature.to_uppercase()).is_ok());
+		assert_eq!(
+			verify(&HmacKey::new(b"other"), b"body", &signature).unwrap_err(),
+			TokenError::InvalidSignature
+		);
+		assert_eq!(
+			verify(&mac, b"body", "abc").unwrap_err(),
+			TokenError::InvalidFormat
+		);
+	}
+
+	#[test]
+	fn test_stripe() {
+		let mac = HmacKey::new(b"whsec_test");
+		let old = HmacKey::new(b"whsec_old");
+		let body = br#"{"id":"evt_1"}"#;
+		let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+		let header = sign_stripe(&mac, body, time);
+		assert!(header.starts_with("t=1700000000,v1="));
+		assert!(
+			verify_stripe_at(&mac, body, &header, DEFAULT_TOLERANCE, time)
+				.is_ok()
+		);
+
+		// multiple signatures while rolling the secret
+		let old_header = sign_stripe(&old, body, time);
+		let rolled = format!("{header},v1={},v0=abc", &old_header[16..]);
+		assert!(
+			verify_stripe_at(&old, body, &rolled, DEFAULT_TOLERANCE, time)
+				.is_ok()
+		);
+
+		let later = time + Duration::from_secs(301);
+		assert_eq!(
+			verify_stripe_at(&mac, body, &header, DEFAULT_TOLERANCE, later)
+				.unwrap_err(),
+			TokenError::Expired
+		);
+		let earlier = time - Duration::from_secs(301);
+		assert_eq!(
+			verify_stripe_at(&mac, body, &header, DEFAULT_TOLERANCE, earlier)
+				.unwrap_err(),
+			TokenError::NotYetValid
+		);
+
+		assert_eq!(
+			verify_stripe_at(&mac, b"{}", &header, DEFAULT_TOLERANCE, time)
+				.unwrap_err(),
+			TokenError::InvalidSignature
+		);
+		assert_eq!(
+			verify_stripe_at(&mac, body, "v1=abc", DEFAULT_TOLERANCE, time)
+				.unwrap_err(),
+			TokenError::InvalidFormat
+		);
+	}
+}